@@ -0,0 +1,47 @@
+// Parser benchmarks over the template corpus in `benches/corpus`:
+//
+//     cargo bench --features bench
+//
+// Each corpus entry is measured per phase (tree-sitter, mapping) and end to
+// end, flat and resolved. Criterion keeps the previous run as its baseline;
+// `-- --save-baseline main` / `-- --baseline main` compare against a named
+// one. `pywire bench` measures the same corpus through the Python API.
+
+use _pywire_parser::bench;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use pyo3::Python;
+use std::hint::black_box;
+
+const CORPUS: &[(&str, &str)] = &[
+    ("small", include_str!("corpus/small.wire")),
+    ("huge", include_str!("corpus/huge.wire")),
+    ("loops", include_str!("corpus/loops.wire")),
+    ("errors", include_str!("corpus/errors.wire")),
+];
+
+fn phases(c: &mut Criterion) {
+    bench::initialize();
+    Python::attach(|py| {
+        for &(name, source) in CORPUS {
+            let mut group = c.benchmark_group(name);
+            group.throughput(Throughput::Bytes(source.len() as u64));
+            group.bench_function("tree", |b| {
+                b.iter(|| bench::tree(black_box(source)).unwrap())
+            });
+            let tree = bench::tree(source).unwrap();
+            group.bench_function("map", |b| {
+                b.iter(|| bench::map(py, black_box(source), &tree).unwrap())
+            });
+            for resolved in [false, true] {
+                let id = BenchmarkId::new("parse", if resolved { "resolved" } else { "flat" });
+                group.bench_with_input(id, &resolved, |b, &resolved| {
+                    b.iter(|| bench::parse(py, black_box(source), resolved).unwrap())
+                });
+            }
+            group.finish();
+        }
+    });
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
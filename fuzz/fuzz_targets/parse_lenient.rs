@@ -0,0 +1,31 @@
+// `parse(source, lenient=True)` over arbitrary input. Lenient parsing must
+// turn every failure into a diagnostic, so a raised error, a panic or an
+// `internal-error` diagnostic (a panic the mapper caught) is a finding:
+//
+//     cargo +nightly fuzz run parse_lenient ../benches/corpus
+//
+// Run from `fuzz/`; the bench corpus makes a useful seed.
+
+#![no_main]
+
+use _pywire_parser::bench;
+use libfuzzer_sys::fuzz_target;
+use pyo3::Python;
+use std::sync::Once;
+
+static INIT: Once = Once::new();
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+    INIT.call_once(bench::initialize);
+    Python::attach(|py| {
+        for resolved in [false, true] {
+            let doc = bench::parse(py, source, resolved).expect("lenient parse raised");
+            if let Some(d) = doc.diagnostics.iter().find(|d| d.code == "internal-error") {
+                panic!("{} at {}:{}", d.message, d.line, d.column);
+            }
+        }
+    });
+});
@@ -0,0 +1,26 @@
+use crate::ParsedNode;
+use pyo3::prelude::*;
+
+pub(crate) const SOURCE_ATTRIBUTE: &str = "data-pw-src";
+
+// Components receive attributes as props and `slot` is structural, so only
+// plain HTML elements are stamped.
+fn stampable(tag: &str) -> bool {
+    !tag.starts_with(|c: char| c.is_ascii_uppercase()) && tag != "slot"
+}
+
+// Dev-mode transform: stamp `data-pw-src="file:line"` onto every element so
+// the devtools extension can map DOM nodes back to template source.
+pub(crate) fn stamp_source(py: Python<'_>, nodes: &[Py<ParsedNode>], file: &str) {
+    for node in nodes {
+        let mut node = node.borrow_mut(py);
+        if node.tag.as_deref().is_some_and(stampable) {
+            let value = format!("{}:{}", file, node.line);
+            if !node.attributes.contains_key(SOURCE_ATTRIBUTE) {
+                node.attributes
+                    .insert(SOURCE_ATTRIBUTE.to_string(), Some(value));
+            }
+        }
+        stamp_source(py, &node.children, file);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::project::ProjectIndex;
+use crate::routes::RouteEntry;
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// An `<img>` element found by `analyze_images`.
+///
+/// `src` is None when the source is an expression; `missing` lists which of
+/// width/height/loading the element does not set.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct ImageInfo {
+    #[pyo3(get)]
+    pub src: Option<String>,
+    #[pyo3(get)]
+    pub alt: Option<String>,
+    #[pyo3(get)]
+    pub width: Option<String>,
+    #[pyo3(get)]
+    pub height: Option<String>,
+    #[pyo3(get)]
+    pub loading: Option<String>,
+    #[pyo3(get)]
+    pub has_srcset: bool,
+    #[pyo3(get)]
+    pub missing: Vec<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl ImageInfo {
+    #[getter]
+    fn is_static(&self) -> bool {
+        self.src.is_some()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ImageInfo(src={:?}, missing={:?})", self.src, self.missing)
+    }
+}
+
+fn static_attr(node: &ParsedNode, name: &str) -> Option<String> {
+    node.attributes
+        .get(name)
+        .map(|v| v.clone().unwrap_or_default())
+        .filter(|v| !v.starts_with('{'))
+}
+
+fn image_info(node: &ParsedNode) -> ImageInfo {
+    let dynamic = |name: &str| {
+        node.attributes
+            .get(name)
+            .and_then(|v| v.as_deref())
+            .is_some_and(|v| v.starts_with('{'))
+    };
+    let missing = ["width", "height", "loading"]
+        .iter()
+        .filter(|a| !node.attributes.contains_key(a))
+        .map(|a| a.to_string())
+        .collect();
+    ImageInfo {
+        src: static_attr(node, "src").filter(|_| !dynamic("src")),
+        alt: static_attr(node, "alt"),
+        width: static_attr(node, "width"),
+        height: static_attr(node, "height"),
+        loading: static_attr(node, "loading"),
+        has_srcset: node.attributes.contains_key("srcset"),
+        missing,
+        line: node.line,
+        column: node.column,
+    }
+}
+
+fn collect_images(py: Python<'_>, nodes: &[Py<ParsedNode>], out: &mut Vec<ImageInfo>) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if node
+            .tag
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case("img"))
+        {
+            out.push(image_info(&node));
+        }
+        collect_images(py, &node.children, out);
+    }
+}
+
+/// Every `<img>` in a template, in document order.
+#[pyfunction]
+pub fn analyze_images(py: Python<'_>, document: PyRef<'_, ParsedDocument>) -> Vec<ImageInfo> {
+    let _span = tracing::info_span!("analyze", pass = "images").entered();
+    let mut out = Vec::new();
+    collect_images(py, &document.template, &mut out);
+    out
+}
+
+// Byte offset of the `>` (or `/>`) closing the start tag that begins at `start`,
+// skipping quoted values and `{...}` expressions.
+fn start_tag_end(source: &str, start: usize) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut quote = None;
+    let mut depth = 0usize;
+    for i in start..bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None => match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'{' => depth += 1,
+                b'}' => depth = depth.saturating_sub(1),
+                b'>' if depth == 0 => {
+                    return Some(if i > start && bytes[i - 1] == b'/' {
+                        i - 1
+                    } else {
+                        i
+                    });
+                }
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+pub(crate) fn offset_of(source: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column).min(source.len())
+}
+
+/// Rewrite `<img>` tags in template `source`.
+///
+/// `hook(info)` is called for each image and may return a dict of attributes
+/// to add (e.g. width/height from the image file, a generated srcset);
+/// attributes the element already has are left untouched. Returns the new
+/// source.
+#[pyfunction]
+pub fn rewrite_images(py: Python<'_>, source: String, hook: Bound<'_, PyAny>) -> PyResult<String> {
+    let doc = crate::parse_with(py, &source, &Default::default())?;
+    let mut images = Vec::new();
+    collect_images(py, &doc.template, &mut images);
+
+    let mut edits: Vec<(usize, String)> = Vec::new();
+    for info in images {
+        let start = offset_of(&source, info.line, info.column);
+        let Some(end) = start_tag_end(&source, start) else {
+            continue;
+        };
+        let existing = &source[start..end];
+        let result = hook.call1((info,))?;
+        if result.is_none() {
+            continue;
+        }
+        let additions = result.downcast::<PyDict>()?;
+        let mut insert = String::new();
+        for (name, value) in additions.iter() {
+            let name: String = name.extract()?;
+            let present = existing.match_indices(name.as_str()).any(|(i, _)| {
+                existing[..i].ends_with(|c: char| c.is_whitespace())
+                    && !existing[i + name.len()..]
+                        .starts_with(|c: char| c.is_alphanumeric() || c == '-')
+            });
+            if present {
+                continue;
+            }
+            insert.push(' ');
+            insert.push_str(&name);
+            if !value.is_none() {
+                let value: String = value.str()?.to_string();
+                insert.push_str("=\"");
+                insert.push_str(&value.replace('"', "&quot;"));
+                insert.push('"');
+            }
+        }
+        if !insert.is_empty() {
+            // Before the whitespace preceding `>` or `/>`, if any.
+            let at = source[..end]
+                .char_indices()
+                .next_back()
+                .filter(|(_, c)| c.is_whitespace())
+                .map_or(end, |(i, _)| i);
+            edits.push((at, insert));
+        }
+    }
+
+    let mut out = source;
+    for (at, insert) in edits.into_iter().rev() {
+        out.insert_str(at, &insert);
+    }
+    Ok(out)
+}
+
+/// Prefetch/preconnect hints for one route, from `link_hints`.
+///
+/// `prefetch` holds internal link targets that resolve to a known route;
+/// `preconnect` the distinct external origins the page loads from; `data`
+/// the `{$await ...}` expressions of the route and its components.
+#[pyclass(frozen)]
+pub struct RouteHints {
+    #[pyo3(get)]
+    pub route: RouteEntry,
+    #[pyo3(get)]
+    pub links: Vec<String>,
+    #[pyo3(get)]
+    pub prefetch: Vec<String>,
+    #[pyo3(get)]
+    pub preconnect: Vec<String>,
+    #[pyo3(get)]
+    pub components: Vec<String>,
+    #[pyo3(get)]
+    pub data: Vec<String>,
+}
+
+#[pymethods]
+impl RouteHints {
+    fn __repr__(&self) -> String {
+        format!(
+            "RouteHints(path={:?}, prefetch={:?}, preconnect={:?})",
+            self.route.path, self.prefetch, self.preconnect
+        )
+    }
+}
+
+#[derive(Default)]
+struct PageRefs {
+    links: Vec<String>,
+    origins: Vec<String>,
+    data: Vec<String>,
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
+
+fn url_origin(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("//"))?;
+    let host_len = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    Some(&url[..url.len() - rest.len() + host_len])
+}
+
+fn collect_refs(py: Python<'_>, nodes: &[Py<ParsedNode>], refs: &mut PageRefs) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if node.is_block && node.block_keyword.as_deref() == Some("await") {
+            push_unique(&mut refs.data, node.expression.as_deref().unwrap_or(""));
+        }
+        for name in ["href", "src"] {
+            let Some(url) = static_attr(&node, name) else {
+                continue;
+            };
+            if let Some(origin) = url_origin(&url) {
+                push_unique(&mut refs.origins, origin);
+            } else if name == "href" && node.tag.as_deref() == Some("a") && url.starts_with('/') {
+                push_unique(&mut refs.links, &url);
+            }
+        }
+        collect_refs(py, &node.children, refs);
+    }
+}
+
+// Whether a concrete path matches a route pattern (`:id`, `{id}` and `[id]`
+// match one segment, a trailing `*` the rest).
+pub(crate) fn route_matches(pattern: &str, path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or("");
+    let mut pat = pattern.trim_matches('/').split('/');
+    let mut segs = path.trim_matches('/').split('/');
+    loop {
+        match (pat.next(), segs.next()) {
+            (None, None) => return true,
+            (Some(p), _) if p.starts_with('*') => return true,
+            (Some(p), Some(s)) => {
+                let param = p.starts_with(':')
+                    || (p.starts_with('{') && p.ends_with('}'))
+                    || (p.starts_with('[') && p.ends_with(']'));
+                let matched = if param { !s.is_empty() } else { p == s };
+                if !matched {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Prefetch, preconnect and data-dependency hints for every route in the
+/// project, following component usage so links and origins inside components
+/// count for the pages that render them.
+#[pyfunction]
+pub fn link_hints(py: Python<'_>, index: PyRef<'_, ProjectIndex>) -> PyResult<Vec<RouteHints>> {
+    let routes = index.route_table(py)?;
+    let mut hints = Vec::with_capacity(routes.len());
+    for route in &routes {
+        let file = route.file.clone().unwrap_or_default();
+        let components = index.component_closure(py, &file);
+        let mut refs = PageRefs::default();
+        for path in std::iter::once(&file).chain(&components) {
+            if let Some(doc) = index.files.get(path) {
+                collect_refs(py, &doc.borrow(py).template, &mut refs);
+            }
+        }
+        let prefetch = refs
+            .links
+            .iter()
+            .filter(|link| {
+                let target = link.split(['?', '#']).next().unwrap_or("");
+                target != route.path && routes.iter().any(|r| route_matches(&r.path, link))
+            })
+            .cloned()
+            .collect();
+        hints.push(RouteHints {
+            route: route.clone(),
+            links: refs.links,
+            prefetch,
+            preconnect: refs.origins,
+            components,
+            data: refs.data,
+        });
+    }
+    Ok(hints)
+}
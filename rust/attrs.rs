@@ -0,0 +1,576 @@
+// Element attributes in source order, each a ParsedAttribute. Python also
+// sees an ordered dict, so `node.attributes["class"]` keeps working while
+// iteration follows the template. In the dict, `{name}` shorthands are keyed
+// `__pw_sh_name` and a `{**props}` spread `__pywire_spread__` (the last one
+// when there are several), with the braced source as the value; new code
+// should read `parsed_attributes` instead.
+
+use crate::kinds::{AttrKind, bind_target, class_toggle, event_spec, style_property};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ops::Index;
+use tree_sitter::Node;
+
+const SPREAD_KEY: &str = "__pywire_spread__";
+const SHORTHAND_PREFIX: &str = "__pw_sh_";
+
+/// A piece of an attribute value: literal text, or an `{expression}` with
+/// `text` the expression without its braces. The position and byte span
+/// cover the segment as written, braces included.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttrSegment {
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub is_expression: bool,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+}
+
+#[pymethods]
+impl AttrSegment {
+    fn __repr__(&self) -> String {
+        if self.is_expression {
+            format!("AttrSegment({{{}}})", self.text)
+        } else {
+            format!("AttrSegment({:?})", self.text)
+        }
+    }
+}
+
+// `{expr}` -> `expr`; other values are only trimmed.
+pub(crate) fn unbrace(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('{')
+        .and_then(|v| v.strip_suffix('}'))
+        .unwrap_or(value)
+        .trim()
+        .to_string()
+}
+
+// End of the `{...}` starting at `open` (exclusive), skipping braces inside
+// string literals; None if it is never closed.
+fn matching_brace(value: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in value[open..].char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + i + 1);
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+// Split `value`, which starts at `start_byte` / (`line`, `column`) in the
+// source, into literal and expression segments. Unclosed and empty braces
+// are literal text.
+pub(crate) fn split_segments(
+    value: &str,
+    start_byte: usize,
+    line: usize,
+    column: usize,
+) -> Vec<AttrSegment> {
+    let position = |offset: usize| {
+        let before = &value[..offset];
+        match before.rfind('\n') {
+            Some(nl) => (line + before.matches('\n').count(), offset - nl - 1),
+            None => (line, column + offset),
+        }
+    };
+    let mut out = Vec::new();
+    let mut push = |text: &str, is_expression: bool, from: usize, to: usize| {
+        let (line, column) = position(from);
+        out.push(AttrSegment {
+            text: text.to_string(),
+            is_expression,
+            line,
+            column,
+            start_byte: start_byte + from,
+            end_byte: start_byte + to,
+        });
+    };
+    let mut literal = 0;
+    let mut i = 0;
+    while let Some(open) = value[i..].find('{').map(|o| i + o) {
+        let Some(end) = matching_brace(value, open) else {
+            break;
+        };
+        let expr = value[open + 1..end - 1].trim();
+        if !expr.is_empty() {
+            if literal < open {
+                push(&value[literal..open], false, literal, open);
+            }
+            push(expr, true, open, end);
+            literal = end;
+        }
+        i = end;
+    }
+    if literal < value.len() {
+        push(&value[literal..], false, literal, value.len());
+    }
+    out
+}
+
+/// One attribute as written. `name` is the attribute name (`class`,
+/// `@click`, `$if`), the bound name of a `{name}` shorthand, or `**` for a
+/// `{**props}` spread. `value` is unquoted, or the expression of a shorthand
+/// or spread; `raw_value` is the source text with its quotes or braces and
+/// `quote` the quote character, if any. `value_start_byte`/`value_end_byte`
+/// locate `value` in the source, and `segments` split it into literal text
+/// and `{expression}` parts (one expression for shorthands and spreads).
+///
+/// Event attributes (`@click.prevent={save}`, `on:click.prevent={save}`)
+/// also carry `event` ("click"), `modifiers` (["prevent"]) and
+/// `handler_expr` ("save"); these are None/empty for other attributes.
+/// Likewise `bind:value={form.name}` carries `bind_target` ("value") and
+/// `expression` ("form.name"), the place the binding reads and writes.
+/// `class:active={cond}` and `style:color={c}` carry their condition or
+/// value in `expression`; written without a value they stand for
+/// `class:active={active}` and `style:color={color}`.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedAttribute {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub value: Option<String>,
+    #[pyo3(get)]
+    pub raw_value: Option<String>,
+    #[pyo3(get)]
+    pub quote: Option<char>,
+    #[pyo3(get)]
+    pub kind: AttrKind,
+    #[pyo3(get)]
+    pub is_shorthand: bool,
+    #[pyo3(get)]
+    pub is_spread: bool,
+    #[pyo3(get)]
+    pub segments: Vec<AttrSegment>,
+    /// Some segment is an expression.
+    #[pyo3(get)]
+    pub has_interpolation: bool,
+    #[pyo3(get)]
+    pub event: Option<String>,
+    #[pyo3(get)]
+    pub modifiers: Vec<String>,
+    #[pyo3(get)]
+    pub handler_expr: Option<String>,
+    #[pyo3(get)]
+    pub bind_target: Option<String>,
+    #[pyo3(get)]
+    pub expression: Option<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub value_start_byte: Option<usize>,
+    #[pyo3(get)]
+    pub value_end_byte: Option<usize>,
+    // Key and value in the `attributes` dict.
+    key: String,
+    dict_value: Option<String>,
+}
+
+#[pymethods]
+impl ParsedAttribute {
+    fn __repr__(&self) -> String {
+        match &self.raw_value {
+            Some(raw) if self.is_shorthand || self.is_spread => {
+                format!("ParsedAttribute({})", raw)
+            }
+            Some(raw) => format!("ParsedAttribute({}={})", self.name, raw),
+            None => format!("ParsedAttribute({})", self.name),
+        }
+    }
+}
+
+impl ParsedAttribute {
+    // An attribute added after parsing, e.g. by dev-mode annotation.
+    pub(crate) fn new(name: String, value: Option<String>) -> Self {
+        let mut attr = ParsedAttribute {
+            key: name.clone(),
+            name,
+            value: None,
+            raw_value: None,
+            quote: None,
+            kind: AttrKind::Boolean,
+            is_shorthand: false,
+            is_spread: false,
+            segments: Vec::new(),
+            has_interpolation: false,
+            event: None,
+            modifiers: Vec::new(),
+            handler_expr: None,
+            bind_target: None,
+            expression: None,
+            line: 0,
+            column: 0,
+            value_start_byte: None,
+            value_end_byte: None,
+            dict_value: None,
+        };
+        attr.set_value(value);
+        attr
+    }
+
+    // An `attribute` node.
+    pub(crate) fn from_node(source: &str, node: Node) -> Self {
+        let start = node.start_position();
+        let mut cursor = node.walk();
+        let shorthand = node
+            .children(&mut cursor)
+            .find(|c| matches!(c.kind(), "attribute_shorthand" | "spread_shorthand"));
+        if let Some(short) = shorthand {
+            return Self::shorthand(source, short, start.row + 1, start.column);
+        }
+
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| source[n.start_byte()..n.end_byte()].to_string())
+            .unwrap_or_default();
+        let mut attr = ParsedAttribute::new(name, None);
+        (attr.line, attr.column) = (start.row + 1, start.column);
+        if let Some(v) = node.child_by_field_name("value") {
+            let raw = &source[v.start_byte()..v.end_byte()];
+            let quote = raw
+                .chars()
+                .next()
+                .filter(|&q| (q == '"' || q == '\'') && raw.len() >= 2 && raw.ends_with(q));
+            let (start, end) = match quote {
+                Some(_) => (v.start_byte() + 1, v.end_byte() - 1),
+                None => (v.start_byte(), v.end_byte()),
+            };
+            let pos = v.start_position();
+            let column = pos.column + quote.map_or(0, |_| 1);
+            attr.value_start_byte = Some(start);
+            attr.value_end_byte = Some(end);
+            attr.set_value(Some(source[start..end].to_string()));
+            attr.segments = split_segments(&source[start..end], start, pos.row + 1, column);
+            attr.has_interpolation = attr.segments.iter().any(|s| s.is_expression);
+            attr.raw_value = Some(raw.to_string());
+            attr.quote = quote;
+        }
+        attr
+    }
+
+    // `{name}`, or `{**expr}` (which the grammar may also report as an
+    // `attribute_shorthand`).
+    fn shorthand(source: &str, node: Node, line: usize, column: usize) -> Self {
+        let text = &source[node.start_byte()..node.end_byte()];
+        let is_spread = node.kind() == "spread_shorthand" || text.starts_with("{**");
+        let open = if is_spread { 3 } else { 1 };
+        let inner_start = (node.start_byte() + open).min(node.end_byte());
+        let inner_end = node.end_byte().saturating_sub(1).max(inner_start);
+        let inner = &source[inner_start..inner_end];
+        let expr = inner.trim();
+        let expr_start = inner_start + (inner.len() - inner.trim_start().len());
+        let (name, key, kind) = if is_spread {
+            ("**".to_string(), SPREAD_KEY.to_string(), AttrKind::Spread)
+        } else {
+            (
+                expr.to_string(),
+                format!("{}{}", SHORTHAND_PREFIX, expr),
+                AttrKind::Shorthand,
+            )
+        };
+        ParsedAttribute {
+            name,
+            value: Some(expr.to_string()),
+            raw_value: Some(text.to_string()),
+            quote: None,
+            kind,
+            is_shorthand: !is_spread,
+            is_spread,
+            segments: vec![AttrSegment {
+                text: expr.to_string(),
+                is_expression: true,
+                line,
+                column,
+                start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+            }],
+            has_interpolation: true,
+            event: None,
+            modifiers: Vec::new(),
+            handler_expr: None,
+            bind_target: None,
+            expression: None,
+            line,
+            column,
+            value_start_byte: Some(expr_start),
+            value_end_byte: Some(expr_start + expr.len()),
+            key,
+            dict_value: Some(text.to_string()),
+        }
+    }
+
+    // The value as the `attributes` dict has it.
+    pub(crate) fn dict_value(&self) -> Option<&str> {
+        self.dict_value.as_deref()
+    }
+
+    // `event`, `modifiers` and `handler_expr` of an event attribute.
+    fn set_event(&mut self, value: Option<&str>) {
+        let spec = event_spec(&self.name).filter(|_| self.kind == AttrKind::Event);
+        let mut parts = spec.into_iter().flat_map(|s| s.split('.'));
+        self.event = parts.next().map(str::to_string);
+        self.modifiers = parts
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .collect();
+        self.handler_expr = spec.and(value).map(unbrace);
+    }
+
+    // `bind_target` and `expression` of `bind:`, `class:` and `style:`
+    // attributes.
+    fn set_bind(&mut self, value: Option<&str>) {
+        let target = bind_target(&self.name).filter(|_| self.kind == AttrKind::Bind);
+        self.bind_target = target.map(str::to_string);
+        self.expression = match self.kind {
+            AttrKind::Bind => value.map(unbrace),
+            AttrKind::ClassToggle | AttrKind::StyleProperty => {
+                let short = class_toggle(&self.name).or_else(|| style_property(&self.name));
+                Some(value.map_or_else(|| short.unwrap_or_default().to_string(), unbrace))
+            }
+            _ => None,
+        }
+        .filter(|e| !e.is_empty());
+    }
+
+    // Set the value as the `attributes` dict sees it; for shorthands and
+    // spreads that is the braced form.
+    fn set_value(&mut self, value: Option<String>) {
+        if self.is_shorthand || self.is_spread {
+            let open = if self.is_spread { "{**" } else { "{" };
+            self.value = value.as_deref().map(|v| {
+                v.strip_prefix(open)
+                    .and_then(|v| v.strip_suffix('}'))
+                    .unwrap_or(v)
+                    .trim()
+                    .to_string()
+            });
+        } else {
+            self.kind = AttrKind::classify(&self.name, value.as_deref());
+            self.segments = value.as_deref().map_or_else(Vec::new, |v| {
+                split_segments(
+                    v,
+                    self.value_start_byte.unwrap_or(0),
+                    self.line,
+                    self.column,
+                )
+            });
+            self.has_interpolation = self.segments.iter().any(|s| s.is_expression);
+            self.set_event(value.as_deref());
+            self.set_bind(value.as_deref());
+            self.value = value.clone();
+        }
+        self.dict_value = value;
+    }
+}
+
+/// A `class:name={condition}` or `style:property={value}` attribute as an
+/// entry of `ParsedNode.conditional_classes` / `conditional_styles`: `name`
+/// is the class or CSS property and `expression` the condition or value.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ConditionalAttribute {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub expression: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl ConditionalAttribute {
+    fn __repr__(&self) -> String {
+        format!(
+            "ConditionalAttribute({}={{{}}})",
+            self.name, self.expression
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes(Vec<ParsedAttribute>);
+
+impl Attributes {
+    // A repeated attribute keeps its first position and takes the new
+    // value, returning the replaced one; every spread is kept.
+    pub(crate) fn push(&mut self, attr: ParsedAttribute) -> Option<ParsedAttribute> {
+        let existing = self
+            .0
+            .iter_mut()
+            .find(|a| !attr.is_spread && a.key == attr.key);
+        match existing {
+            Some(existing) => Some(std::mem::replace(existing, attr)),
+            None => {
+                self.0.push(attr);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn parsed(&self) -> &[ParsedAttribute] {
+        &self.0
+    }
+
+    // `class:` (ClassToggle) or `style:` (StyleProperty) entries, in
+    // source order.
+    pub(crate) fn conditionals(&self, kind: AttrKind) -> Vec<ConditionalAttribute> {
+        self.0
+            .iter()
+            .filter(|a| a.kind == kind)
+            .filter_map(|a| {
+                let (_, name) = a.name.split_once(':')?;
+                Some(ConditionalAttribute {
+                    name: name.to_string(),
+                    expression: a.expression.clone()?,
+                    line: a.line,
+                    column: a.column,
+                })
+            })
+            .collect()
+    }
+
+    // DOM attributes a re-render can change, in source order and without
+    // repeats: expression values and interpolated strings under their own
+    // name, `class:`/`style:` entries under `class`/`style`. Spreads have no
+    // names known at compile time.
+    pub(crate) fn dynamic_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for attr in &self.0 {
+            let name = match attr.kind {
+                AttrKind::Reactive | AttrKind::Shorthand => attr.name.as_str(),
+                AttrKind::Static if attr.has_interpolation => attr.name.as_str(),
+                AttrKind::ClassToggle => "class",
+                AttrKind::StyleProperty => "style",
+                _ => continue,
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    // Attributes as the dict has them: only the last spread is kept.
+    fn dict_entries(&self) -> impl Iterator<Item = &ParsedAttribute> {
+        let last_spread = self.0.iter().rposition(|a| a.is_spread);
+        self.0
+            .iter()
+            .enumerate()
+            .filter(move |(i, a)| !a.is_spread || Some(*i) == last_spread)
+            .map(|(_, a)| a)
+    }
+
+    // Lookup by dict key.
+    pub(crate) fn find(&self, key: &str) -> Option<&ParsedAttribute> {
+        self.0.iter().rev().find(|a| a.key == key)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Option<String>> {
+        self.find(key).map(|a| &a.dict_value)
+    }
+
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: String, value: Option<String>) {
+        match self.0.iter_mut().rev().find(|a| a.key == key) {
+            Some(existing) => existing.set_value(value),
+            None => self.0.push(ParsedAttribute::new(key, value)),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.dict_entries().map(|a| &a.key)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &Option<String>)> {
+        self.dict_entries().map(|a| (&a.key, &a.dict_value))
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &Option<String>> {
+        self.dict_entries().map(|a| &a.dict_value)
+    }
+}
+
+impl<Q: AsRef<str> + ?Sized> Index<&Q> for Attributes {
+    type Output = Option<String>;
+
+    fn index(&self, key: &Q) -> &Option<String> {
+        self.get(key.as_ref()).expect("no such attribute")
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = (&'a String, &'a Option<String>);
+    type IntoIter = Box<dyn Iterator<Item = Self::Item> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl FromIterator<(String, Option<String>)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (String, Option<String>)>>(iter: I) -> Self {
+        let mut out = Attributes::default();
+        for (key, value) in iter {
+            out.insert(key, value);
+        }
+        out
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &Attributes {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in self {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Attributes {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        (&self).into_pyobject(py)
+    }
+}
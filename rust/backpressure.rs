@@ -0,0 +1,134 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// Bounded per-connection queue of region updates waiting to be sent.
+///
+/// A region has at most one queued update: pushing another one for the same
+/// region replaces the payload in place (coalescing), since only the newest
+/// render matters. When the queue is full, the update of the region queued
+/// longest is dropped to make room; `take_dropped()` hands those regions back
+/// so they can be re-rendered once the client catches up.
+#[pyclass]
+pub struct UpdateQueue {
+    order: VecDeque<String>,
+    payloads: HashMap<String, Py<PyAny>>,
+    capacity: usize,
+    lost: Vec<String>,
+    /// Updates accepted by `push()`.
+    #[pyo3(get)]
+    queued: usize,
+    /// Updates that replaced a pending one for the same region.
+    #[pyo3(get)]
+    coalesced: usize,
+    /// Updates evicted because the queue was full.
+    #[pyo3(get)]
+    dropped: usize,
+}
+
+#[pymethods]
+impl UpdateQueue {
+    #[new]
+    #[pyo3(signature = (capacity=64))]
+    fn new(capacity: usize) -> PyResult<Self> {
+        if capacity == 0 {
+            return Err(PyValueError::new_err("capacity must be at least 1"));
+        }
+        Ok(UpdateQueue {
+            order: VecDeque::new(),
+            payloads: HashMap::new(),
+            capacity,
+            lost: Vec::new(),
+            queued: 0,
+            coalesced: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Queue `payload` for `region`. Returns the region whose update was
+    /// dropped to make room, if any.
+    fn push(&mut self, region: String, payload: Py<PyAny>) -> Option<String> {
+        self.queued += 1;
+        if let Some(pending) = self.payloads.get_mut(&region) {
+            *pending = payload;
+            self.coalesced += 1;
+            return None;
+        }
+        let mut evicted = None;
+        if self.order.len() >= self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.payloads.remove(&oldest);
+            self.dropped += 1;
+            if !self.lost.contains(&oldest) {
+                self.lost.push(oldest.clone());
+            }
+            evicted = Some(oldest);
+        }
+        self.lost.retain(|r| *r != region);
+        self.order.push_back(region.clone());
+        self.payloads.insert(region, payload);
+        evicted
+    }
+
+    /// Next `(region, payload)` to send, or None when empty.
+    fn pop(&mut self) -> Option<(String, Py<PyAny>)> {
+        let region = self.order.pop_front()?;
+        let payload = self.payloads.remove(&region)?;
+        Some((region, payload))
+    }
+
+    /// Take up to `limit` updates (all by default) in queue order.
+    #[pyo3(signature = (limit=None))]
+    fn drain(&mut self, limit: Option<usize>) -> Vec<(String, Py<PyAny>)> {
+        let count = limit.unwrap_or(usize::MAX).min(self.order.len());
+        let mut out = Vec::with_capacity(count);
+        for _ in 0..count {
+            if let Some(item) = self.pop() {
+                out.push(item);
+            }
+        }
+        out
+    }
+
+    /// Regions whose updates were dropped and not superseded since the last
+    /// call, oldest first.
+    fn take_dropped(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.lost)
+    }
+
+    /// Queued region ids, in send order.
+    #[getter]
+    fn regions(&self) -> Vec<String> {
+        self.order.iter().cloned().collect()
+    }
+
+    #[getter]
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn is_full(&self) -> bool {
+        self.order.len() >= self.capacity
+    }
+
+    fn clear(&mut self) {
+        self.order.clear();
+        self.payloads.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.order.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "UpdateQueue(len={}, capacity={}, queued={}, coalesced={}, dropped={})",
+            self.order.len(),
+            self.capacity,
+            self.queued,
+            self.coalesced,
+            self.dropped
+        )
+    }
+}
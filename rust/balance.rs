@@ -0,0 +1,300 @@
+// Source-level check that every element in the template is closed by its own
+// end tag. tree-sitter's error recovery often folds a `<div>` closed by
+// `</span>`, or never closed at all, into a neighbouring element, and the
+// mistake only surfaces as a confusing nested render. This pass walks the
+// template text with a small tag scanner (skipping `{...}` expressions,
+// comments and raw-text elements) and reports both ends of each problem.
+
+use crate::columns::LineIndex;
+use crate::diagnostics::Diagnostic;
+use crate::html::{implied_close, is_void};
+use crate::pairing::{BRANCHES, OPENERS};
+use std::ops::Range;
+
+// Elements whose end tag may be omitted: they close silently with their
+// parent or at the end of the template.
+const OPTIONAL_END: &[&str] = &[
+    "body", "colgroup", "dd", "dt", "head", "html", "li", "optgroup", "option", "p", "rp", "rt",
+    "tbody", "td", "tfoot", "th", "thead", "tr",
+];
+
+// Elements whose content is not markup.
+const RAW_TEXT: &[&str] = &["script", "style", "textarea", "title"];
+
+// Codes of the tree-sitter diagnostics this pass supersedes.
+const TAG_CODES: &[&str] = &["unclosed-tag", "unexpected-close-tag"];
+
+struct Open {
+    name: String,
+    // The start tag.
+    tag: Range<usize>,
+    // First end tag that failed to match while this was the innermost element.
+    stray: Option<Range<usize>>,
+}
+
+struct Scanner<'a> {
+    source: &'a str,
+    index: LineIndex<'a>,
+    open: Vec<Open>,
+    // Element depth at each open `{$block}`: tags inside a block (or one of
+    // its branches) must be closed before it ends.
+    blocks: Vec<usize>,
+    // Diagnostics with the byte ranges they cover, primary first.
+    found: Vec<(Diagnostic, Vec<Range<usize>>)>,
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b':' | b'.' | b'_')
+}
+
+// Index just past the `}` matching the `{` at `pos`, skipping quoted strings;
+// `pos + 1` if it is never closed, so a stray brace stays plain text.
+fn skip_braces(bytes: &[u8], pos: usize, end: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut i = pos;
+    while i < end {
+        let b = bytes[i];
+        match quote {
+            Some(_) if b == b'\\' => i += 1,
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None => match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + 1;
+                    }
+                }
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+    pos + 1
+}
+
+impl Scanner<'_> {
+    fn line(&self, offset: usize) -> usize {
+        self.index.position(offset).0
+    }
+
+    fn report(
+        &mut self,
+        code: &str,
+        message: String,
+        at: Range<usize>,
+        related: Option<Range<usize>>,
+    ) {
+        let (line, column) = self.index.position(at.start);
+        let mut diagnostic = Diagnostic::error(code, message, line, column)
+            .spanning(self.index.position(at.end), (at.start, at.end));
+        let mut ranges = vec![at];
+        if let Some(other) = related {
+            let (start, end) = (
+                self.index.position(other.start),
+                self.index.position(other.end),
+            );
+            diagnostic.related.push((start.0, start.1, end.0, end.1));
+            ranges.push(other);
+        }
+        self.found.push((diagnostic, ranges));
+    }
+
+    // Close elements down to `depth`, reporting those that needed an end tag.
+    // `by` is what ended them: an end tag, block marker or the template end.
+    fn close_to(&mut self, depth: usize, by: Range<usize>, what: &str) {
+        while self.open.len() > depth {
+            let el = self.open.pop().unwrap();
+            if OPTIONAL_END.contains(&el.name.to_ascii_lowercase().as_str()) {
+                continue;
+            }
+            let message = format!("<{}> is never closed before {}", el.name, what);
+            let related = el.stray.unwrap_or_else(|| by.clone());
+            self.report("unclosed-tag", message, el.tag, Some(related));
+        }
+    }
+
+    fn floor(&self) -> usize {
+        self.blocks.last().copied().unwrap_or(0)
+    }
+
+    fn start_tag(&mut self, name: &str, tag: Range<usize>, self_closing: bool) {
+        let lower = name.to_ascii_lowercase();
+        while self.open.len() > self.floor()
+            && implied_close(&self.open.last().unwrap().name.to_ascii_lowercase(), &lower)
+        {
+            self.open.pop();
+        }
+        if !self_closing && !is_void(&lower) {
+            self.open.push(Open {
+                name: name.to_string(),
+                tag,
+                stray: None,
+            });
+        }
+    }
+
+    fn end_tag(&mut self, name: &str, tag: Range<usize>) {
+        let floor = self.floor();
+        if let Some(at) = self.open[floor..]
+            .iter()
+            .rposition(|el| el.name.eq_ignore_ascii_case(name))
+        {
+            let what = format!("</{}> at line {}", name, self.line(tag.start));
+            self.close_to(floor + at + 1, tag, &what);
+            self.open.pop();
+            return;
+        }
+        if self.open.len() > floor {
+            let innermost = self.open.last_mut().unwrap();
+            innermost.stray.get_or_insert_with(|| tag.clone());
+            let (open_name, open_tag) = (innermost.name.clone(), innermost.tag.clone());
+            let message = format!(
+                "</{}> does not match the open <{}> at line {}",
+                name,
+                open_name,
+                self.line(open_tag.start)
+            );
+            self.report("unexpected-close-tag", message, tag, Some(open_tag));
+        } else {
+            let message = format!("closing tag </{}> has no matching open tag", name);
+            self.report("unexpected-close-tag", message, tag, None);
+        }
+    }
+
+    // `{...}` at `pos`: block markers open and close tag scopes; everything
+    // else is an expression or comment to skip. Returns where to resume.
+    fn brace(&mut self, pos: usize, end: usize) -> usize {
+        let rest = &self.source[pos..end];
+        if rest.starts_with("{#") {
+            return rest.find("#}").map_or(end, |i| pos + i + 2);
+        }
+        let after = skip_braces(self.source.as_bytes(), pos, end);
+        let inner = &self.source[pos + 1..after.saturating_sub(1).max(pos + 1)];
+        let keyword =
+            |s: &str| -> String { s.chars().take_while(|c| c.is_ascii_alphabetic()).collect() };
+        if let Some(marker) = inner.strip_prefix('$') {
+            let kw = keyword(marker);
+            if OPENERS.contains(&kw.as_str()) {
+                self.blocks.push(self.open.len());
+            } else if BRANCHES.contains(&kw.as_str())
+                && let Some(&depth) = self.blocks.last()
+            {
+                let what = format!("'{{${}}}' at line {}", kw, self.line(pos));
+                self.close_to(depth, pos..after, &what);
+            }
+        } else if let Some(marker) = inner.strip_prefix('/') {
+            let kw = keyword(marker);
+            if OPENERS.contains(&kw.as_str())
+                && let Some(depth) = self.blocks.pop()
+            {
+                let what = format!("'{{/{}}}' at line {}", kw, self.line(pos));
+                self.close_to(depth, pos..after, &what);
+            }
+        }
+        after
+    }
+
+    // Markup at a `<`. Returns where to resume.
+    fn angle(&mut self, pos: usize, end: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let rest = &self.source[pos..end];
+        let find = |needle: &str, from: usize| rest[from..].find(needle).map(|i| pos + from + i);
+        if rest.starts_with("<!--") {
+            return find("-->", 4).map_or(end, |i| i + 3);
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            return find(">", 2).map_or(end, |i| i + 1);
+        }
+        let closing = rest.starts_with("</");
+        let name_start = pos + if closing { 2 } else { 1 };
+        if !bytes.get(name_start).is_some_and(u8::is_ascii_alphabetic) {
+            return pos + 1;
+        }
+        let mut i = name_start;
+        while i < end && is_name_byte(bytes[i]) {
+            i += 1;
+        }
+        let name = &self.source[name_start..i];
+        if closing {
+            let after = find(">", i - pos).map_or(end, |gt| gt + 1);
+            self.end_tag(name, pos..after);
+            return after;
+        }
+        // Attributes: quoted values and `{...}` may contain '>'.
+        while i < end {
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    let quote = bytes[i] as char;
+                    i = self.source[i + 1..end]
+                        .find(quote)
+                        .map_or(end, |q| i + 1 + q + 1);
+                }
+                b'{' => i = skip_braces(bytes, i, end),
+                // A new tag before '>': leave the broken one to tree-sitter.
+                b'<' => return i,
+                b'>' => break,
+                _ => i += 1,
+            }
+        }
+        if i >= end {
+            return end;
+        }
+        let self_closing = bytes[i - 1] == b'/';
+        let after = i + 1;
+        self.start_tag(name, pos..after, self_closing);
+        let lower = name.to_ascii_lowercase();
+        if self_closing || !RAW_TEXT.contains(&lower.as_str()) {
+            return after;
+        }
+        // Jump to the raw-text element's end tag, which closes it as usual.
+        let close = format!("</{}", lower);
+        let body = &self.source[after..end];
+        (0..body.len().saturating_sub(close.len() - 1))
+            .find(|&k| body.as_bytes()[k..k + close.len()].eq_ignore_ascii_case(close.as_bytes()))
+            .map_or(end, |k| after + k)
+    }
+}
+
+// Unclosed and mismatched elements in the template section at `range`, each
+// with the other end (the offending end tag, block marker or end of the
+// template) in `related`. Replaces tree-sitter's reports of the same tags.
+pub(crate) fn unbalanced_tags(source: &str, range: Range<usize>, out: &mut Vec<Diagnostic>) {
+    let mut scanner = Scanner {
+        source,
+        index: LineIndex::new(source, 0),
+        open: Vec::new(),
+        blocks: Vec::new(),
+        found: Vec::new(),
+    };
+    let mut pos = range.start;
+    while pos < range.end {
+        pos = match source.as_bytes()[pos] {
+            b'{' => scanner.brace(pos, range.end),
+            b'<' => scanner.angle(pos, range.end),
+            _ => pos + 1,
+        };
+    }
+    scanner.close_to(0, range.end..range.end, "the end of the template");
+    if scanner.found.is_empty() {
+        return;
+    }
+    // Byte ranges touch; zero-width MISSING nodes sit at an edge.
+    let touches = |d: &Diagnostic, r: &Range<usize>| match (d.start_byte, d.end_byte) {
+        (Some(start), Some(end)) => start <= r.end && r.start <= end,
+        _ => false,
+    };
+    out.retain(|d| {
+        !(TAG_CODES.contains(&d.code.as_str())
+            && scanner
+                .found
+                .iter()
+                .any(|(_, ranges)| ranges.iter().any(|r| touches(d, r))))
+    });
+    let mut found: Vec<Diagnostic> = scanner.found.into_iter().map(|(d, _)| d).collect();
+    found.sort_by_key(|d| d.start_byte);
+    out.extend(found);
+}
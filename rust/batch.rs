@@ -0,0 +1,99 @@
+use crate::diagnostics::Diagnostic;
+use crate::limits::ParseLimits;
+use crate::{ParseOptions, ParsedDocument};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+#[cfg(all(feature = "parallel", not(target_family = "wasm")))]
+use rayon::prelude::*;
+use tree_sitter::Tree;
+
+// Tree-sitter phase for every source, without the GIL. Builds without the
+// `parallel` feature (and wasm, which has no threads) parse serially.
+// Sources over `max_source_bytes` are skipped (None) so the limit is
+// reported before any parsing, as in `parse`.
+fn parse_trees(
+    py: Python<'_>,
+    sources: &[(String, String)],
+    limits: Option<&ParseLimits>,
+) -> Vec<Option<PyResult<Tree>>> {
+    py.detach(|| {
+        #[cfg(all(feature = "parallel", not(target_family = "wasm")))]
+        let iter = sources.par_iter();
+        #[cfg(not(all(feature = "parallel", not(target_family = "wasm"))))]
+        let iter = sources.iter();
+        iter.map(|(_, source)| {
+            let over = limits.is_some_and(|l| l.check_source(source).is_some());
+            (!over).then(|| crate::parse_tree(source))
+        })
+        .collect()
+    })
+}
+
+/// Parse many templates at once: `{name: source}` -> `{name: ParsedDocument}`
+/// in the same order.
+///
+/// The tree-sitter phase runs across a thread pool with the GIL released;
+/// mapping to Python objects then runs per file. With `annotate=True` each
+/// name is used as that file's `annotate_file`. Other options match `parse`,
+/// with `limits` applying to each file on its own.
+#[pyfunction]
+#[pyo3(signature = (
+    sources,
+    annotate=false,
+    lenient=false,
+    limits=None,
+    resolved=false,
+    normalize_unicode=false,
+    tab_width=crate::columns::DEFAULT_TAB_WIDTH,
+    fragments=false,
+    strip_comments=false,
+    fold_constants=false,
+    validate_names=false,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn parse_many<'py>(
+    py: Python<'py>,
+    sources: &Bound<'py, PyDict>,
+    annotate: bool,
+    lenient: bool,
+    limits: Option<PyRef<'py, ParseLimits>>,
+    resolved: bool,
+    normalize_unicode: bool,
+    tab_width: usize,
+    fragments: bool,
+    strip_comments: bool,
+    fold_constants: bool,
+    validate_names: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let sources: Vec<(String, String)> = sources
+        .iter()
+        .map(|(name, source)| Ok((name.extract()?, source.extract()?)))
+        .collect::<PyResult<_>>()?;
+    let trees = parse_trees(py, &sources, limits.as_deref());
+
+    let out = PyDict::new(py);
+    for ((name, source), tree) in sources.iter().zip(trees) {
+        let options = ParseOptions {
+            annotate_file: annotate.then_some(name.as_str()),
+            lenient,
+            limits: limits.as_deref(),
+            resolved,
+            normalize_unicode,
+            tab_width,
+            fragments,
+            strip_comments,
+            fold_constants,
+            validate_names,
+        };
+        let doc = match tree {
+            Some(Ok(tree)) => crate::parse_with_tree(py, source, Some(&tree), &options)?,
+            None => crate::parse_with(py, source, &options)?,
+            Some(Err(e)) if lenient => {
+                ParsedDocument::failed(Diagnostic::error("internal-error", e.to_string(), 1, 0))
+            }
+            Some(Err(e)) => return Err(e),
+        };
+        out.set_item(name, Py::new(py, doc)?)?;
+    }
+    Ok(out)
+}
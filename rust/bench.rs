@@ -0,0 +1,39 @@
+// Entry points for the criterion suite in `benches/`, which links the crate
+// as an rlib and embeds an interpreter. Each function runs one phase of
+// `parse` the way the extension does, so representation changes in the
+// mapper can be measured against the same corpus before they land.
+
+use crate::{ParseOptions, ParsedDocument};
+use pyo3::prelude::*;
+use tree_sitter::Tree;
+
+/// Start the embedded interpreter; call once before any other function.
+pub fn initialize() {
+    Python::initialize();
+}
+
+/// Tree-sitter phase only.
+pub fn tree(source: &str) -> PyResult<Tree> {
+    crate::parse_tree(source)
+}
+
+// Lenient so the error-laden corpus entries measure recovery instead of
+// raising.
+fn options(resolved: bool) -> ParseOptions<'static> {
+    ParseOptions {
+        lenient: true,
+        resolved,
+        tab_width: crate::columns::DEFAULT_TAB_WIDTH,
+        ..Default::default()
+    }
+}
+
+/// Mapping phase only, over a tree from `tree(source)`.
+pub fn map(py: Python<'_>, source: &str, tree: &Tree) -> PyResult<ParsedDocument> {
+    crate::map_document(py, source, tree, &options(false))
+}
+
+/// Full `parse(source, lenient=True, resolved=resolved)`.
+pub fn parse(py: Python<'_>, source: &str, resolved: bool) -> PyResult<ParsedDocument> {
+    crate::parse_with(py, source, &options(resolved))
+}
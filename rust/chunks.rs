@@ -0,0 +1,91 @@
+// Static chunk table. Every outermost element whose subtree is fully static
+// (plain attributes, text and comments) is rendered once while parsing, and
+// identical markup shares one entry of `ParsedDocument.static_chunks`: a card
+// repeated in several branches or loop bodies is stored once. The element
+// records its entry in `static_chunk`, so generated code can emit the chunk
+// by index instead of rebuilding the markup node by node.
+
+use crate::ParsedNode;
+use crate::html::is_void;
+use crate::kinds::AttrKind;
+use crate::prerender::decode_attr;
+use crate::serializer::HtmlSerializer;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+// Elements the compiler treats specially even when static.
+pub(crate) const EXCLUDED_TAGS: &[&str] = &["head", "script", "slot", "style", "template"];
+
+fn is_static(py: Python<'_>, node: &ParsedNode) -> bool {
+    if node.is_block {
+        return false;
+    }
+    let Some(tag) = node.tag.as_deref() else {
+        return true;
+    };
+    if EXCLUDED_TAGS.contains(&tag) || node.component_name.is_some() || node.slot_name.is_some() {
+        return false;
+    }
+    let plain = node.attributes.parsed().iter().all(|attr| {
+        matches!(attr.kind, AttrKind::Static | AttrKind::Boolean) && !attr.has_interpolation
+    });
+    plain && node.children.iter().all(|c| is_static(py, &c.borrow(py)))
+}
+
+// Markup of a static subtree, as `prerender` writes it.
+fn write(py: Python<'_>, node: &ParsedNode, out: &mut HtmlSerializer) {
+    let Some(tag) = node.tag.as_deref() else {
+        if let Some(text) = &node.text_content {
+            out.raw(text);
+        }
+        return;
+    };
+    let attrs = node
+        .attributes
+        .parsed()
+        .iter()
+        .map(|a| (a.name.clone(), a.value.as_deref().map(decode_attr)))
+        .collect();
+    out.start_tag(tag, Some(attrs), false);
+    if is_void(tag) {
+        return;
+    }
+    for child in &node.children {
+        write(py, &child.borrow(py), out);
+    }
+    out.end_tag(tag);
+}
+
+// Set `static_chunk` on the outermost static elements of `nodes`, adding
+// their markup to `table` unless an identical chunk is already there.
+pub(crate) fn assign_chunks(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    table: &mut Vec<String>,
+    seen: &mut HashMap<String, usize>,
+) {
+    for node in nodes {
+        let chunk = {
+            let n = node.borrow(py);
+            if n.tag.is_none() {
+                None
+            } else if is_static(py, &n) {
+                let mut out = HtmlSerializer::new(false, false, false);
+                write(py, &n, &mut out);
+                Some(String::from_utf8_lossy(&out.drain()).into_owned())
+            } else {
+                let children: Vec<_> = n.children.iter().map(|c| c.clone_ref(py)).collect();
+                drop(n);
+                assign_chunks(py, &children, table, seen);
+                None
+            }
+        };
+        if let Some(markup) = chunk {
+            let index = *seen.entry(markup).or_insert_with_key(|markup| {
+                table.push(markup.clone());
+                table.len() - 1
+            });
+            node.borrow_mut(py).static_chunk = Some(index);
+        }
+    }
+}
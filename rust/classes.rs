@@ -0,0 +1,190 @@
+use crate::ParsedNode;
+use crate::project::ProjectIndex;
+use pyo3::prelude::*;
+use std::collections::BTreeSet;
+
+/// A class value that depends on an expression.
+///
+/// `attribute` is `class` for `class={...}` / interpolated class strings, or
+/// the full directive name for `class:name={cond}`. `literals` are the class
+/// tokens found in string literals inside the expression.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ClassExpression {
+    #[pyo3(get)]
+    pub file: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub attribute: String,
+    #[pyo3(get)]
+    pub expression: String,
+    #[pyo3(get)]
+    pub literals: Vec<String>,
+}
+
+#[pymethods]
+impl ClassExpression {
+    fn __repr__(&self) -> String {
+        format!(
+            "ClassExpression({}:{} {}={{{}}})",
+            self.file, self.line, self.attribute, self.expression
+        )
+    }
+}
+
+/// Result of `extract_class_tokens`.
+///
+/// `tokens` is the sorted set of every class a purger must keep: static
+/// tokens, `class:` directive names and tokens in string literals of class
+/// expressions.
+#[pyclass(frozen)]
+pub struct ClassTokens {
+    #[pyo3(get)]
+    pub tokens: Vec<String>,
+    #[pyo3(get)]
+    pub expressions: Vec<ClassExpression>,
+}
+
+#[pymethods]
+impl ClassTokens {
+    fn __len__(&self) -> usize {
+        self.tokens.len()
+    }
+
+    fn __contains__(&self, token: &str) -> bool {
+        self.tokens
+            .binary_search_by(|t| t.as_str().cmp(token))
+            .is_ok()
+    }
+}
+
+// Contents of the string literals in a Python expression.
+fn string_literals(expr: &str) -> Vec<&str> {
+    let bytes = expr.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let q = bytes[i];
+        if q != b'"' && q != b'\'' {
+            i += 1;
+            continue;
+        }
+        let start = i + 1;
+        i = start;
+        while i < bytes.len() && bytes[i] != q {
+            i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        out.push(&expr[start..i.min(bytes.len())]);
+        i += 1;
+    }
+    out
+}
+
+// Splits `a {x} b` into static text and `{...}` expressions.
+fn split_interpolated(value: &str) -> (String, Vec<&str>) {
+    let mut text = String::new();
+    let mut exprs = Vec::new();
+    let mut rest = value;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        text.push(' ');
+        let mut depth = 0;
+        let mut close = rest.len();
+        for (i, c) in rest[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = open + i;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        exprs.push(rest[open + 1..close].trim());
+        rest = &rest[(close + 1).min(rest.len())..];
+    }
+    text.push_str(rest);
+    (text, exprs)
+}
+
+struct Collector<'a> {
+    file: &'a str,
+    tokens: BTreeSet<String>,
+    expressions: Vec<ClassExpression>,
+}
+
+impl Collector<'_> {
+    fn expression(&mut self, node: &ParsedNode, attribute: &str, expr: &str) {
+        let literals: Vec<String> = string_literals(expr)
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .map(String::from)
+            .collect();
+        self.tokens.extend(literals.iter().cloned());
+        self.expressions.push(ClassExpression {
+            file: self.file.to_string(),
+            line: node.line,
+            column: node.column,
+            attribute: attribute.to_string(),
+            expression: expr.to_string(),
+            literals,
+        });
+    }
+
+    fn visit(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) {
+        for node in nodes {
+            let node = node.borrow(py);
+            let mut names: Vec<&String> = node.attributes.keys().collect();
+            names.sort();
+            for name in names {
+                let value = node.attributes[name].as_deref().unwrap_or("");
+                if name == "class" {
+                    let (text, exprs) = split_interpolated(value);
+                    self.tokens
+                        .extend(text.split_whitespace().map(String::from));
+                    for expr in exprs {
+                        self.expression(&node, name, expr);
+                    }
+                } else if let Some(token) = name.strip_prefix("class:") {
+                    self.tokens.insert(token.to_string());
+                    let expr = value
+                        .strip_prefix('{')
+                        .and_then(|v| v.strip_suffix('}'))
+                        .unwrap_or(token);
+                    self.expression(&node, name, expr.trim());
+                }
+            }
+            self.visit(py, &node.children);
+        }
+    }
+}
+
+/// Every class token used across the project's templates, plus the location
+/// of each expression-valued class so tooling can inspect what it cannot
+/// enumerate statically.
+#[pyfunction]
+pub fn extract_class_tokens(py: Python<'_>, project_index: PyRef<'_, ProjectIndex>) -> ClassTokens {
+    let _span = tracing::info_span!("analyze", pass = "class_tokens").entered();
+    let mut tokens = BTreeSet::new();
+    let mut expressions = Vec::new();
+    for (file, doc) in &project_index.files {
+        let mut collector = Collector {
+            file,
+            tokens: BTreeSet::new(),
+            expressions: Vec::new(),
+        };
+        collector.visit(py, &doc.borrow(py).template);
+        tokens.append(&mut collector.tokens);
+        expressions.append(&mut collector.expressions);
+    }
+    ClassTokens {
+        tokens: tokens.into_iter().collect(),
+        expressions,
+    }
+}
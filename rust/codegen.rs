@@ -0,0 +1,516 @@
+// Template compilation to Python source. Walks the resolved parse tree and
+// emits the `_render_template` coroutine the Python compiler generates:
+// markup is appended to `parts` as string literals, interpolations go
+// through `escape_html(unwrap_wire(...))`, dynamic attributes through
+// `render_attrs`, and blocks become the matching Python statements (`async
+// for` over `ensure_async_iterator`, with a flag for `{$else}`).
+//
+// Names an expression reads resolve like in the Python compiler, in its
+// order: block bindings and frontmatter imports stay as written, names the
+// frontmatter defines become `self.name` (wires unwrapped), then builtins
+// stay as written and anything else becomes `self.name`. Constructs that need the
+// page class (components, slots, event handlers, bindings, spreads,
+// `class:`/`style:`, `{$await}`, attribute directives) and f-strings are
+// not compiled yet; `compile` raises NotImplementedError for them so
+// callers can fall back to the Python compiler.
+
+use crate::attrs::unbrace;
+use crate::frontmatter::module_names;
+use crate::html::is_void;
+use crate::kinds::AttrKind;
+use crate::prerender::decode_attr;
+use crate::wires::{
+    BUILTINS, block_bindings, declared_wires, expression_reads, offset_in, split_loop_key,
+};
+use crate::{ParseOptions, ParsedDocument, ParsedNode, columns, parse_with};
+use pyo3::exceptions::PyNotImplementedError;
+use pyo3::prelude::*;
+
+const INDENT: &str = "    ";
+
+// Page attributes the Python compiler treats as defined on every page.
+const PAGE_NAMES: &[&str] = &[
+    "path",
+    "params",
+    "query",
+    "url",
+    "request",
+    "error_code",
+    "error_detail",
+    "error_trace",
+];
+
+/// Python source of a template's `_render_template` coroutine. `line_map`
+/// pairs generated lines with the template lines they came from, both
+/// 1-based, in generated order.
+#[pyclass(frozen)]
+pub struct CompiledTemplate {
+    #[pyo3(get)]
+    pub code: String,
+    #[pyo3(get)]
+    pub line_map: Vec<(usize, usize)>,
+}
+
+#[pymethods]
+impl CompiledTemplate {
+    /// Template line of generated line `line`: the mapping of the closest
+    /// mapped line at or before it, None before the first.
+    fn template_line(&self, line: usize) -> Option<usize> {
+        self.line_map
+            .iter()
+            .take_while(|(generated, _)| *generated <= line)
+            .last()
+            .map(|&(_, template)| template)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CompiledTemplate(lines={}, mapped={})",
+            self.code.lines().count(),
+            self.line_map.len()
+        )
+    }
+}
+
+// Python string literal for `text`.
+fn literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('\'');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\x7f' => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+// Whether `src` has an f-string literal, which `compile` leaves to the
+// Python compiler.
+fn has_fstring(src: &str) -> bool {
+    let bytes = src.as_bytes();
+    bytes.iter().enumerate().any(|(i, &b)| {
+        if b != b'"' && b != b'\'' {
+            return false;
+        }
+        let prefix = src[..i]
+            .rsplit(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or("");
+        matches!(prefix.to_ascii_lowercase().as_str(), "f" | "rf" | "fr")
+    })
+}
+
+// Names bound by top-level imports of the frontmatter.
+fn imported_names(python_code: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in python_code.lines() {
+        let aliases = if let Some(rest) = line.strip_prefix("import ") {
+            rest
+        } else if let Some((_, rest)) = line
+            .strip_prefix("from ")
+            .and_then(|rest| rest.split_once(" import "))
+        {
+            rest
+        } else {
+            continue;
+        };
+        for part in aliases.trim_matches(['(', ')', ' ']).split(',') {
+            let part = part.trim();
+            let name = part.rsplit_once(" as ").map_or(part, |(_, alias)| alias);
+            let name = name.split('.').next().unwrap_or("").trim();
+            if !name.is_empty() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+struct Compiler<'a> {
+    imports: Vec<String>,
+    // Names the frontmatter defines, read as page attributes.
+    globals: Vec<String>,
+    wires: Vec<&'a str>,
+    // Names bound by the enclosing blocks.
+    locals: Vec<String>,
+    lines: Vec<String>,
+    line_map: Vec<(usize, usize)>,
+    depth: usize,
+}
+
+impl Compiler<'_> {
+    fn emit(&mut self, line: String, template_line: Option<usize>) {
+        self.lines
+            .push(format!("{}{}", INDENT.repeat(self.depth), line));
+        if let Some(template_line) = template_line {
+            self.line_map.push((self.lines.len(), template_line));
+        }
+    }
+
+    fn append(&mut self, value: String, at: usize) {
+        self.emit(format!("parts.append({})", value), Some(at));
+    }
+
+    // Indented body of a compound statement; `pass` if it emits nothing.
+    fn body(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) -> PyResult<()> {
+        self.depth += 1;
+        let before = self.lines.len();
+        self.nodes(py, nodes)?;
+        if self.lines.len() == before {
+            self.emit("pass".to_string(), None);
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    // `expr` with the names it reads resolved against the page.
+    fn rewrite(&self, expr: &str, at: (usize, usize)) -> PyResult<String> {
+        let expr = expr.trim();
+        if has_fstring(expr) {
+            return Err(unsupported("f-string", at));
+        }
+        let mut out = String::with_capacity(expr.len());
+        let mut last = 0;
+        let mut walrus: Vec<&str> = Vec::new();
+        for name in expression_reads(expr) {
+            let start = offset_in(expr, name);
+            let end = start + name.len();
+            if expr[end..].trim_start().starts_with(":=") {
+                walrus.push(name);
+                continue;
+            }
+            let kept = walrus.contains(&name)
+                || self.locals.iter().any(|l| l == name)
+                || matches!(name, "json" | "escape_html")
+                || self.imports.iter().any(|i| i == name);
+            let global = self.globals.iter().any(|g| g == name) || PAGE_NAMES.contains(&name);
+            if kept || (!global && BUILTINS.contains(&name)) {
+                continue;
+            }
+            out.push_str(&expr[last..start]);
+            if self.wires.contains(&name) {
+                out.push_str(&format!("unwrap_wire(self.{})", name));
+            } else {
+                out.push_str(&format!("self.{}", name));
+            }
+            last = end;
+        }
+        out.push_str(&expr[last..]);
+        Ok(out)
+    }
+
+    fn nodes(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) -> PyResult<()> {
+        for node in nodes {
+            let node = node.borrow(py);
+            if node.is_block {
+                self.block(py, &node)?;
+            } else if node.tag.is_some() {
+                self.element(py, &node)?;
+            } else if let Some(text) = node.text_content.as_deref().filter(|t| !t.is_empty()) {
+                self.append(literal(text), node.line);
+            }
+        }
+        Ok(())
+    }
+
+    fn element(&mut self, py: Python<'_>, node: &ParsedNode) -> PyResult<()> {
+        let at = (node.line, node.column);
+        let tag = node.tag.as_deref().unwrap_or("");
+        if node.component_name.is_some() {
+            return Err(unsupported("component", at));
+        }
+        if node.is_slot {
+            return Err(unsupported("<slot>", at));
+        }
+        let mut open = format!("<{}", tag);
+        let mut dynamic = Vec::new();
+        for attr in node.attributes.parsed() {
+            let at = (attr.line, attr.column);
+            match attr.kind {
+                AttrKind::Static if attr.has_interpolation => {
+                    let mut terms = Vec::new();
+                    for segment in &attr.segments {
+                        if segment.is_expression {
+                            let expr = unbrace(&segment.text);
+                            let at = (segment.line, segment.column);
+                            terms.push(format!("str(unwrap_wire({}))", self.rewrite(&expr, at)?));
+                        } else {
+                            terms.push(literal(&decode_attr(&segment.text)));
+                        }
+                    }
+                    dynamic.push((attr.name.clone(), terms.join(" + ")));
+                }
+                AttrKind::Static | AttrKind::Boolean => match &attr.raw_value {
+                    Some(raw) => open.push_str(&format!(" {}={}", attr.name, raw)),
+                    None => open.push_str(&format!(" {}", attr.name)),
+                },
+                AttrKind::Reactive | AttrKind::Shorthand => {
+                    let expr = unbrace(attr.value.as_deref().unwrap_or(""));
+                    let mut value = format!("unwrap_wire({})", self.rewrite(&expr, at)?);
+                    if attr.name.to_ascii_lowercase().starts_with("aria-") {
+                        // ARIA states render booleans as "true"/"false".
+                        let var = format!("_r_val_{}", dynamic.len());
+                        self.emit(format!("{} = {}", var, value), Some(attr.line));
+                        value = format!(
+                            "'true' if {0} is True else 'false' if {0} is False else {0}",
+                            var
+                        );
+                    }
+                    dynamic.push((attr.name.clone(), value));
+                }
+                AttrKind::Event => return Err(unsupported("event handler", at)),
+                AttrKind::Bind => return Err(unsupported("bind:", at)),
+                AttrKind::Spread => return Err(unsupported("attribute spread", at)),
+                AttrKind::ClassToggle => return Err(unsupported("class:", at)),
+                AttrKind::StyleProperty => return Err(unsupported("style:", at)),
+                AttrKind::Directive => return Err(unsupported("attribute directive", at)),
+            }
+        }
+        if dynamic.is_empty() {
+            open.push('>');
+            self.append(literal(&open), node.line);
+        } else {
+            self.append(literal(&open), node.line);
+            let entries: Vec<String> = dynamic
+                .iter()
+                .map(|(name, value)| format!("{}: {}", literal(name), value))
+                .collect();
+            self.append(
+                format!("render_attrs({{{}}}) + '>'", entries.join(", ")),
+                node.line,
+            );
+        }
+        if is_void(tag) {
+            return Ok(());
+        }
+        self.nodes(py, &node.children)?;
+        self.append(
+            literal(&format!("</{}>", tag)),
+            node.close_line.unwrap_or(node.line),
+        );
+        Ok(())
+    }
+
+    fn block(&mut self, py: Python<'_>, node: &ParsedNode) -> PyResult<()> {
+        let at = (node.line, node.column);
+        let keyword = node.block_keyword.as_deref().unwrap_or("");
+        let expr = node.expression.as_deref().unwrap_or("");
+        // Branch markers of a resolved block are children of its opener.
+        let (body, branches) = split_branches(py, &node.children);
+        match keyword {
+            "interpolation" => {
+                let value = format!("escape_html(unwrap_wire({}))", self.rewrite(expr, at)?);
+                self.append(value, node.line);
+            }
+            "html" => {
+                let value = format!("str(unwrap_wire({}))", self.rewrite(expr, at)?);
+                self.append(value, node.line);
+            }
+            "example" => {}
+            "if" => {
+                self.emit(format!("if {}:", self.rewrite(expr, at)?), Some(node.line));
+                self.body(py, body)?;
+                for branch in branches {
+                    let branch = branch.borrow(py);
+                    let at = (branch.line, branch.column);
+                    match branch.block_keyword.as_deref() {
+                        Some("elif") => {
+                            let cond =
+                                self.rewrite(branch.expression.as_deref().unwrap_or(""), at)?;
+                            self.emit(format!("elif {}:", cond), Some(branch.line));
+                        }
+                        _ => self.emit("else:".to_string(), Some(branch.line)),
+                    }
+                    self.body(py, &branch.children)?;
+                }
+            }
+            "for" => {
+                // The key only matters to keyed DOM updates, not to rendering.
+                let expr = split_loop_key(expr).0;
+                let mut names = Vec::new();
+                let Some(from) = block_bindings(keyword, expr, &mut names) else {
+                    return Err(unsupported("loop without `in`", at));
+                };
+                let target = expr[..from - 4].trim();
+                let iterable = self.rewrite(&expr[from..], at)?;
+                let flag = format!("_loop_any_{}_{}", node.line, node.column);
+                let has_else = !branches.is_empty();
+                if has_else {
+                    self.emit(format!("{} = False", flag), None);
+                }
+                self.emit(
+                    format!(
+                        "async for {} in ensure_async_iterator({}):",
+                        target, iterable
+                    ),
+                    Some(node.line),
+                );
+                let before = self.locals.len();
+                self.locals.extend(names);
+                if has_else {
+                    self.depth += 1;
+                    self.emit(format!("{} = True", flag), None);
+                    self.depth -= 1;
+                }
+                self.body(py, body)?;
+                self.locals.truncate(before);
+                for branch in branches {
+                    let branch = branch.borrow(py);
+                    self.emit(format!("if not {}:", flag), Some(branch.line));
+                    self.body(py, &branch.children)?;
+                }
+            }
+            "try" => {
+                self.emit("try:".to_string(), Some(node.line));
+                self.body(py, body)?;
+                for branch in branches {
+                    let branch = branch.borrow(py);
+                    let at = (branch.line, branch.column);
+                    let kw = branch.block_keyword.as_deref().unwrap_or("");
+                    let before = self.locals.len();
+                    match (kw, branch.expression.as_deref()) {
+                        ("except", Some(expr)) => {
+                            let mut names = Vec::new();
+                            block_bindings(kw, expr, &mut names);
+                            let (types, alias) = match expr.rsplit_once(" as ") {
+                                Some((types, alias)) => (types, format!(" as {}", alias.trim())),
+                                None => (expr, String::new()),
+                            };
+                            let types = self.rewrite(types, at)?;
+                            self.emit(format!("except {}{}:", types, alias), Some(branch.line));
+                            self.locals.extend(names);
+                        }
+                        _ => self.emit(format!("{}:", kw), Some(branch.line)),
+                    }
+                    self.body(py, &branch.children)?;
+                    self.locals.truncate(before);
+                }
+            }
+            "match" => {
+                self.emit(
+                    format!("match {}:", self.rewrite(expr, at)?),
+                    Some(node.line),
+                );
+                self.depth += 1;
+                for branch in branches {
+                    let branch = branch.borrow(py);
+                    let at = (branch.line, branch.column);
+                    let before = self.locals.len();
+                    match (
+                        branch.block_keyword.as_deref(),
+                        branch.expression.as_deref(),
+                    ) {
+                        (Some("case"), Some(expr)) => {
+                            let mut names = Vec::new();
+                            let guard_at = block_bindings("case", expr, &mut names).unwrap_or(0);
+                            self.locals.extend(names);
+                            let line = match expr.find(" if ") {
+                                Some(i) => format!(
+                                    "case {} if {}:",
+                                    expr[..i].trim(),
+                                    self.rewrite(&expr[guard_at..], at)?
+                                ),
+                                None => format!("case {}:", expr.trim()),
+                            };
+                            self.emit(line, Some(branch.line));
+                        }
+                        _ => self.emit("case _:".to_string(), Some(branch.line)),
+                    }
+                    self.body(py, &branch.children)?;
+                    self.locals.truncate(before);
+                }
+                self.depth -= 1;
+            }
+            kw if kw.starts_with('/') => {}
+            "await" | "then" | "catch" => return Err(unsupported("{$await}", at)),
+            _ => return Err(unsupported(&format!("{{${}}}", keyword), at)),
+        }
+        Ok(())
+    }
+}
+
+// Resolved opener children: its own body, then its branch markers.
+fn split_branches<'a>(
+    py: Python<'_>,
+    children: &'a [Py<ParsedNode>],
+) -> (&'a [Py<ParsedNode>], &'a [Py<ParsedNode>]) {
+    let split = children
+        .iter()
+        .position(|c| {
+            let c = c.borrow(py);
+            c.is_block
+                && matches!(
+                    c.block_keyword.as_deref(),
+                    Some("elif" | "else" | "except" | "finally" | "case" | "default")
+                )
+        })
+        .unwrap_or(children.len());
+    children.split_at(split)
+}
+
+fn unsupported(what: &str, at: (usize, usize)) -> PyErr {
+    PyNotImplementedError::new_err(format!(
+        "{} is not supported by the Rust compiler yet (line {}, column {})",
+        what, at.0, at.1
+    ))
+}
+
+fn compile_document(py: Python<'_>, doc: &ParsedDocument) -> PyResult<CompiledTemplate> {
+    doc.raise_for_errors(py)?;
+    let mut compiler = Compiler {
+        imports: imported_names(&doc.python_code),
+        globals: module_names(&doc.python_code),
+        wires: declared_wires(doc)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect(),
+        locals: Vec::new(),
+        lines: Vec::new(),
+        line_map: Vec::new(),
+        depth: 1,
+    };
+    compiler
+        .lines
+        .push("async def _render_template(self):".to_string());
+    for line in [
+        "parts = []",
+        "import json",
+        "from pywire.core.wire import unwrap_wire",
+        "from pywire.runtime.escape import escape_html",
+        "from pywire.runtime.helpers import ensure_async_iterator, render_attrs",
+    ] {
+        compiler.emit(line.to_string(), None);
+    }
+    compiler.nodes(py, &doc.template)?;
+    compiler.emit("return ''.join(parts)".to_string(), None);
+
+    let mut code = compiler.lines.join("\n");
+    code.push('\n');
+    Ok(CompiledTemplate {
+        code,
+        line_map: compiler.line_map,
+    })
+}
+
+/// Compile a `.wire` template to the Python source of its
+/// `async def _render_template(self)` coroutine. Raises the parser's
+/// exception for syntax errors and NotImplementedError for constructs only
+/// the Python compiler handles yet (components, slots, event handlers,
+/// bindings, spreads, `class:`/`style:`, `{$await}`, f-strings).
+#[pyfunction]
+#[pyo3(name = "compile")]
+pub fn compile_template(py: Python<'_>, source: &str) -> PyResult<CompiledTemplate> {
+    let options = ParseOptions {
+        resolved: true,
+        tab_width: columns::DEFAULT_TAB_WIDTH,
+        ..Default::default()
+    };
+    compile_document(py, &parse_with(py, source, &options)?)
+}
@@ -0,0 +1,416 @@
+// Multi-file codemods. Each file is parsed, a transform collects byte-range
+// edits against the tree, and the edits are spliced into the original text,
+// so everything a transform does not touch is kept byte for byte.
+
+use crate::formatting::unified_diff;
+use crate::migrate::{DEPRECATIONS, Deprecation};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+#[cfg(all(feature = "parallel", not(target_family = "wasm")))]
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tree_sitter::Node;
+
+const TRANSFORMS: &[&str] = &[
+    "rename-component",
+    "convert-directives",
+    "add-loop-keys",
+    "migrate",
+];
+
+pub(crate) struct Edit {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) text: String,
+}
+
+impl Edit {
+    pub(crate) fn insert(at: usize, text: String) -> Self {
+        Edit {
+            start: at,
+            end: at,
+            text,
+        }
+    }
+}
+
+enum Transform {
+    // `<From ...>...</From>` -> `<To ...>...</To>`
+    RenameComponent { from: String, to: String },
+    ConvertDirectives,
+    // `{$for x in xs}` -> `{$for x in xs, key=x}`; `key` is a template
+    // where `{}` stands for the first loop variable.
+    AddLoopKeys { key: String },
+    // Pre-0.2 syntax to the current grammar, one rule per deprecation.
+    Migrate { rules: Vec<&'static Deprecation> },
+}
+
+pub(crate) fn is_tag(node: Node) -> bool {
+    matches!(node.kind(), "tag" | "self_closing_tag" | "void_tag")
+}
+
+// Start offset and text of a tag's name: `<ui.Card a="b">` -> `ui.Card`.
+pub(crate) fn tag_name<'s>(source: &'s str, node: Node) -> (usize, &'s str) {
+    let start = node.start_byte() + 1;
+    let rest = source.get(start..node.end_byte()).unwrap_or("");
+    let len = rest
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(rest.len());
+    (start, &rest[..len])
+}
+
+// Start offset and text of the name in a tag's `</name>`, if it has one.
+pub(crate) fn end_tag_name<'s>(source: &'s str, node: Node) -> Option<(usize, &'s str)> {
+    if node.kind() != "tag" {
+        return None;
+    }
+    let text = &source[node.start_byte()..node.end_byte()];
+    let start = node.start_byte() + text.rfind("</")? + 2;
+    let rest = &source[start..node.end_byte()];
+    Some((start, rest[..rest.find('>')?].trim_end()))
+}
+
+pub(crate) fn braced(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let inner = value.strip_prefix('{')?.strip_suffix('}')?;
+    Some(inner.trim())
+}
+
+fn first_loop_var(expr: &str) -> &str {
+    let targets = expr.split(" in ").next().unwrap_or(expr);
+    targets
+        .trim()
+        .trim_start_matches('(')
+        .split(',')
+        .next()
+        .unwrap_or("")
+        .trim()
+}
+
+// `$name={value}` attributes of a tag, with the range to delete for each
+// (including the whitespace before it).
+pub(crate) fn directive_attributes<'s>(
+    source: &'s str,
+    node: Node,
+) -> Vec<(&'s str, &'s str, usize, usize)> {
+    let mut out = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() != "attribute" {
+            continue;
+        }
+        let (Some(name), Some(value)) = (
+            child.child_by_field_name("name"),
+            child.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+        let name = &source[name.start_byte()..name.end_byte()];
+        let Some(value) = braced(&source[value.start_byte()..value.end_byte()]) else {
+            continue;
+        };
+        let start = source[..child.start_byte()].trim_end().len();
+        out.push((name, value, start, child.end_byte()));
+    }
+    out
+}
+
+// `<li $for={x in xs} $key={x.id}>...</li>` -> `{$for x in xs, key=x.id}<li>...</li>{/for}`,
+// and likewise `$if={cond}`. `<template>` elements are left alone.
+pub(crate) fn convert_directives(source: &str, node: Node, out: &mut Vec<Edit>) {
+    if !is_tag(node) || tag_name(source, node).1 == "template" {
+        return;
+    }
+    let attrs = directive_attributes(source, node);
+    let find = |wanted: &str| attrs.iter().find(|(name, ..)| *name == wanted);
+    let mut open = String::new();
+    let mut close = String::new();
+    if let Some((_, expr, ..)) = find("$for") {
+        open.push_str(&format!("{{$for {}", expr));
+        if let Some((_, key, ..)) = find("$key") {
+            open.push_str(&format!(", key={}", key));
+        }
+        open.push('}');
+        close.insert_str(0, "{/for}");
+    }
+    if let Some((_, cond, ..)) = find("$if") {
+        open.push_str(&format!("{{$if {}}}", cond));
+        close.insert_str(0, "{/if}");
+    }
+    if open.is_empty() {
+        return;
+    }
+    out.push(Edit::insert(node.start_byte(), open));
+    for (name, _, start, end) in &attrs {
+        let key = *name == "$key" && find("$for").is_some();
+        if key || matches!(*name, "$for" | "$if") {
+            out.push(Edit {
+                start: *start,
+                end: *end,
+                text: String::new(),
+            });
+        }
+    }
+    out.push(Edit::insert(node.end_byte(), close));
+}
+
+impl Transform {
+    fn from_name(name: &str, options: &HashMap<String, String>) -> PyResult<Self> {
+        let option = |key: &str| {
+            options.get(key).cloned().ok_or_else(|| {
+                PyValueError::new_err(format!("Transform '{}' requires option '{}'", name, key))
+            })
+        };
+        Ok(match name {
+            "rename-component" => Self::RenameComponent {
+                from: option("from")?,
+                to: option("to")?,
+            },
+            "convert-directives" => Self::ConvertDirectives,
+            "add-loop-keys" => Self::AddLoopKeys {
+                key: options.get("key").cloned().unwrap_or_else(|| "{}".into()),
+            },
+            "migrate" => Self::Migrate {
+                rules: match options.get("rules") {
+                    None => DEPRECATIONS.iter().collect(),
+                    Some(codes) => codes
+                        .split(',')
+                        .map(|code| {
+                            let code = code.trim();
+                            DEPRECATIONS.iter().find(|d| d.code == code).ok_or_else(|| {
+                                PyValueError::new_err(format!("Unknown deprecation '{}'", code))
+                            })
+                        })
+                        .collect::<PyResult<_>>()?,
+                },
+            },
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown transform '{}'; expected one of: {}",
+                    name,
+                    TRANSFORMS.join(", ")
+                )));
+            }
+        })
+    }
+
+    fn collect(&self, source: &str, node: Node, out: &mut Vec<Edit>) {
+        let is_tag = is_tag(node);
+        match self {
+            Self::RenameComponent { from, to } if is_tag => {
+                for (start, name) in [Some(tag_name(source, node)), end_tag_name(source, node)]
+                    .into_iter()
+                    .flatten()
+                {
+                    if name == from {
+                        out.push(Edit {
+                            start,
+                            end: start + name.len(),
+                            text: to.clone(),
+                        });
+                    }
+                }
+            }
+            Self::ConvertDirectives => convert_directives(source, node, out),
+            Self::Migrate { rules } => {
+                for rule in rules {
+                    (rule.apply)(source, node, out);
+                }
+            }
+            Self::AddLoopKeys { key } if node.kind() == "brace_block" => {
+                let text = &source[node.start_byte()..node.end_byte()];
+                let inner = text.trim_start_matches("{$").trim_end_matches('}');
+                if let Some(expr) = inner.strip_prefix("for ")
+                    && expr.contains(" in ")
+                    && !expr.contains("key=")
+                    && text.ends_with('}')
+                {
+                    let var = first_loop_var(expr);
+                    out.push(Edit::insert(
+                        node.end_byte() - 1,
+                        format!(", key={}", key.replace("{}", var)),
+                    ));
+                }
+            }
+            Self::AddLoopKeys { key } if is_tag => {
+                let attrs = directive_attributes(source, node);
+                if let Some((_, expr, _, end)) = attrs.iter().find(|(n, ..)| *n == "$for")
+                    && !attrs.iter().any(|(n, ..)| *n == "$key")
+                {
+                    let var = first_loop_var(expr);
+                    out.push(Edit::insert(
+                        *end,
+                        format!(" $key={{{}}}", key.replace("{}", var)),
+                    ));
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.collect(source, child, out);
+        }
+    }
+
+    // Transformed `source`, the number of edits applied and the number
+    // skipped because they overlap an earlier edit.
+    fn apply(&self, source: &str) -> PyResult<(String, usize, usize)> {
+        let tree = crate::parse_tree(source)?;
+        let mut edits = Vec::new();
+        self.collect(source, tree.root_node(), &mut edits);
+        // Stable: insertions at one offset keep the order they were pushed.
+        edits.sort_by_key(|e| e.start);
+        let mut out = String::with_capacity(source.len());
+        let mut last = 0;
+        let (mut applied, mut skipped) = (0, 0);
+        for edit in edits {
+            if edit.start < last {
+                skipped += 1;
+                continue;
+            }
+            out.push_str(&source[last..edit.start]);
+            out.push_str(&edit.text);
+            last = edit.end;
+            applied += 1;
+        }
+        out.push_str(&source[last..]);
+        Ok((out, applied, skipped))
+    }
+}
+
+/// Outcome of a codemod for one file. `edits` is None for script
+/// transforms; `skipped` counts edits left out because they overlap an
+/// earlier one (running the codemod again applies them to the result);
+/// `error` is set when the file could not be read, parsed, transformed or
+/// written, in which case it is left untouched.
+#[pyclass(frozen)]
+pub struct CodemodResult {
+    #[pyo3(get)]
+    pub path: PathBuf,
+    #[pyo3(get)]
+    pub changed: bool,
+    #[pyo3(get)]
+    pub edits: Option<usize>,
+    #[pyo3(get)]
+    pub skipped: usize,
+    #[pyo3(get)]
+    pub diff: String,
+    #[pyo3(get)]
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl CodemodResult {
+    fn __repr__(&self) -> String {
+        match &self.error {
+            Some(e) => format!("CodemodResult({:?}, error={:?})", self.path, e),
+            None if self.skipped > 0 => format!(
+                "CodemodResult({:?}, changed={}, skipped={})",
+                self.path, self.changed, self.skipped
+            ),
+            None => format!("CodemodResult({:?}, changed={})", self.path, self.changed),
+        }
+    }
+}
+
+fn finish(
+    path: PathBuf,
+    old: &str,
+    new: PyResult<(String, Option<usize>, usize)>,
+    write: bool,
+) -> CodemodResult {
+    let label = path.to_string_lossy().into_owned();
+    let written = new.and_then(|(new, edits, skipped)| {
+        let changed = new != old;
+        if changed && write {
+            std::fs::write(&path, &new)?;
+        }
+        Ok((new, edits, skipped, changed))
+    });
+    match written {
+        Ok((new, edits, skipped, changed)) => CodemodResult {
+            diff: unified_diff(old, &new, &label),
+            path,
+            changed,
+            edits,
+            skipped,
+            error: None,
+        },
+        Err(e) => CodemodResult {
+            path,
+            changed: false,
+            edits: None,
+            skipped: 0,
+            diff: String::new(),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn run_file(transform: &Transform, path: &PathBuf, write: bool) -> CodemodResult {
+    let old = match std::fs::read_to_string(path) {
+        Ok(old) => old,
+        Err(e) => return finish(path.clone(), "", Err(e.into()), write),
+    };
+    let new = transform
+        .apply(&old)
+        .map(|(new, edits, skipped)| (new, Some(edits), skipped));
+    finish(path.clone(), &old, new, write)
+}
+
+/// Apply a transform to many `.wire` files and report per-file results, in
+/// the order of `paths`.
+///
+/// `transform` is a registered transform name or a Python callable
+/// `(path, source) -> str | None` returning the new source (None keeps the
+/// file). Registered transforms edit only the spans they change and run in
+/// parallel with the GIL released:
+///   - "rename-component": options `from`, `to`
+///   - "convert-directives": `$for`/`$key`/`$if` attributes to `{$for}`/`{$if}`
+///     blocks (`<template>` elements are left alone)
+///   - "add-loop-keys": add `key=` to keyless loops; option `key` is the key
+///     expression with `{}` for the loop variable (default `{}`)
+///   - "migrate": rewrite pre-0.2 syntax listed by `deprecations()`; option
+///     `rules` is a comma-separated subset of deprecation codes
+///
+/// With `write=False` nothing is written and the diffs are a preview.
+#[pyfunction]
+#[pyo3(signature = (paths, transform, options=None, write=true))]
+pub fn codemod(
+    py: Python<'_>,
+    paths: Vec<PathBuf>,
+    transform: &Bound<'_, PyAny>,
+    options: Option<HashMap<String, String>>,
+    write: bool,
+) -> PyResult<Vec<CodemodResult>> {
+    if let Ok(name) = transform.extract::<String>() {
+        let transform = Transform::from_name(&name, &options.unwrap_or_default())?;
+        return Ok(py.detach(|| {
+            #[cfg(all(feature = "parallel", not(target_family = "wasm")))]
+            let iter = paths.par_iter();
+            #[cfg(not(all(feature = "parallel", not(target_family = "wasm"))))]
+            let iter = paths.iter();
+            iter.map(|path| run_file(&transform, path, write)).collect()
+        }));
+    }
+    if !transform.is_callable() {
+        return Err(PyValueError::new_err(
+            "transform must be a registered transform name or a callable",
+        ));
+    }
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let old = match std::fs::read_to_string(&path) {
+                Ok(old) => old,
+                Err(e) => return finish(path, "", Err(e.into()), write),
+            };
+            let new = transform
+                .call1((&path, &old))
+                .and_then(|out| out.extract::<Option<String>>())
+                .map(|out| (out.unwrap_or_else(|| old.clone()), None, 0));
+            finish(path, &old, new, write)
+        })
+        .collect())
+}
@@ -0,0 +1,93 @@
+// Display columns: `column` fields are byte offsets into the line, which is
+// what tree-sitter reports; editors place carets by expanding tabs instead.
+
+use crate::diagnostics::Diagnostic;
+use crate::{ParsedDirective, ParsedNode};
+use pyo3::prelude::*;
+
+pub(crate) const DEFAULT_TAB_WIDTH: usize = 4;
+
+pub(crate) struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+    tab_width: usize,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(source: &'a str, tab_width: usize) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex {
+            source,
+            starts,
+            // 0 means "unset", which internal callers get from Default.
+            tab_width: if tab_width == 0 {
+                DEFAULT_TAB_WIDTH
+            } else {
+                tab_width
+            },
+        }
+    }
+
+    // Byte offset of `column` on 1-based `line`, clamped to the source.
+    pub(crate) fn offset(&self, line: usize, column: usize) -> usize {
+        let start = self
+            .starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(self.source.len());
+        (start + column).min(self.source.len())
+    }
+
+    // 1-based line and byte column of `offset`.
+    pub(crate) fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&start| start <= offset);
+        (line, offset - self.starts[line - 1])
+    }
+
+    // Column of byte `column` on 1-based `line` with tabs advancing to the
+    // next tab stop and every other character counting as one.
+    pub(crate) fn display_column(&self, line: usize, column: usize) -> usize {
+        let Some(&start) = self.starts.get(line.saturating_sub(1)) else {
+            return column;
+        };
+        let end = (start + column).min(self.source.len());
+        let prefix = self.source.get(start..end).unwrap_or("");
+        prefix.chars().fold(0, |col, c| {
+            if c == '\t' {
+                (col / self.tab_width + 1) * self.tab_width
+            } else {
+                col + 1
+            }
+        })
+    }
+}
+
+pub(crate) fn assign_display_columns(
+    py: Python<'_>,
+    index: &LineIndex<'_>,
+    directives: &mut [ParsedDirective],
+    nodes: &[Py<ParsedNode>],
+    diagnostics: &mut [Diagnostic],
+) {
+    for d in directives {
+        d.display_column = index.display_column(d.line, d.column);
+    }
+    for d in diagnostics {
+        d.display_column = index.display_column(d.line, d.column);
+        if d.start_byte.is_none() {
+            d.start_byte = Some(index.offset(d.line, d.column));
+            d.end_byte = Some(index.offset(d.end_line, d.end_column));
+        }
+    }
+    assign_nodes(py, index, nodes);
+}
+
+fn assign_nodes(py: Python<'_>, index: &LineIndex<'_>, nodes: &[Py<ParsedNode>]) {
+    for node in nodes {
+        let mut node = node.borrow_mut(py);
+        node.display_column = index.display_column(node.line, node.column);
+        assign_nodes(py, index, &node.children);
+    }
+}
@@ -0,0 +1,37 @@
+// Server-only template comments, `{# ... #}`. They are for template authors
+// (notes, pragmas, component docs) and never reach the AST or served HTML.
+// Pragmas and docs read them from the source text instead.
+
+use std::borrow::Cow;
+use tree_sitter::Node;
+
+// Whether `node` is a whole `{# ... #}` comment: a `template_comment` node,
+// or an interpolation (or ERROR node) whose text is fenced by `#`.
+pub(crate) fn is_template_comment(source: &str, node: Node) -> bool {
+    match node.kind() {
+        "template_comment" => true,
+        "interpolation" | "ERROR" => {
+            let text = &source[node.start_byte()..node.end_byte()];
+            text.len() >= 4 && text.starts_with("{#") && text.ends_with("#}")
+        }
+        _ => false,
+    }
+}
+
+// `text` with any complete `{# ... #}` comments removed.
+pub(crate) fn strip(text: &str) -> Cow<'_, str> {
+    if !text.contains("{#") {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{#") {
+        let Some(len) = rest[start + 2..].find("#}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
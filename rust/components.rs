@@ -0,0 +1,101 @@
+// Component invocations: elements whose tag names a component class
+// (`<Card>`, `<ui.Card>`) rather than an HTML element, and the slots that
+// compose them.
+
+use crate::attrs::Attributes;
+use crate::kinds::AttrKind;
+use pyo3::prelude::*;
+
+/// One prop passed to a component invocation. `value` is the literal for
+/// STATIC props, the expression for REACTIVE, SHORTHAND and SPREAD props
+/// (SPREAD props are named `**`), and None for BOOLEAN ones.
+#[pyclass(frozen)]
+pub struct ComponentProp {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub kind: AttrKind,
+    #[pyo3(get)]
+    pub value: Option<String>,
+}
+
+#[pymethods]
+impl ComponentProp {
+    fn __repr__(&self) -> String {
+        match &self.value {
+            Some(v) => format!("ComponentProp({}={:?})", self.name, v),
+            None => format!("ComponentProp({})", self.name),
+        }
+    }
+}
+
+// `Card`, `ui.Card`: the last dotted segment starts with an uppercase letter.
+pub(crate) fn component_name(tag: &str) -> Option<String> {
+    let last = tag.rsplit('.').next()?;
+    last.starts_with(|c: char| c.is_ascii_uppercase())
+        .then(|| tag.to_string())
+}
+
+// Slot a `<slot>` defines, or the slot an element fills via `slot="..."`.
+// Only static names count; `slot={expr}` is resolved at render time.
+pub(crate) fn slot_name(is_slot: bool, attributes: &Attributes) -> Option<String> {
+    let attr = if is_slot { "name" } else { "slot" };
+    let name = attributes
+        .get(attr)
+        .and_then(|v| v.as_deref())
+        .filter(|v| AttrKind::classify(attr, Some(v)) == AttrKind::Static)
+        .map(str::to_string);
+    match name {
+        None if is_slot => Some("default".to_string()),
+        name => name,
+    }
+}
+
+// `open` or `closed` for a declarative shadow root
+// (`<template shadowrootmode="open">`). Other values, and an expression,
+// leave the `<template>` an ordinary one.
+pub(crate) fn shadow_root_mode(tag: Option<&str>, attributes: &Attributes) -> Option<String> {
+    if !tag.is_some_and(|t| t.eq_ignore_ascii_case("template")) {
+        return None;
+    }
+    let mode = attributes.get("shadowrootmode")?.as_deref()?;
+    let mode = mode.trim().to_ascii_lowercase();
+    matches!(mode.as_str(), "open" | "closed").then_some(mode)
+}
+
+fn braced(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('{')
+        .and_then(|v| v.strip_suffix('}'))
+        .unwrap_or(value)
+        .trim()
+}
+
+// Props of a component invocation, sorted by name. Events, bindings,
+// `class:`/`style:` attributes and `$` directives are handled by the
+// compiler and are not props.
+pub(crate) fn props(attributes: &Attributes) -> Vec<ComponentProp> {
+    let mut out: Vec<ComponentProp> = attributes
+        .parsed()
+        .iter()
+        .filter_map(|attr| {
+            let value = match attr.kind {
+                AttrKind::Event
+                | AttrKind::Bind
+                | AttrKind::ClassToggle
+                | AttrKind::StyleProperty
+                | AttrKind::Directive => return None,
+                AttrKind::Reactive => attr.value.as_deref().map(braced).map(str::to_string),
+                _ => attr.value.clone(),
+            };
+            Some(ComponentProp {
+                name: attr.name.clone(),
+                kind: attr.kind,
+                value,
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
@@ -0,0 +1,190 @@
+use crate::serializer::HtmlSerializer;
+use brotli::enc::{BrotliEncoderParams, StandardAlloc};
+use brotli::interface::{InputPair, PredictionModeContextMap, StaticCommand};
+use brotli::{InputReferenceMut, IoReaderWrapper, IoWriterWrapper};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use std::io::{Error, ErrorKind, Read, Write};
+
+const BROTLI_BUFFER_SIZE: usize = 16 * 1024;
+
+// Header of a dictionary-compressed Brotli (`dcb`) body: this magic, then
+// the SHA-256 of the dictionary (RFC 9842).
+const DCB_MAGIC: [u8; 4] = [0xff, 0x44, 0x43, 0x42];
+
+enum Encoding {
+    Gzip,
+    Brotli,
+}
+
+/// Compresses rendered responses without handing the uncompressed body to
+/// Python first.
+///
+/// A Compressor is meant to live for the whole process and can be shared
+/// between threads: the Brotli dictionary is set up once, and each call
+/// uses its own I/O buffers.
+#[pyclass(frozen)]
+pub struct Compressor {
+    encoding: Encoding,
+    level: u32,
+    dictionary: Vec<u8>,
+    // SHA-256 of `dictionary`, when there is one.
+    dictionary_hash: Option<[u8; 32]>,
+}
+
+impl Compressor {
+    fn compress_bytes(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self.encoding {
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(
+                    Vec::with_capacity(data.len() / 2),
+                    Compression::new(self.level),
+                );
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Encoding::Brotli => {
+                let params = BrotliEncoderParams {
+                    quality: self.level as i32,
+                    size_hint: data.len(),
+                    ..BrotliEncoderParams::default()
+                };
+                let mut out = Vec::with_capacity(data.len() / 2);
+                if let Some(hash) = &self.dictionary_hash {
+                    out.extend_from_slice(&DCB_MAGIC);
+                    out.extend_from_slice(hash);
+                }
+                let mut input = data;
+                brotli::BrotliCompressCustomIoCustomDict(
+                    &mut IoReaderWrapper(&mut input),
+                    &mut IoWriterWrapper(&mut out),
+                    &mut vec![0; BROTLI_BUFFER_SIZE],
+                    &mut vec![0; BROTLI_BUFFER_SIZE],
+                    &params,
+                    StandardAlloc::default(),
+                    &mut |_: &mut PredictionModeContextMap<InputReferenceMut>,
+                          _: &mut [StaticCommand],
+                          _: InputPair,
+                          _: &mut StandardAlloc| {},
+                    &self.dictionary,
+                    Error::new(ErrorKind::UnexpectedEof, "Unexpected EOF"),
+                )?;
+                Ok(out)
+            }
+        }
+    }
+
+    fn decompress_bytes(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(data.len() * 4);
+        match self.encoding {
+            Encoding::Gzip => {
+                GzDecoder::new(data).read_to_end(&mut out)?;
+            }
+            Encoding::Brotli => {
+                let mut body = data;
+                if let Some(hash) = &self.dictionary_hash {
+                    let header = [&DCB_MAGIC[..], hash].concat();
+                    body = data.strip_prefix(header.as_slice()).ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            "not compressed with this dictionary",
+                        )
+                    })?;
+                }
+                brotli::Decompressor::new_with_custom_dict(
+                    body,
+                    BROTLI_BUFFER_SIZE,
+                    self.dictionary.clone().into(),
+                )
+                .read_to_end(&mut out)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[pymethods]
+impl Compressor {
+    /// `encoding` is "gzip" or "br". `dictionary` (Brotli only) is a shared
+    /// dictionary the client already holds, e.g. a previous version of the
+    /// page shell; output is then dictionary-compressed Brotli (`dcb`), which
+    /// starts with the dictionary's SHA-256 as browsers require.
+    #[new]
+    #[pyo3(signature = (encoding="br", level=None, dictionary=None))]
+    fn new(encoding: &str, level: Option<u32>, dictionary: Option<Vec<u8>>) -> PyResult<Self> {
+        let (encoding, level, max_level) = match encoding {
+            "gzip" => (Encoding::Gzip, level.unwrap_or(6), 9),
+            "br" => (Encoding::Brotli, level.unwrap_or(5), 11),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported encoding '{}' (expected 'gzip' or 'br')",
+                    other
+                )));
+            }
+        };
+        if level > max_level {
+            return Err(PyValueError::new_err(format!(
+                "Compression level {} out of range (0-{})",
+                level, max_level
+            )));
+        }
+        if dictionary.is_some() && matches!(encoding, Encoding::Gzip) {
+            return Err(PyValueError::new_err(
+                "Shared dictionaries are only supported for 'br'",
+            ));
+        }
+        let dictionary = dictionary.unwrap_or_default();
+        Ok(Compressor {
+            encoding,
+            level,
+            dictionary_hash: (!dictionary.is_empty()).then(|| Sha256::digest(&dictionary).into()),
+            dictionary,
+        })
+    }
+
+    /// Value for the Content-Encoding header: "gzip", "br", or "dcb" with a
+    /// dictionary.
+    #[getter]
+    fn encoding(&self) -> &'static str {
+        match self.encoding {
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli if self.dictionary_hash.is_some() => "dcb",
+            Encoding::Brotli => "br",
+        }
+    }
+
+    fn compress<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+        let out = py
+            .detach(|| self.compress_bytes(data))
+            .map_err(|e| PyRuntimeError::new_err(format!("Compression failed: {}", e)))?;
+        Ok(PyBytes::new(py, &out))
+    }
+
+    /// Inverse of `compress`, e.g. to check a response in tests. Raises
+    /// ValueError for data that is corrupt or was compressed with another
+    /// dictionary.
+    fn decompress<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+        let out = py
+            .detach(|| self.decompress_bytes(data))
+            .map_err(|e| PyValueError::new_err(format!("Decompression failed: {}", e)))?;
+        Ok(PyBytes::new(py, &out))
+    }
+
+    /// Finish `serializer` and return its remaining output compressed.
+    fn compress_output<'py>(
+        &self,
+        py: Python<'py>,
+        mut serializer: PyRefMut<'_, HtmlSerializer>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let body = serializer.finish_bytes()?;
+        let out = py
+            .detach(|| self.compress_bytes(&body))
+            .map_err(|e| PyRuntimeError::new_err(format!("Compression failed: {}", e)))?;
+        Ok(PyBytes::new(py, &out))
+    }
+}
@@ -0,0 +1,244 @@
+// Differential check of the lenient HTML parser (`html.rs`) against
+// html5ever, the spec-conformant reference, on pure-HTML input. Both trees
+// are reduced to elements, attributes and text, then compared node by node;
+// each difference becomes a structured `HtmlDivergence` so a corpus run can
+// be bucketed by kind instead of eyeballed.
+//
+// Our parser keeps entities undecoded on purpose, so text and attribute
+// values containing '&' are not compared. Comments and doctypes are ignored.
+
+use crate::html::{HtmlNode, parse_html};
+use html5ever::tendril::TendrilSink;
+use html5ever::{
+    ParseOpts, QualName, local_name, namespace_url, ns, parse_document, parse_fragment,
+};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use pyo3::prelude::*;
+
+/// Where the two parsers disagree. `path` locates the node from the root
+/// (`/table[0]/tr[0]`, indices among element and text siblings); `kind` is
+/// "tag", "attributes", "text", "node" (element vs text), "missing" (only
+/// html5ever has it) or "extra" (only we have it). `ours`/`reference`
+/// describe each side, None where the node does not exist.
+#[pyclass(frozen)]
+pub struct HtmlDivergence {
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub kind: &'static str,
+    #[pyo3(get)]
+    pub ours: Option<String>,
+    #[pyo3(get)]
+    pub reference: Option<String>,
+}
+
+#[pymethods]
+impl HtmlDivergence {
+    fn __repr__(&self) -> String {
+        format!("HtmlDivergence({} at {})", self.kind, self.path)
+    }
+}
+
+enum Shape {
+    Element {
+        tag: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Shape>,
+    },
+    Text(String),
+}
+
+impl Shape {
+    fn label(&self) -> &str {
+        match self {
+            Shape::Element { tag, .. } => tag,
+            Shape::Text(_) => "#text",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Shape::Element { tag, attrs, .. } => {
+                let mut out = format!("<{}", tag);
+                for (name, value) in attrs {
+                    out.push_str(&format!(" {}={:?}", name, value));
+                }
+                out.push('>');
+                out
+            }
+            Shape::Text(text) => format!("{:?}", text),
+        }
+    }
+}
+
+// Merge adjacent text, collapse whitespace runs and drop blank text, so the
+// parsers' different text splitting does not count as a divergence.
+fn normalize(nodes: Vec<Shape>) -> Vec<Shape> {
+    let mut out: Vec<Shape> = Vec::new();
+    for node in nodes {
+        match (node, out.last_mut()) {
+            (Shape::Text(text), Some(Shape::Text(last))) => last.push_str(&text),
+            (node, _) => out.push(node),
+        }
+    }
+    out.into_iter()
+        .filter_map(|node| match node {
+            Shape::Text(text) => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                (!text.is_empty()).then_some(Shape::Text(text))
+            }
+            element => Some(element),
+        })
+        .collect()
+}
+
+fn ours(nodes: &[HtmlNode]) -> Vec<Shape> {
+    let shapes = nodes
+        .iter()
+        .filter_map(|node| match node {
+            HtmlNode::Element(el) => Some(Shape::Element {
+                tag: el.tag.clone(),
+                attrs: el
+                    .attrs
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone().unwrap_or_default()))
+                    .collect(),
+                children: ours(&el.children),
+            }),
+            HtmlNode::Text(text) => Some(Shape::Text(text.clone())),
+            HtmlNode::Comment(_) | HtmlNode::Doctype(_) => None,
+        })
+        .collect();
+    normalize(shapes)
+}
+
+fn reference(nodes: &[Handle]) -> Vec<Shape> {
+    let shapes = nodes
+        .iter()
+        .filter_map(|node| match &node.data {
+            NodeData::Element {
+                name,
+                attrs,
+                template_contents,
+                ..
+            } => {
+                let children = match &*template_contents.borrow() {
+                    Some(contents) => reference(&contents.children.borrow()),
+                    None => reference(&node.children.borrow()),
+                };
+                let attrs = attrs
+                    .borrow()
+                    .iter()
+                    .map(|a| {
+                        let name = match &a.name.prefix {
+                            Some(prefix) => format!("{}:{}", prefix, a.name.local),
+                            None => a.name.local.to_string(),
+                        };
+                        (name, a.value.to_string())
+                    })
+                    .collect();
+                Some(Shape::Element {
+                    tag: name.local.to_string(),
+                    attrs,
+                    children,
+                })
+            }
+            NodeData::Text { contents } => Some(Shape::Text(contents.borrow().to_string())),
+            _ => None,
+        })
+        .collect();
+    normalize(shapes)
+}
+
+// html5ever's tree for `html`: a whole document when it starts with a
+// doctype or `<html>`, otherwise a fragment parsed in `<body>`.
+fn reference_tree(html: &str) -> Vec<Shape> {
+    let start = html
+        .trim_start()
+        .get(..9)
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if start.starts_with("<!doctype") || start.starts_with("<html") {
+        let dom = parse_document(RcDom::default(), ParseOpts::default()).one(html);
+        return reference(&dom.document.children.borrow());
+    }
+    let body = QualName::new(None, ns!(html), local_name!("body"));
+    let dom = parse_fragment(RcDom::default(), ParseOpts::default(), body, vec![]).one(html);
+    // The fragment's nodes sit under a synthetic <html> root.
+    let root = dom.document.children.borrow();
+    root.first()
+        .map(|html| reference(&html.children.borrow()))
+        .unwrap_or_default()
+}
+
+fn differ(a: &str, b: &str) -> bool {
+    a != b && !a.contains('&')
+}
+
+fn compare(path: &str, ours: &[Shape], theirs: &[Shape], out: &mut Vec<HtmlDivergence>) {
+    for i in 0..ours.len().max(theirs.len()) {
+        let (a, b) = (ours.get(i), theirs.get(i));
+        let label = a.or(b).map_or("", Shape::label);
+        let here = format!("{}/{}[{}]", path, label, i);
+        let mut push = |kind| {
+            out.push(HtmlDivergence {
+                path: here.clone(),
+                kind,
+                ours: a.map(Shape::describe),
+                reference: b.map(Shape::describe),
+            })
+        };
+        match (a, b) {
+            (Some(Shape::Text(x)), Some(Shape::Text(y))) => {
+                if differ(x, y) {
+                    push("text");
+                }
+            }
+            (
+                Some(Shape::Element {
+                    tag,
+                    attrs,
+                    children,
+                }),
+                Some(Shape::Element {
+                    tag: their_tag,
+                    attrs: their_attrs,
+                    children: their_children,
+                }),
+            ) => {
+                if tag != their_tag {
+                    push("tag");
+                    continue;
+                }
+                let same_attrs = attrs.len() == their_attrs.len()
+                    && attrs.iter().all(|(name, value)| {
+                        their_attrs
+                            .iter()
+                            .any(|(n, v)| n == name && !differ(value, v))
+                    });
+                if !same_attrs {
+                    push("attributes");
+                }
+                compare(&here, children, their_children, out);
+            }
+            (Some(_), Some(_)) => push("node"),
+            (Some(_), None) => push("extra"),
+            (None, _) => push("missing"),
+        }
+    }
+}
+
+/// Parse `html` with both our lenient HTML parser and html5ever and list
+/// where the trees differ, in document order. Internal conformance tooling:
+/// only built with the `conformance` feature.
+#[pyfunction]
+pub fn html_divergences(html: &str) -> Vec<HtmlDivergence> {
+    let mut out = Vec::new();
+    compare(
+        "",
+        &ours(&parse_html(html)),
+        &reference_tree(html),
+        &mut out,
+    );
+    out
+}
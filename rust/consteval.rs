@@ -0,0 +1,419 @@
+// Compile-time evaluation of constant expressions. An expression built only
+// from literals (str, int, float, True/False/None), `!const NAME = ...`
+// values, parentheses, `not`/`and`/`or`, comparisons, `+`/`-` and
+// `a if cond else b` is evaluated here; with `fold_constants=True` such
+// interpolations become static text that remembers the expression in
+// `folded_from`. Anything else (calls, attributes, other names) is left to
+// the runtime.
+
+use crate::ParsedDirective;
+use crate::ParsedNode;
+use crate::diagnostics::Diagnostic;
+use crate::frontmatter::module_names;
+use crate::wires::block_bindings;
+use pyo3::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Const {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    None,
+}
+
+impl Const {
+    fn truthy(&self) -> bool {
+        match self {
+            Const::Str(s) => !s.is_empty(),
+            Const::Int(i) => *i != 0,
+            Const::Float(f) => *f != 0.0,
+            Const::Bool(b) => *b,
+            Const::None => false,
+        }
+    }
+
+    // Numeric value; bool counts as int, as in Python.
+    fn number(&self) -> Option<f64> {
+        match self {
+            Const::Int(i) => Some(*i as f64),
+            Const::Float(f) => Some(*f),
+            Const::Bool(b) => Some(f64::from(u8::from(*b))),
+            _ => None,
+        }
+    }
+
+    fn int(&self) -> Option<i64> {
+        match self {
+            Const::Int(i) => Some(*i),
+            Const::Bool(b) => Some(i64::from(*b)),
+            _ => None,
+        }
+    }
+
+    // `str(value)`; None for floats whose Python repr is not reproduced here.
+    fn display(&self) -> Option<String> {
+        Some(match self {
+            Const::Str(s) => s.clone(),
+            Const::Int(i) => i.to_string(),
+            Const::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+            Const::None => "None".to_string(),
+            Const::Float(f) => {
+                let abs = f.abs();
+                if !f.is_finite() || (abs != 0.0 && !(1e-4..1e16).contains(&abs)) {
+                    return None;
+                }
+                if f.fract() == 0.0 {
+                    format!("{:.1}", f)
+                } else {
+                    f.to_string()
+                }
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Num(String),
+    Name(String),
+    Op(&'static str),
+}
+
+const OPERATORS: &[&str] = &["==", "!=", "<=", ">=", "<", ">", "+", "-", "(", ")"];
+
+fn tokenize(src: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = src.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next()?;
+        if c == '"' || c == '\'' {
+            let mut value = String::new();
+            let mut chars = rest[1..].char_indices();
+            let end = loop {
+                let (i, ch) = chars.next()?;
+                match ch {
+                    _ if ch == c => break i + 2,
+                    '\n' => return None,
+                    '\\' => match chars.next()?.1 {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        e @ ('\\' | '\'' | '"') => value.push(e),
+                        _ => return None,
+                    },
+                    _ => value.push(ch),
+                }
+            };
+            tokens.push(Token::Str(value));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit()
+            || (c == '.' && rest[1..].starts_with(|d: char| d.is_ascii_digit()))
+        {
+            let len = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '.' || ch == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Num(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else {
+            let op = OPERATORS.iter().find(|op| rest.starts_with(**op))?;
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        }
+        rest = rest.trim_start();
+    }
+    Some(tokens)
+}
+
+// Recursive descent over the tokens, following Python's precedence.
+struct Evaluator<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    constants: &'a [(String, String, Const)],
+}
+
+impl Evaluator<'_> {
+    fn peek_name(&self, name: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Name(n)) if n == name)
+    }
+
+    fn eat_name(&mut self, name: &str) -> bool {
+        let found = self.peek_name(name);
+        self.pos += usize::from(found);
+        found
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some(Token::Op(o)) if *o == op);
+        self.pos += usize::from(found);
+        found
+    }
+
+    fn expr(&mut self) -> Option<Const> {
+        let value = self.or_test()?;
+        if !self.eat_name("if") {
+            return Some(value);
+        }
+        let cond = self.or_test()?;
+        if !self.eat_name("else") {
+            return None;
+        }
+        let other = self.expr()?;
+        Some(if cond.truthy() { value } else { other })
+    }
+
+    fn or_test(&mut self) -> Option<Const> {
+        let mut value = self.and_test()?;
+        while self.eat_name("or") {
+            let rhs = self.and_test()?;
+            if !value.truthy() {
+                value = rhs;
+            }
+        }
+        Some(value)
+    }
+
+    fn and_test(&mut self) -> Option<Const> {
+        let mut value = self.not_test()?;
+        while self.eat_name("and") {
+            let rhs = self.not_test()?;
+            if value.truthy() {
+                value = rhs;
+            }
+        }
+        Some(value)
+    }
+
+    fn not_test(&mut self) -> Option<Const> {
+        if self.eat_name("not") {
+            return Some(Const::Bool(!self.not_test()?.truthy()));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Option<Const> {
+        let lhs = self.arith()?;
+        let Some(Token::Op(op)) = self.tokens.get(self.pos).cloned() else {
+            return Some(lhs);
+        };
+        if !["==", "!=", "<", ">", "<=", ">="].contains(&op) {
+            return Some(lhs);
+        }
+        self.pos += 1;
+        let rhs = self.arith()?;
+        let ordering = match (&lhs, &rhs) {
+            (Const::Str(a), Const::Str(b)) => a.partial_cmp(b),
+            _ => match (lhs.number(), rhs.number()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ if matches!(op, "==" | "!=") => None,
+                _ => return None,
+            },
+        };
+        let equal = ordering == Some(std::cmp::Ordering::Equal)
+            || (ordering.is_none() && lhs == Const::None && rhs == Const::None);
+        Some(Const::Bool(match op {
+            "==" => equal,
+            "!=" => !equal,
+            "<" => ordering? == std::cmp::Ordering::Less,
+            ">" => ordering? == std::cmp::Ordering::Greater,
+            "<=" => ordering? != std::cmp::Ordering::Greater,
+            _ => ordering? != std::cmp::Ordering::Less,
+        }))
+    }
+
+    fn arith(&mut self) -> Option<Const> {
+        let mut value = self.unary()?;
+        loop {
+            let minus = if self.eat_op("+") {
+                false
+            } else if self.eat_op("-") {
+                true
+            } else {
+                return Some(value);
+            };
+            let rhs = self.unary()?;
+            value = match (&value, &rhs) {
+                (Const::Str(a), Const::Str(b)) if !minus => Const::Str(format!("{}{}", a, b)),
+                _ => match (value.int(), rhs.int()) {
+                    (Some(a), Some(b)) if minus => Const::Int(a.checked_sub(b)?),
+                    (Some(a), Some(b)) => Const::Int(a.checked_add(b)?),
+                    _ => {
+                        let (a, b) = (value.number()?, rhs.number()?);
+                        Const::Float(if minus { a - b } else { a + b })
+                    }
+                },
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Option<Const> {
+        if self.eat_op("-") {
+            return match self.unary()? {
+                Const::Float(f) => Some(Const::Float(-f)),
+                other => Some(Const::Int(other.int()?.checked_neg()?)),
+            };
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Option<Const> {
+        let token = self.tokens.get(self.pos)?.clone();
+        self.pos += 1;
+        match token {
+            Token::Str(mut s) => {
+                // Adjacent literals concatenate: 'a' 'b'.
+                while let Some(Token::Str(next)) = self.tokens.get(self.pos) {
+                    s.push_str(next);
+                    self.pos += 1;
+                }
+                Some(Const::Str(s))
+            }
+            Token::Num(n) => {
+                let n = n.replace('_', "");
+                if n.contains(['.', 'e', 'E']) {
+                    n.parse().ok().map(Const::Float)
+                } else if n.len() > 1 && n.starts_with('0') {
+                    None
+                } else {
+                    n.parse().ok().map(Const::Int)
+                }
+            }
+            Token::Name(name) => match name.as_str() {
+                "True" => Some(Const::Bool(true)),
+                "False" => Some(Const::Bool(false)),
+                "None" => Some(Const::None),
+                _ => self
+                    .constants
+                    .iter()
+                    .find(|(n, _, _)| *n == name)
+                    .map(|(_, _, v)| v.clone()),
+            },
+            Token::Op("(") => {
+                let value = self.expr()?;
+                self.eat_op(")").then_some(value)
+            }
+            Token::Op(_) => None,
+        }
+    }
+}
+
+// Value of `src` if it is a constant expression over `constants`.
+pub(crate) fn evaluate(src: &str, constants: &[(String, String, Const)]) -> Option<Const> {
+    let mut evaluator = Evaluator {
+        tokens: tokenize(src)?,
+        pos: 0,
+        constants,
+    };
+    let value = evaluator.expr()?;
+    (evaluator.pos == evaluator.tokens.len()).then_some(value)
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !["True", "False", "None", "not", "and", "or", "if", "else"].contains(&name)
+}
+
+// `!const NAME = expr` declarations in order, as (name, expression, value).
+// A malformed or non-constant declaration is reported and skipped.
+pub(crate) fn declared_constants(
+    directives: &[ParsedDirective],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<(String, String, Const)> {
+    let mut constants: Vec<(String, String, Const)> = Vec::new();
+    for d in directives.iter().filter(|d| d.name == "const") {
+        let content = d.content.as_deref().unwrap_or("");
+        let declared = content.split_once('=').and_then(|(name, expr)| {
+            let (name, expr) = (name.trim(), expr.trim());
+            if !is_identifier(name) || expr.starts_with('=') {
+                return None;
+            }
+            let value = evaluate(expr, &constants)?;
+            Some((name.to_string(), expr.to_string(), value))
+        });
+        match declared {
+            Some(constant) => {
+                constants.retain(|(n, _, _)| *n != constant.0);
+                constants.push(constant);
+            }
+            None => diagnostics.push(Diagnostic::error(
+                "invalid-directive",
+                format!(
+                    "!const expects NAME = <constant expression>, got '{}'",
+                    content
+                ),
+                d.line,
+                d.column,
+            )),
+        }
+    }
+    constants
+}
+
+// `escape_html` of the runtime: & < > and ".
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Names bound by any block marker in `nodes` (loop targets, `as` names,
+// `{$then}`/`{$catch}` values, case captures).
+fn bound_names(py: Python<'_>, nodes: &[Py<ParsedNode>], out: &mut Vec<String>) {
+    for node in nodes {
+        let n = node.borrow(py);
+        if let (Some(keyword), Some(expr)) = (&n.block_keyword, &n.expression) {
+            block_bindings(keyword, expr, out);
+        }
+        bound_names(py, &n.children, out);
+    }
+}
+
+// Turn every constant interpolation in `template` into static text. A
+// constant whose name the frontmatter assigns or a block binds is not
+// used, since the expression may read that binding instead. Raw `{$html}`
+// output is left alone, and so are values with braces, which the compiler
+// would read as interpolations again.
+pub(crate) fn fold_constants(
+    py: Python<'_>,
+    python_code: &str,
+    template: &[Py<ParsedNode>],
+    constants: &[(String, String, Const)],
+) {
+    let mut shadowed = module_names(python_code);
+    bound_names(py, template, &mut shadowed);
+    let constants: Vec<_> = constants
+        .iter()
+        .filter(|(name, _, _)| !shadowed.contains(name))
+        .cloned()
+        .collect();
+    fold(py, template, &constants);
+}
+
+fn fold(py: Python<'_>, nodes: &[Py<ParsedNode>], constants: &[(String, String, Const)]) {
+    for node in nodes {
+        let mut n = node.borrow_mut(py);
+        if n.block_keyword.as_deref() == Some("interpolation")
+            && let Some(expr) = n.expression.clone()
+            && let Some(text) = evaluate(&expr, constants).and_then(|v| v.display())
+            && !text.contains(['{', '}'])
+        {
+            n.is_block = false;
+            n.block_keyword = None;
+            n.expression = None;
+            n.expression_info = None;
+            n.text_content = Some(escape_html(&text));
+            n.folded_from = Some(expr);
+        }
+        let children: Vec<_> = n.children.iter().map(|c| c.clone_ref(py)).collect();
+        drop(n);
+        fold(py, &children, constants);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::reactive::ReactiveStatement;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Directed dependency graph (wire -> derived wire -> region) with interned node ids.
+///
+/// Removed nodes leave a tombstone slot behind so ids stay stable; re-adding a
+/// removed name allocates a fresh slot.
+#[pyclass]
+#[derive(Default)]
+pub struct DepGraph {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+    alive: Vec<bool>,
+    outgoing: Vec<Vec<u32>>,
+    incoming: Vec<Vec<u32>>,
+    edges: usize,
+}
+
+impl DepGraph {
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.alive.push(true);
+        self.outgoing.push(Vec::new());
+        self.incoming.push(Vec::new());
+        id
+    }
+
+    fn id(&self, name: &str) -> PyResult<u32> {
+        self.ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| PyKeyError::new_err(format!("Unknown node '{}'", name)))
+    }
+
+    fn names_of(&self, ids: impl IntoIterator<Item = u32>) -> Vec<String> {
+        ids.into_iter()
+            .map(|id| self.names[id as usize].clone())
+            .collect()
+    }
+
+    // Unknown names are ignored: a wire nobody depends on simply dirties nothing.
+    fn reachable(&self, changed: &[String]) -> Vec<bool> {
+        let mut seen = vec![false; self.names.len()];
+        let mut stack: Vec<u32> = changed
+            .iter()
+            .filter_map(|n| self.ids.get(n))
+            .copied()
+            .collect();
+        for &id in &stack {
+            seen[id as usize] = true;
+        }
+        while let Some(id) = stack.pop() {
+            for &next in &self.outgoing[id as usize] {
+                if !seen[next as usize] {
+                    seen[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    // Iterative DFS so deep chains can't overflow the stack.
+    fn cycle_from(&self, start: u32, color: &mut [u8]) -> Option<Vec<u32>> {
+        let mut path: Vec<(u32, usize)> = vec![(start, 0)];
+        color[start as usize] = 1;
+        while let Some(&mut (node, ref mut next_idx)) = path.last_mut() {
+            if let Some(&next) = self.outgoing[node as usize].get(*next_idx) {
+                *next_idx += 1;
+                match color[next as usize] {
+                    0 => {
+                        color[next as usize] = 1;
+                        path.push((next, 0));
+                    }
+                    1 => {
+                        let pos = path.iter().position(|&(n, _)| n == next).unwrap();
+                        let mut cycle: Vec<u32> = path[pos..].iter().map(|&(n, _)| n).collect();
+                        cycle.push(next);
+                        return Some(cycle);
+                    }
+                    _ => {}
+                }
+            } else {
+                color[node as usize] = 2;
+                path.pop();
+            }
+        }
+        None
+    }
+}
+
+#[pymethods]
+impl DepGraph {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_node(&mut self, name: &str) {
+        self.intern(name);
+    }
+
+    /// Add an edge meaning "`dst` depends on `src`" (a change to `src` dirties `dst`).
+    fn add_edge(&mut self, src: &str, dst: &str) {
+        let s = self.intern(src);
+        let d = self.intern(dst);
+        if !self.outgoing[s as usize].contains(&d) {
+            self.outgoing[s as usize].push(d);
+            self.incoming[d as usize].push(s);
+            self.edges += 1;
+        }
+    }
+
+    /// Add edges for frontmatter reactive statements: each written name depends
+    /// on everything the statement reads. Statements that write nothing get a
+    /// `$:<line>` node of their own.
+    fn add_reactive(&mut self, statements: Vec<PyRef<'_, ReactiveStatement>>) {
+        for stmt in statements {
+            let targets = if stmt.writes.is_empty() {
+                vec![format!("$:{}", stmt.line)]
+            } else {
+                stmt.writes.clone()
+            };
+            for target in &targets {
+                self.add_node(target);
+                for read in &stmt.reads {
+                    if read != target {
+                        self.add_edge(read, target);
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_edge(&mut self, src: &str, dst: &str) -> PyResult<bool> {
+        let s = self.id(src)?;
+        let d = self.id(dst)?;
+        let before = self.outgoing[s as usize].len();
+        self.outgoing[s as usize].retain(|&n| n != d);
+        if self.outgoing[s as usize].len() == before {
+            return Ok(false);
+        }
+        self.incoming[d as usize].retain(|&n| n != s);
+        self.edges -= 1;
+        Ok(true)
+    }
+
+    fn remove_node(&mut self, name: &str) -> PyResult<()> {
+        let id = self.id(name)?;
+        for succ in std::mem::take(&mut self.outgoing[id as usize]) {
+            self.incoming[succ as usize].retain(|&n| n != id);
+            self.edges -= 1;
+        }
+        for pred in std::mem::take(&mut self.incoming[id as usize]) {
+            self.outgoing[pred as usize].retain(|&n| n != id);
+            self.edges -= 1;
+        }
+        self.alive[id as usize] = false;
+        self.ids.remove(name);
+        Ok(())
+    }
+
+    fn has_node(&self, name: &str) -> bool {
+        self.ids.contains_key(name)
+    }
+
+    fn successors(&self, name: &str) -> PyResult<Vec<String>> {
+        let id = self.id(name)?;
+        Ok(self.names_of(self.outgoing[id as usize].iter().copied()))
+    }
+
+    fn predecessors(&self, name: &str) -> PyResult<Vec<String>> {
+        let id = self.id(name)?;
+        Ok(self.names_of(self.incoming[id as usize].iter().copied()))
+    }
+
+    /// Every node transitively affected by `changed` (including the changed nodes).
+    fn dirty_set(&self, changed: Vec<String>) -> HashSet<String> {
+        let seen = self.reachable(&changed);
+        self.names_of((0..seen.len() as u32).filter(|&id| seen[id as usize]))
+            .into_iter()
+            .collect()
+    }
+
+    /// Dirty nodes in dependency order, so each node comes after everything it depends on.
+    /// Raises ValueError if the dirty subgraph contains a cycle.
+    fn dirty_order(&self, changed: Vec<String>) -> PyResult<Vec<String>> {
+        let seen = self.reachable(&changed);
+        let mut indegree = vec![0usize; self.names.len()];
+        for id in (0..seen.len()).filter(|&id| seen[id]) {
+            indegree[id] = self.incoming[id]
+                .iter()
+                .filter(|&&p| seen[p as usize])
+                .count();
+        }
+
+        let mut queue: VecDeque<u32> = (0..seen.len() as u32)
+            .filter(|&id| seen[id as usize] && indegree[id as usize] == 0)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for &next in &self.outgoing[id as usize] {
+                indegree[next as usize] -= 1;
+                if indegree[next as usize] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let total = seen.iter().filter(|&&s| s).count();
+        if order.len() != total {
+            let mut color: Vec<u8> = seen.iter().map(|&s| if s { 0 } else { 2 }).collect();
+            let cycle = (0..seen.len() as u32)
+                .find_map(|id| {
+                    if color[id as usize] == 0 {
+                        self.cycle_from(id, &mut color)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or_default();
+            return Err(PyValueError::new_err(format!(
+                "Dependency cycle: {}",
+                self.names_of(cycle).join(" -> ")
+            )));
+        }
+        Ok(self.names_of(order))
+    }
+
+    /// Return one cycle as a closed path (first node repeated at the end), or None.
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut color: Vec<u8> = self.alive.iter().map(|&a| if a { 0 } else { 2 }).collect();
+        (0..self.names.len() as u32)
+            .find_map(|id| {
+                if color[id as usize] == 0 {
+                    self.cycle_from(id, &mut color)
+                } else {
+                    None
+                }
+            })
+            .map(|cycle| self.names_of(cycle))
+    }
+
+    #[getter]
+    fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    fn __len__(&self) -> usize {
+        self.ids.len()
+    }
+}
@@ -0,0 +1,391 @@
+use crate::attrs::ParsedAttribute;
+use crate::kinds::AttrKind;
+use pyo3::prelude::*;
+
+/// A problem found while parsing or analyzing a template.
+///
+/// `severity` is "error" or "warning"; `code` is a stable kebab-case
+/// identifier (e.g. "syntax-error", "unclosed-tag") suitable for filtering.
+/// `end_line`/`end_column` close the reported range; `start_byte`/`end_byte`
+/// give it as source offsets and are None for diagnostics not produced by
+/// `parse`. `related` lists other ranges involved, as `(line, column,
+/// end_line, end_column)`, e.g. the first of two duplicate attributes.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    #[pyo3(get)]
+    pub severity: String,
+    #[pyo3(get)]
+    pub code: String,
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    /// `column` with tabs expanded to the parse `tab_width`.
+    #[pyo3(get)]
+    pub display_column: usize,
+    #[pyo3(get)]
+    pub end_line: usize,
+    #[pyo3(get)]
+    pub end_column: usize,
+    #[pyo3(get)]
+    pub start_byte: Option<usize>,
+    #[pyo3(get)]
+    pub end_byte: Option<usize>,
+    #[pyo3(get)]
+    pub related: Vec<(usize, usize, usize, usize)>,
+}
+
+#[pymethods]
+impl Diagnostic {
+    fn __repr__(&self) -> String {
+        format!(
+            "Diagnostic({} {} at {}:{}: {})",
+            self.severity, self.code, self.line, self.column, self.message
+        )
+    }
+}
+
+impl Diagnostic {
+    pub(crate) fn error(
+        code: &str,
+        message: impl Into<String>,
+        line: usize,
+        column: usize,
+    ) -> Self {
+        Self {
+            severity: "error".to_string(),
+            code: code.to_string(),
+            message: message.into(),
+            line,
+            column,
+            display_column: column,
+            end_line: line,
+            end_column: column,
+            start_byte: None,
+            end_byte: None,
+            related: Vec::new(),
+        }
+    }
+
+    pub(crate) fn warning(
+        code: &str,
+        message: impl Into<String>,
+        line: usize,
+        column: usize,
+    ) -> Self {
+        Self {
+            severity: "warning".to_string(),
+            ..Self::error(code, message, line, column)
+        }
+    }
+
+    // Widen to `(end_line, end_column)` and the `[start, end)` byte range.
+    pub(crate) fn spanning(mut self, end: (usize, usize), bytes: (usize, usize)) -> Self {
+        (self.end_line, self.end_column) = end;
+        (self.start_byte, self.end_byte) = (Some(bytes.0), Some(bytes.1));
+        self
+    }
+
+    fn with_span(self, node: tree_sitter::Node) -> Self {
+        let end = node.end_position();
+        self.spanning(
+            (end.row + 1, end.column),
+            (node.start_byte(), node.end_byte()),
+        )
+    }
+}
+
+// Attribute prefixes owned by the framework: the client runtime and the
+// compiler's own encodings.
+const RESERVED_PREFIXES: &[&str] = &["data-pw-", "data-pywire-", "__pw_", "__pywire_"];
+
+// Attributes the compiler generates from `@event` handlers.
+const GENERATED_PREFIXES: &[&str] = &["data-on-", "data-modifiers-"];
+
+// Warning for an author-written attribute that collides with the framework.
+pub(crate) fn reserved_attribute(name: &str, line: usize, column: usize) -> Option<Diagnostic> {
+    let lower = name.to_ascii_lowercase();
+    if let Some(prefix) = RESERVED_PREFIXES.iter().find(|p| lower.starts_with(**p)) {
+        return Some(Diagnostic::warning(
+            "reserved-attribute",
+            format!(
+                "attribute '{}' uses the reserved '{}' prefix and may break the client runtime",
+                name, prefix
+            ),
+            line,
+            column,
+        ));
+    }
+    let prefix = GENERATED_PREFIXES.iter().find(|p| lower.starts_with(**p))?;
+    let event = &name[prefix.len()..];
+    Some(Diagnostic::warning(
+        "shadowed-attribute",
+        format!(
+            "attribute '{}' shadows the one generated for '@{}'; use @{} instead",
+            name, event, event
+        ),
+        line,
+        column,
+    ))
+}
+
+// Warning for markup browsers reinterpret: a non-void element written
+// self-closed (`<div/>` stays open, swallowing its siblings) or a void
+// element given content or an end tag (the content ends up after it).
+pub(crate) fn void_mismatch(
+    tag: &str,
+    is_void: bool,
+    self_closed: bool,
+    has_content: bool,
+    line: usize,
+    column: usize,
+) -> Option<Diagnostic> {
+    let (code, message) = if is_void && has_content {
+        (
+            "void-element-content",
+            format!(
+                "<{}> is a void element and cannot have content or an end tag",
+                tag
+            ),
+        )
+    } else if !is_void && self_closed {
+        (
+            "self-closing-non-void",
+            format!(
+                "<{0}/> is not a void element; browsers ignore the '/' and leave it open, write <{0}></{0}>",
+                tag
+            ),
+        )
+    } else {
+        return None;
+    };
+    Some(Diagnostic::warning(code, message, line, column))
+}
+
+// `name`, `form.name`, `rows[i].value`: a place an assignment can write.
+fn is_assignable(expr: &str) -> bool {
+    let ident_len = |s: &str| {
+        s.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(s.len())
+    };
+    let mut rest = expr.trim();
+    let first = ident_len(rest);
+    if first == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+    rest = &rest[first..];
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let len = ident_len(after);
+            if len == 0 {
+                return false;
+            }
+            rest = &after[len..];
+        } else if rest.starts_with('[') {
+            let mut depth = 0;
+            let Some(end) = rest.find(|c| {
+                match c {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            }) else {
+                return false;
+            };
+            rest = &rest[end + 1..];
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+// Error for a `bind:` attribute whose value is not a `{target}` the
+// binding can write back to.
+pub(crate) fn invalid_bind(attr: &ParsedAttribute) -> Option<Diagnostic> {
+    let target = attr.bind_target.as_deref()?;
+    let braced = attr
+        .value
+        .as_deref()
+        .is_some_and(|v| v.trim().starts_with('{') && v.trim().ends_with('}'));
+    let message = match attr.expression.as_deref() {
+        Some(expr) if braced && is_assignable(expr) => return None,
+        Some(expr) if braced => format!(
+            "bind:{} needs a variable, attribute or item to write to, got '{}'",
+            target, expr
+        ),
+        _ => format!(
+            "bind:{} expects an expression: bind:{}={{name}}",
+            target, target
+        ),
+    };
+    Some(Diagnostic::error(
+        "invalid-bind",
+        message,
+        attr.line,
+        attr.column,
+    ))
+}
+
+// Warning for an attribute written twice on one element. The last value
+// wins; the diagnostic spans the repeat and relates the first one.
+pub(crate) fn duplicate_attribute(
+    name: &str,
+    first: tree_sitter::Node,
+    repeat: tree_sitter::Node,
+) -> Diagnostic {
+    let (start, end) = (first.start_position(), first.end_position());
+    let at = repeat.start_position();
+    let mut diagnostic = Diagnostic::warning(
+        "duplicate-attribute",
+        format!(
+            "attribute '{}' is already set at line {}; the last value is used",
+            name,
+            start.row + 1
+        ),
+        at.row + 1,
+        at.column,
+    )
+    .with_span(repeat);
+    diagnostic.related = vec![(start.row + 1, start.column, end.row + 1, end.column)];
+    diagnostic
+}
+
+// Error for a `class:` or `style:` attribute given a literal instead of an
+// expression (`class:active="yes"`).
+pub(crate) fn invalid_conditional(attr: &ParsedAttribute) -> Option<Diagnostic> {
+    if !matches!(attr.kind, AttrKind::ClassToggle | AttrKind::StyleProperty) {
+        return None;
+    }
+    let value = attr.value.as_deref()?.trim();
+    if value.starts_with('{') && value.ends_with('}') && attr.expression.is_some() {
+        return None;
+    }
+    let what = if attr.kind == AttrKind::ClassToggle {
+        "condition"
+    } else {
+        "value"
+    };
+    Some(Diagnostic::error(
+        "invalid-conditional-attribute",
+        format!(
+            "{} expects an expression: {}={{{}}}",
+            attr.name, attr.name, what
+        ),
+        attr.line,
+        attr.column,
+    ))
+}
+
+// Name of the element `node` sits in, if any.
+fn enclosing_tag(source: &str, node: tree_sitter::Node) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if matches!(n.kind(), "tag" | "script_tag" | "style_tag") {
+            let name = n.child_by_field_name("name").or_else(|| {
+                n.child_by_field_name("start_tag")
+                    .and_then(|s| s.child_by_field_name("name"))
+            });
+            return match name {
+                Some(name) => source.get(name.byte_range()).map(str::to_string),
+                None => Some(n.kind().trim_end_matches("_tag").to_string()),
+            };
+        }
+        current = n.parent();
+    }
+    None
+}
+
+// Tag name at the start of `text` ("<div ..." -> "div").
+fn leading_tag(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix('<')?;
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(rest.len());
+    let name = &rest[..end];
+    (!name.is_empty() && name.starts_with(|c: char| c.is_ascii_alphabetic())).then_some(name)
+}
+
+// Code and message for a MISSING node.
+fn describe_missing(source: &str, node: tree_sitter::Node) -> (&'static str, String) {
+    let kind = node.kind();
+    if kind == "end_tag" || kind.starts_with("</") {
+        if let Some(tag) = enclosing_tag(source, node) {
+            return ("unclosed-tag", format!("<{}> is never closed", tag));
+        }
+        return ("unclosed-tag", "element is never closed".to_string());
+    }
+    ("syntax-error", format!("missing {}", kind))
+}
+
+// Code and message for an ERROR node, from what the unparsable text looks like.
+fn describe_error(source: &str, node: tree_sitter::Node) -> (&'static str, String) {
+    let text = source.get(node.byte_range()).unwrap_or("").trim_start();
+    let snippet: String = text.chars().take(20).collect();
+    if let Some(name) = text.strip_prefix("</") {
+        let name = name.split(|c: char| c == '>' || c.is_whitespace()).next();
+        return (
+            "unexpected-close-tag",
+            format!(
+                "closing tag </{}> has no matching open tag",
+                name.unwrap_or("")
+            ),
+        );
+    }
+    if text.starts_with("{/") {
+        let closer = text.split('}').next().unwrap_or(text);
+        return (
+            "unmatched-block-close",
+            format!("'{}}}' has no open block to close", closer),
+        );
+    }
+    let in_start_tag = node
+        .parent()
+        .is_some_and(|p| matches!(p.kind(), "start_tag" | "attribute" | "self_closing_tag"))
+        || node.prev_sibling().is_some_and(|s| s.kind() == "attribute");
+    if in_start_tag {
+        return (
+            "invalid-attribute",
+            format!("invalid attribute syntax near {:?}", snippet),
+        );
+    }
+    if let Some(tag) = leading_tag(text)
+        && !text.contains(&format!("</{}", tag))
+    {
+        return ("unclosed-tag", format!("<{}> is never closed", tag));
+    }
+    ("syntax-error", format!("unexpected {:?}", snippet))
+}
+
+// ERROR and MISSING nodes of a tree-sitter tree, outermost first. ERROR
+// nodes the mapper reads as a block marker or template comment are not
+// errors.
+pub(crate) fn syntax_errors(source: &str, node: tree_sitter::Node, out: &mut Vec<Diagnostic>) {
+    if node.is_error()
+        && (crate::node_kind(source, node) != "ERROR"
+            || crate::comments::is_template_comment(source, node))
+    {
+        return;
+    }
+    let start = node.start_position();
+    if node.is_missing() || node.is_error() {
+        let (code, message) = if node.is_missing() {
+            describe_missing(source, node)
+        } else {
+            describe_error(source, node)
+        };
+        out.push(Diagnostic::error(code, message, start.row + 1, start.column).with_span(node));
+        return;
+    }
+    if !node.has_error() {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        syntax_errors(source, child, out);
+    }
+}
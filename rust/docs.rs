@@ -0,0 +1,74 @@
+// Component documentation: the `!doc` directive or a leading `{# ... #}`
+// comment becomes `ParsedDocument.docstring`.
+
+use crate::ParsedDirective;
+
+// `"text"`, `'''text'''` etc. -> `text`; anything else is returned as is.
+pub(crate) fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    for quote in ["\"\"\"", "'''", "\"", "'"] {
+        if text.len() >= 2 * quote.len()
+            && let Some(inner) = text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    text
+}
+
+// Strip the indentation shared by all non-blank lines, and blank edge lines.
+fn dedent(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let body: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            if i == 0 {
+                l.trim()
+            } else {
+                l.get(indent..).unwrap_or(l.trim_start()).trim_end()
+            }
+        })
+        .collect();
+    body.join("\n").trim_matches('\n').to_string()
+}
+
+// Body of a `{# ... #}` comment at `offset`, after whitespace and any
+// directives. Pragma comments (`{# pywire: ... #}`) are not documentation.
+fn comment_at(source: &str, directives: &[ParsedDirective], mut offset: usize) -> Option<String> {
+    loop {
+        offset += source[offset..].len() - source[offset..].trim_start().len();
+        match directives.iter().find(|d| d.start_byte == offset) {
+            Some(d) if d.end_byte > offset => offset = d.end_byte,
+            _ => break,
+        }
+    }
+    let rest = source[offset..].strip_prefix("{#")?;
+    let body = &rest[..rest.find("#}")?];
+    if body.trim_start().starts_with("pywire:") {
+        return None;
+    }
+    Some(dedent(body)).filter(|d| !d.is_empty())
+}
+
+// Docstring of a template: `!doc` content, else a comment opening the
+// document (which starts at `start`) or its template section.
+pub(crate) fn docstring(
+    source: &str,
+    directives: &[ParsedDirective],
+    start: usize,
+    template_start: Option<usize>,
+) -> Option<String> {
+    if let Some(doc) = directives.iter().find(|d| d.name == "doc") {
+        return Some(dedent(unquote(doc.content.as_deref().unwrap_or(""))));
+    }
+    comment_at(source, directives, start)
+        .or_else(|| template_start.and_then(|start| comment_at(source, directives, start)))
+}
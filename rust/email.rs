@@ -0,0 +1,381 @@
+// Rendered HTML -> markup for email clients. Rules from `<style>` elements
+// are inlined into `style` attributes (cascade order: specificity, then
+// source order, then the element's own `style`, then `!important` rules,
+// then the element's own `!important` declarations); rules that cannot be
+// inlined (`@media`, pseudo-classes) are kept in one `<style>` element.
+// Scripts and event handler attributes are removed, and with `tables=True`
+// block containers become single-cell presentation tables, which Outlook
+// lays out reliably.
+
+use crate::diagnostics::Diagnostic;
+use crate::html::{Element, HtmlNode, parse_html, serialize_node};
+use crate::query::{Selector, html_elements};
+use pyo3::prelude::*;
+use std::collections::HashSet;
+
+// Containers lowered to tables with `tables=True`.
+const BLOCKS: &[&str] = &[
+    "div", "section", "article", "header", "footer", "main", "nav", "aside",
+];
+
+// Event handler attributes: `on*` and the compiler's generated encodings.
+const HANDLER_PREFIXES: &[&str] = &["on", "data-on-", "data-modifiers-"];
+
+// (property, value substring or "" for any value, why). Support follows the
+// Word-based Outlook renderer, the least capable common client.
+const UNSUPPORTED: &[(&str, &str, &str)] = &[
+    ("display", "flex", "flexbox is not supported by Outlook"),
+    ("display", "grid", "grid layout is not supported by Outlook"),
+    ("position", "", "positioning is not supported by Outlook"),
+    ("float", "", "floats are unreliable in Outlook"),
+    ("max-width", "", "max-width is ignored by Outlook"),
+    ("border-radius", "", "border-radius is ignored by Outlook"),
+    (
+        "box-shadow",
+        "",
+        "box-shadow is not supported by most email clients",
+    ),
+    (
+        "transform",
+        "",
+        "transforms are not supported by most email clients",
+    ),
+    (
+        "transition",
+        "",
+        "transitions are not supported by most email clients",
+    ),
+    (
+        "animation",
+        "",
+        "animations are not supported by most email clients",
+    ),
+    ("opacity", "", "opacity is ignored by Outlook"),
+    (
+        "background-image",
+        "",
+        "background images need VML fallbacks in Outlook",
+    ),
+    (
+        "",
+        "var(",
+        "custom properties are not supported by most email clients",
+    ),
+    ("", "calc(", "calc() is not supported by Outlook"),
+];
+
+/// Result of `email_html`: the transformed markup and "email-unsupported-css"
+/// warnings for CSS the common email clients ignore.
+#[pyclass(frozen)]
+pub struct EmailHtml {
+    #[pyo3(get)]
+    pub html: String,
+    #[pyo3(get)]
+    pub warnings: Vec<Diagnostic>,
+}
+
+#[pymethods]
+impl EmailHtml {
+    fn __repr__(&self) -> String {
+        format!(
+            "EmailHtml({} bytes, warnings={})",
+            self.html.len(),
+            self.warnings.len()
+        )
+    }
+}
+
+type Declarations = Vec<(String, String)>;
+
+struct Rule {
+    selector: Selector,
+    specificity: (usize, usize, usize),
+    declarations: Declarations,
+}
+
+// Declarations of a `style` attribute or rule body.
+fn declarations(body: &str) -> Declarations {
+    body.split(';')
+        .filter_map(|decl| {
+            let (property, value) = decl.split_once(':')?;
+            let (property, value) = (property.trim(), value.trim());
+            (!property.is_empty() && !value.is_empty())
+                .then(|| (property.to_ascii_lowercase(), value.to_string()))
+        })
+        .collect()
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+// Split a stylesheet into inlinable rules and the CSS that has to stay in a
+// `<style>` element.
+fn parse_stylesheet(css: &str, rules: &mut Vec<Rule>, kept: &mut String) {
+    let css = strip_comments(css);
+    let mut rest = css.as_str();
+    while let Some(open) = rest.find('{') {
+        let prelude = rest[..open].trim();
+        let body_start = open + 1;
+        // At-rules may nest blocks; keep them whole.
+        let mut depth = 1;
+        let mut end = rest.len();
+        for (i, c) in rest[body_start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                end = body_start + i;
+                break;
+            }
+        }
+        let body = &rest[body_start..end];
+        rest = rest.get(end + 1..).unwrap_or("");
+        if prelude.starts_with('@') {
+            kept.push_str(&format!("{}{{{}}}", prelude, body.trim()));
+            continue;
+        }
+        for part in prelude.split(',') {
+            match Selector::parse(part.trim()) {
+                Ok(selector) => rules.push(Rule {
+                    specificity: selector.specificity(),
+                    selector,
+                    declarations: declarations(body),
+                }),
+                Err(_) => kept.push_str(&format!("{}{{{}}}", part.trim(), body.trim())),
+            }
+        }
+    }
+}
+
+fn collect_styles(nodes: &[HtmlNode], rules: &mut Vec<Rule>, kept: &mut String) {
+    for node in nodes {
+        if let HtmlNode::Element(el) = node {
+            if el.tag.eq_ignore_ascii_case("style") {
+                for child in &el.children {
+                    if let HtmlNode::Text(css) = child {
+                        parse_stylesheet(css, rules, kept);
+                    }
+                }
+            } else {
+                collect_styles(&el.children, rules, kept);
+            }
+        }
+    }
+}
+
+fn set(out: &mut Declarations, property: &str, value: &str) {
+    match out.iter_mut().find(|(p, _)| p == property) {
+        Some(existing) => existing.1 = value.to_string(),
+        None => out.push((property.to_string(), value.to_string())),
+    }
+}
+
+fn is_important(value: &str) -> bool {
+    value
+        .trim_end()
+        .to_ascii_lowercase()
+        .ends_with("!important")
+}
+
+struct Inliner {
+    // Final declarations per element, in document order.
+    styles: Vec<Declarations>,
+    kept: String,
+    tables: bool,
+    next: usize,
+}
+
+impl Inliner {
+    fn element_count(nodes: &[HtmlNode]) -> usize {
+        nodes
+            .iter()
+            .map(|node| match node {
+                HtmlNode::Element(el) => 1 + Self::element_count(&el.children),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn transform(&mut self, nodes: Vec<HtmlNode>) -> Vec<HtmlNode> {
+        let mut out = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let HtmlNode::Element(mut el) = node else {
+                out.push(node);
+                continue;
+            };
+            let index = self.next;
+            self.next += 1;
+            let tag = el.tag.to_ascii_lowercase();
+            if tag == "script" || tag == "style" {
+                self.next += Self::element_count(&el.children);
+                // Leftover rules go where the first stylesheet was.
+                if tag == "style" && !self.kept.is_empty() {
+                    el.children = vec![HtmlNode::Text(std::mem::take(&mut self.kept))];
+                    el.attrs.clear();
+                    out.push(HtmlNode::Element(el));
+                }
+                continue;
+            }
+            el.attrs.retain(|(name, _)| {
+                let name = name.to_ascii_lowercase();
+                !HANDLER_PREFIXES.iter().any(|p| name.starts_with(p))
+            });
+            el.attrs
+                .retain(|(name, _)| !name.eq_ignore_ascii_case("style"));
+            let style = &self.styles[index];
+            if !style.is_empty() {
+                let value = style
+                    .iter()
+                    .map(|(p, v)| format!("{}: {}", p, v))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                el.attrs.push(("style".to_string(), Some(value)));
+            }
+            el.children = self.transform(el.children);
+            if self.tables && BLOCKS.contains(&tag.as_str()) {
+                out.push(HtmlNode::Element(table(el)));
+            } else {
+                out.push(HtmlNode::Element(el));
+            }
+        }
+        out
+    }
+}
+
+// `<div class="x">...</div>` ->
+// `<table role="presentation" ...><tr><td class="x">...</td></tr></table>`.
+fn table(el: Element) -> Element {
+    let cell = Element {
+        tag: "td".to_string(),
+        attrs: el.attrs,
+        children: el.children,
+    };
+    let row = Element {
+        tag: "tr".to_string(),
+        attrs: Vec::new(),
+        children: vec![HtmlNode::Element(cell)],
+    };
+    let attr = |name: &str, value: &str| (name.to_string(), Some(value.to_string()));
+    Element {
+        tag: "table".to_string(),
+        attrs: vec![
+            attr("role", "presentation"),
+            attr("width", "100%"),
+            attr("cellpadding", "0"),
+            attr("cellspacing", "0"),
+            attr("border", "0"),
+        ],
+        children: vec![HtmlNode::Element(row)],
+    }
+}
+
+fn check_css(html: &str, styles: &[Declarations]) -> Vec<Diagnostic> {
+    let mut seen = HashSet::new();
+    let mut warnings = Vec::new();
+    for (property, value) in styles.iter().flatten() {
+        let lower = value.to_ascii_lowercase();
+        let Some((_, _, why)) = UNSUPPORTED.iter().find(|(p, v, _)| {
+            (p.is_empty() || p == property) && (v.is_empty() || lower.contains(v))
+        }) else {
+            continue;
+        };
+        if !seen.insert((property.clone(), value.clone())) {
+            continue;
+        }
+        // Located at the first place the property is written.
+        let offset = html.find(property.as_str()).unwrap_or(0);
+        let before = &html[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = offset - before.rfind('\n').map_or(0, |i| i + 1);
+        warnings.push(Diagnostic::warning(
+            "email-unsupported-css",
+            format!("`{}: {}`: {}", property, value, why),
+            line,
+            column,
+        ));
+    }
+    warnings
+}
+
+/// Make rendered HTML safe for email clients: inline the rules of its
+/// `<style>` elements into `style` attributes, drop `<script>` elements and
+/// event handler attributes, and (with `tables=True`) turn `div`, `section`
+/// and similar containers into single-cell `role="presentation"` tables.
+///
+/// Rules that cannot be inlined, such as `@media` queries and `:hover`,
+/// are kept in a single `<style>` element. CSS that Outlook or most email
+/// clients ignore is reported in `warnings`.
+#[pyfunction]
+#[pyo3(signature = (html, tables=false))]
+pub fn email_html(html: &str, tables: bool) -> EmailHtml {
+    let nodes = parse_html(html);
+    let mut rules = Vec::new();
+    let mut kept = String::new();
+    collect_styles(&nodes, &mut rules, &mut kept);
+
+    let elements = html_elements(&nodes);
+    let styles: Vec<Declarations> = (0..elements.len())
+        .map(|i| {
+            let mut matched: Vec<(usize, &Rule)> = rules
+                .iter()
+                .enumerate()
+                .filter(|(_, rule)| rule.selector.matches(&elements, i))
+                .collect();
+            matched.sort_by_key(|(order, rule)| (rule.specificity, *order));
+            let mut style = Declarations::new();
+            let mut important = Declarations::new();
+            for (_, rule) in &matched {
+                for (property, value) in &rule.declarations {
+                    let target = if is_important(value) {
+                        &mut important
+                    } else {
+                        &mut style
+                    };
+                    set(target, property, value);
+                }
+            }
+            let own = elements[i]
+                .attrs
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("style"))
+                .and_then(|(_, value)| value.as_deref())
+                .map(declarations)
+                .unwrap_or_default();
+            let (own_important, own): (Declarations, Declarations) =
+                own.into_iter().partition(|(_, value)| is_important(value));
+            for (property, value) in own.iter().chain(&important).chain(&own_important) {
+                set(&mut style, property, value);
+            }
+            style
+        })
+        .collect();
+
+    let warnings = check_css(html, &styles);
+    let mut inliner = Inliner {
+        styles,
+        kept,
+        tables,
+        next: 0,
+    };
+    let nodes = inliner.transform(nodes);
+    let mut out = String::with_capacity(html.len());
+    for node in &nodes {
+        serialize_node(&mut out, node);
+    }
+    EmailHtml {
+        html: out,
+        warnings,
+    }
+}
@@ -0,0 +1,183 @@
+// Event contracts between components: `!emits a, b` declares the events a
+// component raises with `$emit("a", payload)`, and parents subscribe with
+// `<Child @a={...}>`.
+
+use crate::diagnostics::Diagnostic;
+use crate::kinds::event_spec;
+use crate::project::{ProjectIndex, is_component_tag};
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+
+const EMIT_CALL: &str = "$emit(";
+
+// A `$emit("name", ...)` call. `trigger` is the DOM event of the handler it
+// sits in, if any.
+pub(crate) struct EmitUsage {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+    pub trigger: Option<String>,
+}
+
+// Events declared by the document's `!emits` directives, with the line of
+// the first declaration of each.
+pub(crate) fn declared(doc: &ParsedDocument) -> Vec<(String, usize)> {
+    let mut out: Vec<(String, usize)> = Vec::new();
+    for d in doc.directives.iter().filter(|d| d.name == "emits") {
+        let content = d.content.as_deref().unwrap_or("");
+        for name in content.split(|c: char| c == ',' || c.is_whitespace()) {
+            if !name.is_empty() && !out.iter().any(|(n, _)| n == name) {
+                out.push((name.to_string(), d.line));
+            }
+        }
+    }
+    out
+}
+
+// Event names (with byte offsets) passed as string literals to `$emit`.
+fn emit_calls(src: &str) -> Vec<(String, usize)> {
+    let mut out = Vec::new();
+    for (i, _) in src.match_indices(EMIT_CALL) {
+        let arg = src[i + EMIT_CALL.len()..].trim_start();
+        let Some(quote) = arg.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        if let Some(end) = arg[1..].find(quote) {
+            out.push((arg[1..end + 1].to_string(), i));
+        }
+    }
+    out
+}
+
+fn template_usages(py: Python<'_>, nodes: &[Py<ParsedNode>], out: &mut Vec<EmitUsage>) {
+    for node in nodes {
+        let node = node.borrow(py);
+        let mut sources: Vec<(&str, Option<String>)> = Vec::new();
+        for (name, value) in &node.attributes {
+            if let Some(value) = value {
+                let trigger =
+                    event_spec(name).map(|e| e.split('.').next().unwrap_or(e).to_string());
+                sources.push((value, trigger));
+            }
+        }
+        if let Some(expr) = &node.expression {
+            sources.push((expr, None));
+        }
+        for (src, trigger) in sources {
+            for (name, _) in emit_calls(src) {
+                out.push(EmitUsage {
+                    name,
+                    line: node.line,
+                    column: node.column,
+                    trigger: trigger.clone(),
+                });
+            }
+        }
+        template_usages(py, &node.children, out);
+    }
+}
+
+// Every `$emit` call in the frontmatter and template of `doc`.
+pub(crate) fn usages(py: Python<'_>, doc: &ParsedDocument) -> Vec<EmitUsage> {
+    let mut out = Vec::new();
+    for (i, text) in doc.python_code.lines().enumerate() {
+        for (name, column) in emit_calls(text) {
+            out.push(EmitUsage {
+                name,
+                line: doc.python_line + i,
+                column,
+                trigger: None,
+            });
+        }
+    }
+    template_usages(py, &doc.template, &mut out);
+    out
+}
+
+// `@event` subscriptions on component usages: (tag, event, line, column).
+fn subscriptions(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    out: &mut Vec<(String, String, usize, usize)>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(tag) = node.tag.as_deref().filter(|t| is_component_tag(t)) {
+            for name in node.attributes.keys() {
+                if let Some(event) = event_spec(name) {
+                    let event = event.split('.').next().unwrap_or(event);
+                    out.push((tag.to_string(), event.to_string(), node.line, node.column));
+                }
+            }
+        }
+        subscriptions(py, &node.children, out);
+    }
+}
+
+impl ProjectIndex {
+    // Event contract diagnostics per file, for files with at least one.
+    pub(crate) fn event_report(&self, py: Python<'_>) -> BTreeMap<String, Vec<Diagnostic>> {
+        let _span = tracing::info_span!("analyze", pass = "emits").entered();
+        let mut report: BTreeMap<String, Vec<Diagnostic>> = BTreeMap::new();
+        let mut contracts = BTreeMap::new();
+        for (tag, file) in self.component_map(py) {
+            let doc = self.files[&file].borrow(py);
+            let events = declared(&doc);
+            if events.is_empty() {
+                continue;
+            }
+            let used = usages(py, &doc);
+            let out = report.entry(file.clone()).or_default();
+            for usage in &used {
+                if !events.iter().any(|(n, _)| *n == usage.name) {
+                    out.push(Diagnostic::error(
+                        "undeclared-emit",
+                        format!(
+                            "<{}> emits '{}' which !emits does not declare",
+                            tag, usage.name
+                        ),
+                        usage.line,
+                        usage.column,
+                    ));
+                }
+            }
+            for (name, line) in &events {
+                if !used.iter().any(|u| u.name == *name) {
+                    out.push(Diagnostic::warning(
+                        "unused-emit",
+                        format!("<{}> declares '{}' but never emits it", tag, name),
+                        *line,
+                        0,
+                    ));
+                }
+            }
+            contracts.insert(tag, (file, events));
+        }
+        for (path, doc) in &self.files {
+            let mut subs = Vec::new();
+            subscriptions(py, &doc.borrow(py).template, &mut subs);
+            for (tag, event, line, column) in subs {
+                let Some((file, events)) = contracts.get(&tag) else {
+                    continue;
+                };
+                if !events.iter().any(|(n, _)| *n == event) {
+                    report
+                        .entry(path.clone())
+                        .or_default()
+                        .push(Diagnostic::error(
+                            "unknown-event",
+                            format!(
+                                "<{}> does not emit '{}' (see !emits in {})",
+                                tag, event, file
+                            ),
+                            line,
+                            column,
+                        ));
+                }
+            }
+        }
+        report.retain(|_, diagnostics| !diagnostics.is_empty());
+        report
+    }
+}
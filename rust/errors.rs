@@ -0,0 +1,63 @@
+// Exception classes raised by the parser. Template problems derive from
+// `PyWireParseError`, itself a `ValueError` so callers written against the
+// old bare `ValueError`s keep working.
+
+use crate::diagnostics::Diagnostic;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    _pywire_parser,
+    PyWireParseError,
+    PyValueError,
+    "A template could not be parsed. Base class of the parser's template errors."
+);
+create_exception!(
+    _pywire_parser,
+    PyWireSyntaxError,
+    PyWireParseError,
+    "The template is malformed. Attributes `line` and `column` locate the problem."
+);
+create_exception!(
+    _pywire_parser,
+    PyWireLimitError,
+    PyWireParseError,
+    "The template exceeds a configured `ParseLimits` bound."
+);
+create_exception!(
+    _pywire_parser,
+    PyWireDirectiveError,
+    PyWireParseError,
+    "A `!directive` has an invalid or duplicated value."
+);
+create_exception!(
+    _pywire_parser,
+    PyWireGrammarError,
+    PyRuntimeError,
+    "The tree-sitter grammar could not be loaded or run."
+);
+
+// Exception for an error diagnostic, chosen by its code.
+pub(crate) fn from_diagnostic(py: Python<'_>, diagnostic: &Diagnostic) -> PyErr {
+    let message = format!(
+        "{} (line {}, column {})",
+        diagnostic.message, diagnostic.line, diagnostic.column
+    );
+    let err = match diagnostic.code.as_str() {
+        "limit-exceeded" => PyWireLimitError::new_err(message),
+        "invalid-directive" => PyWireDirectiveError::new_err(message),
+        "syntax-error"
+        | "map-error"
+        | "unclosed-tag"
+        | "unexpected-close-tag"
+        | "invalid-attribute"
+        | "unclosed-block"
+        | "unmatched-block-close" => PyWireSyntaxError::new_err(message),
+        _ => PyWireParseError::new_err(message),
+    };
+    let value = err.value(py);
+    let _ = value.setattr("line", diagnostic.line);
+    let _ = value.setattr("column", diagnostic.column);
+    err
+}
@@ -0,0 +1,76 @@
+// `{$example "Title"} ... {/example}` blocks: documented component states
+// for gallery tools. The compiler drops them from rendered output.
+
+use crate::ParsedNode;
+use pyo3::prelude::*;
+
+/// One `{$example}` block. `source` is the raw body between the markers and
+/// `children` its parsed nodes; positions refer to the whole template.
+#[pyclass(frozen)]
+pub struct Example {
+    #[pyo3(get)]
+    pub title: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub source: String,
+    #[pyo3(get)]
+    pub children: Vec<Py<ParsedNode>>,
+}
+
+#[pymethods]
+impl Example {
+    fn __repr__(&self) -> String {
+        format!("Example({:?} at {}:{})", self.title, self.line, self.column)
+    }
+}
+
+// `"Primary button"` -> `Primary button`; unquoted titles are kept as written.
+fn title(expression: Option<&str>) -> String {
+    let expr = expression.unwrap_or("").trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = expr.strip_prefix(quote).and_then(|e| e.strip_suffix(quote)) {
+            return inner.to_string();
+        }
+    }
+    expr.to_string()
+}
+
+fn collect(py: Python<'_>, source: &str, nodes: &[Py<ParsedNode>], out: &mut Vec<Example>) {
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = nodes[i].borrow(py);
+        if node.is_block
+            && node.block_keyword.as_deref() == Some("example")
+            && let Some(close) = node.close_index
+        {
+            let end = nodes[close].borrow(py).start_byte;
+            out.push(Example {
+                title: title(node.expression.as_deref()),
+                line: node.line,
+                column: node.column,
+                source: source.get(node.end_byte..end).unwrap_or("").to_string(),
+                children: nodes[i + 1..close]
+                    .iter()
+                    .map(|n| n.clone_ref(py))
+                    .collect(),
+            });
+            i = close + 1;
+            continue;
+        }
+        collect(py, source, &node.children, out);
+        i += 1;
+    }
+}
+
+/// Extract the `{$example "Title"}...{/example}` blocks of template `source`,
+/// in document order, for component-gallery tools.
+#[pyfunction]
+pub fn extract_examples(py: Python<'_>, source: &str) -> PyResult<Vec<Example>> {
+    let doc = crate::parse_with(py, source, &Default::default())?;
+    let mut out = Vec::new();
+    collect(py, source, &doc.template, &mut out);
+    Ok(out)
+}
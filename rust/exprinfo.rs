@@ -0,0 +1,179 @@
+// Static dependencies of template expressions: the free names an expression
+// reads, the attribute chains it follows and the values it subscripts. The
+// reactivity engine uses them to invalidate exactly the regions reading a
+// changed value instead of discovering reads while rendering.
+
+use crate::reactive::KEYWORDS;
+use crate::wires::{block_bindings, expression_reads};
+use pyo3::prelude::*;
+
+/// What an expression reads. `names` are its free names (comprehension and
+/// lambda variables excluded), `attribute_chains` the dotted paths it
+/// follows from them (`user.profile.name`; a called method is not part of
+/// the chain) and `subscripts` the values it indexes (`user.tags` in
+/// `user.tags[0]`). Each list is in first-use order without repeats.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ExpressionInfo {
+    #[pyo3(get)]
+    pub expression: String,
+    #[pyo3(get)]
+    pub names: Vec<String>,
+    #[pyo3(get)]
+    pub attribute_chains: Vec<String>,
+    #[pyo3(get)]
+    pub subscripts: Vec<String>,
+}
+
+#[pymethods]
+impl ExpressionInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "ExpressionInfo({:?}, names={:?})",
+            self.expression, self.names
+        )
+    }
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+// Index just past the string literal opening at `i`.
+fn skip_string(bytes: &[u8], i: usize) -> usize {
+    let quote = bytes[i];
+    let mut i = i + 1;
+    while i < bytes.len() && bytes[i] != quote {
+        i += if bytes[i] == b'\\' { 2 } else { 1 };
+    }
+    i + 1
+}
+
+// Attribute chains read by `expr`, e.g. `a.b` and `c.d.e` in
+// `a.b + c.d.e.f()`. String literals are skipped.
+pub(crate) fn chains(expr: &str) -> Vec<String> {
+    let bytes = expr.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'"' || b == b'\'' {
+            i = skip_string(bytes, i);
+            continue;
+        }
+        let starts = (b.is_ascii_alphabetic() || b == b'_')
+            && (i == 0 || !(is_ident(bytes[i - 1]) || bytes[i - 1] == b'.'));
+        if !starts {
+            i += 1;
+            continue;
+        }
+        let mut parts = Vec::new();
+        loop {
+            let start = i;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+            parts.push(&expr[start..i]);
+            let next = i + 1;
+            if bytes.get(i) == Some(&b'.')
+                && bytes
+                    .get(next)
+                    .is_some_and(|&c| c.is_ascii_alphabetic() || c == b'_')
+            {
+                i = next;
+            } else {
+                break;
+            }
+        }
+        if expr[i..].trim_start().starts_with('(') {
+            parts.pop();
+        }
+        if parts.len() >= 2 {
+            out.push(parts.join("."));
+        }
+    }
+    out
+}
+
+// Names and dotted paths directly followed by `[`: `items` in `items[0]`,
+// `user.tags` in `user.tags[-1]`.
+fn subscripts(expr: &str) -> Vec<&str> {
+    let bytes = expr.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = skip_string(bytes, i);
+                continue;
+            }
+            b'[' if i > 0 && is_ident(bytes[i - 1]) => {
+                let start = expr[..i]
+                    .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                    .map_or(0, |at| at + 1);
+                let target = &expr[start..i];
+                let valid = target.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && !KEYWORDS.contains(&target);
+                if valid {
+                    out.push(target);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    out
+}
+
+pub(crate) fn analyze(expr: &str) -> ExpressionInfo {
+    let mut names: Vec<String> = Vec::new();
+    for name in expression_reads(expr) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    let root = |path: &str| path.split('.').next().unwrap_or("").to_string();
+    let mut attribute_chains: Vec<String> = Vec::new();
+    for chain in chains(expr) {
+        if names.contains(&root(&chain)) && !attribute_chains.contains(&chain) {
+            attribute_chains.push(chain);
+        }
+    }
+    let mut subscript_targets: Vec<String> = Vec::new();
+    for target in subscripts(expr) {
+        if names.contains(&root(target)) && !subscript_targets.iter().any(|t| t == target) {
+            subscript_targets.push(target.to_string());
+        }
+    }
+    ExpressionInfo {
+        expression: expr.to_string(),
+        names,
+        attribute_chains,
+        subscripts: subscript_targets,
+    }
+}
+
+// Reads of a block marker's expression: the iterable of a `{$for}`, the
+// guard of a `{$case}` (minus its captures), all of an `{$if}`. Names the
+// marker binds are not reads.
+pub(crate) fn analyze_block(keyword: &str, expr: &str) -> ExpressionInfo {
+    let mut bound = Vec::new();
+    let from = block_bindings(keyword, expr, &mut bound).unwrap_or(0);
+    let mut info = analyze(expr.get(from..).unwrap_or(""));
+    info.expression = expr.to_string();
+    if !bound.is_empty() {
+        let root = |path: &String| path.split('.').next().unwrap_or("").to_string();
+        info.names.retain(|n| !bound.contains(n));
+        info.attribute_chains.retain(|c| !bound.contains(&root(c)));
+        info.subscripts.retain(|s| !bound.contains(&root(s)));
+    }
+    info
+}
+
+/// Names, attribute chains and subscripted values read by Python
+/// expression `expr`. Parsed templates carry the same analysis for every
+/// interpolation and block marker in `ParsedNode.expression_info`.
+#[pyfunction]
+pub fn analyze_expression(expr: &str) -> ExpressionInfo {
+    analyze(expr.trim())
+}
@@ -0,0 +1,332 @@
+// `!feed` templates: RSS 2.0 (`!feed` / `!feed rss`) or Atom (`!feed atom`).
+// The grammar is HTML-oriented, so a feed is parsed from a masked copy of
+// the source: CDATA sections and `<?xml ...?>` processing instructions
+// become plain text, and HTML void names
+// (`<link>`, `<source>`) are renamed so they keep their content. Mapping
+// reads the original source, whose byte offsets are unchanged, so tag names
+// and text come out as written. The template is then checked for the
+// structure and required fields of the format.
+
+use crate::diagnostics::Diagnostic;
+use crate::html::is_void;
+use crate::{ParsedDirective, ParsedNode};
+use pyo3::prelude::*;
+
+// (open, close) of markup passed through verbatim.
+const VERBATIM: &[(&str, &str)] = &[("<![CDATA[", "]]>"), ("<?", "?>")];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FeedFormat {
+    Rss,
+    Atom,
+}
+
+impl FeedFormat {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+        }
+    }
+
+    fn root(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "feed",
+        }
+    }
+}
+
+// Format declared by the `!feed` directive, if any.
+pub(crate) fn feed_format(directives: &[ParsedDirective]) -> Result<Option<FeedFormat>, String> {
+    let Some(directive) = directives.iter().find(|d| d.name == "feed") else {
+        return Ok(None);
+    };
+    match directive.content.as_deref().map(str::trim) {
+        None | Some("rss") => Ok(Some(FeedFormat::Rss)),
+        Some("atom") => Ok(Some(FeedFormat::Atom)),
+        Some(other) => Err(format!("!feed expects 'rss' or 'atom', got '{}'", other)),
+    }
+}
+
+fn declares_feed(source: &str) -> bool {
+    source.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("!feed")
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    })
+}
+
+// Byte ranges of the CDATA sections and processing instructions of
+// `source`; an unterminated one runs to the end.
+fn verbatim_ranges(source: &str) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut from = 0;
+    loop {
+        let next = VERBATIM
+            .iter()
+            .filter_map(|&(open, close)| Some((from + source[from..].find(open)?, open, close)))
+            .min_by_key(|&(start, _, _)| start);
+        let Some((start, open, close)) = next else {
+            return out;
+        };
+        let body = start + open.len();
+        let end = source[body..]
+            .find(close)
+            .map_or(source.len(), |i| body + i + close.len());
+        out.push((start, end));
+        from = end;
+    }
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b':'
+}
+
+// The source the grammar sees for a feed template; None when the template
+// is not a feed. Same length and line breaks as `source`.
+pub(crate) fn mask(source: &str) -> Option<String> {
+    if !declares_feed(source) {
+        return None;
+    }
+    let mut bytes = source.as_bytes().to_vec();
+    for (start, end) in verbatim_ranges(source) {
+        for b in &mut bytes[start..end] {
+            if *b != b'\n' {
+                *b = b'x';
+            }
+        }
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'<' {
+            i += 1;
+            continue;
+        }
+        let name_start = i + 1 + usize::from(bytes.get(i + 1) == Some(&b'/'));
+        let mut name_end = name_start;
+        while name_end < bytes.len() && is_name_byte(bytes[name_end]) {
+            name_end += 1;
+        }
+        let name = String::from_utf8_lossy(&bytes[name_start..name_end]).to_ascii_lowercase();
+        if is_void(&name) {
+            bytes[name_start] = b'x';
+        }
+        i = name_end.max(i + 1);
+    }
+    String::from_utf8(bytes).ok()
+}
+
+// Text nodes holding CDATA or a processing instruction are passed through
+// verbatim.
+pub(crate) fn keep_verbatim(py: Python<'_>, source: &str, nodes: &[Py<ParsedNode>]) {
+    let ranges = verbatim_ranges(source);
+    if !ranges.is_empty() {
+        mark_verbatim(py, source, &ranges, nodes);
+    }
+}
+
+fn mark_verbatim(
+    py: Python<'_>,
+    source: &str,
+    ranges: &[(usize, usize)],
+    nodes: &[Py<ParsedNode>],
+) {
+    for node in nodes {
+        let mut n = node.borrow_mut(py);
+        mark_verbatim(py, source, ranges, &n.children);
+        let is_text = n.tag.is_none() && !n.is_block && !n.is_comment;
+        let (start, end) = (n.start_byte, n.end_byte);
+        if is_text && ranges.iter().any(|&(s, e)| s < end && start < e) {
+            n.is_raw = true;
+            n.text_content = Some(source[start..end].to_string());
+        }
+    }
+}
+
+struct Checker {
+    format: FeedFormat,
+    out: Vec<Diagnostic>,
+}
+
+// Element children, looking through block markers and resolved blocks.
+fn elements(py: Python<'_>, nodes: &[Py<ParsedNode>], out: &mut Vec<Py<ParsedNode>>) {
+    for node in nodes {
+        let n = node.borrow(py);
+        if n.tag.is_some() {
+            out.push(node.clone_ref(py));
+        } else if n.is_block {
+            elements(py, &n.children, out);
+        }
+    }
+}
+
+fn children(py: Python<'_>, node: &ParsedNode) -> Vec<Py<ParsedNode>> {
+    let mut out = Vec::new();
+    elements(py, &node.children, &mut out);
+    out
+}
+
+fn has_child(py: Python<'_>, node: &ParsedNode, tag: &str) -> bool {
+    children(py, node)
+        .iter()
+        .any(|c| c.borrow(py).tag.as_deref() == Some(tag))
+}
+
+impl Checker {
+    fn error(&mut self, code: &str, message: String, node: &ParsedNode) {
+        self.out.push(
+            Diagnostic::error(code, message, node.line, node.column).spanning(
+                (node.end_line, node.end_column),
+                (node.start_byte, node.end_byte),
+            ),
+        );
+    }
+
+    fn require(&mut self, py: Python<'_>, node: &ParsedNode, fields: &[&str]) {
+        let tag = node.tag.as_deref().unwrap_or_default();
+        for field in fields {
+            if !has_child(py, node, field) {
+                self.error(
+                    "feed-missing-field",
+                    format!("<{}> is missing required <{}>", tag, field),
+                    node,
+                );
+            }
+        }
+    }
+
+    fn check(&mut self, py: Python<'_>, template: &[Py<ParsedNode>]) {
+        let mut roots = Vec::new();
+        elements(py, template, &mut roots);
+        let root_tag = self.format.root();
+        let Some(root) = roots.first() else {
+            return;
+        };
+        for extra in &roots[1..] {
+            self.error(
+                "feed-root",
+                "a feed has a single root element".to_string(),
+                &extra.borrow(py),
+            );
+        }
+        let root = root.borrow(py);
+        if root.tag.as_deref() != Some(root_tag) {
+            self.error(
+                "feed-root",
+                format!(
+                    "the root of {} feed must be <{}>",
+                    match self.format {
+                        FeedFormat::Rss => "an RSS",
+                        FeedFormat::Atom => "an Atom",
+                    },
+                    root_tag
+                ),
+                &root,
+            );
+            return;
+        }
+        match self.format {
+            FeedFormat::Rss => self.rss(py, &root),
+            FeedFormat::Atom => self.atom(py, &root),
+        }
+    }
+
+    fn rss(&mut self, py: Python<'_>, rss: &ParsedNode) {
+        if !rss.attributes.contains_key("version") {
+            self.error(
+                "feed-missing-field",
+                "<rss> is missing its version attribute".to_string(),
+                rss,
+            );
+        }
+        let channels: Vec<_> = children(py, rss)
+            .into_iter()
+            .filter(|c| c.borrow(py).tag.as_deref() == Some("channel"))
+            .collect();
+        match channels.as_slice() {
+            [] => self.error(
+                "feed-missing-field",
+                "<rss> is missing required <channel>".to_string(),
+                rss,
+            ),
+            [channel, rest @ ..] => {
+                for extra in rest {
+                    self.error(
+                        "feed-structure",
+                        "<rss> has a single <channel>".to_string(),
+                        &extra.borrow(py),
+                    );
+                }
+                let channel = channel.borrow(py);
+                self.require(py, &channel, &["title", "link", "description"]);
+                for item in children(py, &channel) {
+                    let item = item.borrow(py);
+                    if item.tag.as_deref() == Some("item")
+                        && !has_child(py, &item, "title")
+                        && !has_child(py, &item, "description")
+                    {
+                        self.error(
+                            "feed-missing-field",
+                            "<item> needs a <title> or a <description>".to_string(),
+                            &item,
+                        );
+                    }
+                }
+            }
+        }
+        self.misplaced(py, rss, "item", "channel");
+    }
+
+    fn atom(&mut self, py: Python<'_>, feed: &ParsedNode) {
+        self.require(py, feed, &["id", "title", "updated"]);
+        let entries: Vec<_> = children(py, feed)
+            .into_iter()
+            .filter(|c| c.borrow(py).tag.as_deref() == Some("entry"))
+            .collect();
+        let feed_author = has_child(py, feed, "author");
+        for entry in &entries {
+            let entry = entry.borrow(py);
+            self.require(py, &entry, &["id", "title", "updated"]);
+            if !feed_author && !has_child(py, &entry, "author") {
+                self.error(
+                    "feed-missing-field",
+                    "<entry> needs an <author> when <feed> has none".to_string(),
+                    &entry,
+                );
+            }
+        }
+        self.misplaced(py, feed, "entry", "feed");
+    }
+
+    // `tag` elements anywhere but directly inside `parent`.
+    fn misplaced(&mut self, py: Python<'_>, node: &ParsedNode, tag: &str, parent: &str) {
+        let is_parent = node.tag.as_deref() == Some(parent);
+        for child in children(py, node) {
+            let child = child.borrow(py);
+            if !is_parent && child.tag.as_deref() == Some(tag) {
+                self.error(
+                    "feed-structure",
+                    format!("<{}> belongs directly inside <{}>", tag, parent),
+                    &child,
+                );
+            }
+            self.misplaced(py, &child, tag, parent);
+        }
+    }
+}
+
+// Structure and required-field errors ("feed-root", "feed-structure",
+// "feed-missing-field") of a feed template.
+pub(crate) fn check(
+    py: Python<'_>,
+    format: FeedFormat,
+    template: &[Py<ParsedNode>],
+) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        format,
+        out: Vec::new(),
+    };
+    checker.check(py, template);
+    checker.out
+}
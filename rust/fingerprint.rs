@@ -0,0 +1,193 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{
+    PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple,
+};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over explicit byte input. Unlike `std`'s SipHash it is stable across
+/// processes and Rust versions, which memo caches persisted in snapshots rely on.
+#[derive(Clone, Copy)]
+pub(crate) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(crate) fn new() -> Self {
+        Fnv64(FNV_OFFSET)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub(crate) fn write_u64(&mut self, v: u64) {
+        self.write(&v.to_le_bytes());
+    }
+
+    // splitmix64 finalizer: FNV alone distributes short inputs poorly in the high bits
+    pub(crate) fn finish(self) -> u64 {
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+const TAG_NONE: u8 = 0;
+const TAG_BOOL: u8 = 1;
+const TAG_INT: u8 = 2;
+const TAG_FLOAT: u8 = 3;
+const TAG_STR: u8 = 4;
+const TAG_BYTES: u8 = 5;
+const TAG_LIST: u8 = 6;
+const TAG_TUPLE: u8 = 7;
+const TAG_DICT: u8 = 8;
+const TAG_SET: u8 = 9;
+const TAG_CYCLE: u8 = 10;
+const TAG_TRUNCATED: u8 = 11;
+
+struct Fingerprinter {
+    max_depth: usize,
+    // containers on the current path, for cycle detection
+    path: Vec<usize>,
+}
+
+impl Fingerprinter {
+    fn hash(&mut self, obj: &Bound<'_, PyAny>) -> PyResult<u64> {
+        let mut h = Fnv64::new();
+        self.feed(&mut h, obj)?;
+        Ok(h.finish())
+    }
+
+    fn feed(&mut self, h: &mut Fnv64, obj: &Bound<'_, PyAny>) -> PyResult<()> {
+        if obj.is_none() {
+            h.write(&[TAG_NONE]);
+            return Ok(());
+        }
+        if let Ok(b) = obj.downcast::<PyBool>() {
+            h.write(&[TAG_BOOL, b.is_true() as u8]);
+            return Ok(());
+        }
+        if obj.is_instance_of::<PyInt>() {
+            h.write(&[TAG_INT]);
+            match obj.extract::<i64>() {
+                Ok(v) => h.write(&v.to_le_bytes()),
+                Err(_) => h.write(obj.str()?.to_str()?.as_bytes()),
+            }
+            return Ok(());
+        }
+        if let Ok(f) = obj.downcast::<PyFloat>() {
+            h.write(&[TAG_FLOAT]);
+            let v = f.value();
+            // -0.0 == 0.0 in Python, so they must fingerprint identically
+            let v = if v == 0.0 { 0.0 } else { v };
+            h.write(&v.to_bits().to_le_bytes());
+            return Ok(());
+        }
+        if let Ok(s) = obj.downcast::<PyString>() {
+            let s = s.to_str()?;
+            h.write(&[TAG_STR]);
+            h.write_u64(s.len() as u64);
+            h.write(s.as_bytes());
+            return Ok(());
+        }
+        if let Ok(b) = obj.downcast::<PyBytes>() {
+            h.write(&[TAG_BYTES]);
+            h.write_u64(b.as_bytes().len() as u64);
+            h.write(b.as_bytes());
+            return Ok(());
+        }
+
+        let is_container = obj.is_instance_of::<PyList>()
+            || obj.is_instance_of::<PyTuple>()
+            || obj.is_instance_of::<PyDict>()
+            || obj.is_instance_of::<PySet>()
+            || obj.is_instance_of::<PyFrozenSet>();
+        if !is_container {
+            return Err(PyTypeError::new_err(format!(
+                "Cannot fingerprint value of type '{}'",
+                obj.get_type().name()?
+            )));
+        }
+
+        let addr = obj.as_ptr() as usize;
+        if let Some(pos) = self.path.iter().position(|&a| a == addr) {
+            // back-reference by path position keeps the result stable across runs
+            h.write(&[TAG_CYCLE]);
+            h.write_u64(pos as u64);
+            return Ok(());
+        }
+        if self.path.len() >= self.max_depth {
+            h.write(&[TAG_TRUNCATED]);
+            h.write_u64(obj.len()? as u64);
+            return Ok(());
+        }
+
+        self.path.push(addr);
+        let result = self.feed_container(h, obj);
+        self.path.pop();
+        result
+    }
+
+    fn feed_container(&mut self, h: &mut Fnv64, obj: &Bound<'_, PyAny>) -> PyResult<()> {
+        if let Ok(list) = obj.downcast::<PyList>() {
+            h.write(&[TAG_LIST]);
+            h.write_u64(list.len() as u64);
+            for item in list.iter() {
+                self.feed(h, &item)?;
+            }
+        } else if let Ok(tuple) = obj.downcast::<PyTuple>() {
+            h.write(&[TAG_TUPLE]);
+            h.write_u64(tuple.len() as u64);
+            for item in tuple.iter() {
+                self.feed(h, &item)?;
+            }
+        } else if let Ok(dict) = obj.downcast::<PyDict>() {
+            // order-independent: hash each entry separately, then combine sorted
+            let mut entries = Vec::with_capacity(dict.len());
+            for (key, value) in dict.iter() {
+                let mut eh = Fnv64::new();
+                self.feed(&mut eh, &key)?;
+                self.feed(&mut eh, &value)?;
+                entries.push(eh.finish());
+            }
+            entries.sort_unstable();
+            h.write(&[TAG_DICT]);
+            h.write_u64(entries.len() as u64);
+            for e in entries {
+                h.write_u64(e);
+            }
+        } else {
+            let mut items = Vec::new();
+            for item in obj.try_iter()? {
+                items.push(self.hash(&item?)?);
+            }
+            items.sort_unstable();
+            h.write(&[TAG_SET]);
+            h.write_u64(items.len() as u64);
+            for i in items {
+                h.write_u64(i);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stable 64-bit structural hash of nested builtins, for memo argument comparison.
+///
+/// Dicts and sets hash independently of iteration order, cycles hash to a
+/// back-reference marker, and containers nested deeper than `max_depth`
+/// contribute only their length.
+#[pyfunction]
+#[pyo3(signature = (obj, max_depth=64))]
+pub fn value_fingerprint(obj: &Bound<'_, PyAny>, max_depth: usize) -> PyResult<u64> {
+    Fingerprinter {
+        max_depth,
+        path: Vec::new(),
+    }
+    .hash(obj)
+}
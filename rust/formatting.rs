@@ -0,0 +1,367 @@
+// Whitespace formatter for `.wire` files and its `--check` mode. The rules
+// only touch layout that never reaches rendered output:
+//   - line endings become `\n`
+//   - trailing whitespace is removed, except inside `<pre>`/`<textarea>`
+//   - leading blank lines are dropped and runs of blank lines are capped at two
+//   - a non-empty file ends with exactly one newline
+//
+// `format` additionally re-indents the template from its parse tree: every
+// line starting a tag, brace block, text or directive is indented by its
+// nesting depth, and the other lines of a node move along with the line
+// above. The frontmatter and the content of `<pre>`, `<textarea>`,
+// `<script>`, `<style>` and comments are left as written.
+
+use crate::textdiff::{Op, myers};
+use crate::{ParseOptions, ParsedNode, columns, parse_with};
+use pyo3::prelude::*;
+
+const CONTEXT: usize = 3;
+// Edit distance (in lines) above which `unified_diff` stops minimising.
+const MAX_DIFF_COST: usize = 1000;
+const MAX_BLANK_LINES: usize = 2;
+
+// Elements whose text is rendered verbatim.
+const VERBATIM: &[&str] = &["pre", "textarea"];
+
+// Elements whose content `format` and `minify` leave as written.
+pub(crate) const KEEP_CONTENT: &[&str] = &["pre", "textarea", "script", "style"];
+
+fn verbatim_depth(line: &str, depth: usize) -> usize {
+    let lower = line.to_ascii_lowercase();
+    let mut depth = depth;
+    for tag in VERBATIM {
+        depth += lower.matches(&format!("<{}", tag)).count();
+        depth = depth.saturating_sub(lower.matches(&format!("</{}", tag)).count());
+    }
+    depth
+}
+
+pub(crate) fn format_text(source: &str) -> String {
+    let source = source.replace("\r\n", "\n").replace('\r', "\n");
+    let mut out = String::with_capacity(source.len());
+    let mut blank_run = 0;
+    let mut depth = 0;
+    for line in source.split('\n') {
+        let verbatim = depth > 0;
+        depth = verbatim_depth(line, depth);
+        let line = if verbatim { line } else { line.trim_end() };
+        if line.is_empty() && !verbatim {
+            blank_run += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            for _ in 0..blank_run.min(MAX_BLANK_LINES) {
+                out.push('\n');
+            }
+        }
+        blank_run = 0;
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+// Target depth of the lines that start a node, and the lines `format`
+// must leave alone. Lines are 0-based.
+struct Layout<'a> {
+    lines: Vec<&'a str>,
+    starts: Vec<usize>,
+    // Byte offset of each line's first non-blank character.
+    first: Vec<usize>,
+    depth: Vec<Option<usize>>,
+    // Inside some node, so it moves with the line above.
+    covered: Vec<bool>,
+    keep: Vec<bool>,
+}
+
+impl Layout<'_> {
+    // Indent `line` (1-based) to `depth` if the node at byte `offset` is
+    // the first thing on it.
+    fn anchor(&mut self, line: usize, offset: usize, depth: usize) {
+        let i = line - 1;
+        if i < self.lines.len() && self.first[i] == offset && self.depth[i].is_none() {
+            self.depth[i] = Some(depth);
+        }
+    }
+
+    fn mark(&mut self, lines: std::ops::RangeInclusive<usize>, keep: bool) {
+        for line in lines {
+            if let Some(i) = line.checked_sub(1).filter(|&i| i < self.lines.len()) {
+                self.covered[i] = true;
+                self.keep[i] |= keep;
+            }
+        }
+    }
+
+    fn visit(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>], depth: usize) {
+        let mut open: Vec<usize> = Vec::new();
+        for (i, node) in nodes.iter().enumerate() {
+            let node = node.borrow(py);
+            open.retain(|&close| close >= i);
+            let enclosing = open.iter().filter(|&&close| close > i).count();
+            let here = match node.open_index {
+                // A closing marker sits at its opener's depth, a branch
+                // marker (`{$else}`) at the depth of the still-open opener.
+                Some(_) if open.contains(&i) => depth + enclosing,
+                Some(_) => depth + enclosing.saturating_sub(1),
+                None => depth + enclosing,
+            };
+            if let (Some(close), None) = (node.close_index, node.open_index) {
+                open.push(close);
+            }
+            self.mark(node.line..=node.end_line, false);
+
+            let Some(tag) = node.tag.as_deref() else {
+                // Text and multi-line comments: every line starting inside
+                // the node is indented, unless it is kept verbatim.
+                if node.is_comment || node.is_raw {
+                    self.anchor(node.line, node.start_byte, here);
+                    self.mark(node.line + 1..=node.end_line, true);
+                    continue;
+                }
+                for line in node.line..=node.end_line {
+                    let first = self.first.get(line - 1).copied().unwrap_or(usize::MAX);
+                    if (node.start_byte..node.end_byte).contains(&first) {
+                        self.anchor(line, first, here);
+                    }
+                }
+                continue;
+            };
+            self.anchor(node.line, node.start_byte, here);
+            if KEEP_CONTENT.iter().any(|t| tag.eq_ignore_ascii_case(t)) {
+                self.mark(node.line + 1..=node.end_line, true);
+                continue;
+            }
+            if let (Some(line), Some(column)) = (node.close_line, node.close_column)
+                && let Some(&start) = self.starts.get(line - 1)
+            {
+                self.anchor(line, start + column, here);
+            }
+            self.visit(py, &node.children, here + 1);
+        }
+    }
+}
+
+fn indent_width(text: &str, width: usize) -> usize {
+    text.chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .map(|c| if c == '\t' { width } else { 1 })
+        .sum()
+}
+
+fn indent_string(columns: usize, width: usize, use_tabs: bool) -> String {
+    if use_tabs && width > 0 {
+        format!(
+            "{}{}",
+            "\t".repeat(columns / width),
+            " ".repeat(columns % width)
+        )
+    } else {
+        " ".repeat(columns)
+    }
+}
+
+fn reindent(py: Python<'_>, source: &str, width: usize, use_tabs: bool) -> PyResult<String> {
+    let source = source.replace("\r\n", "\n").replace('\r', "\n");
+    let options = ParseOptions {
+        tab_width: columns::DEFAULT_TAB_WIDTH,
+        ..Default::default()
+    };
+    let doc = parse_with(py, &source, &options)?;
+    doc.raise_for_errors(py)?;
+
+    let lines: Vec<&str> = source.split('\n').collect();
+    let (mut starts, mut first) = (Vec::new(), Vec::new());
+    let mut offset = 0;
+    for line in &lines {
+        starts.push(offset);
+        first.push(offset + (line.len() - line.trim_start_matches([' ', '\t']).len()));
+        offset += line.len() + 1;
+    }
+    let count = lines.len();
+    let mut layout = Layout {
+        lines,
+        starts,
+        first,
+        depth: vec![None; count],
+        covered: vec![false; count],
+        keep: vec![false; count],
+    };
+    for d in &doc.directives {
+        layout.mark(d.line..=d.end_line, false);
+        layout.anchor(d.line, d.start_byte, 0);
+    }
+    layout.visit(py, &doc.template, 0);
+
+    let mut out = String::with_capacity(source.len());
+    let mut shift: isize = 0;
+    for (i, line) in layout.lines.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let content = line.trim_start_matches([' ', '\t']);
+        let old = indent_width(line, width) as isize;
+        let new = match layout.depth[i] {
+            _ if layout.keep[i] || content.is_empty() => None,
+            Some(depth) => Some((depth * width) as isize),
+            None if layout.covered[i] => Some((old + shift).max(0)),
+            None => None,
+        };
+        match new {
+            Some(new) => {
+                if layout.depth[i].is_some() {
+                    shift = new - old;
+                }
+                out.push_str(&indent_string(new as usize, width, use_tabs));
+                out.push_str(content);
+            }
+            None => out.push_str(line),
+        }
+    }
+    Ok(format_text(&out))
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+// Unified diff (`diff -u` layout) from `old` to `new`; empty when equal.
+pub(crate) fn unified_diff(old: &str, new: &str, path: &str) -> String {
+    let a: Vec<&str> = old.split_inclusive('\n').collect();
+    let b: Vec<&str> = new.split_inclusive('\n').collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    // Past MAX_DIFF_COST changed lines (e.g. a CRLF -> LF rewrite) the middle
+    // becomes one replace hunk rather than a minimal diff.
+    let middle = myers(a_mid, b_mid, MAX_DIFF_COST).unwrap_or_else(|| {
+        let mut ops = vec![Op::Delete; a_mid.len()];
+        ops.resize(a_mid.len() + b_mid.len(), Op::Insert);
+        ops
+    });
+    let mut ops = vec![Op::Equal; prefix];
+    ops.extend(middle);
+    ops.resize(ops.len() + suffix, Op::Equal);
+
+    // Line positions in `a` and `b` before each op.
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut ai, mut bi) = (0, 0);
+    for op in &ops {
+        positions.push((ai, bi));
+        match op {
+            Op::Equal => (ai, bi) = (ai + 1, bi + 1),
+            Op::Delete => ai += 1,
+            Op::Insert => bi += 1,
+        }
+    }
+    positions.push((ai, bi));
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| ops[i] != Op::Equal).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut group_start = 0;
+    while group_start < changes.len() {
+        let mut group_end = group_start;
+        while group_end + 1 < changes.len()
+            && changes[group_end + 1] - changes[group_end] <= 2 * CONTEXT + 1
+        {
+            group_end += 1;
+        }
+        let first = changes[group_start].saturating_sub(CONTEXT);
+        let last = (changes[group_end] + CONTEXT + 1).min(ops.len());
+        let (a_start, b_start) = positions[first];
+        let (a_end, b_end) = positions[last];
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(a_start, a_end - a_start),
+            hunk_range(b_start, b_end - b_start)
+        ));
+        for i in first..last {
+            let (ai, bi) = positions[i];
+            match ops[i] {
+                Op::Equal => push_line(&mut out, ' ', a[ai]),
+                Op::Delete => push_line(&mut out, '-', a[ai]),
+                Op::Insert => push_line(&mut out, '+', b[bi]),
+            }
+        }
+        group_start = group_end + 1;
+    }
+    out
+}
+
+/// Result of `format_check`: whether the source is already formatted and,
+/// if not, a unified diff of what `format_source` would change.
+#[pyclass(frozen)]
+pub struct FormatCheck {
+    #[pyo3(get)]
+    pub is_formatted: bool,
+    #[pyo3(get)]
+    pub diff: String,
+}
+
+#[pymethods]
+impl FormatCheck {
+    fn __bool__(&self) -> bool {
+        self.is_formatted
+    }
+
+    fn __repr__(&self) -> String {
+        format!("FormatCheck(is_formatted={})", self.is_formatted)
+    }
+}
+
+/// Format a `.wire` source: normalized line endings, no trailing whitespace
+/// outside `<pre>`/`<textarea>`, at most two blank lines in a row and one
+/// final newline.
+#[pyfunction]
+pub fn format_source(py: Python<'_>, source: &str) -> String {
+    py.detach(|| format_text(source))
+}
+
+/// Format a `.wire` source like `format_source` and re-indent its template
+/// by nesting depth: `indent` columns per level, as tabs with
+/// `use_tabs=True`. The frontmatter and verbatim content are kept as
+/// written. Raises the parser's exception if the source has syntax errors.
+#[pyfunction]
+#[pyo3(name = "format", signature = (source, indent=2, use_tabs=false))]
+pub fn format_template(
+    py: Python<'_>,
+    source: &str,
+    indent: usize,
+    use_tabs: bool,
+) -> PyResult<String> {
+    reindent(py, source, indent, use_tabs)
+}
+
+/// Check mode of `format_source`, without writing anything. `path` labels the
+/// `--- a/` and `+++ b/` lines of the diff.
+#[pyfunction]
+#[pyo3(signature = (source, path="<source>"))]
+pub fn format_check(py: Python<'_>, source: &str, path: &str) -> FormatCheck {
+    py.detach(|| {
+        let formatted = format_text(source);
+        FormatCheck {
+            is_formatted: formatted == source,
+            diff: unified_diff(source, &formatted, path),
+        }
+    })
+}
@@ -0,0 +1,144 @@
+// Templates embedded in Python string literals, e.g. `html("""<b>{x}</b>""")`
+// in the frontmatter. Each literal is parsed in place with tree-sitter's
+// included ranges, so positions are already those of the outer file.
+
+use crate::{ParseOptions, ParsedDocument};
+use pyo3::prelude::*;
+use tree_sitter::{Point, Range};
+
+// Calls whose string argument is template markup; `x.html(...)` counts too.
+const FRAGMENT_CALLS: &[&str] = &["html"];
+
+/// A template found in a Python string literal. `line`/`column` point at the
+/// first character inside the quotes; positions in `document` are relative
+/// to the outer file. Escape sequences in the literal are not interpreted.
+#[pyclass(frozen)]
+pub struct Fragment {
+    #[pyo3(get)]
+    pub function: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub document: Py<ParsedDocument>,
+}
+
+#[pymethods]
+impl Fragment {
+    fn __repr__(&self) -> String {
+        format!(
+            "Fragment({}() at {}:{})",
+            self.function, self.line, self.column
+        )
+    }
+}
+
+// Dotted callee when `before` ends in `<callee>(`, if it is a fragment call.
+fn fragment_call(before: &str) -> Option<&str> {
+    let before = before.trim_end().strip_suffix('(')?.trim_end();
+    let start = before
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .map_or(0, |i| i + 1);
+    let callee = &before[start..];
+    let last = callee.rsplit('.').next()?;
+    FRAGMENT_CALLS.contains(&last).then_some(callee)
+}
+
+// Byte ranges (relative to `code`) of the contents of string literals passed
+// to fragment calls. Comments and other literals are skipped over.
+fn literals(code: &str) -> Vec<(&str, usize, usize)> {
+    let bytes = code.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => i = code[i..].find('\n').map_or(bytes.len(), |n| i + n),
+            q @ (b'"' | b'\'') => {
+                let delim = if bytes[i..].starts_with(&[q; 3]) {
+                    3
+                } else {
+                    1
+                };
+                let start = i + delim;
+                let mut j = start;
+                let end = loop {
+                    if j >= bytes.len() || (delim == 1 && bytes[j] == b'\n') {
+                        break None;
+                    }
+                    if bytes[j] == b'\\' {
+                        j += 2;
+                        continue;
+                    }
+                    if bytes[j..].starts_with(&[q; 3][..delim]) {
+                        break Some(j);
+                    }
+                    j += 1;
+                };
+                let Some(end) = end else {
+                    return found;
+                };
+                let prefix_len = code[..i]
+                    .bytes()
+                    .rev()
+                    .take_while(u8::is_ascii_alphabetic)
+                    .count();
+                let prefix = code[i - prefix_len..i].to_ascii_lowercase();
+                if matches!(prefix.as_str(), "" | "r" | "u")
+                    && let Some(callee) = fragment_call(&code[..i - prefix_len])
+                {
+                    found.push((callee, start, end));
+                }
+                i = end + delim;
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+pub(crate) fn point_at(source: &str, offset: usize) -> Point {
+    let before = &source[..offset];
+    Point {
+        row: before.matches('\n').count(),
+        column: offset - before.rfind('\n').map_or(0, |i| i + 1),
+    }
+}
+
+// Parse every fragment inside the Python byte ranges `python` of `source`.
+pub(crate) fn parse_fragments(
+    py: Python<'_>,
+    source: &str,
+    python: &[(usize, usize)],
+    options: &ParseOptions<'_>,
+) -> PyResult<Vec<Py<Fragment>>> {
+    let options = ParseOptions {
+        fragments: false,
+        // Fragment names are locals of the function building them.
+        validate_names: false,
+        ..*options
+    };
+    let mut out = Vec::new();
+    for &(base, end) in python {
+        for (callee, start, stop) in literals(&source[base..end]) {
+            let range = Range {
+                start_byte: base + start,
+                end_byte: base + stop,
+                start_point: point_at(source, base + start),
+                end_point: point_at(source, base + stop),
+            };
+            let tree = crate::parse_range(source, range)?;
+            let document = crate::map_document(py, source, &tree, &options)?;
+            out.push(Py::new(
+                py,
+                Fragment {
+                    function: callee.to_string(),
+                    line: range.start_point.row + 1,
+                    column: range.start_point.column,
+                    document: Py::new(py, document)?,
+                },
+            )?);
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,207 @@
+// Static summary of the frontmatter: what it defines at module level, the
+// wires it declares, what it imports and its async functions. The framework
+// registers wires and checks template references from this without running
+// the module. Only top-level statements are read; like the other frontmatter
+// analyses this works on lines, not a Python syntax tree.
+
+use crate::ParsedDocument;
+use crate::wires::{definitions, simple_assignment, wire_constructor};
+use pyo3::prelude::*;
+
+/// A top-level `name = wire(...)` (or `WireList(...)`, `WireDict(...)`,
+/// ...) in the frontmatter. `constructor` is the called name without its
+/// module and `value` the call as written.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct WireDeclaration {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub constructor: String,
+    #[pyo3(get)]
+    pub value: String,
+    #[pyo3(get)]
+    pub line: usize,
+}
+
+#[pymethods]
+impl WireDeclaration {
+    fn __repr__(&self) -> String {
+        format!("WireDeclaration({} = {})", self.name, self.value)
+    }
+}
+
+/// One name bound by an import. `import a.b as c` gives module "a.b",
+/// name None and bound "c"; `from .x import y` gives module ".x", name "y"
+/// and bound "y".
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct FrontmatterImport {
+    #[pyo3(get)]
+    pub module: String,
+    #[pyo3(get)]
+    pub name: Option<String>,
+    #[pyo3(get)]
+    pub bound: String,
+    #[pyo3(get)]
+    pub line: usize,
+}
+
+#[pymethods]
+impl FrontmatterImport {
+    fn __repr__(&self) -> String {
+        match &self.name {
+            Some(name) => format!("FrontmatterImport(from {} import {})", self.module, name),
+            None => format!("FrontmatterImport(import {})", self.module),
+        }
+    }
+}
+
+/// Result of `frontmatter_info`. `names` are the module-level names the
+/// frontmatter binds (assignments, functions, classes and imports) in
+/// first-definition order; `async_functions` the names of its top-level
+/// `async def`s.
+#[pyclass(frozen)]
+pub struct FrontmatterInfo {
+    #[pyo3(get)]
+    pub names: Vec<String>,
+    #[pyo3(get)]
+    pub wires: Vec<WireDeclaration>,
+    #[pyo3(get)]
+    pub imports: Vec<FrontmatterImport>,
+    #[pyo3(get)]
+    pub async_functions: Vec<String>,
+}
+
+#[pymethods]
+impl FrontmatterInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "FrontmatterInfo(names={:?}, wires={})",
+            self.names,
+            self.wires.len()
+        )
+    }
+}
+
+// `(name, alias)` pairs of an import list: `a as b, c`.
+fn imported_names(list: &str) -> Vec<(&str, &str)> {
+    list.trim_matches(['(', ')', ' '])
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once(" as ") {
+            Some((name, alias)) => (name.trim(), alias.trim()),
+            None => (part, part),
+        })
+        .collect()
+}
+
+fn imports(stmt: &str, line: usize, out: &mut Vec<FrontmatterImport>) {
+    let stmt = stmt.split('#').next().unwrap_or("").trim_end();
+    if let Some(list) = stmt.strip_prefix("import ") {
+        for (module, alias) in imported_names(list) {
+            // `import a.b` binds `a`.
+            let bound = if module == alias {
+                module.split('.').next().unwrap_or(module)
+            } else {
+                alias
+            };
+            out.push(FrontmatterImport {
+                module: module.to_string(),
+                name: None,
+                bound: bound.to_string(),
+                line,
+            });
+        }
+    } else if let Some((module, list)) = stmt
+        .strip_prefix("from ")
+        .and_then(|rest| rest.split_once(" import "))
+    {
+        for (name, alias) in imported_names(list) {
+            out.push(FrontmatterImport {
+                module: module.trim().to_string(),
+                name: Some(name.to_string()),
+                bound: alias.to_string(),
+                line,
+            });
+        }
+    }
+}
+
+// Top-level statements of `code` with the line each starts on. A statement
+// continues over lines while brackets are open, so parenthesized import
+// lists come out whole.
+fn statements(code: &str, first_line: usize) -> Vec<(String, usize)> {
+    let mut out: Vec<(String, usize)> = Vec::new();
+    let mut depth = 0i32;
+    for (i, text) in code.lines().enumerate() {
+        let code = text.split('#').next().unwrap_or("");
+        if depth > 0 {
+            if let Some((stmt, _)) = out.last_mut() {
+                stmt.push(' ');
+                stmt.push_str(code.trim());
+            }
+        } else if !text.starts_with(char::is_whitespace) && !text.trim().is_empty() {
+            out.push((text.trim_end().to_string(), first_line + i));
+        } else {
+            continue;
+        }
+        depth += code.matches(['(', '[', '{']).count() as i32;
+        depth -= code.matches([')', ']', '}']).count() as i32;
+        depth = depth.max(0);
+    }
+    out
+}
+
+// Names the frontmatter `code` binds at module level, in first-definition
+// order.
+pub(crate) fn module_names(code: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for (stmt, _) in statements(code, 1) {
+        let mut defined = Vec::new();
+        definitions(&stmt, &mut defined);
+        for name in defined {
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Summarize the frontmatter of `document` without executing it: the
+/// module-level names it binds, its wire declarations, imports and top-level
+/// async functions (see `FrontmatterInfo`).
+#[pyfunction]
+pub fn frontmatter_info(document: PyRef<'_, ParsedDocument>) -> FrontmatterInfo {
+    let names = module_names(&document.python_code);
+    let mut wires = Vec::new();
+    let mut found_imports = Vec::new();
+    let mut async_functions = Vec::new();
+    for (stmt, line) in statements(&document.python_code, document.python_line) {
+        imports(&stmt, line, &mut found_imports);
+        if let Some(rest) = stmt.strip_prefix("async def ") {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            async_functions.push(rest[..end].to_string());
+        }
+        if let Some((name, value)) = simple_assignment(&stmt)
+            && let Some(constructor) = wire_constructor(value)
+        {
+            wires.push(WireDeclaration {
+                name: name.to_string(),
+                constructor: constructor.to_string(),
+                value: value.to_string(),
+                line,
+            });
+        }
+    }
+    FrontmatterInfo {
+        names,
+        wires,
+        imports: found_imports,
+        async_functions,
+    }
+}
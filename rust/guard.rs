@@ -0,0 +1,66 @@
+// Panic safety net for the parse pipeline. A panic is turned into a Python
+// exception carrying enough context to reproduce it instead of unwinding into
+// the interpreter.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::any::Any;
+use std::cell::Cell;
+use xxhash_rust::xxh3::xxh3_64;
+
+create_exception!(
+    _pywire_parser,
+    InternalParserError,
+    PyRuntimeError,
+    "A bug in the template parser. Attributes `source_hash`, `grammar_version` and `byte_offset` identify the failing input."
+);
+
+thread_local! {
+    static OFFSET: Cell<usize> = const { Cell::new(0) };
+}
+
+// Remember the byte offset being processed for the panic report.
+pub(crate) fn record_offset(offset: usize) {
+    OFFSET.with(|o| o.set(offset));
+}
+
+pub(crate) fn current_offset() -> usize {
+    OFFSET.with(Cell::get)
+}
+
+pub(crate) fn grammar_version() -> String {
+    let language: tree_sitter::Language = tree_sitter_pywire::language() as _;
+    format!("{} (abi {})", env!("CARGO_PKG_VERSION"), language.version())
+}
+
+pub(crate) fn source_hash(source: &str) -> String {
+    format!("{:016x}", xxh3_64(source.as_bytes()))
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+pub(crate) fn internal_error(py: Python<'_>, source: &str, payload: &(dyn Any + Send)) -> PyErr {
+    let hash = source_hash(source);
+    let grammar = grammar_version();
+    let offset = current_offset();
+    let err = InternalParserError::new_err(format!(
+        "internal parser error at byte {} (source {}, grammar {}): {}",
+        offset,
+        hash,
+        grammar,
+        panic_message(payload)
+    ));
+    let value = err.value(py);
+    // Best effort: the message already carries the same context.
+    let _ = value.setattr("source_hash", hash);
+    let _ = value.setattr("grammar_version", grammar);
+    let _ = value.setattr("byte_offset", offset);
+    err
+}
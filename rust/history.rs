@@ -0,0 +1,331 @@
+// Bounded history of wire-state snapshots for the dev tools' time-travel
+// panel. States are kept in the binary snapshot encoding (see `dumps_state`),
+// which is also what gets diffed: equal subtrees have equal bytes, so the
+// diff only descends where the encodings differ and decodes just the values
+// it reports.
+
+use crate::snapshot::{
+    MAX_DEPTH, Reader, TAG_BIGINT, TAG_BYTES, TAG_DICT, TAG_FALSE, TAG_FLOAT, TAG_FROZENSET,
+    TAG_INT, TAG_LIST, TAG_NONE, TAG_SET, TAG_STR, TAG_TRUE, TAG_TUPLE, decode_value, encode_value,
+};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// One difference between two recorded states. `path` locates it from the
+/// top-level state (`todos[2].title`, `scores['a b']`); `kind` is "added",
+/// "removed" or "changed", and `old`/`new` are the values on each side
+/// (None on the side where the value does not exist).
+#[pyclass(frozen)]
+pub struct StateChange {
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub kind: &'static str,
+    #[pyo3(get)]
+    pub old: Option<Py<PyAny>>,
+    #[pyo3(get)]
+    pub new: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl StateChange {
+    fn __repr__(&self) -> String {
+        format!("StateChange({} {})", self.kind, self.path)
+    }
+}
+
+// An encoded value split into the containers the diff descends into. Every
+// variant keeps its full encoding in `raw` for equality and decoding.
+enum Tree<'a> {
+    Leaf(&'a [u8]),
+    // Lists and tuples; `raw[0]` tells them apart.
+    Seq(&'a [u8], Vec<Tree<'a>>),
+    // Key encodings with their values.
+    Dict(&'a [u8], Vec<(&'a [u8], Tree<'a>)>),
+    // Sets are unordered: their item encodings, sorted.
+    Set(&'a [u8], Vec<&'a [u8]>),
+}
+
+impl<'a> Tree<'a> {
+    fn raw(&self) -> &'a [u8] {
+        match self {
+            Tree::Leaf(raw) | Tree::Seq(raw, _) | Tree::Dict(raw, _) | Tree::Set(raw, _) => raw,
+        }
+    }
+
+    fn same(&self, other: &Tree<'_>) -> bool {
+        match (self, other) {
+            (Tree::Set(_, a), Tree::Set(_, b)) => self.raw()[0] == other.raw()[0] && a == b,
+            _ => self.raw() == other.raw(),
+        }
+    }
+}
+
+fn parse<'a>(reader: &mut Reader<'a>, depth: usize) -> PyResult<Tree<'a>> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err(
+            "State snapshot exceeds maximum nesting depth",
+        ));
+    }
+    let start = reader.pos;
+    let tag = reader.byte()?;
+    let raw = |reader: &Reader<'a>| &reader.data[start..reader.pos];
+    let tree = match tag {
+        TAG_NONE | TAG_FALSE | TAG_TRUE => Tree::Leaf(raw(reader)),
+        TAG_INT => {
+            reader.varint()?;
+            Tree::Leaf(raw(reader))
+        }
+        TAG_FLOAT => {
+            reader.take(8)?;
+            Tree::Leaf(raw(reader))
+        }
+        TAG_BIGINT | TAG_STR | TAG_BYTES => {
+            let n = reader.len()?;
+            reader.take(n)?;
+            Tree::Leaf(raw(reader))
+        }
+        TAG_LIST | TAG_TUPLE => {
+            let n = reader.len()?;
+            let mut items = Vec::with_capacity(n);
+            for _ in 0..n {
+                items.push(parse(reader, depth + 1)?);
+            }
+            Tree::Seq(raw(reader), items)
+        }
+        TAG_SET | TAG_FROZENSET => {
+            let n = reader.len()?;
+            let mut items = Vec::with_capacity(n);
+            for _ in 0..n {
+                items.push(parse(reader, depth + 1)?.raw());
+            }
+            items.sort();
+            Tree::Set(raw(reader), items)
+        }
+        TAG_DICT => {
+            let n = reader.len()?;
+            let mut entries = Vec::with_capacity(n);
+            for _ in 0..n {
+                let key = parse(reader, depth + 1)?.raw();
+                entries.push((key, parse(reader, depth + 1)?));
+            }
+            Tree::Dict(raw(reader), entries)
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown tag {} in state snapshot",
+                other
+            )));
+        }
+    };
+    Ok(tree)
+}
+
+fn decode(py: Python<'_>, raw: &[u8]) -> PyResult<Py<PyAny>> {
+    Ok(decode_value(py, &mut Reader { data: raw, pos: 0 }, 0)?.unbind())
+}
+
+// `path` extended by dict key `key`: `.name` for identifiers (bare at the
+// top level), `[repr(key)]` otherwise.
+fn key_path(py: Python<'_>, path: &str, key: &[u8]) -> PyResult<String> {
+    let key = decode(py, key)?.into_bound(py);
+    if let Ok(name) = key.extract::<String>()
+        && name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    {
+        return Ok(if path.is_empty() {
+            name
+        } else {
+            format!("{}.{}", path, name)
+        });
+    }
+    Ok(format!("{}[{}]", path, key.repr()?))
+}
+
+fn change(
+    py: Python<'_>,
+    path: String,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+) -> PyResult<StateChange> {
+    let kind = match (old, new) {
+        (None, _) => "added",
+        (_, None) => "removed",
+        _ => "changed",
+    };
+    Ok(StateChange {
+        path,
+        kind,
+        old: old.map(|raw| decode(py, raw)).transpose()?,
+        new: new.map(|raw| decode(py, raw)).transpose()?,
+    })
+}
+
+fn diff_trees(
+    py: Python<'_>,
+    path: &str,
+    old: &Tree<'_>,
+    new: &Tree<'_>,
+    out: &mut Vec<StateChange>,
+) -> PyResult<()> {
+    if old.same(new) {
+        return Ok(());
+    }
+    match (old, new) {
+        (Tree::Dict(_, before), Tree::Dict(_, after)) => {
+            let index: HashMap<&[u8], &Tree<'_>> = after.iter().map(|(k, v)| (*k, v)).collect();
+            for (key, value) in before {
+                match index.get(key) {
+                    Some(now) if value.same(now) => {}
+                    Some(now) => diff_trees(py, &key_path(py, path, key)?, value, now, out)?,
+                    None => {
+                        let path = key_path(py, path, key)?;
+                        out.push(change(py, path, Some(value.raw()), None)?);
+                    }
+                }
+            }
+            let known: HashSet<&[u8]> = before.iter().map(|(k, _)| *k).collect();
+            for (key, value) in after {
+                if !known.contains(key) {
+                    let path = key_path(py, path, key)?;
+                    out.push(change(py, path, None, Some(value.raw()))?);
+                }
+            }
+        }
+        (Tree::Seq(a, before), Tree::Seq(b, after)) if a[0] == b[0] => {
+            for i in 0..before.len().max(after.len()) {
+                let path = || format!("{}[{}]", path, i);
+                match (before.get(i), after.get(i)) {
+                    (Some(then), Some(now)) if then.same(now) => {}
+                    (Some(then), Some(now)) => diff_trees(py, &path(), then, now, out)?,
+                    (then, now) => {
+                        out.push(change(py, path(), then.map(Tree::raw), now.map(Tree::raw))?)
+                    }
+                }
+            }
+        }
+        _ => out.push(change(
+            py,
+            path.to_string(),
+            Some(old.raw()),
+            Some(new.raw()),
+        )?),
+    }
+    Ok(())
+}
+
+/// Ring of the last `capacity` wire-state snapshots, numbered by render.
+///
+/// `record(state)` stores a copy of a dict of builtin values (as accepted by
+/// `dumps_state`) and returns its render number, counting from 0; the oldest
+/// state is dropped once the ring is full. `diff(a, b)` lists what changed
+/// from render `a` to render `b` as `StateChange`s, in key and index order;
+/// `changes(n)` is the diff from the render before `n`.
+#[pyclass]
+pub struct StateHistory {
+    capacity: usize,
+    next: u64,
+    states: VecDeque<(u64, Vec<u8>)>,
+}
+
+impl StateHistory {
+    fn encoded(&self, render: u64) -> PyResult<&[u8]> {
+        self.states
+            .iter()
+            .find(|(n, _)| *n == render)
+            .map(|(_, data)| data.as_slice())
+            .ok_or_else(|| {
+                PyIndexError::new_err(format!("Render {} is not in the state history", render))
+            })
+    }
+}
+
+#[pymethods]
+impl StateHistory {
+    #[new]
+    #[pyo3(signature = (capacity=100))]
+    fn new(capacity: usize) -> PyResult<Self> {
+        if capacity == 0 {
+            return Err(PyValueError::new_err("capacity must be at least 1"));
+        }
+        Ok(StateHistory {
+            capacity,
+            next: 0,
+            states: VecDeque::with_capacity(capacity),
+        })
+    }
+
+    fn record(&mut self, state: &Bound<'_, PyAny>) -> PyResult<u64> {
+        let mut data = Vec::new();
+        encode_value(&mut data, state, 0)?;
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        let render = self.next;
+        self.states.push_back((render, data));
+        self.next += 1;
+        Ok(render)
+    }
+
+    /// The state recorded at `render`, rebuilt from builtin types.
+    fn state(&self, py: Python<'_>, render: u64) -> PyResult<Py<PyAny>> {
+        decode(py, self.encoded(render)?)
+    }
+
+    fn diff(&self, py: Python<'_>, a: u64, b: u64) -> PyResult<Vec<StateChange>> {
+        let (before, after) = (self.encoded(a)?, self.encoded(b)?);
+        let old = parse(
+            &mut Reader {
+                data: before,
+                pos: 0,
+            },
+            0,
+        )?;
+        let new = parse(
+            &mut Reader {
+                data: after,
+                pos: 0,
+            },
+            0,
+        )?;
+        let mut out = Vec::new();
+        diff_trees(py, "", &old, &new, &mut out)?;
+        Ok(out)
+    }
+
+    fn changes(&self, py: Python<'_>, render: u64) -> PyResult<Vec<StateChange>> {
+        let Some(previous) = render.checked_sub(1) else {
+            return Err(PyIndexError::new_err("Render 0 has no previous state"));
+        };
+        self.diff(py, previous, render)
+    }
+
+    /// Render numbers still in the ring, oldest first.
+    #[getter]
+    fn renders(&self) -> Vec<u64> {
+        self.states.iter().map(|(n, _)| *n).collect()
+    }
+
+    /// Number of the most recent render, or None before the first.
+    #[getter]
+    fn latest(&self) -> Option<u64> {
+        self.states.back().map(|(n, _)| *n)
+    }
+
+    fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.states.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StateHistory({} of {} renders)",
+            self.states.len(),
+            self.capacity
+        )
+    }
+}
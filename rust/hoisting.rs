@@ -0,0 +1,178 @@
+// Hoisting hints: attribute chains (`user.profile.display_name`) that a
+// template evaluates several times in one render. Each chain belongs to the
+// innermost block binding its root name (a `{$for}` target, an `{$except}`
+// alias, ...) or to the whole render, and can be evaluated once there.
+// Calls are never hoisted: `a.b.c()` contributes `a.b`, and expressions
+// with comprehensions or lambdas, whose names are local, are skipped.
+
+use crate::ParsedDocument;
+use crate::ParsedNode;
+use crate::attrs::unbrace;
+use crate::exprinfo::chains;
+use crate::kinds::AttrKind;
+use crate::wires::block_bindings;
+use pyo3::prelude::*;
+
+/// An expression repeated `count` times in the same scope, at
+/// `occurrences` (line, column). `scope` is the (line, column) of the block
+/// marker binding a name it reads, or None if it can be evaluated once per
+/// render.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct HoistHint {
+    #[pyo3(get)]
+    pub expression: String,
+    #[pyo3(get)]
+    pub count: usize,
+    #[pyo3(get)]
+    pub occurrences: Vec<(usize, usize)>,
+    #[pyo3(get)]
+    pub scope: Option<(usize, usize)>,
+}
+
+#[pymethods]
+impl HoistHint {
+    fn __repr__(&self) -> String {
+        format!("HoistHint({:?} x{})", self.expression, self.count)
+    }
+}
+
+// (line, column) of a node or attribute.
+type Position = (usize, usize);
+
+struct Frame {
+    at: Position,
+    names: Vec<String>,
+    // Sibling index the frame ends at and the opener it belongs to, for
+    // frames opened in the flat shape.
+    end: Option<usize>,
+    opener: usize,
+}
+
+struct Collector {
+    frames: Vec<Frame>,
+    found: Vec<(Option<Position>, String, Position)>,
+}
+
+impl Collector {
+    fn note(&mut self, expr: &str, at: Position) {
+        if expr.contains(" for ") || expr.contains("lambda") {
+            return;
+        }
+        for chain in chains(expr) {
+            let root = chain.split('.').next().unwrap_or("");
+            let scope = self
+                .frames
+                .iter()
+                .rev()
+                .find(|f| f.names.iter().any(|n| n == root))
+                .map(|f| f.at);
+            self.found.push((scope, chain, at));
+        }
+    }
+
+    fn node(&mut self, node: &ParsedNode) {
+        for attr in node.attributes.parsed() {
+            let at = (attr.line, attr.column);
+            match attr.kind {
+                AttrKind::Reactive | AttrKind::Shorthand => {
+                    self.note(&unbrace(attr.value.as_deref().unwrap_or("")), at)
+                }
+                AttrKind::ClassToggle | AttrKind::StyleProperty => {
+                    self.note(attr.expression.as_deref().unwrap_or(""), at)
+                }
+                AttrKind::Static if attr.has_interpolation => {
+                    for segment in attr.segments.iter().filter(|s| s.is_expression) {
+                        self.note(&unbrace(&segment.text), (segment.line, segment.column));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) {
+        let base = self.frames.len();
+        for (i, node) in nodes.iter().enumerate() {
+            let node = node.borrow(py);
+            // Frames of this list end at their closing marker; a branch
+            // marker ends the frames of the previous branch.
+            while self.frames.len() > base
+                && self.frames.last().is_some_and(|f| {
+                    f.end.is_some_and(|end| end <= i) || Some(f.opener) == node.open_index
+                })
+            {
+                self.frames.pop();
+            }
+            self.node(&node);
+            let mut pushed = false;
+            if let Some(expr) = &node.expression {
+                let at = (node.line, node.column);
+                let keyword = node.block_keyword.as_deref().unwrap_or("");
+                let mut names = Vec::new();
+                let from = block_bindings(keyword, expr, &mut names).unwrap_or(0);
+                self.note(&expr[from.min(expr.len())..], at);
+                if !names.is_empty() {
+                    let opener = node.open_index.unwrap_or(i);
+                    let end = match node.open_index {
+                        Some(o) => nodes.get(o).and_then(|n| n.borrow(py).close_index),
+                        None => node.close_index,
+                    };
+                    self.frames.push(Frame {
+                        at,
+                        names,
+                        end,
+                        opener,
+                    });
+                    pushed = end.is_none();
+                }
+            }
+            self.visit(py, &node.children);
+            // Resolved blocks bind names for their children only.
+            if pushed {
+                self.frames.pop();
+            }
+        }
+        self.frames.truncate(base);
+    }
+}
+
+/// Attribute chains `document` evaluates at least `min_count` times in the
+/// same scope, most repeated first. Hoisting each into a local evaluated
+/// once per scope saves the repeated lookups.
+#[pyfunction]
+#[pyo3(signature = (document, min_count=2))]
+pub fn hoisting_hints(
+    py: Python<'_>,
+    document: PyRef<'_, ParsedDocument>,
+    min_count: usize,
+) -> Vec<HoistHint> {
+    let _span = tracing::info_span!("analyze", pass = "hoisting").entered();
+    let mut collector = Collector {
+        frames: Vec::new(),
+        found: Vec::new(),
+    };
+    collector.visit(py, &document.template);
+
+    let mut hints: Vec<HoistHint> = Vec::new();
+    for (scope, expression, at) in collector.found {
+        match hints
+            .iter_mut()
+            .find(|h| h.scope == scope && h.expression == expression)
+        {
+            Some(hint) => {
+                hint.count += 1;
+                hint.occurrences.push(at);
+            }
+            None => hints.push(HoistHint {
+                expression,
+                count: 1,
+                occurrences: vec![at],
+                scope,
+            }),
+        }
+    }
+    hints.retain(|h| h.count >= min_count.max(2));
+    hints.sort_by_key(|h| std::cmp::Reverse(h.count));
+    hints
+}
@@ -0,0 +1,372 @@
+// Lenient HTML parser for plain HTML strings (rendered output, imported pages).
+//
+// It never fails: stray end tags are dropped, unclosed elements are closed at
+// EOF, and the common implied-close rules (li, p, td, option, ...) are applied.
+// Text and attribute values are kept exactly as written (entities undecoded) so
+// serializing the tree reproduces the author's markup.
+
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title", "xmp"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum HtmlNode {
+    Element(Element),
+    Text(String),
+    Comment(String),
+    Doctype(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Element {
+    pub tag: String,
+    pub attrs: Vec<(String, Option<String>)>,
+    pub children: Vec<HtmlNode>,
+}
+
+impl Element {
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+}
+
+pub(crate) fn is_void(tag: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag)
+}
+
+// Whether opening `tag` implicitly closes the currently open `open` element.
+pub(crate) fn implied_close(open: &str, tag: &str) -> bool {
+    match open {
+        "li" => tag == "li",
+        "dt" | "dd" => matches!(tag, "dt" | "dd"),
+        "option" => matches!(tag, "option" | "optgroup"),
+        "tr" => tag == "tr",
+        "td" | "th" => matches!(tag, "td" | "th" | "tr"),
+        "thead" | "tbody" => matches!(tag, "tbody" | "tfoot"),
+        "p" => matches!(
+            tag,
+            "address"
+                | "article"
+                | "aside"
+                | "blockquote"
+                | "div"
+                | "dl"
+                | "fieldset"
+                | "footer"
+                | "form"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "header"
+                | "hr"
+                | "main"
+                | "nav"
+                | "ol"
+                | "p"
+                | "pre"
+                | "section"
+                | "table"
+                | "ul"
+        ),
+        _ => false,
+    }
+}
+
+struct TreeBuilder {
+    // stack[0] is the synthetic root
+    stack: Vec<Element>,
+}
+
+impl TreeBuilder {
+    fn push_node(&mut self, node: HtmlNode) {
+        self.stack.last_mut().unwrap().children.push(node);
+    }
+
+    fn pop(&mut self) {
+        let el = self.stack.pop().unwrap();
+        self.push_node(HtmlNode::Element(el));
+    }
+
+    fn open(&mut self, el: Element, self_closing: bool) {
+        while self.stack.len() > 1 && implied_close(&self.stack.last().unwrap().tag, &el.tag) {
+            self.pop();
+        }
+        if self_closing || is_void(&el.tag) {
+            self.push_node(HtmlNode::Element(el));
+        } else {
+            self.stack.push(el);
+        }
+    }
+
+    fn close(&mut self, tag: &str) {
+        // Stray end tags (no matching open element) are ignored. `<template>`
+        // content (a declarative shadow root, say) is its own scope: an end
+        // tag inside it cannot close an element outside.
+        let scope = self
+            .stack
+            .iter()
+            .rposition(|e| e.tag == "template" && tag != "template")
+            .unwrap_or(0);
+        if let Some(pos) = self.stack.iter().rposition(|e| e.tag == tag)
+            && pos > scope
+        {
+            while self.stack.len() > pos {
+                self.pop();
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<HtmlNode> {
+        while self.stack.len() > 1 {
+            self.pop();
+        }
+        self.stack.pop().unwrap().children
+    }
+}
+
+fn is_name_char(c: u8) -> bool {
+    !c.is_ascii_whitespace() && !matches!(c, b'>' | b'/' | b'=' | b'"' | b'\'' | b'<')
+}
+
+// Parses a start tag at `pos` (just after '<'); returns (element, self_closing, end).
+fn parse_start_tag(src: &str, mut pos: usize) -> Option<(Element, bool, usize)> {
+    let bytes = src.as_bytes();
+    let name_start = pos;
+    while pos < bytes.len() && is_name_char(bytes[pos]) {
+        pos += 1;
+    }
+    if pos == name_start || !bytes[name_start].is_ascii_alphabetic() {
+        return None;
+    }
+    let tag = src[name_start..pos].to_ascii_lowercase();
+    let mut attrs: Vec<(String, Option<String>)> = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return Some((
+                Element {
+                    tag,
+                    attrs,
+                    children: Vec::new(),
+                },
+                false,
+                pos,
+            ));
+        }
+        match bytes[pos] {
+            b'>' => {
+                pos += 1;
+                break;
+            }
+            b'/' => {
+                pos += 1;
+                if bytes.get(pos) == Some(&b'>') {
+                    self_closing = true;
+                    pos += 1;
+                    break;
+                }
+            }
+            _ => {
+                let attr_start = pos;
+                while pos < bytes.len() && is_name_char(bytes[pos]) {
+                    pos += 1;
+                }
+                if pos == attr_start {
+                    // lone quote or '=': skip it rather than loop forever
+                    pos += 1;
+                    continue;
+                }
+                let name = src[attr_start..pos].to_ascii_lowercase();
+                let mut value = None;
+                let mut look = pos;
+                while look < bytes.len() && bytes[look].is_ascii_whitespace() {
+                    look += 1;
+                }
+                if bytes.get(look) == Some(&b'=') {
+                    pos = look + 1;
+                    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                        pos += 1;
+                    }
+                    match bytes.get(pos) {
+                        Some(&q @ (b'"' | b'\'')) => {
+                            let v_start = pos + 1;
+                            let v_end = src[v_start..]
+                                .find(q as char)
+                                .map_or(bytes.len(), |i| v_start + i);
+                            value = Some(src[v_start..v_end].to_string());
+                            pos = (v_end + 1).min(bytes.len());
+                        }
+                        _ => {
+                            let v_start = pos;
+                            while pos < bytes.len()
+                                && !bytes[pos].is_ascii_whitespace()
+                                && bytes[pos] != b'>'
+                            {
+                                pos += 1;
+                            }
+                            value = Some(src[v_start..pos].to_string());
+                        }
+                    }
+                }
+                // First occurrence wins, as in browsers.
+                if !attrs.iter().any(|(k, _)| *k == name) {
+                    attrs.push((name, value));
+                }
+            }
+        }
+    }
+    Some((
+        Element {
+            tag,
+            attrs,
+            children: Vec::new(),
+        },
+        self_closing,
+        pos,
+    ))
+}
+
+pub(crate) fn parse_html(src: &str) -> Vec<HtmlNode> {
+    let bytes = src.as_bytes();
+    let mut builder = TreeBuilder {
+        stack: vec![Element {
+            tag: String::new(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }],
+    };
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    macro_rules! flush_text {
+        ($end:expr) => {
+            if $end > text_start {
+                builder.push_node(HtmlNode::Text(src[text_start..$end].to_string()));
+            }
+        };
+    }
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            pos += 1;
+            continue;
+        }
+        let rest = &src[pos..];
+        if let Some(body) = rest.strip_prefix("<!--") {
+            flush_text!(pos);
+            let end = body.find("-->").map_or(src.len(), |i| pos + 4 + i);
+            builder.push_node(HtmlNode::Comment(src[pos + 4..end].to_string()));
+            pos = (end + 3).min(src.len());
+            text_start = pos;
+        } else if rest
+            .as_bytes()
+            .get(..9)
+            .is_some_and(|b| b.eq_ignore_ascii_case(b"<!doctype"))
+        {
+            flush_text!(pos);
+            let end = rest.find('>').map_or(src.len(), |i| pos + i);
+            builder.push_node(HtmlNode::Doctype(src[pos + 2..end].to_string()));
+            pos = (end + 1).min(src.len());
+            text_start = pos;
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let name_end = close
+                .find(|c: char| c == '>' || c.is_ascii_whitespace())
+                .unwrap_or(close.len());
+            let tag = close[..name_end].to_ascii_lowercase();
+            if tag.is_empty() || !tag.as_bytes()[0].is_ascii_alphabetic() {
+                pos += 1;
+                continue;
+            }
+            flush_text!(pos);
+            builder.close(&tag);
+            pos = rest.find('>').map_or(src.len(), |i| pos + i + 1);
+            text_start = pos;
+        } else if let Some((el, self_closing, end)) = parse_start_tag(src, pos + 1) {
+            flush_text!(pos);
+            pos = end;
+            if RAW_TEXT_ELEMENTS.contains(&el.tag.as_str()) && !self_closing {
+                let close = format!("</{}", el.tag);
+                let body_end =
+                    find_ascii_case_insensitive(&src[pos..], &close).map_or(src.len(), |i| pos + i);
+                let mut el = el;
+                if body_end > pos {
+                    el.children
+                        .push(HtmlNode::Text(src[pos..body_end].to_string()));
+                }
+                builder.open(el, true);
+                pos = src[body_end..]
+                    .find('>')
+                    .map_or(src.len(), |i| body_end + i + 1);
+            } else {
+                builder.open(el, self_closing);
+            }
+            text_start = pos;
+        } else {
+            pos += 1;
+        }
+    }
+    flush_text!(src.len());
+    builder.finish()
+}
+
+fn find_ascii_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    let h = haystack.as_bytes();
+    let n = needle.as_bytes();
+    (0..h.len().saturating_sub(n.len() - 1)).find(|&i| h[i..i + n.len()].eq_ignore_ascii_case(n))
+}
+
+pub(crate) fn write_attrs(out: &mut String, attrs: &[(String, Option<String>)]) {
+    for (name, value) in attrs {
+        out.push(' ');
+        out.push_str(name);
+        if let Some(v) = value {
+            out.push_str("=\"");
+            out.push_str(&v.replace('"', "&quot;"));
+            out.push('"');
+        }
+    }
+}
+
+pub(crate) fn serialize_node(out: &mut String, node: &HtmlNode) {
+    match node {
+        HtmlNode::Text(t) => out.push_str(t),
+        HtmlNode::Comment(c) => {
+            out.push_str("<!--");
+            out.push_str(c);
+            out.push_str("-->");
+        }
+        HtmlNode::Doctype(d) => {
+            out.push_str("<!");
+            out.push_str(d);
+            out.push('>');
+        }
+        HtmlNode::Element(el) => {
+            out.push('<');
+            out.push_str(&el.tag);
+            write_attrs(out, &el.attrs);
+            out.push('>');
+            if is_void(&el.tag) {
+                return;
+            }
+            for child in &el.children {
+                serialize_node(out, child);
+            }
+            out.push_str("</");
+            out.push_str(&el.tag);
+            out.push('>');
+        }
+    }
+}
@@ -0,0 +1,380 @@
+// Plain HTML -> `.wire`. The page goes through the lenient HTML parser and
+// is written back out with template syntax escaped: `{`/`}` outside
+// `<script>`/`<style>` become `&#123;`/`&#125;` so they are not read as
+// interpolations. Attributes that pywire would read as its own syntax
+// (`@click`, `:class` from Vue or Alpine markup) are dropped with a warning.
+// Optionally, runs of sibling elements with the same structure become a
+// `{$for}` over a list literal in the frontmatter.
+
+use crate::diagnostics::Diagnostic;
+use crate::html::{HtmlNode, is_void, parse_html, serialize_node, write_attrs};
+use crate::kinds::AttrKind;
+use crate::query::decode_entities;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+// Fewer repeats than this are left as written.
+const MIN_REPEAT: usize = 3;
+
+// Elements whose content is not template syntax.
+const RAW: &[&str] = &["script", "style"];
+
+fn is_raw(tag: &str) -> bool {
+    RAW.contains(&tag)
+}
+
+pub(crate) fn escape_braces(text: &str) -> String {
+    text.replace('{', "&#123;").replace('}', "&#125;")
+}
+
+// Python string literal for `value`.
+fn py_str(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// What pywire would read an attribute named `name` as, if not a plain
+// HTML attribute.
+fn template_syntax(name: &str) -> Option<&'static str> {
+    if name.starts_with(':') {
+        return Some("binding");
+    }
+    match AttrKind::classify(name, None) {
+        AttrKind::Event => Some("event handler"),
+        AttrKind::Directive => Some("directive"),
+        AttrKind::Boolean => None,
+        _ => Some("binding"),
+    }
+}
+
+// Remove attributes that are template syntax, with a warning for each.
+// `at` is where the search for the next one in `html` starts, so repeated
+// names are reported on their own line.
+fn drop_template_attrs(
+    html: &str,
+    nodes: &mut [HtmlNode],
+    at: &mut usize,
+    out: &mut Vec<Diagnostic>,
+) {
+    for node in nodes {
+        let HtmlNode::Element(el) = node else {
+            continue;
+        };
+        el.attrs.retain(|(name, _)| {
+            let Some(what) = template_syntax(name) else {
+                return true;
+            };
+            if let Some(i) = html[*at..].find(name.as_str()) {
+                *at += i + name.len();
+            }
+            let before = &html[..*at];
+            let line = before.matches('\n').count() + 1;
+            let column = before.rfind('\n').map_or(*at, |i| *at - i - 1);
+            out.push(Diagnostic::warning(
+                "html-template-attribute",
+                format!(
+                    "Dropped `{}` from <{}>: pywire would read it as a {}",
+                    name, el.tag, what
+                ),
+                line,
+                column.saturating_sub(name.len()),
+            ));
+            false
+        });
+        if !is_raw(&el.tag) {
+            drop_template_attrs(html, &mut el.children, at, out);
+        }
+    }
+}
+
+// Structure of a node with attribute values and text left out; repeated
+// items share it.
+fn shape(node: &HtmlNode, out: &mut String) {
+    match node {
+        HtmlNode::Element(el) => {
+            out.push('<');
+            out.push_str(&el.tag);
+            for (name, value) in &el.attrs {
+                out.push(' ');
+                out.push_str(name);
+                if value.is_none() {
+                    out.push('!');
+                }
+            }
+            out.push('>');
+            for child in &el.children {
+                if is_raw(&el.tag) {
+                    serialize_node(out, child);
+                } else {
+                    shape(child, out);
+                }
+            }
+            out.push_str("</>");
+        }
+        HtmlNode::Text(_) => out.push_str("#text"),
+        other => serialize_node(out, other),
+    }
+}
+
+// Attribute values and texts of a node in document order, labelled with the
+// attribute name or "text".
+fn slots<'a>(node: &'a HtmlNode, out: &mut Vec<(&'a str, &'a str)>) {
+    match node {
+        HtmlNode::Element(el) => {
+            for (name, value) in &el.attrs {
+                if let Some(value) = value {
+                    out.push((name, value));
+                }
+            }
+            if !is_raw(&el.tag) {
+                for child in &el.children {
+                    slots(child, out);
+                }
+            }
+        }
+        HtmlNode::Text(text) => out.push(("text", text)),
+        _ => {}
+    }
+}
+
+fn is_blank(node: &HtmlNode) -> bool {
+    matches!(node, HtmlNode::Text(t) if t.trim().is_empty())
+}
+
+struct Converter {
+    detect_loops: bool,
+    frontmatter: String,
+    names: HashMap<String, usize>,
+}
+
+impl Converter {
+    fn unique(names: &mut HashMap<String, usize>, base: String) -> String {
+        let count = names.entry(base.clone()).or_insert(0);
+        *count += 1;
+        match *count {
+            1 => base,
+            n => format!("{}_{}", base, n),
+        }
+    }
+
+    // Write `node`; slot `i` of it is `item[fields[i]]` where that is set.
+    fn emit(
+        &mut self,
+        node: &HtmlNode,
+        fields: &[Option<String>],
+        next: &mut usize,
+        out: &mut String,
+    ) {
+        match node {
+            HtmlNode::Element(el) => {
+                out.push('<');
+                out.push_str(&el.tag);
+                for (name, value) in &el.attrs {
+                    let Some(value) = value else {
+                        write_attrs(out, &[(name.clone(), None)]);
+                        continue;
+                    };
+                    match take(fields, next) {
+                        Some(f) => out.push_str(&format!(" {}={{item[{}]}}", name, py_str(&f))),
+                        None => write_attrs(out, &[(name.clone(), Some(escape_braces(value)))]),
+                    }
+                }
+                out.push('>');
+                if is_void(&el.tag) {
+                    return;
+                }
+                if is_raw(&el.tag) {
+                    for child in &el.children {
+                        serialize_node(out, child);
+                    }
+                } else if fields.is_empty() {
+                    self.children(&el.children, out);
+                } else {
+                    for child in &el.children {
+                        self.emit(child, fields, next, out);
+                    }
+                }
+                out.push_str("</");
+                out.push_str(&el.tag);
+                out.push('>');
+            }
+            HtmlNode::Text(text) => match take(fields, next) {
+                Some(f) => out.push_str(&format!("{{item[{}]}}", py_str(&f))),
+                None => out.push_str(&escape_braces(text)),
+            },
+            other => serialize_node(out, other),
+        }
+    }
+
+    // Indices of a run of same-shaped elements starting at `start`, with only
+    // whitespace between them.
+    fn find_run(nodes: &[HtmlNode], start: usize) -> Option<Vec<usize>> {
+        let HtmlNode::Element(_) = &nodes[start] else {
+            return None;
+        };
+        let mut first = String::new();
+        shape(&nodes[start], &mut first);
+        let mut run = vec![start];
+        let mut i = start + 1;
+        loop {
+            while i < nodes.len() && is_blank(&nodes[i]) {
+                i += 1;
+            }
+            let mut next = String::new();
+            match nodes.get(i) {
+                Some(node @ HtmlNode::Element(_)) => shape(node, &mut next),
+                _ => break,
+            }
+            if next != first {
+                break;
+            }
+            run.push(i);
+            i += 1;
+        }
+        (run.len() >= MIN_REPEAT).then_some(run)
+    }
+
+    // Replace a run with `{$for item in <tag>_items}` over a frontmatter list.
+    // Returns false if every item is identical (nothing to loop over).
+    fn emit_loop(&mut self, nodes: &[HtmlNode], run: &[usize], out: &mut String) -> bool {
+        let items: Vec<Vec<(&str, &str)>> = run
+            .iter()
+            .map(|&i| {
+                let mut s = Vec::new();
+                slots(&nodes[i], &mut s);
+                s
+            })
+            .collect();
+        let mut labels = HashMap::new();
+        let fields: Vec<Option<String>> = (0..items[0].len())
+            .map(|i| {
+                let varies = items.iter().any(|item| item[i].1 != items[0][i].1);
+                varies.then(|| Self::unique(&mut labels, items[0][i].0.to_string()))
+            })
+            .collect();
+        if fields.iter().all(Option::is_none) {
+            return false;
+        }
+
+        let HtmlNode::Element(el) = &nodes[run[0]] else {
+            return false;
+        };
+        let list = Self::unique(
+            &mut self.names,
+            format!("{}_items", el.tag.replace('-', "_")),
+        );
+        self.frontmatter.push_str(&format!("{} = [\n", list));
+        for item in &items {
+            let entries: Vec<String> = fields
+                .iter()
+                .zip(item)
+                .filter_map(|(field, (_, value))| {
+                    let field = field.as_ref()?;
+                    Some(format!(
+                        "{}: {}",
+                        py_str(field),
+                        py_str(&decode_entities(value))
+                    ))
+                })
+                .collect();
+            self.frontmatter
+                .push_str(&format!("    {{{}}},\n", entries.join(", ")));
+        }
+        self.frontmatter.push_str("]\n");
+
+        let sep = match &nodes[run[0] + 1] {
+            HtmlNode::Text(t) if run[1] > run[0] + 1 => t.as_str(),
+            _ => "",
+        };
+        out.push_str(&format!("{{$for item in {}}}{}", list, sep));
+        self.emit(&nodes[run[0]], &fields, &mut 0, out);
+        out.push_str(sep);
+        out.push_str("{/for}");
+        true
+    }
+
+    fn children(&mut self, nodes: &[HtmlNode], out: &mut String) {
+        let mut i = 0;
+        while i < nodes.len() {
+            if self.detect_loops
+                && let Some(run) = Self::find_run(nodes, i)
+                && self.emit_loop(nodes, &run, out)
+            {
+                i = run[run.len() - 1] + 1;
+                continue;
+            }
+            self.emit(&nodes[i], &[], &mut 0, out);
+            i += 1;
+        }
+    }
+}
+
+// Field for the next slot, if it varies between loop items.
+fn take(fields: &[Option<String>], next: &mut usize) -> Option<String> {
+    *next += 1;
+    fields.get(*next - 1).cloned().flatten()
+}
+
+/// Result of `from_html`: the converted `.wire` source and the attributes
+/// that were dropped from it.
+#[pyclass(frozen)]
+pub struct HtmlImport {
+    #[pyo3(get)]
+    pub wire: String,
+    /// "html-template-attribute" warnings for attributes such as `@click`
+    /// or `:class` that pywire would read as its own syntax.
+    #[pyo3(get)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[pymethods]
+impl HtmlImport {
+    fn __repr__(&self) -> String {
+        format!("HtmlImport(diagnostics={})", self.diagnostics.len())
+    }
+}
+
+/// Convert a plain HTML page to a `.wire` template. The HTML is parsed
+/// leniently and written back with `{`/`}` escaped outside `<script>` and
+/// `<style>`. Attributes that pywire would read as an event handler,
+/// binding or directive (`@click`, `:class`, `$x`) are dropped and
+/// reported in `diagnostics`.
+///
+/// With `detect_loops=True`, three or more sibling elements with the same
+/// structure become a `{$for item in <tag>_items}` loop, with the values
+/// that differ between them moved to a list in the frontmatter.
+#[pyfunction]
+#[pyo3(signature = (html, detect_loops=false))]
+pub fn from_html(py: Python<'_>, html: &str, detect_loops: bool) -> HtmlImport {
+    py.detach(|| {
+        let mut nodes = parse_html(html);
+        let mut diagnostics = Vec::new();
+        drop_template_attrs(html, &mut nodes, &mut 0, &mut diagnostics);
+        let mut converter = Converter {
+            detect_loops,
+            frontmatter: String::new(),
+            names: HashMap::new(),
+        };
+        let mut template = String::with_capacity(html.len());
+        converter.children(&nodes, &mut template);
+        let wire = if converter.frontmatter.is_empty() {
+            template
+        } else {
+            format!("---\n{}---\n{}", converter.frontmatter, template)
+        };
+        HtmlImport { wire, diagnostics }
+    })
+}
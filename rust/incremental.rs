@@ -0,0 +1,175 @@
+use crate::fragments::point_at;
+use crate::limits::ParseLimits;
+use crate::{ParseOptions, ParsedDocument};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use tree_sitter::{InputEdit, Tree};
+
+/// A template kept alive between parses so edits only re-parse what changed.
+///
+/// Apply each change with `edit()`, then call `reparse()` for the updated
+/// `ParsedDocument`; tree-sitter reuses the unchanged parts of the previous
+/// tree. Options match `parse`, including its panic guard.
+#[pyclass]
+pub struct Document {
+    source: String,
+    tree: Tree,
+    edited: bool,
+    changed: Vec<(usize, usize)>,
+    annotate_file: Option<String>,
+    lenient: bool,
+    limits: Option<ParseLimits>,
+    resolved: bool,
+    normalize_unicode: bool,
+    tab_width: usize,
+    fragments: bool,
+    strip_comments: bool,
+    fold_constants: bool,
+    validate_names: bool,
+}
+
+impl Document {
+    fn options(&self) -> ParseOptions<'_> {
+        ParseOptions {
+            annotate_file: self.annotate_file.as_deref(),
+            lenient: self.lenient,
+            limits: self.limits.as_ref(),
+            resolved: self.resolved,
+            normalize_unicode: self.normalize_unicode,
+            tab_width: self.tab_width,
+            fragments: self.fragments,
+            strip_comments: self.strip_comments,
+            fold_constants: self.fold_constants,
+            validate_names: self.validate_names,
+        }
+    }
+}
+
+#[pymethods]
+impl Document {
+    #[new]
+    #[pyo3(signature = (
+        source,
+        annotate_file=None,
+        lenient=false,
+        limits=None,
+        resolved=false,
+        normalize_unicode=false,
+        tab_width=crate::columns::DEFAULT_TAB_WIDTH,
+        fragments=false,
+        strip_comments=false,
+        fold_constants=false,
+        validate_names=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        source: String,
+        annotate_file: Option<String>,
+        lenient: bool,
+        limits: Option<PyRef<'_, ParseLimits>>,
+        resolved: bool,
+        normalize_unicode: bool,
+        tab_width: usize,
+        fragments: bool,
+        strip_comments: bool,
+        fold_constants: bool,
+        validate_names: bool,
+    ) -> PyResult<Self> {
+        let tree = crate::parse_tree(&source)?;
+        Ok(Document {
+            source,
+            tree,
+            edited: false,
+            changed: Vec::new(),
+            annotate_file,
+            lenient,
+            limits: limits.map(|l| l.clone()),
+            resolved,
+            normalize_unicode,
+            tab_width,
+            fragments,
+            strip_comments,
+            fold_constants,
+            validate_names,
+        })
+    }
+
+    /// Replace bytes `start_byte..old_end` of the source with `text`, which
+    /// ends at `new_end` in the new source.
+    fn edit(
+        &mut self,
+        start_byte: usize,
+        old_end: usize,
+        new_end: usize,
+        text: &str,
+    ) -> PyResult<()> {
+        if start_byte > old_end || old_end > self.source.len() {
+            return Err(PyValueError::new_err(format!(
+                "Edit range {}..{} is outside the {}-byte source",
+                start_byte,
+                old_end,
+                self.source.len()
+            )));
+        }
+        if new_end != start_byte + text.len() {
+            return Err(PyValueError::new_err(format!(
+                "new_end {} does not match {} + len(text) = {}",
+                new_end,
+                start_byte,
+                start_byte + text.len()
+            )));
+        }
+        if !self.source.is_char_boundary(start_byte) || !self.source.is_char_boundary(old_end) {
+            return Err(PyValueError::new_err("Edit range splits a UTF-8 character"));
+        }
+        let start_position = point_at(&self.source, start_byte);
+        let old_end_position = point_at(&self.source, old_end);
+        self.source.replace_range(start_byte..old_end, text);
+        self.tree.edit(&InputEdit {
+            start_byte,
+            old_end_byte: old_end,
+            new_end_byte: new_end,
+            start_position,
+            old_end_position,
+            new_end_position: point_at(&self.source, new_end),
+        });
+        self.edited = true;
+        Ok(())
+    }
+
+    /// Parse the current source, reusing the previous tree after edits.
+    fn reparse(&mut self, py: Python<'_>) -> PyResult<ParsedDocument> {
+        if let Some(diagnostic) = self
+            .limits
+            .as_ref()
+            .and_then(|l| l.check_source(&self.source))
+        {
+            if self.lenient {
+                return Ok(ParsedDocument::failed(diagnostic));
+            }
+            return Err(crate::errors::PyWireLimitError::new_err(diagnostic.message));
+        }
+        if self.edited {
+            let tree = crate::reparse_tree(&self.source, &self.tree)?;
+            self.changed = self
+                .tree
+                .changed_ranges(&tree)
+                .map(|r| (r.start_byte, r.end_byte))
+                .collect();
+            self.tree = tree;
+            self.edited = false;
+        }
+        crate::parse_with_tree(py, &self.source, Some(&self.tree), &self.options())
+    }
+
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Byte ranges whose syntax changed in the last `reparse()`.
+    #[getter]
+    fn changed_ranges(&self) -> Vec<(usize, usize)> {
+        self.changed.clone()
+    }
+}
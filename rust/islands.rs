@@ -0,0 +1,348 @@
+// Partial hydration. An island is the outermost subtree that needs client
+// code: an element with event handlers or `bind:` bindings, one rendering a
+// wire through its attributes or its own interpolations and blocks, or a
+// top-level block or interpolation reading a wire. Everything outside the
+// islands is static once rendered and needs no client runtime.
+//
+// Component invocations cannot be judged from the page alone and are
+// islands of their own. `ProjectIndex.islands` treats a component that is
+// static, along with every component it uses, like a plain element.
+
+use crate::kinds::AttrKind;
+use crate::project::{ProjectIndex, collect_component_tags};
+use crate::routes::free_identifiers;
+use crate::wires::declared_wires;
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::BTreeMap;
+
+/// One interactive subtree of a page. `handlers` are its `(event, handler)`
+/// entry points (`("click", "save")`, `("bind:value", "form.name")`) and
+/// `wires` the wires it renders, i.e. its update subscriptions. `component`
+/// is set when the island is a component invocation; `region` is the
+/// island's render region address, if it has one.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct Island {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub tag: Option<String>,
+    #[pyo3(get)]
+    pub component: Option<String>,
+    #[pyo3(get)]
+    pub region: Option<String>,
+    #[pyo3(get)]
+    pub handlers: Vec<(String, String)>,
+    #[pyo3(get)]
+    pub wires: Vec<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+}
+
+#[pymethods]
+impl Island {
+    fn __repr__(&self) -> String {
+        let what = match (&self.component, &self.tag) {
+            (Some(c), _) => format!("<{}>", c),
+            (None, Some(t)) => format!("<{}>", t),
+            (None, None) => "block".to_string(),
+        };
+        format!(
+            "Island({} {} at {}:{}, handlers={}, wires={:?})",
+            self.id,
+            what,
+            self.line,
+            self.column,
+            self.handlers.len(),
+            self.wires
+        )
+    }
+}
+
+/// Island manifest of a page: its interactive `islands` in document order.
+/// A page without islands is fully static.
+#[pyclass(frozen)]
+pub struct IslandManifest {
+    #[pyo3(get)]
+    pub islands: Vec<Island>,
+}
+
+#[pymethods]
+impl IslandManifest {
+    /// True when the page needs no client code at all.
+    #[getter]
+    fn is_static(&self) -> bool {
+        self.islands.is_empty()
+    }
+
+    /// Plain dict/list/str form, ready for `json.dumps`.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let islands = PyList::empty(py);
+        for island in &self.islands {
+            let dict = PyDict::new(py);
+            dict.set_item("id", &island.id)?;
+            dict.set_item("tag", &island.tag)?;
+            dict.set_item("component", &island.component)?;
+            dict.set_item("region", &island.region)?;
+            let handlers = PyList::empty(py);
+            for (event, handler) in &island.handlers {
+                let entry = PyDict::new(py);
+                entry.set_item("event", event)?;
+                entry.set_item("handler", handler)?;
+                handlers.append(entry)?;
+            }
+            dict.set_item("handlers", handlers)?;
+            dict.set_item("wires", &island.wires)?;
+            dict.set_item("start_byte", island.start_byte)?;
+            dict.set_item("end_byte", island.end_byte)?;
+            islands.append(dict)?;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("static", self.is_static())?;
+        dict.set_item("islands", islands)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("IslandManifest(islands={})", self.islands.len())
+    }
+}
+
+// What a node contributes by itself: handlers of its attributes and wires
+// read by its attributes or its expression.
+#[derive(Default)]
+struct Needs {
+    handlers: Vec<(String, String)>,
+    wires: Vec<String>,
+}
+
+impl Needs {
+    fn is_empty(&self) -> bool {
+        self.handlers.is_empty() && self.wires.is_empty()
+    }
+
+    fn read(&mut self, wires: &[&str], src: &str) {
+        for ident in free_identifiers(src) {
+            let name = ident.trim_start_matches('$');
+            if wires.contains(&name) && !self.wires.iter().any(|w| w == name) {
+                self.wires.push(name.to_string());
+            }
+        }
+    }
+
+    fn merge(&mut self, other: Needs) {
+        for handler in other.handlers {
+            if !self.handlers.contains(&handler) {
+                self.handlers.push(handler);
+            }
+        }
+        for wire in other.wires {
+            if !self.wires.contains(&wire) {
+                self.wires.push(wire);
+            }
+        }
+    }
+}
+
+fn own_needs(wires: &[&str], node: &ParsedNode) -> Needs {
+    let mut needs = Needs::default();
+    for attr in node.attributes.parsed() {
+        match attr.kind {
+            AttrKind::Event => needs.handlers.push((
+                attr.event.clone().unwrap_or_default(),
+                attr.handler_expr.clone().unwrap_or_default(),
+            )),
+            AttrKind::Bind => {
+                let expr = attr.expression.clone().unwrap_or_default();
+                needs.read(wires, &expr);
+                needs.handlers.push((attr.name.clone(), expr));
+            }
+            AttrKind::Reactive | AttrKind::Shorthand | AttrKind::Spread => {
+                needs.read(wires, attr.value.as_deref().unwrap_or_default());
+            }
+            AttrKind::ClassToggle | AttrKind::StyleProperty => {
+                needs.read(wires, attr.expression.as_deref().unwrap_or_default());
+            }
+            _ => {}
+        }
+    }
+    if let Some(expr) = &node.expression {
+        needs.read(wires, expr);
+    }
+    needs
+}
+
+// Everything `nodes` and their descendants need.
+fn subtree_needs(py: Python<'_>, wires: &[&str], nodes: &[Py<ParsedNode>], out: &mut Needs) {
+    for node in nodes {
+        let node = node.borrow(py);
+        out.merge(own_needs(wires, &node));
+        subtree_needs(py, wires, &node.children, out);
+    }
+}
+
+// End of the last byte `nodes` cover, descendants included.
+fn span_end(py: Python<'_>, nodes: &[Py<ParsedNode>]) -> usize {
+    nodes
+        .iter()
+        .map(|n| {
+            let n = n.borrow(py);
+            n.end_byte.max(span_end(py, &n.children))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+struct Finder<'a> {
+    wires: &'a [&'a str],
+    // Components known to need no client code; these are treated like
+    // plain elements.
+    static_components: &'a [String],
+    islands: Vec<Island>,
+}
+
+impl Finder<'_> {
+    fn island(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>], mut needs: Needs) {
+        subtree_needs(py, self.wires, nodes, &mut needs);
+        let first = nodes[0].borrow(py);
+        self.islands.push(Island {
+            id: format!("island-{}", self.islands.len() + 1),
+            tag: first.tag.clone(),
+            component: first.component_name.clone(),
+            region: first.region.clone(),
+            handlers: needs.handlers,
+            wires: needs.wires,
+            line: first.line,
+            column: first.column,
+            start_byte: first.start_byte,
+            end_byte: span_end(py, nodes),
+        });
+    }
+
+    // `nodes` are siblings outside any island.
+    fn visit(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) {
+        let mut i = 0;
+        while i < nodes.len() {
+            let node = nodes[i].borrow(py);
+            let mut needs = own_needs(self.wires, &node);
+            if node.tag.is_some() {
+                // An element hosts the interpolations and blocks directly
+                // inside it.
+                for child in &node.children {
+                    let child = child.borrow(py);
+                    if child.is_block {
+                        needs.merge(own_needs(self.wires, &child));
+                    }
+                }
+                let children: Vec<_> = node.children.iter().map(|c| c.clone_ref(py)).collect();
+                let is_component = node
+                    .component_name
+                    .as_ref()
+                    .is_some_and(|c| !self.static_components.contains(c));
+                drop(node);
+                if is_component || !needs.is_empty() {
+                    self.island(py, &nodes[i..=i], needs);
+                } else {
+                    self.visit(py, &children);
+                }
+                i += 1;
+                continue;
+            }
+            // A block marker's body runs to its closing marker.
+            let end = node.close_index.unwrap_or(i).max(i);
+            let children: Vec<_> = node.children.iter().map(|c| c.clone_ref(py)).collect();
+            drop(node);
+            if needs.is_empty() {
+                self.visit(py, &children);
+                i += 1;
+            } else {
+                self.island(py, &nodes[i..=end], needs);
+                i = end + 1;
+            }
+        }
+    }
+}
+
+fn find_islands(py: Python<'_>, doc: &ParsedDocument, static_components: &[String]) -> Vec<Island> {
+    let wires: Vec<&str> = declared_wires(doc).into_iter().map(|(n, _)| n).collect();
+    let mut finder = Finder {
+        wires: &wires,
+        static_components,
+        islands: Vec::new(),
+    };
+    finder.visit(py, &doc.template);
+    finder.islands
+}
+
+/// Split `document` into static content and interactive islands. Component
+/// invocations are kept as islands, since their own templates are not
+/// known here; `ProjectIndex.islands` resolves them.
+#[pyfunction]
+pub fn analyze_islands(py: Python<'_>, document: PyRef<'_, ParsedDocument>) -> IslandManifest {
+    let _span = tracing::info_span!("analyze", pass = "islands").entered();
+    IslandManifest {
+        islands: find_islands(py, &document, &[]),
+    }
+}
+
+impl ProjectIndex {
+    // Whether the component file at `path` and the components it uses are
+    // all static. Cycles count as static; the other members decide.
+    fn is_static_component(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        components: &BTreeMap<String, String>,
+        visiting: &mut Vec<String>,
+    ) -> bool {
+        if visiting.iter().any(|p| p == path) {
+            return true;
+        }
+        let Some(doc) = self.files.get(path) else {
+            return false;
+        };
+        let doc = doc.borrow(py);
+        // A component island is static only if nothing inside it needs code.
+        let interactive = find_islands(py, &doc, &[])
+            .iter()
+            .any(|i| i.component.is_none() || !i.handlers.is_empty() || !i.wires.is_empty());
+        if interactive {
+            return false;
+        }
+        let mut tags = Vec::new();
+        collect_component_tags(py, &doc.template, &mut tags);
+        visiting.push(path.to_string());
+        let all_static = tags.iter().all(|tag| {
+            components
+                .get(tag)
+                .is_some_and(|file| self.is_static_component(py, file, components, visiting))
+        });
+        visiting.pop();
+        all_static
+    }
+
+    pub(crate) fn island_manifest(&self, py: Python<'_>, path: &str) -> PyResult<IslandManifest> {
+        let doc = self
+            .files
+            .get(path)
+            .ok_or_else(|| PyKeyError::new_err(path.to_string()))?;
+        let components = self.component_map(py);
+        let static_components: Vec<String> = components
+            .iter()
+            .filter(|(_, file)| self.is_static_component(py, file, &components, &mut Vec::new()))
+            .map(|(name, _)| name.clone())
+            .collect();
+        Ok(IslandManifest {
+            islands: find_islands(py, &doc.borrow(py), &static_components),
+        })
+    }
+}
@@ -0,0 +1,614 @@
+// Jinja/Django template -> `.wire`. Tags and output expressions are
+// rewritten one at a time; constructs with no pywire equivalent are left in
+// a `{# TODO(jinja): ... #}` comment and reported as "jinja-todo" warnings.
+// Literal `{`/`}` in markup are escaped as in `from_html`.
+
+use crate::diagnostics::Diagnostic;
+use crate::htmlimport::escape_braces;
+use crate::props::split_top;
+use pyo3::prelude::*;
+
+// Filters that are a Python call or method on the value. `{}` is the value,
+// `{args}` the filter arguments.
+const FILTERS: &[(&str, &str)] = &[
+    ("abs", "abs({})"),
+    ("capitalize", "({}).capitalize()"),
+    ("count", "len({})"),
+    ("default", "({} or {args})"),
+    ("first", "({})[0]"),
+    ("float", "float({})"),
+    ("int", "int({})"),
+    ("join", "{args}.join({})"),
+    ("last", "({})[-1]"),
+    ("length", "len({})"),
+    ("list", "list({})"),
+    ("lower", "({}).lower()"),
+    ("max", "max({})"),
+    ("min", "min({})"),
+    ("replace", "({}).replace({args})"),
+    ("reverse", "list(reversed({}))"),
+    ("round", "round({})"),
+    ("sort", "sorted({})"),
+    ("string", "str({})"),
+    ("sum", "sum({})"),
+    ("title", "({}).title()"),
+    ("trim", "({}).strip()"),
+    ("upper", "({}).upper()"),
+];
+
+// Filters that only affect escaping; pywire escapes interpolations itself.
+const ESCAPING: &[&str] = &["e", "escape", "force_escape"];
+
+const RAW_TAGS: &[&str] = &["script", "style"];
+
+/// Result of `from_jinja`: the converted `.wire` source and what could not
+/// be converted. `report` summarizes both for a migration log.
+#[pyclass(frozen)]
+pub struct JinjaImport {
+    #[pyo3(get)]
+    pub wire: String,
+    /// Number of tags and expressions rewritten.
+    #[pyo3(get)]
+    pub converted: usize,
+    /// "jinja-todo" warnings for constructs left as TODO comments, and
+    /// "jinja-include" warnings for includes that relied on shared context.
+    #[pyo3(get)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[pymethods]
+impl JinjaImport {
+    #[getter]
+    fn report(&self) -> String {
+        let mut out = format!(
+            "Converted {} constructs; {} need manual changes\n",
+            self.converted,
+            self.diagnostics.len()
+        );
+        for d in &self.diagnostics {
+            out.push_str(&format!("  line {}: {} ({})\n", d.line, d.message, d.code));
+        }
+        out
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "JinjaImport(converted={}, diagnostics={})",
+            self.converted,
+            self.diagnostics.len()
+        )
+    }
+}
+
+struct Open {
+    tag: &'static str,
+    // Output offset of the opener, for wrapping a `for ... else` in `{$if}`.
+    at: usize,
+    iterable: String,
+    has_else: bool,
+}
+
+struct Converter<'a> {
+    source: &'a str,
+    extends: bool,
+    out: String,
+    directives: Vec<String>,
+    frontmatter: String,
+    stack: Vec<Open>,
+    raw: Option<&'static str>,
+    converted: usize,
+    diagnostics: Vec<Diagnostic>,
+    // Byte offset of the construct being converted.
+    at: usize,
+}
+
+impl Converter<'_> {
+    fn position(&self) -> (usize, usize) {
+        let before = &self.source[..self.at];
+        let line = before.matches('\n').count() + 1;
+        (line, self.at - before.rfind('\n').map_or(0, |i| i + 1))
+    }
+
+    fn warn(&mut self, code: &str, message: String) {
+        let (line, column) = self.position();
+        self.diagnostics
+            .push(Diagnostic::warning(code, message, line, column));
+    }
+
+    fn todo(&mut self, original: &str, why: &str) {
+        self.out
+            .push_str(&format!("{{# TODO(jinja): {} #}}", original.trim()));
+        self.warn("jinja-todo", format!("{}: {}", why, original.trim()));
+    }
+
+    // Markup between template tags; braces are escaped outside `<script>`
+    // and `<style>`.
+    fn text(&mut self, text: &str) {
+        let mut rest = text;
+        while !rest.is_empty() {
+            let lower = rest.to_ascii_lowercase();
+            match self.raw {
+                Some(tag) => match lower.find(&format!("</{}", tag)) {
+                    Some(i) => {
+                        self.out.push_str(&rest[..i]);
+                        self.raw = None;
+                        rest = &rest[i..];
+                    }
+                    None => {
+                        self.out.push_str(rest);
+                        return;
+                    }
+                },
+                None => {
+                    let opener = RAW_TAGS
+                        .iter()
+                        .filter_map(|&tag| lower.find(&format!("<{}", tag)).map(|i| (i, tag)))
+                        .min();
+                    match opener {
+                        Some((i, tag)) => {
+                            let end = rest[i..].find('>').map_or(rest.len(), |j| i + j + 1);
+                            self.out.push_str(&escape_braces(&rest[..end]));
+                            self.raw = Some(tag);
+                            rest = &rest[end..];
+                        }
+                        None => {
+                            self.out.push_str(&escape_braces(rest));
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Jinja expression -> Python; None if it uses something with no
+    // equivalent.
+    fn expression(&mut self, expr: &str) -> Option<String> {
+        let parts = split_top(expr, '|');
+        let mut value = python_literals(parts[0].trim());
+        for filter in &parts[1..] {
+            let filter = filter.trim();
+            let (name, args) = match filter.find(['(', ':']) {
+                Some(i) if filter[i..].starts_with('(') => (
+                    &filter[..i],
+                    filter[i + 1..]
+                        .strip_suffix(')')
+                        .unwrap_or(&filter[i + 1..]),
+                ),
+                Some(i) => (&filter[..i], &filter[i + 1..]),
+                None => (filter, ""),
+            };
+            let name = name.trim();
+            if ESCAPING.contains(&name) {
+                continue;
+            }
+            let Some((_, template)) = FILTERS.iter().find(|(f, _)| *f == name) else {
+                self.warn(
+                    "jinja-todo",
+                    format!("filter '{}' has no pywire equivalent", name),
+                );
+                return None;
+            };
+            value = template
+                .replace("{args}", &python_literals(args.trim()))
+                .replace("{}", &value);
+        }
+        for unsupported in ["loop.", "forloop.", " is defined", " is not defined", "~"] {
+            if value.contains(unsupported) {
+                self.warn(
+                    "jinja-todo",
+                    format!("'{}' has no pywire equivalent", unsupported.trim()),
+                );
+                return None;
+            }
+        }
+        Some(value)
+    }
+
+    // `{{ expr }}`; `markers` are the pywire trim markers.
+    fn output(&mut self, body: &str, markers: (&str, &str)) {
+        let filters = split_top(body, '|');
+        let safe = filters.len() > 1 && filters[filters.len() - 1].trim() == "safe";
+        let expr = if safe {
+            body[..body.rfind('|').unwrap_or(body.len())].trim()
+        } else {
+            body
+        };
+        match self.expression(expr) {
+            Some(value) if safe => self
+                .out
+                .push_str(&format!("{{{}$html {}{}}}", markers.0, value, markers.1)),
+            Some(value) => self
+                .out
+                .push_str(&format!("{{{}{}{}}}", markers.0, value, markers.1)),
+            None => {
+                self.out
+                    .push_str(&format!("{{# TODO(jinja): {{{{ {} }}}} #}}", body.trim()));
+                return;
+            }
+        }
+        self.converted += 1;
+    }
+
+    fn block(&mut self, keyword: &str, expr: &str, markers: (&str, &str)) {
+        let (before, after) = markers;
+        match expr {
+            "" => self
+                .out
+                .push_str(&format!("{{{}${}{}}}", before, keyword, after)),
+            _ => self
+                .out
+                .push_str(&format!("{{{}${} {}{}}}", before, keyword, expr, after)),
+        }
+    }
+
+    fn close(&mut self, keyword: &str, markers: (&str, &str)) {
+        self.out
+            .push_str(&format!("{{{}/{}{}}}", markers.0, keyword, markers.1));
+    }
+
+    // `{% name args %}`. Returns false if it was left as a TODO.
+    fn tag(&mut self, original: &str, name: &str, args: &str, markers: (&str, &str)) -> bool {
+        let top = self.stack.last().map(|o| o.tag);
+        if top == Some("todo") && matches!(name, "elif" | "else" | "empty" | "endif" | "endfor") {
+            if name.starts_with("end") {
+                self.stack.pop();
+            }
+            self.todo(original, "part of an unconverted block");
+            return false;
+        }
+        match name {
+            "if" | "elif" => {
+                let Some(cond) = self.expression(args) else {
+                    if name == "if" {
+                        return self.skip_block(original, "condition not convertible");
+                    }
+                    self.todo(original, "condition not convertible");
+                    return false;
+                };
+                self.block(name, &cond, markers);
+                if name == "if" {
+                    self.push("if");
+                }
+            }
+            "else" | "empty" if top == Some("for") => {
+                let Some(open) = self.stack.last_mut() else {
+                    return false;
+                };
+                open.has_else = true;
+                let wrap = format!("{{$if {}}}", open.iterable);
+                let at = open.at;
+                self.out.insert_str(at, &wrap);
+                self.close("for", markers);
+                self.block("else", "", ("", ""));
+            }
+            "else" => self.block("else", "", markers),
+            "endif" => {
+                self.stack.pop();
+                self.close("if", markers);
+            }
+            "for" => {
+                let Some((target, iterable)) = args.split_once(" in ") else {
+                    return self.skip_block(original, "loop not convertible");
+                };
+                if iterable.contains(" if ") || iterable.trim_end().ends_with("recursive") {
+                    return self.skip_block(
+                        original,
+                        "loop filters and recursive loops are not supported",
+                    );
+                }
+                let Some(iterable) = self.expression(iterable) else {
+                    return self.skip_block(original, "loop not convertible");
+                };
+                let at = self.out.len();
+                self.block(
+                    "for",
+                    &format!("{} in {}", target.trim(), iterable),
+                    markers,
+                );
+                self.stack.push(Open {
+                    tag: "for",
+                    at,
+                    iterable,
+                    has_else: false,
+                });
+            }
+            "endfor" => match self.stack.pop() {
+                Some(open) if open.has_else => self.close("if", markers),
+                _ => self.close("for", markers),
+            },
+            "block" => {
+                let slot = args.split_whitespace().next().unwrap_or("default");
+                if self.extends {
+                    self.out.push_str(&format!("<template slot=\"{}\">", slot));
+                } else {
+                    self.out.push_str(&format!("<slot name=\"{}\">", slot));
+                }
+                self.push("block");
+            }
+            "endblock" => {
+                self.stack.pop();
+                self.out.push_str(if self.extends {
+                    "</template>"
+                } else {
+                    "</slot>"
+                });
+            }
+            "extends" => {
+                let Some(path) = string_literal(args) else {
+                    self.todo(original, "dynamic extends");
+                    return false;
+                };
+                self.directives
+                    .push(format!("!layout \"{}\"", wire_path(path)));
+            }
+            "include" => {
+                let Some(path) = string_literal(args) else {
+                    self.todo(original, "dynamic include");
+                    return false;
+                };
+                let component = component_name(path);
+                let directive = format!("!component \"{}\" as {}", wire_path(path), component);
+                if !self.directives.contains(&directive) {
+                    self.directives.push(directive);
+                }
+                self.out.push_str(&format!("<{} />", component));
+                self.warn(
+                    "jinja-include",
+                    format!(
+                        "'{}' no longer sees the including template's variables; pass them as props",
+                        path
+                    ),
+                );
+            }
+            "set" if self.stack.is_empty() => {
+                let Some((target, value)) = args.split_once('=') else {
+                    self.todo(original, "block assignments are not supported");
+                    return false;
+                };
+                let Some(value) = self.expression(value.trim()) else {
+                    self.todo(original, "value not convertible");
+                    return false;
+                };
+                self.frontmatter
+                    .push_str(&format!("{} = {}\n", target.trim(), value));
+            }
+            "load" => {}
+            _ => {
+                self.todo(original, &format!("'{}' has no pywire equivalent", name));
+                return false;
+            }
+        }
+        true
+    }
+
+    // Leave an `if`/`for` opener as a TODO; its branches and end tag follow.
+    fn skip_block(&mut self, original: &str, why: &str) -> bool {
+        self.todo(original, why);
+        self.push("todo");
+        false
+    }
+
+    fn push(&mut self, tag: &'static str) {
+        self.stack.push(Open {
+            tag,
+            at: self.out.len(),
+            iterable: String::new(),
+            has_else: false,
+        });
+    }
+
+    // Skip to `{% end %}` and copy what is between as text or a comment.
+    fn verbatim(&mut self, rest: &str, end: &str, comment: bool) -> usize {
+        let (body, consumed) = match find_tag(rest, end) {
+            Some((start, stop)) => (&rest[..start], stop),
+            None => (rest, rest.len()),
+        };
+        if comment {
+            self.out.push_str(&format!("{{#{}#}}", body));
+        } else {
+            self.text(body);
+        }
+        self.converted += 1;
+        consumed
+    }
+
+    fn run(&mut self) {
+        let source = self.source;
+        let mut i = 0;
+        while i < source.len() {
+            let Some(start) = ["{{", "{%", "{#"]
+                .iter()
+                .filter_map(|open| source[i..].find(open).map(|j| i + j))
+                .min()
+            else {
+                self.text(&source[i..]);
+                break;
+            };
+            self.text(&source[i..start]);
+            self.at = start;
+            let close = match &source[start..start + 2] {
+                "{{" => "}}",
+                "{%" => "%}",
+                _ => "#}",
+            };
+            let Some(len) = source[start + 2..].find(close) else {
+                let rest = source[start..].to_string();
+                self.warn("jinja-todo", "unclosed template tag".to_string());
+                self.text(&rest);
+                break;
+            };
+            let end = start + 2 + len + 2;
+            let original = &source[start..end];
+            let inner = &source[start + 2..end - 2];
+            // Jinja `{%-`/`-%}` map onto pywire's trim markers.
+            let before = if inner.starts_with('-') { "- " } else { "" };
+            let after = if inner.ends_with('-') { " -" } else { "" };
+            let body = inner
+                .strip_prefix(['-', '+'])
+                .unwrap_or(inner)
+                .trim_end_matches(['-', '+'])
+                .trim();
+            i = end;
+            match close {
+                "}}" => self.output(body, (before, after)),
+                "#}" => {
+                    self.out.push_str(original);
+                    self.converted += 1;
+                }
+                _ => {
+                    let (name, args) = body
+                        .split_once(char::is_whitespace)
+                        .map_or((body, ""), |(n, a)| (n, a.trim()));
+                    i += match name {
+                        "comment" => self.verbatim(&source[end..], "endcomment", true),
+                        "raw" => self.verbatim(&source[end..], "endraw", false),
+                        "verbatim" => self.verbatim(&source[end..], "endverbatim", false),
+                        _ => {
+                            if self.tag(original, name, args, (before, after)) {
+                                self.converted += 1;
+                            }
+                            0
+                        }
+                    };
+                }
+            }
+        }
+    }
+}
+
+// Start and end of the first `{% name %}` tag in `text`.
+fn find_tag(text: &str, name: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    while let Some(i) = text[from..].find("{%").map(|i| from + i) {
+        let close = text[i..].find("%}").map(|j| i + j + 2)?;
+        let inner = text[i + 2..close - 2].trim_matches(['-', '+']).trim();
+        if inner == name {
+            return Some((i, close));
+        }
+        from = close;
+    }
+    None
+}
+
+// Jinja's lowercase `true`/`false`/`none` outside string literals.
+fn python_literals(expr: &str) -> String {
+    let mut out = String::with_capacity(expr.len());
+    let mut quote = None;
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        out.push_str(match word.as_str() {
+            "true" => "True",
+            "false" => "False",
+            "none" => "None",
+            w => w,
+        });
+        word.clear();
+    };
+    for c in expr.chars() {
+        match quote {
+            Some(q) => {
+                out.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_alphanumeric() || c == '_' || (c == '.' && !word.is_empty()) => {
+                word.push(c)
+            }
+            None => {
+                flush(&mut word, &mut out);
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+fn string_literal(args: &str) -> Option<&str> {
+    let args = args.trim();
+    let quote = args.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &args[1..];
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+// `partials/nav.html` -> `partials/nav.wire`.
+fn wire_path(path: &str) -> String {
+    let stem = [".html", ".htm", ".jinja2", ".jinja", ".j2", ".djhtml"]
+        .iter()
+        .find_map(|ext| path.strip_suffix(ext))
+        .unwrap_or(path);
+    format!("{}.wire", stem)
+}
+
+// `partials/site_nav.html` -> `SiteNav`.
+fn component_name(path: &str) -> String {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let stem = file.split('.').next().unwrap_or(file);
+    stem.split(['_', '-'])
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            chars
+                .next()
+                .map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+// Whether any `{% name ... %}` tag appears in `source`.
+fn has_tag(source: &str, name: &str) -> bool {
+    source.match_indices("{%").any(|(i, _)| {
+        source[i + 2..]
+            .trim_start_matches(['-', '+'])
+            .trim_start()
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+    })
+}
+
+/// Convert a Jinja or Django template to a `.wire` template.
+///
+/// `{{ expr|filter }}` becomes `{expr}` with common filters rewritten as
+/// Python (`|safe` becomes `{$html ...}`); `if`/`for`/`else`/`empty` become
+/// brace blocks; `{% extends %}` becomes `!layout` and `{% block %}` a
+/// `<slot>` (in a base template) or `<template slot>` (in a child);
+/// `{% include %}` becomes a component; top-level `{% set %}` moves to the
+/// frontmatter. Anything else is kept in a `{# TODO(jinja): ... #}` comment
+/// and reported in `diagnostics`.
+#[pyfunction]
+pub fn from_jinja(py: Python<'_>, source: &str) -> JinjaImport {
+    py.detach(|| {
+        let mut converter = Converter {
+            source,
+            extends: has_tag(source, "extends"),
+            out: String::with_capacity(source.len()),
+            directives: Vec::new(),
+            frontmatter: String::new(),
+            stack: Vec::new(),
+            raw: None,
+            converted: 0,
+            diagnostics: Vec::new(),
+            at: 0,
+        };
+        converter.run();
+        let mut wire = String::new();
+        for directive in &converter.directives {
+            wire.push_str(directive);
+            wire.push('\n');
+        }
+        if !converter.frontmatter.is_empty() {
+            wire.push_str(&format!("---\n{}---\n", converter.frontmatter));
+        }
+        wire.push_str(&converter.out);
+        JinjaImport {
+            wire,
+            converted: converter.converted,
+            diagnostics: converter.diagnostics,
+        }
+    })
+}
@@ -0,0 +1,200 @@
+use pyo3::prelude::*;
+
+/// What a ParsedNode represents. `END_BLOCK` is a closing marker such as
+/// `{/if}`; `BLOCK` covers opening and branch markers (`{$if}`, `{$else}`).
+/// `COMPONENT` is an element whose tag names a component, e.g. `<Card>`;
+/// `COMMENT` is an HTML comment and `DOCTYPE` a `<!DOCTYPE ...>` declaration.
+#[pyclass(eq, eq_int, frozen, hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    #[pyo3(name = "ELEMENT")]
+    Element,
+    #[pyo3(name = "TEXT")]
+    Text,
+    #[pyo3(name = "INTERPOLATION")]
+    Interpolation,
+    #[pyo3(name = "BLOCK")]
+    Block,
+    #[pyo3(name = "END_BLOCK")]
+    EndBlock,
+    #[pyo3(name = "COMPONENT")]
+    Component,
+    #[pyo3(name = "COMMENT")]
+    Comment,
+    #[pyo3(name = "DOCTYPE")]
+    Doctype,
+}
+
+/// Keyword of a block marker; opening and closing markers share a keyword.
+#[pyclass(eq, eq_int, frozen, hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlockKeyword {
+    #[pyo3(name = "IF")]
+    If,
+    #[pyo3(name = "ELIF")]
+    Elif,
+    #[pyo3(name = "ELSE")]
+    Else,
+    #[pyo3(name = "FOR")]
+    For,
+    #[pyo3(name = "TRY")]
+    Try,
+    #[pyo3(name = "EXCEPT")]
+    Except,
+    #[pyo3(name = "FINALLY")]
+    Finally,
+    #[pyo3(name = "AWAIT")]
+    Await,
+    #[pyo3(name = "THEN")]
+    Then,
+    #[pyo3(name = "CATCH")]
+    Catch,
+    #[pyo3(name = "HTML")]
+    Html,
+    #[pyo3(name = "EXAMPLE")]
+    Example,
+    #[pyo3(name = "MATCH")]
+    Match,
+    #[pyo3(name = "CASE")]
+    Case,
+    #[pyo3(name = "DEFAULT")]
+    Default,
+}
+
+#[pymethods]
+impl BlockKeyword {
+    /// The keyword as written in templates, e.g. "elif".
+    #[getter]
+    fn value(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+impl BlockKeyword {
+    pub(crate) fn parse(keyword: &str) -> Option<Self> {
+        Some(match keyword.trim_start_matches('/') {
+            "if" => Self::If,
+            "elif" => Self::Elif,
+            "else" => Self::Else,
+            "for" => Self::For,
+            "try" => Self::Try,
+            "except" => Self::Except,
+            "finally" => Self::Finally,
+            "await" => Self::Await,
+            "then" => Self::Then,
+            "catch" => Self::Catch,
+            "html" => Self::Html,
+            "example" => Self::Example,
+            "match" => Self::Match,
+            "case" => Self::Case,
+            "default" => Self::Default,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Elif => "elif",
+            Self::Else => "else",
+            Self::For => "for",
+            Self::Try => "try",
+            Self::Except => "except",
+            Self::Finally => "finally",
+            Self::Await => "await",
+            Self::Then => "then",
+            Self::Catch => "catch",
+            Self::Html => "html",
+            Self::Example => "example",
+            Self::Match => "match",
+            Self::Case => "case",
+            Self::Default => "default",
+        }
+    }
+}
+
+// `click.prevent` of an `@click.prevent` or `on:click.prevent` attribute.
+pub(crate) fn event_spec(name: &str) -> Option<&str> {
+    name.strip_prefix('@').or_else(|| name.strip_prefix("on:"))
+}
+
+// `active` of `class:active`, `color` of `style:color`.
+pub(crate) fn class_toggle(name: &str) -> Option<&str> {
+    name.strip_prefix("class:").filter(|t| !t.is_empty())
+}
+
+pub(crate) fn style_property(name: &str) -> Option<&str> {
+    name.strip_prefix("style:").filter(|t| !t.is_empty())
+}
+
+// `value` of a `bind:value` attribute.
+pub(crate) fn bind_target(name: &str) -> Option<&str> {
+    name.strip_prefix("bind:").filter(|t| !t.is_empty())
+}
+
+/// How the compiler treats an attribute, from its name and raw value.
+#[pyclass(eq, eq_int, frozen, hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttrKind {
+    /// Plain `name="value"`.
+    #[pyo3(name = "STATIC")]
+    Static,
+    /// Valueless `name`.
+    #[pyo3(name = "BOOLEAN")]
+    Boolean,
+    /// `name={expr}`.
+    #[pyo3(name = "REACTIVE")]
+    Reactive,
+    /// `@event={handler}` or `on:event={handler}`.
+    #[pyo3(name = "EVENT")]
+    Event,
+    /// `$directive`.
+    #[pyo3(name = "DIRECTIVE")]
+    Directive,
+    /// `bind:property={target}` two-way binding.
+    #[pyo3(name = "BIND")]
+    Bind,
+    /// `class:name={condition}`, or `class:name` for `class:name={name}`.
+    #[pyo3(name = "CLASS_TOGGLE")]
+    ClassToggle,
+    /// `style:property={value}`, or `style:property` for
+    /// `style:property={property}`.
+    #[pyo3(name = "STYLE_PROPERTY")]
+    StyleProperty,
+    /// `{name}` shorthand.
+    #[pyo3(name = "SHORTHAND")]
+    Shorthand,
+    /// `{**props}` spread.
+    #[pyo3(name = "SPREAD")]
+    Spread,
+}
+
+impl AttrKind {
+    pub(crate) fn classify(name: &str, value: Option<&str>) -> Self {
+        if name == "__pywire_spread__" {
+            Self::Spread
+        } else if name.starts_with("__pw_sh_") {
+            Self::Shorthand
+        } else if event_spec(name).is_some() || name.starts_with("__pw_on_") {
+            Self::Event
+        } else if bind_target(name).is_some() {
+            Self::Bind
+        } else if class_toggle(name).is_some() {
+            Self::ClassToggle
+        } else if style_property(name).is_some() {
+            Self::StyleProperty
+        } else if name.starts_with('$') || name.starts_with("__pw_dir_") {
+            Self::Directive
+        } else {
+            match value.map(str::trim) {
+                None => Self::Boolean,
+                Some(v) if v.starts_with('{') && v.ends_with('}') => Self::Reactive,
+                Some(_) => Self::Static,
+            }
+        }
+    }
+}
@@ -1,6 +1,87 @@
 use pyo3::prelude::*;
 use std::collections::HashMap;
-use tree_sitter::{Node, Parser};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use tracing::field::Empty;
+use tree_sitter::{Node, Parser, Range, Tree};
+
+mod annotate;
+mod assets;
+mod attrs;
+mod backpressure;
+mod balance;
+mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod chunks;
+mod classes;
+mod codegen;
+mod codemod;
+mod columns;
+mod comments;
+mod components;
+#[cfg(feature = "compression")]
+mod compress;
+#[cfg(feature = "conformance")]
+mod conformance;
+mod consteval;
+mod depgraph;
+mod diagnostics;
+mod docs;
+mod email;
+mod emits;
+mod entities;
+mod errors;
+mod examples;
+mod exprinfo;
+mod feed;
+mod fingerprint;
+mod formatting;
+mod fragments;
+mod frontmatter;
+mod guard;
+mod history;
+mod hoisting;
+mod html;
+mod htmlimport;
+mod incremental;
+mod islands;
+mod jinjaimport;
+mod kinds;
+mod library;
+mod limits;
+mod listdiff;
+mod manifest;
+mod meta;
+mod migrate;
+mod minify;
+mod morph;
+mod pairing;
+mod pragmas;
+mod prerender;
+mod profile;
+mod project;
+mod props;
+mod provider;
+mod query;
+mod reactive;
+mod regions;
+mod routes;
+mod rows;
+mod scheduler;
+mod selective;
+mod sequencing;
+mod serializer;
+mod snapshot;
+mod sprites;
+mod stream;
+mod strict;
+mod textdiff;
+mod trace;
+mod trim;
+mod unicode;
+mod versions;
+mod wires;
 
 #[pyclass]
 #[derive(Clone)]
@@ -13,6 +94,19 @@ pub struct ParsedDirective {
     pub line: usize,
     #[pyo3(get)]
     pub column: usize,
+    /// `column` with tabs expanded to the parse `tab_width`.
+    #[pyo3(get)]
+    pub display_column: usize,
+    /// End of the source range (exclusive); `[start_byte, end_byte)` is the
+    /// same range as byte offsets into the source.
+    #[pyo3(get)]
+    pub end_line: usize,
+    #[pyo3(get)]
+    pub end_column: usize,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
 }
 
 #[pyclass]
@@ -28,7 +122,7 @@ pub struct ParsedNode {
     #[pyo3(get)]
     pub expression: Option<String>,
     #[pyo3(get)]
-    pub attributes: HashMap<String, Option<String>>,
+    pub attributes: attrs::Attributes,
     #[pyo3(get)]
     pub children: Vec<Py<ParsedNode>>,
     #[pyo3(get)]
@@ -37,6 +131,186 @@ pub struct ParsedNode {
     pub column: usize,
     #[pyo3(get)]
     pub is_raw: bool,
+    /// End of the source range (exclusive); `[start_byte, end_byte)` is the
+    /// same range as byte offsets into the source.
+    #[pyo3(get)]
+    pub end_line: usize,
+    #[pyo3(get)]
+    pub end_column: usize,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+    /// `column` with tabs expanded to the parse `tab_width`.
+    #[pyo3(get)]
+    pub display_column: usize,
+    /// Stable address of the render region this node starts, if it is one.
+    #[pyo3(get)]
+    pub region: Option<String>,
+    /// On branch (`{$else}`) and closing (`{/if}`) markers: index of the
+    /// opening marker among the same siblings.
+    #[pyo3(get)]
+    pub open_index: Option<usize>,
+    /// On opening block markers: index of the closing marker among the same
+    /// siblings.
+    #[pyo3(get)]
+    pub close_index: Option<usize>,
+    /// Start of the element's end tag or the block's closing marker.
+    #[pyo3(get)]
+    pub close_line: Option<usize>,
+    #[pyo3(get)]
+    pub close_column: Option<usize>,
+    /// With `resolved=True`, the `(condition, body)` branches of an `{$if}`
+    /// chain in order; empty for other nodes.
+    #[pyo3(get)]
+    pub branches: Vec<Py<pairing::Branch>>,
+    /// The tag when it names a component (`<Card>`, `<ui.Card>`); None for
+    /// HTML elements and other nodes.
+    #[pyo3(get)]
+    pub component_name: Option<String>,
+    /// True for `<slot>` elements.
+    #[pyo3(get)]
+    pub is_slot: bool,
+    /// On `<slot>`: the slot it defines (`name`, else "default"). On other
+    /// elements: the slot they fill, from a static `slot="..."` attribute.
+    #[pyo3(get)]
+    pub slot_name: Option<String>,
+    /// `-` trim markers on a block or interpolation (`{- /if}`, `{$if c -}`):
+    /// whitespace in the adjacent text before/after it has been removed.
+    #[pyo3(get)]
+    pub trim_before: bool,
+    #[pyo3(get)]
+    pub trim_after: bool,
+    /// True for `<!-- ... -->`; `text_content` is the comment as written.
+    #[pyo3(get)]
+    pub is_comment: bool,
+    /// True for `<!DOCTYPE ...>`; `text_content` is the declaration as
+    /// written. It marks where the document shell begins.
+    #[pyo3(get)]
+    pub is_doctype: bool,
+    /// True for HTML void elements (`<br>`, `<img>`, `<input>`, ...), which
+    /// never have content or an end tag.
+    #[pyo3(get)]
+    pub is_void: bool,
+    /// True for elements written self-closed (`<br/>`, `<Card />`).
+    #[pyo3(get)]
+    pub is_self_closing: bool,
+    /// On `<template shadowrootmode="...">`: the declarative shadow root's
+    /// mode, "open" or "closed". Its content is the shadow tree, so a
+    /// `<slot>` inside it is a native slot (`is_slot` is False).
+    #[pyo3(get)]
+    pub shadow_root_mode: Option<String>,
+    /// On the outermost element of a fully static subtree: index of its
+    /// rendered markup in `ParsedDocument.static_chunks`. Identical subtrees
+    /// share an index.
+    #[pyo3(get)]
+    pub static_chunk: Option<usize>,
+    /// On `{$for}` openers whose body is plain markup and interpolations:
+    /// the body precompiled into static parts and expression slots.
+    #[pyo3(get)]
+    pub row_template: Option<Py<rows::RowTemplate>>,
+    /// On text folded from a constant interpolation (`fold_constants=True`):
+    /// the original expression.
+    #[pyo3(get)]
+    pub folded_from: Option<String>,
+    /// What `expression` reads, on interpolations and block markers.
+    #[pyo3(get)]
+    pub expression_info: Option<exprinfo::ExpressionInfo>,
+    // The whole source the node was parsed from, shared by every node.
+    source: Arc<str>,
+}
+
+#[pymethods]
+impl ParsedNode {
+    #[getter]
+    fn kind(&self) -> kinds::NodeKind {
+        match self.block_keyword.as_deref() {
+            _ if self.is_comment => kinds::NodeKind::Comment,
+            _ if self.is_doctype => kinds::NodeKind::Doctype,
+            _ if !self.is_block => match self.tag {
+                Some(_) if self.component_name.is_some() => kinds::NodeKind::Component,
+                Some(_) => kinds::NodeKind::Element,
+                None => kinds::NodeKind::Text,
+            },
+            Some("interpolation") => kinds::NodeKind::Interpolation,
+            Some(kw) if kw.starts_with('/') => kinds::NodeKind::EndBlock,
+            _ => kinds::NodeKind::Block,
+        }
+    }
+
+    /// Text of the children split into static runs and addressable
+    /// interpolations, e.g. `Hello, {name}!` as "Hello, ", `name`, "!".
+    /// Empty unless the node is a region with an interpolated child.
+    #[getter]
+    fn text_parts(&self, py: Python<'_>) -> Vec<regions::TextPart> {
+        regions::text_parts(py, self)
+    }
+
+    /// The node exactly as written, `source[start_byte:end_byte]`, for
+    /// tools that must reproduce the author's markup byte for byte.
+    #[getter]
+    fn source_text(&self) -> &str {
+        self.source
+            .get(self.start_byte..self.end_byte)
+            .unwrap_or("")
+    }
+
+    /// `block_keyword` as a BlockKeyword, for both opening and closing
+    /// markers; None for other nodes.
+    #[getter]
+    fn keyword(&self) -> Option<kinds::BlockKeyword> {
+        self.block_keyword
+            .as_deref()
+            .filter(|_| self.is_block)
+            .and_then(kinds::BlockKeyword::parse)
+    }
+
+    /// Props passed to a component invocation, sorted by name; empty for
+    /// other nodes. Events, `bind:`, `class:` and `style:` attributes and `$`
+    /// directives are not props.
+    #[getter]
+    fn props(&self) -> Vec<components::ComponentProp> {
+        if self.component_name.is_none() {
+            return Vec::new();
+        }
+        components::props(&self.attributes)
+    }
+
+    /// `class:name={condition}` attributes, in source order.
+    #[getter]
+    fn conditional_classes(&self) -> Vec<attrs::ConditionalAttribute> {
+        self.attributes.conditionals(kinds::AttrKind::ClassToggle)
+    }
+
+    /// `style:property={value}` attributes, in source order.
+    #[getter]
+    fn conditional_styles(&self) -> Vec<attrs::ConditionalAttribute> {
+        self.attributes.conditionals(kinds::AttrKind::StyleProperty)
+    }
+
+    /// Dynamic attributes a re-render patches together with one `set_attrs`
+    /// op, in source order; empty when fewer than two can change.
+    #[getter]
+    fn attribute_batch(&self) -> Vec<String> {
+        let names = self.attributes.dynamic_names();
+        if names.len() < 2 {
+            return Vec::new();
+        }
+        names.into_iter().map(str::to_string).collect()
+    }
+
+    /// Attributes as ParsedAttribute objects, in source order. Unlike
+    /// `attributes`, every `{**spread}` is included.
+    #[getter]
+    fn parsed_attributes(&self) -> Vec<attrs::ParsedAttribute> {
+        self.attributes.parsed().to_vec()
+    }
+
+    /// Classify attribute `name` (an `attributes` key); None if the node has
+    /// no such attribute.
+    fn attribute_kind(&self, name: &str) -> Option<kinds::AttrKind> {
+        self.attributes.find(name).map(|attr| attr.kind)
+    }
 }
 
 #[pyclass]
@@ -45,8 +319,37 @@ pub struct ParsedDocument {
     pub directives: Vec<ParsedDirective>,
     #[pyo3(get)]
     pub python_code: String,
+    /// Line of the file where `python_code` starts.
+    #[pyo3(get)]
+    pub python_line: usize,
     #[pyo3(get)]
     pub template: Vec<Py<ParsedNode>>,
+    #[pyo3(get)]
+    pub cache_policy: Option<routes::CachePolicy>,
+    #[pyo3(get)]
+    pub diagnostics: Vec<diagnostics::Diagnostic>,
+    /// Number of warnings silenced by suppression pragmas, per code.
+    #[pyo3(get)]
+    pub suppressed: HashMap<String, usize>,
+    /// `$:` statements and `@reactive` functions in the frontmatter.
+    #[pyo3(get)]
+    pub reactive: Vec<reactive::ReactiveStatement>,
+    /// Templates embedded in frontmatter strings; filled with `fragments=True`.
+    #[pyo3(get)]
+    pub fragments: Vec<Py<fragments::Fragment>>,
+    /// `!doc` content or the `{# ... #}` comment opening the file.
+    #[pyo3(get)]
+    pub docstring: Option<String>,
+    /// Deduplicated markup of the static subtrees, indexed by
+    /// `ParsedNode.static_chunk`.
+    #[pyo3(get)]
+    pub static_chunks: Vec<String>,
+    /// `!const NAME = expr` declarations as `(name, expression)` pairs.
+    #[pyo3(get)]
+    pub constants: Vec<(String, String)>,
+    suppressions: pragmas::Suppressions,
+    // The text the byte offsets refer to.
+    source: Arc<str>,
 }
 
 #[pyfunction]
@@ -54,26 +357,325 @@ fn version() -> &'static str {
     "0.2.0-unified-v2"
 }
 
+/// Parse a `.wire` template.
+///
+/// With `lenient=True` parsing never raises: internal failures and
+/// unmappable nodes are reported in `diagnostics` and the rest of the
+/// document is still returned. Syntax errors are reported in `diagnostics`
+/// in both modes; `ParsedDocument.raise_for_errors()` turns them into
+/// exceptions.
+///
+/// `limits` (a `ParseLimits`) bounds source size, node count and attributes
+/// per element; exceeding one raises `PyWireLimitError`, or when lenient stops
+/// mapping with a "limit-exceeded" diagnostic.
+///
+/// With `resolved=True` block bodies become children of their opening
+/// marker, branch markers (`{$elif}`, `{$else}`, `{$except}`, ...) become
+/// children of the opener holding their own body, and closing markers are
+/// dropped (the opener keeps `close_line`/`close_column`). `{$if}` nodes
+/// additionally list their chain in `branches`.
+///
+/// Render regions (blocks and dynamic elements) carry a `region` path built
+/// from their own content and loop keys, e.g. `for.1c9e02[item.id]/li.77a0b4`,
+/// so adding unrelated siblings does not change it. The full address is
+/// `<file>#<region>` with key values substituted at render time.
+///
+/// Fully static subtrees are rendered into `static_chunks`, one entry per
+/// distinct markup, and their root element points at it with `static_chunk`.
+///
+/// `{$for}` loops over plain markup carry a `row_template` that renders
+/// each item by splicing slot values into precompiled static parts.
+///
+/// With `fold_constants=True` interpolations built only from literals and
+/// `!const NAME = ...` values are evaluated and become text nodes, keeping
+/// the expression in `folded_from`; see `constants`.
+///
+/// With `validate_names=True` template references to names that the
+/// frontmatter, its imports, `!const` declarations and Python builtins do not
+/// define are reported as "undefined-name" warnings, e.g. a misspelled
+/// `{countt}`.
+///
+/// With `normalize_unicode=True` expressions are NFC-normalized and
+/// invisible or confusable characters in them are reported as warnings.
+///
+/// Warnings can be silenced with `{# pywire: disable=code,... #}` or
+/// `!nolint code ...` pragmas, file-wide above the first markup line or
+/// otherwise for the following element; see `suppressed`.
+///
+/// With `fragments=True` string literals passed to `html(...)` in the
+/// frontmatter are parsed as templates too and listed in `fragments`.
+///
+/// HTML comments are kept as `is_comment` nodes; `strip_comments=True`
+/// drops them.
+///
+/// Every `column` is a byte offset into its line; `display_column` expands
+/// tabs to `tab_width` for editor carets.
+///
+/// A panic inside the parser is raised as `InternalParserError` (or, when
+/// lenient, reported as an "internal-error" diagnostic).
 #[pyfunction]
-fn parse(py: Python<'_>, source: String) -> PyResult<ParsedDocument> {
+#[pyo3(signature = (
+    source,
+    annotate_file=None,
+    lenient=false,
+    limits=None,
+    resolved=false,
+    normalize_unicode=false,
+    tab_width=columns::DEFAULT_TAB_WIDTH,
+    fragments=false,
+    strip_comments=false,
+    fold_constants=false,
+    validate_names=false,
+))]
+#[allow(clippy::too_many_arguments)]
+fn parse(
+    py: Python<'_>,
+    source: String,
+    annotate_file: Option<&str>,
+    lenient: bool,
+    limits: Option<PyRef<'_, limits::ParseLimits>>,
+    resolved: bool,
+    normalize_unicode: bool,
+    tab_width: usize,
+    fragments: bool,
+    strip_comments: bool,
+    fold_constants: bool,
+    validate_names: bool,
+) -> PyResult<ParsedDocument> {
+    let options = ParseOptions {
+        annotate_file,
+        lenient,
+        limits: limits.as_deref(),
+        resolved,
+        normalize_unicode,
+        tab_width,
+        fragments,
+        strip_comments,
+        fold_constants,
+        validate_names,
+    };
+    parse_with(py, &source, &options)
+}
+
+// `parse` with the panic guard, for callers inside the crate.
+pub(crate) fn parse_with(
+    py: Python<'_>,
+    source: &str,
+    options: &ParseOptions<'_>,
+) -> PyResult<ParsedDocument> {
+    parse_with_tree(py, source, None, options)
+}
+
+// `parse_with` for a caller that already has the tree-sitter tree of
+// `source` (incremental, batched or ranged parses); None parses it here.
+pub(crate) fn parse_with_tree(
+    py: Python<'_>,
+    source: &str,
+    tree: Option<&Tree>,
+    options: &ParseOptions<'_>,
+) -> PyResult<ParsedDocument> {
+    guard::record_offset(0);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        parse_document(py, source, tree, options)
+    }));
+    match result {
+        Ok(result) => result,
+        Err(payload) if options.lenient => {
+            let offset = guard::current_offset().min(source.len());
+            let before = source.get(..offset).unwrap_or("");
+            let line = before.matches('\n').count() + 1;
+            let column = offset - before.rfind('\n').map_or(0, |i| i + 1);
+            Ok(ParsedDocument::failed(diagnostics::Diagnostic::error(
+                "internal-error",
+                format!("internal parser error: {}", guard::panic_message(&*payload)),
+                line,
+                column,
+            )))
+        }
+        Err(payload) => Err(guard::internal_error(py, source, &*payload)),
+    }
+}
+
+#[pymethods]
+impl ParsedDocument {
+    /// Raise the exception matching the first error diagnostic, if any:
+    /// `PyWireSyntaxError`, `PyWireLimitError`, `PyWireDirectiveError` or
+    /// `PyWireParseError`. Warnings are ignored.
+    fn raise_for_errors(&self, py: Python<'_>) -> PyResult<()> {
+        match self.diagnostics.iter().find(|d| d.severity == "error") {
+            Some(d) => Err(errors::from_diagnostic(py, d)),
+            None => Ok(()),
+        }
+    }
+
+    /// "rss" or "atom" for a `!feed` template, else None. Feeds are parsed
+    /// as XML (CDATA kept verbatim, `<link>` with content) and checked for
+    /// the format's required elements.
+    #[getter]
+    fn feed(&self) -> Option<&'static str> {
+        feed::feed_format(&self.directives)
+            .ok()
+            .flatten()
+            .map(feed::FeedFormat::name)
+    }
+
+    /// Whether a `{# pywire: disable=... #}` or `!nolint` pragma silences
+    /// rule `code` at `line`, for lint passes run outside the parser.
+    fn is_suppressed(&self, code: &str, line: usize) -> bool {
+        self.suppressions.is_suppressed(code, line)
+    }
+}
+
+impl ParsedDocument {
+    // Empty document carrying a single fatal diagnostic (lenient mode).
+    fn failed(diagnostic: diagnostics::Diagnostic) -> Self {
+        ParsedDocument {
+            directives: Vec::new(),
+            python_code: String::new(),
+            python_line: 1,
+            template: Vec::new(),
+            cache_policy: None,
+            diagnostics: vec![diagnostic],
+            suppressed: HashMap::new(),
+            reactive: Vec::new(),
+            fragments: Vec::new(),
+            docstring: None,
+            static_chunks: Vec::new(),
+            constants: Vec::new(),
+            suppressions: Default::default(),
+            source: Arc::from(""),
+        }
+    }
+}
+
+// Options of `parse`, threaded through both phases.
+#[derive(Default)]
+pub(crate) struct ParseOptions<'a> {
+    pub annotate_file: Option<&'a str>,
+    pub lenient: bool,
+    pub limits: Option<&'a limits::ParseLimits>,
+    pub resolved: bool,
+    pub normalize_unicode: bool,
+    pub tab_width: usize,
+    pub fragments: bool,
+    pub strip_comments: bool,
+    pub fold_constants: bool,
+    pub validate_names: bool,
+}
+
+fn parse_document(
+    py: Python<'_>,
+    source: &str,
+    tree: Option<&Tree>,
+    options: &ParseOptions<'_>,
+) -> PyResult<ParsedDocument> {
+    let lenient = options.lenient;
+    let span = tracing::info_span!("parse", bytes = source.len(), nodes = Empty);
+    let _enter = span.enter();
+    if let Some(diagnostic) = options.limits.and_then(|l| l.check_source(source)) {
+        if lenient {
+            return Ok(ParsedDocument::failed(diagnostic));
+        }
+        return Err(errors::PyWireLimitError::new_err(diagnostic.message));
+    }
+    let parsed;
+    let tree = match tree {
+        Some(tree) => tree,
+        None => {
+            parsed = match parse_tree(source) {
+                Ok(tree) => tree,
+                Err(e) if lenient => {
+                    return Ok(ParsedDocument::failed(diagnostics::Diagnostic::error(
+                        "internal-error",
+                        e.to_string(),
+                        1,
+                        0,
+                    )));
+                }
+                Err(e) => return Err(e),
+            };
+            &parsed
+        }
+    };
+    let doc = map_document(py, source, tree, options)?;
+    if !span.is_disabled() {
+        span.record("nodes", trace::count_nodes(py, &doc.template));
+    }
+    Ok(doc)
+}
+
+fn new_parser() -> PyResult<Parser> {
     let mut parser = Parser::new();
     parser
         .set_language(&tree_sitter_pywire::language() as _)
         .map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                "Failed to set language: {}",
-                e
-            ))
+            errors::PyWireGrammarError::new_err(format!("Failed to set language: {}", e))
         })?;
+    Ok(parser)
+}
 
-    let tree = parser.parse(&source, None).ok_or_else(|| {
-        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Failed to parse source")
-    })?;
+// Tree-sitter phase of `parse`. A `!feed` template is parsed from its
+// masked copy (see feed.rs), which keeps every byte offset.
+pub(crate) fn parse_tree(source: &str) -> PyResult<Tree> {
+    let masked = feed::mask(source);
+    new_parser()?
+        .parse(masked.as_deref().unwrap_or(source), None)
+        .ok_or_else(|| errors::PyWireGrammarError::new_err("Failed to parse source"))
+}
 
-    let root = tree.root_node();
+// Re-parse `source` after edits were applied to `old` with `Tree::edit`.
+// Masking a feed can change bytes outside the edited range, so feeds are
+// parsed from scratch.
+pub(crate) fn reparse_tree(source: &str, old: &Tree) -> PyResult<Tree> {
+    let masked = feed::mask(source);
+    new_parser()?
+        .parse(
+            masked.as_deref().unwrap_or(source),
+            masked.is_none().then_some(old),
+        )
+        .ok_or_else(|| errors::PyWireGrammarError::new_err("Failed to parse source"))
+}
+
+// Parse only `range` of `source`, keeping outer-file positions. The range
+// is masked on its own when it is a `!feed` template.
+pub(crate) fn parse_range(source: &str, range: Range) -> PyResult<Tree> {
+    let mut parser = new_parser()?;
+    parser
+        .set_included_ranges(&[range])
+        .map_err(|e| errors::PyWireGrammarError::new_err(format!("Invalid range: {:?}", e)))?;
+    let (start, end) = (range.start_byte, range.end_byte);
+    let masked = feed::mask(&source[start..end])
+        .map(|part| format!("{}{}{}", &source[..start], part, &source[end..]));
+    parser
+        .parse(masked.as_deref().unwrap_or(source), None)
+        .ok_or_else(|| errors::PyWireGrammarError::new_err("Failed to parse source"))
+}
+
+// Mapping phase of `parse`: tree-sitter tree -> ParsedDocument.
+pub(crate) fn map_document(
+    py: Python<'_>,
+    source: &str,
+    tree: &Tree,
+    options: &ParseOptions<'_>,
+) -> PyResult<ParsedDocument> {
+    let lenient = options.lenient;
+    let mut ctx = MapContext {
+        budget: limits::Budget::new(options.limits),
+        diagnostics: Vec::new(),
+        strip_comments: options.strip_comments,
+        shadow_depth: 0,
+        foreign_depth: 0,
+        source: Arc::from(source),
+    };
     let mut directives: Vec<ParsedDirective> = Vec::new();
     let mut python_code = String::new();
+    let mut python_ranges = Vec::new();
     let mut template = Vec::new();
+    let mut template_start = None;
+    let mut diagnostics = Vec::new();
+
+    let root = tree.root_node();
+    diagnostics::syntax_errors(source, root, &mut diagnostics);
 
     let count = root.child_count();
 
@@ -85,45 +687,189 @@ fn parse(py: Python<'_>, source: String) -> PyResult<ParsedDocument> {
             "directives_section" => {
                 let mut cursor = child.walk();
                 for d_node in child.children(&mut cursor) {
-                    directives.push(map_any_directive(&source, d_node));
+                    directives.push(map_any_directive(source, d_node));
                 }
             }
             "frontmatter" => {
                 if let Some(content_node) = child.child_by_field_name("python_content") {
-                    python_code.push_str(&get_node_text(&source, content_node));
+                    python_code.push_str(&get_node_text(source, content_node));
+                    python_ranges.push((content_node.start_byte(), content_node.end_byte()));
                 } else {
                     // Also check for anonymous children if field name isn't set (it should be)
                     for j in 0..child.child_count() {
                         let inner = child.child(j).unwrap();
                         if inner.kind() == "python_content" {
-                            python_code.push_str(&get_node_text(&source, inner));
+                            python_code.push_str(&get_node_text(source, inner));
+                            python_ranges.push((inner.start_byte(), inner.end_byte()));
                         }
                     }
                 }
             }
             "template_section" => {
+                template_start = Some(child.start_byte());
+                // Feeds are XML, where `<link>` has content.
+                if !matches!(feed::feed_format(&directives), Ok(Some(_))) {
+                    balance::unbalanced_tags(source, child.byte_range(), &mut diagnostics);
+                }
+                let map_span = tracing::info_span!("map", nodes = Empty).entered();
                 let mut cursor = child.walk();
                 for t_node in child.children(&mut cursor) {
                     // Filter out any punctuation or whitespace that tree-sitter might expose
-                    match t_node.kind() {
+                    match node_kind(source, t_node) {
+                        _ if comments::is_template_comment(source, t_node) => {}
+                        "comment" if options.strip_comments => {}
                         "tag" | "self_closing_tag" | "void_tag" | "script_tag" | "style_tag"
                         | "text" | "interpolation" | "brace_block" | "end_brace_block"
-                        | "doctype" | "hyphen" | "bang" => {
-                            let mapped = map_node(py, &source, t_node)?;
-                            template.push(Py::new(py, mapped)?);
+                        | "doctype" | "hyphen" | "bang" | "comment" => {
+                            if !lenient {
+                                let mapped = map_node(py, source, t_node, &mut ctx)?;
+                                template.push(Py::new(py, mapped)?);
+                                continue;
+                            }
+                            // Drop the node rather than the document; a panic
+                            // deep in the mapper must not reach the dev server.
+                            let mapped = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                                Py::new(py, map_node(py, source, t_node, &mut ctx)?)
+                            }));
+                            let start = t_node.start_position();
+                            match mapped {
+                                Ok(Ok(node)) => template.push(node),
+                                Ok(Err(e)) => match ctx.budget.exceeded.take() {
+                                    Some(limit) => {
+                                        diagnostics.push(limit);
+                                        break;
+                                    }
+                                    None => diagnostics.push(diagnostics::Diagnostic::error(
+                                        "map-error",
+                                        e.to_string(),
+                                        start.row + 1,
+                                        start.column,
+                                    )),
+                                },
+                                Err(_) => diagnostics.push(diagnostics::Diagnostic::error(
+                                    "internal-error",
+                                    format!("internal error while mapping {}", t_node.kind()),
+                                    start.row + 1,
+                                    start.column,
+                                )),
+                            }
                         }
                         _ => {}
                     }
                 }
+                if !map_span.is_disabled() {
+                    map_span.record("nodes", trace::count_nodes(py, &template));
+                }
             }
             _ => {}
         }
     }
 
+    diagnostics.append(&mut ctx.diagnostics);
+    match feed::feed_format(&directives) {
+        Ok(Some(format)) => {
+            feed::keep_verbatim(py, source, &template);
+            diagnostics.extend(feed::check(py, format, &template));
+        }
+        Ok(None) => {}
+        Err(message) => {
+            let d = directives.iter().find(|d| d.name == "feed");
+            let (line, column) = d.map_or((1, 0), |d| (d.line, d.column));
+            diagnostics.push(diagnostics::Diagnostic::error(
+                "invalid-directive",
+                message,
+                line,
+                column,
+            ));
+        }
+    }
+    if options.normalize_unicode {
+        unicode::normalize_expressions(py, source, &template, &mut diagnostics);
+    }
+    template = trim::apply_markers(py, template);
+    pairing::pair_blocks(py, &template);
+    pairing::unmatched_blocks(py, &template, &mut diagnostics);
+    if options.resolved {
+        template = pairing::resolve_blocks(py, template);
+        pairing::attach_branches(py, &template)?;
+    }
+
+    let constants = consteval::declared_constants(&directives, &mut diagnostics);
+    if options.fold_constants {
+        consteval::fold_constants(py, &python_code, &template, &constants);
+    }
+    if options.validate_names {
+        let names: Vec<&str> = constants.iter().map(|(name, _, _)| name.as_str()).collect();
+        diagnostics.extend(wires::validate_names(py, &python_code, &template, &names));
+    }
+
+    regions::assign_regions(py, &template, "", &mut HashMap::new());
+
+    if let Some(file) = options.annotate_file {
+        annotate::stamp_source(py, &template, file);
+    }
+    let mut static_chunks = Vec::new();
+    chunks::assign_chunks(py, &template, &mut static_chunks, &mut HashMap::new());
+    rows::assign_row_templates(py, &template)?;
+
+    let cache_policy = match routes::document_cache_policy(&directives) {
+        Ok(policy) => policy,
+        Err(e) if lenient => {
+            let (line, column) = directives
+                .iter()
+                .find(|d| d.name == "cache")
+                .map_or((1, 0), |d| (d.line, d.column));
+            diagnostics.push(diagnostics::Diagnostic::error(
+                "invalid-directive",
+                e.to_string(),
+                line,
+                column,
+            ));
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    let line_of = |offset: usize| source[..offset].matches('\n').count() + 1;
+    let python_line = python_ranges
+        .first()
+        .map_or(1, |&(start, _)| line_of(start));
+    let mut reactive = Vec::new();
+    for &(start, end) in &python_ranges {
+        reactive::scan(&source[start..end], line_of(start), &mut reactive);
+    }
+
+    let fragments = if options.fragments {
+        fragments::parse_fragments(py, source, &python_ranges, options)?
+    } else {
+        Vec::new()
+    };
+
+    let docstring = docs::docstring(source, &directives, root.start_byte(), template_start);
+    let suppressions = pragmas::Suppressions::scan(py, source, &template);
+    let suppressed = suppressions.apply(&mut diagnostics);
+
+    let index = columns::LineIndex::new(source, options.tab_width);
+    columns::assign_display_columns(py, &index, &mut directives, &template, &mut diagnostics);
+
     Ok(ParsedDocument {
         directives,
         python_code,
+        python_line,
         template,
+        cache_policy,
+        diagnostics,
+        suppressed,
+        reactive,
+        fragments,
+        docstring,
+        static_chunks,
+        constants: constants
+            .into_iter()
+            .map(|(name, expr, _)| (name, expr))
+            .collect(),
+        suppressions,
+        source: ctx.source,
     })
 }
 
@@ -131,7 +877,31 @@ fn get_node_text(source: &str, node: Node) -> String {
     source[node.start_byte()..node.end_byte()].to_string()
 }
 
+// `node.kind()`, except that an ERROR node whose whole text is one block
+// marker the grammar did not accept (`{$case 1}`, `{- /if}`) is read as the
+// brace_block or end_brace_block it spells.
+pub(crate) fn node_kind(source: &str, node: Node) -> &'static str {
+    if !node.is_error() {
+        return node.kind();
+    }
+    let text = &source[node.byte_range()];
+    let Some(inner) = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) else {
+        return "ERROR";
+    };
+    if inner.contains(['{', '}']) {
+        return "ERROR";
+    }
+    let (_, body, _) = trim::split_markers(inner);
+    let word = |rest: &str| rest.starts_with(|c: char| c.is_ascii_alphabetic());
+    match body.split_at_checked(1) {
+        Some(("$", rest)) if word(rest) => "brace_block",
+        Some(("/", rest)) if word(rest.trim_start()) => "end_brace_block",
+        _ => "ERROR",
+    }
+}
+
 fn map_any_directive(source: &str, node: Node) -> ParsedDirective {
+    guard::record_offset(node.start_byte());
     let text = get_node_text(source, node);
     let trimmed = text.trim();
 
@@ -156,22 +926,48 @@ fn map_any_directive(source: &str, node: Node) -> ParsedDirective {
     };
 
     let start_point = node.start_position();
+    let end_point = node.end_position();
 
     ParsedDirective {
         name: name_part.to_string(),
         content,
         line: start_point.row + 1,
         column: start_point.column,
+        display_column: start_point.column,
+        end_line: end_point.row + 1,
+        end_column: end_point.column,
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
     }
 }
 
-fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
+// Mutable state of one mapping pass.
+struct MapContext {
+    budget: limits::Budget,
+    diagnostics: Vec<diagnostics::Diagnostic>,
+    strip_comments: bool,
+    // Number of declarative shadow roots enclosing the node being mapped.
+    shadow_depth: usize,
+    // Number of `<svg>`/`<math>` elements enclosing it; self-closing tags
+    // are valid there.
+    foreign_depth: usize,
+    source: Arc<str>,
+}
+
+fn map_node(
+    py: Python<'_>,
+    source: &str,
+    node: Node,
+    ctx: &mut MapContext,
+) -> PyResult<ParsedNode> {
+    guard::record_offset(node.start_byte());
+    ctx.budget.take_node(node)?;
     let mut tag = None;
     let mut is_block = false;
     let mut block_keyword = None;
     let mut text_content = None;
     let mut expression = None;
-    let mut attributes = HashMap::new();
+    let mut attributes = attrs::Attributes::default();
     let mut children = Vec::new();
 
     let start_point = node.start_position();
@@ -179,8 +975,13 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
     let column = start_point.column;
 
     let is_raw = false;
+    let mut close = None;
+    let mut trim_before = false;
+    let mut trim_after = false;
+    let is_comment = node.kind() == "comment";
+    let is_doctype = node.kind() == "doctype";
 
-    let kind = node.kind();
+    let kind = node_kind(source, node);
 
     match kind {
         "tag" | "self_closing_tag" | "void_tag" | "script_tag" | "style_tag" => {
@@ -200,6 +1001,7 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
                 is_raw_tag = true;
                 let mut start_byte = 0;
                 let mut end_byte = 0;
+                let mut end_point = node.end_position();
                 let mut found_start = false;
 
                 let mut cursor = node.walk();
@@ -210,6 +1012,7 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
                         found_start = true;
                     } else if k == "</script>" || k == "</style>" {
                         end_byte = child.start_byte();
+                        end_point = child.start_position();
                     }
                 }
 
@@ -222,73 +1025,98 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
                             block_keyword: None,
                             text_content: Some(raw_text),
                             expression: None,
-                            attributes: HashMap::new(),
+                            attributes: Default::default(),
                             children: Vec::new(),
                             line,
                             column,
                             is_raw: true,
+                            display_column: column,
+                            end_line: end_point.row + 1,
+                            end_column: end_point.column,
+                            start_byte,
+                            end_byte,
+                            region: None,
+                            open_index: None,
+                            close_index: None,
+                            close_line: None,
+                            close_column: None,
+                            branches: Vec::new(),
+                            component_name: None,
+                            is_slot: false,
+                            slot_name: None,
+                            trim_before: false,
+                            trim_after: false,
+                            is_comment: false,
+                            is_doctype: false,
+                            is_void: false,
+                            is_self_closing: false,
+                            shadow_root_mode: None,
+                            static_chunk: None,
+                            row_template: None,
+                            folded_from: None,
+                            expression_info: None,
+                            source: ctx.source.clone(),
                         };
                         children.push(Py::new(py, text_node)?);
                     }
                 }
             }
 
+            let mut attribute_count = 0;
+            let mut attribute_nodes = Vec::new();
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                let kind = child.kind();
+                let kind = node_kind(source, child);
+                if kind == "end_tag" || kind.starts_with("</") {
+                    close = Some(child.start_position());
+                }
                 if kind == "attribute" {
-                    let mut is_shorthand = false;
-
-                    let mut cursor_logic = child.walk();
-                    for attr_child in child.children(&mut cursor_logic) {
-                        let k = attr_child.kind();
-                        if k == "attribute_shorthand" {
-                            let text = get_node_text(source, attr_child);
-                            // text is "{name}"
-                            let inner = text[1..text.len() - 1].trim().to_string();
-
-                            // Check if it's actually a spread that got parsed as shorthand
-                            if text.starts_with("{**") {
-                                attributes.insert("__pywire_spread__".to_string(), Some(text));
-                            } else {
-                                attributes.insert(format!("__pw_sh_{}", inner), Some(text));
-                            }
-                            is_shorthand = true;
-                            break;
-                        } else if k == "spread_shorthand" {
-                            let text = get_node_text(source, attr_child);
-                            // text is "{**expr}"
-                            attributes.insert("__pywire_spread__".to_string(), Some(text));
-                            is_shorthand = true;
-                            break;
-                        }
+                    attribute_count += 1;
+                    ctx.budget.check_attributes(attribute_count, node)?;
+                    let attr = attrs::ParsedAttribute::from_node(source, child);
+                    if !attr.is_shorthand && !attr.is_spread {
+                        ctx.diagnostics.extend(diagnostics::reserved_attribute(
+                            &attr.name,
+                            attr.line,
+                            attr.column,
+                        ));
                     }
-
-                    if !is_shorthand {
-                        let mut attr_name = String::new();
-                        let mut attr_value = None;
-                        if let Some(n) = child.child_by_field_name("name") {
-                            attr_name = get_node_text(source, n);
-                        }
-                        if let Some(v) = child.child_by_field_name("value") {
-                            let text = get_node_text(source, v);
-                            if (text.starts_with('"') && text.ends_with('"'))
-                                || (text.starts_with('\'') && text.ends_with('\''))
-                            {
-                                attr_value = Some(text[1..text.len() - 1].to_string());
-                            } else {
-                                attr_value = Some(text);
-                            }
+                    ctx.diagnostics.extend(diagnostics::invalid_bind(&attr));
+                    ctx.diagnostics
+                        .extend(diagnostics::invalid_conditional(&attr));
+                    if let Some(first) = attributes.push(attr) {
+                        let at = |n: &Node| {
+                            let p = n.start_position();
+                            (p.row + 1, p.column) == (first.line, first.column)
+                        };
+                        if let Some(&first_node) = attribute_nodes.iter().find(|n| at(n)) {
+                            ctx.diagnostics.push(diagnostics::duplicate_attribute(
+                                &first.name,
+                                first_node,
+                                child,
+                            ));
                         }
-                        attributes.insert(attr_name, attr_value);
                     }
+                    attribute_nodes.push(child);
                 } else if !is_raw_tag {
                     match kind {
+                        _ if comments::is_template_comment(source, child) => {}
+                        "comment" if ctx.strip_comments => {}
                         "tag" | "self_closing_tag" | "void_tag" | "script_tag" | "style_tag"
                         | "text" | "interpolation" | "brace_block" | "end_brace_block"
                         | "ERROR" | "hyphen" | "bang" | "comment" => {
-                            let mapped = map_node(py, source, child)?;
-                            children.push(Py::new(py, mapped)?);
+                            // Attributes precede the content, so they are all known here.
+                            let shadow = usize::from(
+                                components::shadow_root_mode(tag.as_deref(), &attributes).is_some(),
+                            );
+                            let foreign =
+                                usize::from(matches!(tag.as_deref(), Some("svg" | "math")));
+                            ctx.shadow_depth += shadow;
+                            ctx.foreign_depth += foreign;
+                            let mapped = map_node(py, source, child, ctx);
+                            ctx.shadow_depth -= shadow;
+                            ctx.foreign_depth -= foreign;
+                            children.push(Py::new(py, mapped?)?);
                         }
                         _ => {}
                     }
@@ -300,13 +1128,16 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
             // brace_block is now a single token: "{$keyword expr}"
             // Parse the text to extract keyword and expression
             let text = get_node_text(source, node);
-            // Strip {$ prefix and } suffix
-            let inner = text.trim_start_matches("{$").trim_end_matches('}');
+            // Strip the braces, trim markers and $ prefix
+            let braced = text.strip_prefix('{').unwrap_or(&text);
+            let (before, body, after) = trim::split_markers(braced.trim_end_matches('}'));
+            (trim_before, trim_after) = (before, after);
+            let inner = body.trim_start_matches('$');
 
             // Find the keyword (first word)
             let keywords = [
                 "if", "for", "try", "await", "elif", "else", "finally", "except", "then", "catch",
-                "html",
+                "html", "example", "match", "case", "default",
             ];
             for kw in keywords {
                 if let Some(stripped) = inner.strip_prefix(kw) {
@@ -323,17 +1154,31 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
             is_block = true;
             // end_brace_block is now a single token: "{/keyword}"
             let text = get_node_text(source, node);
-            let inner = text.trim_start_matches("{/").trim_end_matches('}');
+            let braced = text.strip_prefix('{').unwrap_or(&text);
+            let (before, body, after) = trim::split_markers(braced.trim_end_matches('}'));
+            (trim_before, trim_after) = (before, after);
+            let inner = body.trim_start_matches('/').trim();
             block_keyword = Some(format!("/{}", inner));
         }
         "interpolation" => {
             is_block = true;
             block_keyword = Some("interpolation".to_string());
             if let Some(expr_node) = node.child_by_field_name("expr") {
-                expression = Some(get_node_text(source, expr_node));
+                let text = get_node_text(source, expr_node);
+                let (before, body, after) = trim::split_markers(&text);
+                (trim_before, trim_after) = (before, after);
+                expression = Some(if before || after {
+                    body.to_string()
+                } else {
+                    text
+                });
             }
         }
-        "text" | "python_line" | "hyphen" | "bang" => {
+        "text" => {
+            let text = get_node_text(source, node);
+            text_content = Some(comments::strip(&text).into_owned());
+        }
+        "python_line" | "hyphen" | "bang" | "comment" | "doctype" => {
             text_content = Some(get_node_text(source, node));
         }
         "ERROR" => {
@@ -342,6 +1187,43 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
         _ => {}
     }
 
+    let component_name = tag.as_deref().and_then(components::component_name);
+    let is_slot = tag.as_deref() == Some("slot") && ctx.shadow_depth == 0;
+    let slot_name = match tag.as_deref() {
+        Some("slot") if !is_slot => None,
+        _ => components::slot_name(is_slot, &attributes),
+    };
+    let shadow_root_mode = components::shadow_root_mode(tag.as_deref(), &attributes);
+    let is_void = component_name.is_none()
+        && tag
+            .as_deref()
+            .is_some_and(|t| html::is_void(&t.to_ascii_lowercase()));
+    // From the start tag alone: an element whose end tag was omitted can
+    // end in a self-closed child (`<li><img /></ul>`).
+    let is_self_closing = tag.is_some()
+        && match node.kind() {
+            "self_closing_tag" => true,
+            "void_tag" => get_node_text(source, node).ends_with("/>"),
+            _ => node
+                .child_by_field_name("start_tag")
+                .is_some_and(|start| get_node_text(source, start).ends_with("/>")),
+        };
+    if let Some(name) = tag.as_deref().filter(|_| component_name.is_none()) {
+        // Self-closing is valid in SVG/MathML, and framework `<slot/>`s
+        // never reach the browser.
+        let foreign = ctx.foreign_depth > 0 || matches!(name, "svg" | "math");
+        ctx.diagnostics.extend(diagnostics::void_mismatch(
+            name,
+            is_void,
+            is_self_closing && !foreign && !is_slot,
+            !children.is_empty() || close.is_some(),
+            line,
+            column,
+        ));
+    }
+    let expression_info = expression
+        .as_deref()
+        .map(|expr| exprinfo::analyze_block(block_keyword.as_deref().unwrap_or(""), expr));
     Ok(ParsedNode {
         tag,
         is_block,
@@ -353,6 +1235,32 @@ fn map_node(py: Python<'_>, source: &str, node: Node) -> PyResult<ParsedNode> {
         line,
         column,
         is_raw,
+        display_column: column,
+        end_line: node.end_position().row + 1,
+        end_column: node.end_position().column,
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
+        region: None,
+        open_index: None,
+        close_index: None,
+        close_line: close.map(|p| p.row + 1),
+        close_column: close.map(|p| p.column),
+        branches: Vec::new(),
+        component_name,
+        is_slot,
+        slot_name,
+        trim_before,
+        trim_after,
+        is_comment,
+        is_doctype,
+        is_void,
+        is_self_closing,
+        shadow_root_mode,
+        static_chunk: None,
+        row_template: None,
+        folded_from: None,
+        expression_info,
+        source: ctx.source.clone(),
     })
 }
 
@@ -361,7 +1269,149 @@ fn _pywire_parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ParsedDirective>()?;
     m.add_class::<ParsedNode>()?;
     m.add_class::<ParsedDocument>()?;
+    m.add_class::<kinds::NodeKind>()?;
+    m.add_class::<kinds::BlockKeyword>()?;
+    m.add_class::<kinds::AttrKind>()?;
+    m.add_class::<attrs::ParsedAttribute>()?;
+    m.add_class::<attrs::AttrSegment>()?;
+    m.add_class::<attrs::ConditionalAttribute>()?;
+    m.add_class::<pairing::Branch>()?;
+    m.add_class::<depgraph::DepGraph>()?;
+    m.add_class::<scheduler::RegionScheduler>()?;
+    m.add_class::<versions::VersionStore>()?;
+    m.add_class::<versions::VersionSnapshot>()?;
+    m.add_class::<textdiff::TextEdit>()?;
+    m.add_class::<listdiff::ListDiff>()?;
+    m.add_class::<morph::MorphOp>()?;
+    m.add_class::<serializer::HtmlSerializer>()?;
+    m.add_class::<stream::StreamEncoder>()?;
+    m.add_class::<routes::CachePolicy>()?;
+    m.add_class::<routes::RouteEntry>()?;
+    m.add_class::<meta::MetaTag>()?;
+    m.add_class::<meta::PageMeta>()?;
+    m.add_class::<prerender::PrerenderedPage>()?;
+    m.add_class::<assets::ImageInfo>()?;
+    m.add_class::<assets::RouteHints>()?;
+    m.add_class::<project::ProjectIndex>()?;
+    m.add_class::<sprites::SpriteSymbol>()?;
+    m.add_class::<sprites::SpritePlan>()?;
+    m.add_class::<classes::ClassExpression>()?;
+    m.add_class::<classes::ClassTokens>()?;
+    m.add_class::<query::QueryMatch>()?;
+    m.add_class::<diagnostics::Diagnostic>()?;
+    m.add_class::<profile::SubtreeCost>()?;
+    m.add_class::<profile::ProfileReport>()?;
+    m.add_class::<limits::ParseLimits>()?;
+    m.add_class::<fragments::Fragment>()?;
+    m.add_class::<reactive::ReactiveStatement>()?;
+    m.add_class::<wires::WireSuggestion>()?;
+    m.add_class::<props::PropSignature>()?;
+    m.add_class::<props::EventSignature>()?;
+    m.add_class::<props::ComponentSignature>()?;
+    m.add_class::<sequencing::RegionSequencer>()?;
+    m.add_class::<sequencing::ReorderBuffer>()?;
+    m.add_class::<backpressure::UpdateQueue>()?;
+    m.add_class::<incremental::Document>()?;
+    m.add_class::<islands::Island>()?;
+    m.add_class::<islands::IslandManifest>()?;
+    m.add_class::<rows::RowTemplate>()?;
+    m.add_class::<hoisting::HoistHint>()?;
+    m.add_class::<codegen::CompiledTemplate>()?;
+    m.add_class::<regions::TextPart>()?;
+    m.add_class::<exprinfo::ExpressionInfo>()?;
+    m.add_class::<selective::RegionUpdate>()?;
+    m.add_class::<selective::RenderTrace>()?;
+    m.add_class::<frontmatter::WireDeclaration>()?;
+    m.add_class::<frontmatter::FrontmatterImport>()?;
+    m.add_class::<frontmatter::FrontmatterInfo>()?;
+    m.add_class::<history::StateChange>()?;
+    m.add_class::<history::StateHistory>()?;
+    m.add_class::<rows::RowSlot>()?;
+    m.add_class::<library::LibraryComponent>()?;
+    m.add_class::<examples::Example>()?;
+    m.add_class::<formatting::FormatCheck>()?;
+    m.add_class::<components::ComponentProp>()?;
+    m.add_class::<codemod::CodemodResult>()?;
+    m.add_class::<migrate::DeprecatedSyntax>()?;
+    m.add_class::<htmlimport::HtmlImport>()?;
+    m.add_class::<jinjaimport::JinjaImport>()?;
+    m.add_class::<email::EmailHtml>()?;
+    m.add_class::<strict::StrictProfile>()?;
+    #[cfg(feature = "compression")]
+    m.add_class::<compress::Compressor>()?;
+    #[cfg(feature = "conformance")]
+    m.add_class::<conformance::HtmlDivergence>()?;
+    m.add(
+        "InternalParserError",
+        m.py().get_type::<guard::InternalParserError>(),
+    )?;
+    m.add(
+        "PyWireParseError",
+        m.py().get_type::<errors::PyWireParseError>(),
+    )?;
+    m.add(
+        "PyWireSyntaxError",
+        m.py().get_type::<errors::PyWireSyntaxError>(),
+    )?;
+    m.add(
+        "PyWireLimitError",
+        m.py().get_type::<errors::PyWireLimitError>(),
+    )?;
+    m.add(
+        "PyWireDirectiveError",
+        m.py().get_type::<errors::PyWireDirectiveError>(),
+    )?;
+    m.add(
+        "PyWireGrammarError",
+        m.py().get_type::<errors::PyWireGrammarError>(),
+    )?;
     m.add_function(wrap_pyfunction!(parse, m)?)?;
     m.add_function(wrap_pyfunction!(version, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot::dumps_state, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot::loads_state, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot::snapshot_schema_version, m)?)?;
+    m.add_function(wrap_pyfunction!(fingerprint::value_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(textdiff::text_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(listdiff::keyed_list_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(morph::morph_html, m)?)?;
+    m.add_function(wrap_pyfunction!(serializer::content_etag, m)?)?;
+    m.add_function(wrap_pyfunction!(routes::extract_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(routes::sitemap_xml, m)?)?;
+    m.add_function(wrap_pyfunction!(meta::extract_meta, m)?)?;
+    m.add_function(wrap_pyfunction!(prerender::prerender, m)?)?;
+    m.add_function(wrap_pyfunction!(selective::render_dirty, m)?)?;
+    m.add_function(wrap_pyfunction!(selective::replay, m)?)?;
+    m.add_function(wrap_pyfunction!(assets::analyze_images, m)?)?;
+    m.add_function(wrap_pyfunction!(assets::rewrite_images, m)?)?;
+    m.add_function(wrap_pyfunction!(assets::link_hints, m)?)?;
+    m.add_function(wrap_pyfunction!(sprites::extract_sprites, m)?)?;
+    m.add_function(wrap_pyfunction!(sprites::rewrite_sprites, m)?)?;
+    m.add_function(wrap_pyfunction!(classes::extract_class_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(query::select, m)?)?;
+    m.add_function(wrap_pyfunction!(query::assert_contains, m)?)?;
+    m.add_function(wrap_pyfunction!(trace::set_trace_hook, m)?)?;
+    m.add_function(wrap_pyfunction!(profile::profile, m)?)?;
+    m.add_function(wrap_pyfunction!(wires::suggest_wires, m)?)?;
+    m.add_function(wrap_pyfunction!(wires::check_state, m)?)?;
+    m.add_function(wrap_pyfunction!(frontmatter::frontmatter_info, m)?)?;
+    m.add_function(wrap_pyfunction!(islands::analyze_islands, m)?)?;
+    m.add_function(wrap_pyfunction!(hoisting::hoisting_hints, m)?)?;
+    m.add_function(wrap_pyfunction!(library::parse_library, m)?)?;
+    m.add_function(wrap_pyfunction!(batch::parse_many, m)?)?;
+    m.add_function(wrap_pyfunction!(examples::extract_examples, m)?)?;
+    m.add_function(wrap_pyfunction!(formatting::format_source, m)?)?;
+    m.add_function(wrap_pyfunction!(formatting::format_template, m)?)?;
+    m.add_function(wrap_pyfunction!(minify::minify, m)?)?;
+    m.add_function(wrap_pyfunction!(codegen::compile_template, m)?)?;
+    m.add_function(wrap_pyfunction!(exprinfo::analyze_expression, m)?)?;
+    m.add_function(wrap_pyfunction!(formatting::format_check, m)?)?;
+    m.add_function(wrap_pyfunction!(codemod::codemod, m)?)?;
+    m.add_function(wrap_pyfunction!(migrate::deprecations, m)?)?;
+    m.add_function(wrap_pyfunction!(htmlimport::from_html, m)?)?;
+    m.add_function(wrap_pyfunction!(jinjaimport::from_jinja, m)?)?;
+    m.add_function(wrap_pyfunction!(email::email_html, m)?)?;
+    m.add_function(wrap_pyfunction!(strict::check_profile, m)?)?;
+    #[cfg(feature = "conformance")]
+    m.add_function(wrap_pyfunction!(conformance::html_divergences, m)?)?;
     Ok(())
 }
@@ -0,0 +1,147 @@
+// Component libraries (`.wirelib`): several templates in one file, each
+// introduced by a `=== Name ===` fence line. Like fragments, every component
+// is parsed in place with tree-sitter's included ranges, so positions in its
+// document are those of the library file.
+
+use crate::diagnostics::Diagnostic;
+use crate::fragments::point_at;
+use crate::{ParseOptions, ParsedDocument, errors};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashSet;
+use tree_sitter::Range;
+
+/// One component of a library file. `line` is the first line after its
+/// fence; `[start_byte, end_byte)` is its body in the library source.
+#[pyclass(frozen)]
+pub struct LibraryComponent {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+    #[pyo3(get)]
+    pub document: Py<ParsedDocument>,
+}
+
+#[pymethods]
+impl LibraryComponent {
+    fn __repr__(&self) -> String {
+        format!("LibraryComponent({} at line {})", self.name, self.line)
+    }
+}
+
+// Component name of a `=== Name ===` fence line, if `line` is one.
+fn fence_name(line: &str) -> Option<&str> {
+    let inner = line.trim().strip_prefix("===")?.strip_suffix("===")?.trim();
+    let valid = !inner.is_empty()
+        && inner
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    valid.then_some(inner)
+}
+
+// `(name, fence line, body start, body end)` of every component.
+fn split<'a>(py: Python<'_>, source: &'a str) -> PyResult<Vec<(&'a str, usize, usize, usize)>> {
+    let mut parts: Vec<(&str, usize, usize, usize)> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut offset = 0;
+    for (i, line) in source.split_inclusive('\n').enumerate() {
+        let next = offset + line.len();
+        if let Some(name) = fence_name(line) {
+            if !seen.insert(name) {
+                return Err(errors::from_diagnostic(
+                    py,
+                    &Diagnostic::error(
+                        "duplicate-component",
+                        format!("component '{}' is defined more than once", name),
+                        i + 1,
+                        0,
+                    ),
+                ));
+            }
+            if let Some(last) = parts.last_mut() {
+                last.3 = offset;
+            }
+            parts.push((name, i + 1, next, next));
+        } else if parts.is_empty() && !line.trim().is_empty() {
+            return Err(errors::from_diagnostic(
+                py,
+                &Diagnostic::error(
+                    "content-before-component",
+                    "library content must start with a '=== Name ===' fence",
+                    i + 1,
+                    0,
+                ),
+            ));
+        }
+        offset = next;
+    }
+    if let Some(last) = parts.last_mut() {
+        last.3 = source.len();
+    }
+    Ok(parts)
+}
+
+/// Parse a component library: a file of templates each introduced by a
+/// `=== Name ===` line. Returns `{name: LibraryComponent}` in file order.
+///
+/// Each component may have its own directives and frontmatter; positions in
+/// its `document` refer to the library file. A duplicate name or content
+/// before the first fence raises `PyWireParseError`. Other options match
+/// `parse`.
+#[pyfunction]
+#[pyo3(signature = (
+    source,
+    annotate_file=None,
+    lenient=false,
+    resolved=false,
+    normalize_unicode=false,
+    tab_width=crate::columns::DEFAULT_TAB_WIDTH,
+))]
+pub fn parse_library<'py>(
+    py: Python<'py>,
+    source: &str,
+    annotate_file: Option<&str>,
+    lenient: bool,
+    resolved: bool,
+    normalize_unicode: bool,
+    tab_width: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let options = ParseOptions {
+        annotate_file,
+        lenient,
+        limits: None,
+        resolved,
+        normalize_unicode,
+        tab_width,
+        fragments: false,
+        strip_comments: false,
+        fold_constants: false,
+        validate_names: false,
+    };
+    let parts = split(py, source)?;
+    let out = PyDict::new(py);
+    for (name, fence_line, start, end) in parts {
+        let range = Range {
+            start_byte: start,
+            end_byte: end,
+            start_point: point_at(source, start),
+            end_point: point_at(source, end),
+        };
+        let tree = crate::parse_range(source, range)?;
+        let document = crate::parse_with_tree(py, source, Some(&tree), &options)?;
+        let component = LibraryComponent {
+            name: name.to_string(),
+            line: fence_line + 1,
+            start_byte: start,
+            end_byte: end,
+            document: Py::new(py, document)?,
+        };
+        out.set_item(name, Py::new(py, component)?)?;
+    }
+    Ok(out)
+}
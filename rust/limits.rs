@@ -0,0 +1,111 @@
+use crate::diagnostics::Diagnostic;
+use crate::errors::PyWireLimitError;
+use pyo3::prelude::*;
+use tree_sitter::Node;
+
+/// Resource limits for `parse`, for user-uploaded or generated templates.
+///
+/// `max_attributes` applies per element. None means unlimited.
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct ParseLimits {
+    #[pyo3(get)]
+    pub max_source_bytes: Option<usize>,
+    #[pyo3(get)]
+    pub max_nodes: Option<usize>,
+    #[pyo3(get)]
+    pub max_attributes: Option<usize>,
+}
+
+#[pymethods]
+impl ParseLimits {
+    #[new]
+    #[pyo3(signature = (max_source_bytes=None, max_nodes=None, max_attributes=None))]
+    fn new(
+        max_source_bytes: Option<usize>,
+        max_nodes: Option<usize>,
+        max_attributes: Option<usize>,
+    ) -> Self {
+        ParseLimits {
+            max_source_bytes,
+            max_nodes,
+            max_attributes,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParseLimits(max_source_bytes={:?}, max_nodes={:?}, max_attributes={:?})",
+            self.max_source_bytes, self.max_nodes, self.max_attributes
+        )
+    }
+}
+
+impl ParseLimits {
+    pub(crate) fn check_source(&self, source: &str) -> Option<Diagnostic> {
+        let max = self.max_source_bytes?;
+        (source.len() > max).then(|| {
+            Diagnostic::error(
+                "limit-exceeded",
+                format!("template is {} bytes, limit is {} bytes", source.len(), max),
+                1,
+                0,
+            )
+        })
+    }
+}
+
+// Running node count for one mapping pass. Exceeding a limit records the
+// diagnostic and fails the current node so mapping stops early instead of
+// materializing the whole tree.
+pub(crate) struct Budget {
+    limits: ParseLimits,
+    nodes: usize,
+    pub(crate) exceeded: Option<Diagnostic>,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: Option<&ParseLimits>) -> Self {
+        Budget {
+            limits: limits.cloned().unwrap_or_default(),
+            nodes: 0,
+            exceeded: None,
+        }
+    }
+
+    fn exceed(&mut self, node: Node, message: String) -> PyErr {
+        let start = node.start_position();
+        let err = PyWireLimitError::new_err(format!(
+            "{} (line {}, column {})",
+            message,
+            start.row + 1,
+            start.column
+        ));
+        self.exceeded = Some(Diagnostic::error(
+            "limit-exceeded",
+            message,
+            start.row + 1,
+            start.column,
+        ));
+        err
+    }
+
+    pub(crate) fn take_node(&mut self, node: Node) -> PyResult<()> {
+        self.nodes += 1;
+        match self.limits.max_nodes {
+            Some(max) if self.nodes > max => {
+                Err(self.exceed(node, format!("template has more than {} nodes", max)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_attributes(&mut self, count: usize, node: Node) -> PyResult<()> {
+        match self.limits.max_attributes {
+            Some(max) if count > max => {
+                Err(self.exceed(node, format!("element has more than {} attributes", max)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
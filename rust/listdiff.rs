@@ -0,0 +1,131 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Result of `keyed_list_diff`.
+///
+/// `sources[i]` is the old index reused for new position `i` (or -1 for a new
+/// item). Clients remove `removed`, then walk new positions from last to first,
+/// inserting items in `inserted` and relocating items in `moved` before the
+/// node at position `i + 1`. Everything else stays put.
+#[pyclass(frozen)]
+pub struct ListDiff {
+    #[pyo3(get)]
+    pub sources: Vec<isize>,
+    #[pyo3(get)]
+    pub removed: Vec<usize>,
+    #[pyo3(get)]
+    pub inserted: Vec<usize>,
+    #[pyo3(get)]
+    pub moved: Vec<usize>,
+}
+
+#[pymethods]
+impl ListDiff {
+    /// True when the old list can be reused without any DOM operations.
+    #[getter]
+    fn is_noop(&self) -> bool {
+        self.removed.is_empty() && self.inserted.is_empty() && self.moved.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ListDiff(removed={:?}, inserted={:?}, moved={:?})",
+            self.removed, self.inserted, self.moved
+        )
+    }
+}
+
+// Positions (into `seq`) of one longest strictly increasing subsequence.
+pub(crate) fn longest_increasing_subsequence(seq: &[usize]) -> Vec<usize> {
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![usize::MAX; seq.len()];
+    for (i, &value) in seq.iter().enumerate() {
+        let pos = tails.partition_point(|&t| seq[t] < value);
+        if pos > 0 {
+            prev[i] = tails[pos - 1];
+        }
+        if pos == tails.len() {
+            tails.push(i);
+        } else {
+            tails[pos] = i;
+        }
+    }
+    let mut result = Vec::with_capacity(tails.len());
+    let mut cursor = tails.last().copied().unwrap_or(usize::MAX);
+    while cursor != usize::MAX {
+        result.push(cursor);
+        cursor = prev[cursor];
+    }
+    result.reverse();
+    result
+}
+
+pub(crate) fn diff_sources(sources: &[isize], old_len: usize) -> ListDiff {
+    let mut used = vec![false; old_len];
+    let mut kept_positions = Vec::new();
+    let mut kept_values = Vec::new();
+    let mut inserted = Vec::new();
+    for (i, &src) in sources.iter().enumerate() {
+        if src < 0 {
+            inserted.push(i);
+        } else {
+            used[src as usize] = true;
+            kept_positions.push(i);
+            kept_values.push(src as usize);
+        }
+    }
+
+    let mut stable = vec![false; sources.len()];
+    for p in longest_increasing_subsequence(&kept_values) {
+        stable[kept_positions[p]] = true;
+    }
+
+    ListDiff {
+        sources: sources.to_vec(),
+        removed: (0..old_len).filter(|&i| !used[i]).collect(),
+        inserted,
+        moved: kept_positions.into_iter().filter(|&i| !stable[i]).collect(),
+    }
+}
+
+/// Diff two keyed lists into removes/inserts/moves, keeping the longest run of
+/// items whose relative order is unchanged in place.
+///
+/// Keys are compared with Python equality, so any hashable key works.
+/// Duplicate keys within either list raise ValueError.
+#[pyfunction]
+pub fn keyed_list_diff(
+    py: Python<'_>,
+    old_keys: Vec<Bound<'_, PyAny>>,
+    new_keys: Vec<Bound<'_, PyAny>>,
+) -> PyResult<ListDiff> {
+    let index = PyDict::new(py);
+    for (i, key) in old_keys.iter().enumerate() {
+        if index.contains(key)? {
+            return Err(PyValueError::new_err(format!(
+                "Duplicate key {} in old list",
+                key.repr()?
+            )));
+        }
+        index.set_item(key, i)?;
+    }
+
+    let seen = PyDict::new(py);
+    let mut sources = Vec::with_capacity(new_keys.len());
+    for key in &new_keys {
+        if seen.contains(key)? {
+            return Err(PyValueError::new_err(format!(
+                "Duplicate key {} in new list",
+                key.repr()?
+            )));
+        }
+        seen.set_item(key, true)?;
+        sources.push(match index.get_item(key)? {
+            Some(i) => i.extract::<isize>()?,
+            None => -1,
+        });
+    }
+
+    Ok(diff_sources(&sources, old_keys.len()))
+}
@@ -0,0 +1,157 @@
+// Custom Elements Manifest (https://github.com/webcomponents/custom-elements-manifest)
+// export of a project's components, so editors and design tools that read
+// web-component metadata can list pywire components, their attributes,
+// events and slots.
+
+use crate::project::ProjectIndex;
+use crate::props::{ComponentSignature, PropSignature};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+const SCHEMA_VERSION: &str = "1.0.0";
+
+// `UserCard` -> `<prefix>-user-card`, `ui.Card` -> `<prefix>-ui-card`,
+// `HTMLView` -> `<prefix>-html-view`. A custom element name needs a hyphen,
+// hence the prefix.
+fn custom_tag_name(prefix: &str, component: &str) -> String {
+    let mut out = format!("{}-", prefix.trim_end_matches('-'));
+    let chars: Vec<char> = component.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, '.' | '_' | '-') {
+            if !out.ends_with('-') {
+                out.push('-');
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_ascii_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_ascii_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase))
+            });
+        if boundary && !out.ends_with('-') {
+            out.push('-');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+fn reference<'py>(py: Python<'py>, name: &str, module: &str) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("name", name)?;
+    dict.set_item("module", module)?;
+    Ok(dict)
+}
+
+fn prop_entry<'py>(
+    py: Python<'py>,
+    prop: &PropSignature,
+    kind: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    if let Some(kind) = kind {
+        dict.set_item("kind", kind)?;
+    }
+    dict.set_item("name", &prop.name)?;
+    if let Some(annotation) = &prop.annotation {
+        let ty = PyDict::new(py);
+        ty.set_item("text", annotation)?;
+        dict.set_item("type", ty)?;
+    }
+    if let Some(default) = &prop.default {
+        dict.set_item("default", default)?;
+    }
+    if let Some(doc) = &prop.doc {
+        dict.set_item("description", doc)?;
+    }
+    Ok(dict)
+}
+
+fn declaration<'py>(
+    py: Python<'py>,
+    sig: &ComponentSignature,
+    tag: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("kind", "class")?;
+    dict.set_item("name", &sig.name)?;
+    dict.set_item("tagName", tag)?;
+    dict.set_item("customElement", true)?;
+    if let Some(doc) = &sig.doc {
+        dict.set_item("description", doc)?;
+    }
+
+    // Callback props are listed as events, not attributes.
+    let is_event = |p: &PropSignature| sig.events.iter().any(|e| e.prop.as_ref() == Some(&p.name));
+    let attributes = PyList::empty(py);
+    let members = PyList::empty(py);
+    for prop in &sig.props {
+        members.append(prop_entry(py, prop, Some("field"))?)?;
+        if !is_event(prop) {
+            let attr = prop_entry(py, prop, None)?;
+            attr.set_item("fieldName", &prop.name)?;
+            attributes.append(attr)?;
+        }
+    }
+    dict.set_item("attributes", attributes)?;
+    dict.set_item("members", members)?;
+
+    let events = PyList::empty(py);
+    for event in &sig.events {
+        let entry = PyDict::new(py);
+        entry.set_item("name", &event.name)?;
+        if let Some(prop) = &event.prop {
+            entry.set_item(
+                "description",
+                format!("Raised through the `{}` prop.", prop),
+            )?;
+        }
+        events.append(entry)?;
+    }
+    dict.set_item("events", events)?;
+
+    // The manifest names the default slot "".
+    let slots = PyList::empty(py);
+    for slot in &sig.slots {
+        let entry = PyDict::new(py);
+        entry.set_item("name", if slot == "default" { "" } else { slot })?;
+        slots.append(entry)?;
+    }
+    dict.set_item("slots", slots)?;
+    Ok(dict)
+}
+
+impl ProjectIndex {
+    pub(crate) fn manifest<'py>(
+        &self,
+        py: Python<'py>,
+        prefix: &str,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let modules = PyList::empty(py);
+        for sig in self.signatures(py) {
+            let tag = custom_tag_name(prefix, &sig.name);
+            let module = PyDict::new(py);
+            module.set_item("kind", "javascript-module")?;
+            module.set_item("path", &sig.file)?;
+            module.set_item("declarations", vec![declaration(py, &sig, &tag)?])?;
+
+            let js = PyDict::new(py);
+            js.set_item("kind", "js")?;
+            js.set_item("name", &sig.name)?;
+            js.set_item("declaration", reference(py, &sig.name, &sig.file)?)?;
+            let element = PyDict::new(py);
+            element.set_item("kind", "custom-element-definition")?;
+            element.set_item("name", &tag)?;
+            element.set_item("declaration", reference(py, &sig.name, &sig.file)?)?;
+            module.set_item("exports", vec![js, element])?;
+            modules.append(module)?;
+        }
+        let manifest = PyDict::new(py);
+        manifest.set_item("schemaVersion", SCHEMA_VERSION)?;
+        manifest.set_item("modules", modules)?;
+        Ok(manifest)
+    }
+}
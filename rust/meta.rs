@@ -0,0 +1,148 @@
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+
+const DEFAULT_REQUIRED: &[&str] = &[
+    "title",
+    "description",
+    "og:title",
+    "og:description",
+    "og:image",
+    "twitter:card",
+];
+
+/// One piece of page metadata: `title`, `description`, or an `og:`/`twitter:`
+/// property. When `is_expression` is set, `value` is template source (with
+/// `{...}` interpolations) rather than the final text.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct MetaTag {
+    #[pyo3(get)]
+    pub key: String,
+    #[pyo3(get)]
+    pub value: String,
+    #[pyo3(get)]
+    pub is_expression: bool,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl MetaTag {
+    fn __repr__(&self) -> String {
+        format!("MetaTag({:?}, {:?})", self.key, self.value)
+    }
+}
+
+/// Result of `extract_meta`, tags in document order.
+#[pyclass(frozen)]
+pub struct PageMeta {
+    #[pyo3(get)]
+    pub tags: Vec<MetaTag>,
+}
+
+#[pymethods]
+impl PageMeta {
+    /// First tag with the given key.
+    fn get(&self, key: &str) -> Option<MetaTag> {
+        self.tags.iter().find(|t| t.key == key).cloned()
+    }
+
+    #[getter]
+    fn title(&self) -> Option<MetaTag> {
+        self.get("title")
+    }
+
+    #[getter]
+    fn description(&self) -> Option<MetaTag> {
+        self.get("description")
+    }
+
+    /// Keys from `required` (a social-card baseline by default) that the page
+    /// does not set.
+    #[pyo3(signature = (required=None))]
+    fn missing(&self, required: Option<Vec<String>>) -> Vec<String> {
+        let required =
+            required.unwrap_or_else(|| DEFAULT_REQUIRED.iter().map(|k| k.to_string()).collect());
+        required
+            .into_iter()
+            .filter(|k| !self.tags.iter().any(|t| t.key == *k))
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.tags.len()
+    }
+}
+
+// Text of a node's children, with interpolations written back as `{expr}`.
+fn inline_source(py: Python<'_>, children: &[Py<ParsedNode>]) -> (String, bool) {
+    let mut out = String::new();
+    let mut is_expression = false;
+    for child in children {
+        let child = child.borrow(py);
+        if child.block_keyword.as_deref() == Some("interpolation") {
+            out.push('{');
+            out.push_str(child.expression.as_deref().unwrap_or(""));
+            out.push('}');
+            is_expression = true;
+        } else if !child.is_comment
+            && let Some(text) = &child.text_content
+        {
+            out.push_str(text);
+        }
+    }
+    (out.trim().to_string(), is_expression)
+}
+
+fn attr<'a>(node: &'a ParsedNode, name: &str) -> Option<&'a str> {
+    node.attributes.get(name).and_then(|v| v.as_deref())
+}
+
+fn collect(py: Python<'_>, nodes: &[Py<ParsedNode>], tags: &mut Vec<MetaTag>) {
+    for node in nodes {
+        let node = node.borrow(py);
+        match node.tag.as_deref() {
+            Some("title") => {
+                let (value, is_expression) = inline_source(py, &node.children);
+                tags.push(MetaTag {
+                    key: "title".to_string(),
+                    value,
+                    is_expression,
+                    line: node.line,
+                    column: node.column,
+                });
+            }
+            Some("meta") => {
+                let key = attr(&node, "property")
+                    .or_else(|| attr(&node, "name"))
+                    .filter(|k| {
+                        *k == "description" || k.starts_with("og:") || k.starts_with("twitter:")
+                    });
+                if let Some(key) = key {
+                    let raw = attr(&node, "content").unwrap_or("");
+                    let is_expression = raw.starts_with('{') && raw.ends_with('}');
+                    tags.push(MetaTag {
+                        key: key.to_string(),
+                        value: raw.to_string(),
+                        is_expression,
+                        line: node.line,
+                        column: node.column,
+                    });
+                }
+            }
+            _ => collect(py, &node.children, tags),
+        }
+    }
+}
+
+/// Title, meta description and `og:`/`twitter:` tags declared in a template,
+/// static or expression-valued, for social-card checks and doc previews.
+#[pyfunction]
+pub fn extract_meta(py: Python<'_>, document: PyRef<'_, ParsedDocument>) -> PageMeta {
+    let _span = tracing::info_span!("analyze", pass = "meta").entered();
+    let mut tags = Vec::new();
+    collect(py, &document.template, &mut tags);
+    PageMeta { tags }
+}
@@ -0,0 +1,171 @@
+// Pre-0.2 template syntax and how to rewrite it. The table drives both
+// `deprecations()` and the codemod "migrate" transform.
+
+use crate::codemod::{
+    Edit, braced, convert_directives, directive_attributes, end_tag_name, is_tag, tag_name,
+};
+use pyo3::prelude::*;
+use tree_sitter::Node;
+
+pub(crate) struct Deprecation {
+    pub(crate) code: &'static str,
+    since: &'static str,
+    old: &'static str,
+    new: &'static str,
+    message: &'static str,
+    // Collect the edits this rule makes at `node` (children are visited
+    // separately).
+    pub(crate) apply: fn(&str, Node, &mut Vec<Edit>),
+}
+
+pub(crate) const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        code: "directive-attributes",
+        since: "0.2.0",
+        old: "<li $for={item in items} $key={item.id}>",
+        new: "{$for item in items, key=item.id}<li>...</li>{/for}",
+        message: "`$for`/`$if` attributes are replaced by brace blocks",
+        apply: convert_directives,
+    },
+    Deprecation {
+        code: "colon-binding",
+        since: "0.2.0",
+        old: "<a :href=\"url\" :title>",
+        new: "<a href={url} {title}>",
+        message: "`:name` bindings are replaced by `name={expr}` and `{name}` shorthand",
+        apply: colon_bindings,
+    },
+    Deprecation {
+        code: "tag-blocks",
+        since: "0.2.0",
+        old: "<$if when={ready}>...</$if>",
+        new: "{$if ready}...{/if}",
+        message: "`<$if>`/`<$for>` tags are replaced by brace blocks",
+        apply: tag_blocks,
+    },
+];
+
+// `:href="url"` -> `href={url}`, valueless `:title` -> `{title}`.
+fn colon_bindings(source: &str, node: Node, out: &mut Vec<Edit>) {
+    if !is_tag(node) {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() != "attribute" {
+            continue;
+        }
+        let Some(name) = child
+            .child_by_field_name("name")
+            .and_then(|n| source[n.start_byte()..n.end_byte()].strip_prefix(':'))
+        else {
+            continue;
+        };
+        let text = match child.child_by_field_name("value") {
+            None => format!("{{{}}}", name),
+            Some(v) => {
+                let value = source[v.start_byte()..v.end_byte()].trim();
+                let expr = braced(value).unwrap_or_else(|| {
+                    value
+                        .strip_prefix(['"', '\''])
+                        .and_then(|v| v.strip_suffix(['"', '\'']))
+                        .unwrap_or(value)
+                });
+                format!("{}={{{}}}", name, expr.trim())
+            }
+        };
+        out.push(Edit {
+            start: child.start_byte(),
+            end: child.end_byte(),
+            text,
+        });
+    }
+}
+
+// `<$if when={x}>...</$if>` -> `{$if x}...{/if}` and
+// `<$for each={x in xs} key={x.id}>` -> `{$for x in xs, key=x.id}`: the
+// block expression is the first braced attribute other than `key`.
+fn tag_blocks(source: &str, node: Node, out: &mut Vec<Edit>) {
+    if node.kind() != "tag" {
+        return;
+    }
+    let (name_start, name) = tag_name(source, node);
+    let Some(keyword) = name
+        .strip_prefix('$')
+        .filter(|k| matches!(*k, "if" | "for"))
+    else {
+        return;
+    };
+    let Some((end_start, _)) = end_tag_name(source, node) else {
+        return;
+    };
+    let attrs = directive_attributes(source, node);
+    let Some((_, expr, ..)) = attrs.iter().find(|(n, ..)| *n != "key") else {
+        return;
+    };
+    let mut open = format!("{{${} {}", keyword, expr);
+    if keyword == "for"
+        && let Some((_, key, ..)) = attrs.iter().find(|(n, ..)| *n == "key")
+    {
+        open.push_str(&format!(", key={}", key));
+    }
+    open.push('}');
+
+    let after_attrs = attrs
+        .iter()
+        .map(|&(.., end)| end)
+        .max()
+        .unwrap_or(name_start + name.len());
+    let Some(gt) = source[after_attrs..].find('>') else {
+        return;
+    };
+    out.push(Edit {
+        start: node.start_byte(),
+        end: after_attrs + gt + 1,
+        text: open,
+    });
+    out.push(Edit {
+        start: end_start - 2,
+        end: node.end_byte(),
+        text: format!("{{/{}}}", keyword),
+    });
+}
+
+/// A pre-0.2 syntax form: `old` is an example of it and `new` the same
+/// template in the current grammar. `codemod(paths, "migrate")` rewrites it.
+#[pyclass(frozen)]
+pub struct DeprecatedSyntax {
+    #[pyo3(get)]
+    pub code: &'static str,
+    #[pyo3(get)]
+    pub since: &'static str,
+    #[pyo3(get)]
+    pub old: &'static str,
+    #[pyo3(get)]
+    pub new: &'static str,
+    #[pyo3(get)]
+    pub message: &'static str,
+}
+
+#[pymethods]
+impl DeprecatedSyntax {
+    fn __repr__(&self) -> String {
+        format!("DeprecatedSyntax({}, since={})", self.code, self.since)
+    }
+}
+
+/// Syntax forms deprecated since 0.2, in the order the "migrate" codemod
+/// applies them.
+#[pyfunction]
+pub fn deprecations() -> Vec<DeprecatedSyntax> {
+    DEPRECATIONS
+        .iter()
+        .map(|d| DeprecatedSyntax {
+            code: d.code,
+            since: d.since,
+            old: d.old,
+            new: d.new,
+            message: d.message,
+        })
+        .collect()
+}
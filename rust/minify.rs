@@ -0,0 +1,210 @@
+// Template minifier. It edits the source text guided by the parse tree, so
+// the result is still a `.wire` template that renders the same page:
+//   - HTML comments are removed; `{# ... #}` comments never reach the output
+//     and may carry pragmas, so they stay
+//   - whitespace-only text spanning lines is removed between block-level
+//     tags and collapsed to one space next to inline content, where it can
+//     show
+//   - other whitespace runs in text collapse to one space
+//   - boolean attributes drop a redundant value: `disabled="disabled"`,
+//     `hidden=""`
+// The frontmatter, brace blocks (`{$html ...}` included) and the content of
+// `<pre>`, `<textarea>`, `<script>` and `<style>` are left as written.
+
+use crate::formatting::KEEP_CONTENT;
+use crate::kinds::AttrKind;
+use crate::{ParseOptions, ParsedDocument, ParsedNode, columns, parse_with};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+// Elements laid out inline, where whitespace between them renders.
+const INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "meter", "output", "picture", "progress", "q", "s",
+    "samp", "select", "small", "span", "strong", "sub", "sup", "svg", "textarea", "time", "u",
+    "var", "video", "wbr",
+];
+
+// Whether whitespace next to `node` can show: inline elements, components
+// (whose markup is unknown here), text and brace blocks.
+fn is_inline(node: &ParsedNode) -> bool {
+    match &node.tag {
+        Some(_) if node.component_name.is_some() => true,
+        Some(tag) => INLINE.iter().any(|t| tag.eq_ignore_ascii_case(t)),
+        None => !node.is_comment && !node.is_doctype,
+    }
+}
+
+fn collapse(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+struct Minifier<'a> {
+    source: &'a str,
+    // (start, end, replacement) byte edits, in source order.
+    edits: Vec<(usize, usize, String)>,
+}
+
+impl Minifier<'_> {
+    fn edit(&mut self, start: usize, end: usize, replacement: String) {
+        if self.source[start..end] != replacement {
+            self.edits.push((start, end, replacement));
+        }
+    }
+
+    fn attributes(&mut self, node: &ParsedNode) {
+        for attr in node.attributes.parsed() {
+            let redundant = attr.kind == AttrKind::Static
+                && BOOLEAN_ATTRIBUTES.contains(&attr.name.to_ascii_lowercase().as_str())
+                && attr
+                    .value
+                    .as_deref()
+                    .is_some_and(|v| v.is_empty() || v.eq_ignore_ascii_case(&attr.name));
+            let (Some(start), Some(end)) = (attr.value_start_byte, attr.value_end_byte) else {
+                continue;
+            };
+            if !redundant {
+                continue;
+            }
+            // Drop everything from the `=` to the closing quote.
+            let quote = usize::from(attr.quote.is_some());
+            let before = self.source[..start - quote].trim_end();
+            let Some(name_end) = before.strip_suffix('=').map(|s| s.trim_end().len()) else {
+                continue;
+            };
+            self.edit(name_end, end + quote, String::new());
+        }
+    }
+
+    // `nodes` are the children of an element with tag `parent`, or the
+    // template's top level.
+    fn visit(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>], parent: Option<&str>) {
+        let boundary_inline =
+            parent.is_some_and(|t| INLINE.iter().any(|i| t.eq_ignore_ascii_case(i)));
+        for (i, node) in nodes.iter().enumerate() {
+            let node = node.borrow(py);
+            if node.is_comment {
+                self.edit(node.start_byte, node.end_byte, String::new());
+                continue;
+            }
+            if let Some(tag) = &node.tag {
+                self.attributes(&node);
+                if !KEEP_CONTENT.iter().any(|t| tag.eq_ignore_ascii_case(t)) {
+                    self.visit(py, &node.children, Some(tag));
+                }
+                continue;
+            }
+            if node.is_block || node.is_raw || node.folded_from.is_some() {
+                // Resolved block bodies are children of their marker.
+                self.visit(py, &node.children, parent);
+                continue;
+            }
+            let Some(text) = self.source.get(node.start_byte..node.end_byte) else {
+                continue;
+            };
+            let neighbour_inline = |j: Option<usize>| match j.and_then(|j| nodes.get(j)) {
+                Some(n) => is_inline(&n.borrow(py)),
+                None => boundary_inline,
+            };
+            let replacement = if text.trim().is_empty()
+                && text.contains('\n')
+                && !neighbour_inline(i.checked_sub(1))
+                && !neighbour_inline(Some(i + 1))
+            {
+                String::new()
+            } else {
+                collapse(text)
+            };
+            self.edit(node.start_byte, node.end_byte, replacement);
+        }
+    }
+}
+
+fn minify_document(py: Python<'_>, doc: &ParsedDocument) -> PyResult<String> {
+    doc.raise_for_errors(py)?;
+    let source = &doc.source;
+    let mut minifier = Minifier {
+        source,
+        edits: Vec::new(),
+    };
+    minifier.visit(py, &doc.template, None);
+    minifier.edits.sort_by_key(|&(start, _, _)| start);
+
+    let mut out = String::with_capacity(source.len());
+    let mut at = 0;
+    for (start, end, replacement) in minifier.edits {
+        if start < at {
+            continue;
+        }
+        out.push_str(&source[at..start]);
+        out.push_str(&replacement);
+        at = end;
+    }
+    out.push_str(&source[at..]);
+    Ok(out)
+}
+
+/// Minify a `.wire` template, given as source text or a ParsedDocument:
+/// HTML comments and layout whitespace are removed and boolean attributes
+/// lose redundant values. The result is a template rendering the same page;
+/// `<pre>`, `<textarea>`, `<script>`, `<style>` and brace blocks are kept
+/// as written. Raises the parser's exception if the template has syntax
+/// errors.
+#[pyfunction]
+pub fn minify(py: Python<'_>, source_or_document: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(doc) = source_or_document.downcast::<ParsedDocument>() {
+        return minify_document(py, &doc.borrow());
+    }
+    if let Ok(source) = source_or_document.extract::<String>() {
+        let options = ParseOptions {
+            tab_width: columns::DEFAULT_TAB_WIDTH,
+            ..Default::default()
+        };
+        return minify_document(py, &parse_with(py, &source, &options)?);
+    }
+    Err(PyTypeError::new_err(format!(
+        "expected a template string or a ParsedDocument, got {}",
+        source_or_document.get_type().name()?
+    )))
+}
@@ -0,0 +1,243 @@
+use crate::html::{HtmlNode, parse_html, serialize_node};
+use crate::listdiff::diff_sources;
+use pyo3::prelude::*;
+
+/// One DOM patch produced by `morph_html`.
+///
+/// Paths are child-index paths from the fragment root, valid at the point the
+/// op is applied (ops are ordered so a parent's `reorder` precedes any op on
+/// its children). Kinds:
+/// - `set_attr` / `remove_attr`: `name`, `value`
+/// - `set_attrs`: `attrs` holds `(name, value)` for every changed attribute
+///   of one element, with None for a removed one and "" for a valueless
+///   one; emitted instead of `set_attr`/`remove_attr` ops when an element
+///   has several attribute changes
+/// - `set_text`: `value` is the new text/comment data
+/// - `replace`: `html` is the replacement outer HTML
+/// - `reorder`: children of `path` follow `keyed_list_diff` semantics, with
+///   `inserted` carrying `(new_index, html)` pairs
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct MorphOp {
+    #[pyo3(get)]
+    pub kind: &'static str,
+    #[pyo3(get)]
+    pub path: Vec<usize>,
+    #[pyo3(get)]
+    pub name: Option<String>,
+    #[pyo3(get)]
+    pub value: Option<String>,
+    #[pyo3(get)]
+    pub html: Option<String>,
+    #[pyo3(get)]
+    pub sources: Vec<isize>,
+    #[pyo3(get)]
+    pub removed: Vec<usize>,
+    #[pyo3(get)]
+    pub moved: Vec<usize>,
+    #[pyo3(get)]
+    pub inserted: Vec<(usize, String)>,
+    #[pyo3(get)]
+    pub attrs: Vec<(String, Option<String>)>,
+}
+
+impl MorphOp {
+    fn new(kind: &'static str, path: Vec<usize>) -> Self {
+        MorphOp {
+            kind,
+            path,
+            name: None,
+            value: None,
+            html: None,
+            sources: Vec::new(),
+            removed: Vec::new(),
+            moved: Vec::new(),
+            inserted: Vec::new(),
+            attrs: Vec::new(),
+        }
+    }
+}
+
+#[pymethods]
+impl MorphOp {
+    fn __repr__(&self) -> String {
+        format!("MorphOp(kind={:?}, path={:?})", self.kind, self.path)
+    }
+}
+
+// Elements carrying an explicit identity only ever match the same identity.
+fn node_key(node: &HtmlNode) -> Option<(&str, &str)> {
+    match node {
+        HtmlNode::Element(el) => ["id", "data-key", "key"]
+            .iter()
+            .find_map(|a| el.attr(a))
+            .map(|k| (el.tag.as_str(), k)),
+        _ => None,
+    }
+}
+
+fn same_kind(a: &HtmlNode, b: &HtmlNode) -> bool {
+    match (a, b) {
+        (HtmlNode::Element(x), HtmlNode::Element(y)) => x.tag == y.tag,
+        (HtmlNode::Text(_), HtmlNode::Text(_))
+        | (HtmlNode::Comment(_), HtmlNode::Comment(_))
+        | (HtmlNode::Doctype(_), HtmlNode::Doctype(_)) => true,
+        _ => false,
+    }
+}
+
+fn outer_html(node: &HtmlNode) -> String {
+    let mut out = String::new();
+    serialize_node(&mut out, node);
+    out
+}
+
+// For each new child, the index of the old child it reuses (or -1).
+fn match_children(old: &[HtmlNode], new: &[HtmlNode]) -> Vec<isize> {
+    let mut used = vec![false; old.len()];
+    let mut sources = vec![-1isize; new.len()];
+
+    for (ni, n) in new.iter().enumerate() {
+        if let Some(key) = node_key(n)
+            && let Some(oi) =
+                (0..old.len()).find(|&oi| !used[oi] && node_key(&old[oi]) == Some(key))
+        {
+            used[oi] = true;
+            sources[ni] = oi as isize;
+        }
+    }
+
+    // Unkeyed nodes match in order by kind, preferring candidates after the
+    // previous match so untouched runs stay in place.
+    let mut cursor = 0;
+    for (ni, n) in new.iter().enumerate() {
+        if sources[ni] >= 0 {
+            cursor = sources[ni] as usize + 1;
+            continue;
+        }
+        if node_key(n).is_some() {
+            continue;
+        }
+        let candidate =
+            |oi: &usize| !used[*oi] && node_key(&old[*oi]).is_none() && same_kind(&old[*oi], n);
+        if let Some(oi) = (cursor..old.len())
+            .find(candidate)
+            .or_else(|| (0..cursor.min(old.len())).find(candidate))
+        {
+            used[oi] = true;
+            sources[ni] = oi as isize;
+            cursor = oi + 1;
+        }
+    }
+    sources
+}
+
+// One `set_attrs` op for several attribute changes, so the client patches
+// the element once; a lone change stays a `set_attr`/`remove_attr`.
+fn attr_ops(
+    path: &[usize],
+    set: Vec<(String, Option<String>)>,
+    removed: Vec<String>,
+    ops: &mut Vec<MorphOp>,
+) {
+    if set.len() + removed.len() > 1 {
+        let mut op = MorphOp::new("set_attrs", path.to_vec());
+        // A valueless attribute is set to "", leaving None for removal.
+        op.attrs = set
+            .into_iter()
+            .map(|(name, value)| (name, Some(value.unwrap_or_default())))
+            .chain(removed.into_iter().map(|name| (name, None)))
+            .collect();
+        ops.push(op);
+    } else if let Some((name, value)) = set.into_iter().next() {
+        let mut op = MorphOp::new("set_attr", path.to_vec());
+        op.name = Some(name);
+        op.value = value;
+        ops.push(op);
+    } else if let Some(name) = removed.into_iter().next() {
+        let mut op = MorphOp::new("remove_attr", path.to_vec());
+        op.name = Some(name);
+        ops.push(op);
+    }
+}
+
+fn morph_node(old: &HtmlNode, new: &HtmlNode, path: &mut Vec<usize>, ops: &mut Vec<MorphOp>) {
+    match (old, new) {
+        (HtmlNode::Element(o), HtmlNode::Element(n)) if o.tag == n.tag => {
+            let mut set = Vec::new();
+            for (name, value) in &n.attrs {
+                let old_value = o.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v);
+                if old_value != Some(value) {
+                    set.push((name.clone(), value.clone()));
+                }
+            }
+            let removed: Vec<String> = o
+                .attrs
+                .iter()
+                .filter(|(name, _)| !n.attrs.iter().any(|(k, _)| k == name))
+                .map(|(name, _)| name.clone())
+                .collect();
+            attr_ops(path, set, removed, ops);
+            morph_children(&o.children, &n.children, path, ops);
+        }
+        (HtmlNode::Text(a), HtmlNode::Text(b))
+        | (HtmlNode::Comment(a), HtmlNode::Comment(b))
+        | (HtmlNode::Doctype(a), HtmlNode::Doctype(b)) => {
+            if a != b {
+                let mut op = MorphOp::new("set_text", path.clone());
+                op.value = Some(b.clone());
+                ops.push(op);
+            }
+        }
+        _ => {
+            let mut op = MorphOp::new("replace", path.clone());
+            op.html = Some(outer_html(new));
+            ops.push(op);
+        }
+    }
+}
+
+fn morph_children(
+    old: &[HtmlNode],
+    new: &[HtmlNode],
+    path: &mut Vec<usize>,
+    ops: &mut Vec<MorphOp>,
+) {
+    let sources = match_children(old, new);
+    let diff = diff_sources(&sources, old.len());
+    if !(diff.removed.is_empty() && diff.inserted.is_empty() && diff.moved.is_empty()) {
+        let mut op = MorphOp::new("reorder", path.clone());
+        op.inserted = diff
+            .inserted
+            .iter()
+            .map(|&i| (i, outer_html(&new[i])))
+            .collect();
+        op.sources = diff.sources;
+        op.removed = diff.removed;
+        op.moved = diff.moved;
+        ops.push(op);
+    }
+    for (ni, &src) in sources.iter().enumerate() {
+        if src >= 0 {
+            path.push(ni);
+            morph_node(&old[src as usize], &new[ni], path, ops);
+            path.pop();
+        }
+    }
+}
+
+pub(crate) fn morph(old_html: &str, new_html: &str) -> Vec<MorphOp> {
+    let old = parse_html(old_html);
+    let new = parse_html(new_html);
+    let mut ops = Vec::new();
+    morph_children(&old, &new, &mut Vec::new(), &mut ops);
+    ops
+}
+
+/// Patch ops that turn the DOM for `old_html` into `new_html`, reusing elements
+/// matched by `id`/`data-key`/`key` (then by position and tag) instead of
+/// replacing the whole fragment.
+#[pyfunction]
+pub fn morph_html(py: Python<'_>, old_html: &str, new_html: &str) -> Vec<MorphOp> {
+    py.detach(|| morph(old_html, new_html))
+}
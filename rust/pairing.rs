@@ -0,0 +1,279 @@
+use crate::ParsedNode;
+use crate::diagnostics::Diagnostic;
+use pyo3::prelude::*;
+
+/// One arm of a resolved `{$if}` chain. `condition` is None for `else`.
+#[pyclass(frozen)]
+pub struct Branch {
+    #[pyo3(get)]
+    pub keyword: String,
+    #[pyo3(get)]
+    pub condition: Option<String>,
+    #[pyo3(get)]
+    pub children: Vec<Py<ParsedNode>>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl Branch {
+    fn __repr__(&self) -> String {
+        match &self.condition {
+            Some(c) => format!("Branch({} {})", self.keyword, c),
+            None => format!("Branch({})", self.keyword),
+        }
+    }
+}
+
+// Keywords that open a block closed by `{/keyword}`.
+pub(crate) const OPENERS: &[&str] = &["if", "for", "try", "await", "example", "match"];
+
+// Link block markers within each sibling list: branches and closers get
+// `open_index`, openers get `close_index` and the closer's position.
+// Mismatched closers are left unpaired.
+pub(crate) fn pair_blocks(py: Python<'_>, nodes: &[Py<ParsedNode>]) {
+    let mut open: Vec<(usize, String)> = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let mut node_ref = node.borrow_mut(py);
+        pair_blocks(py, &node_ref.children);
+        if !node_ref.is_block {
+            continue;
+        }
+        let Some(kw) = node_ref.block_keyword.clone() else {
+            continue;
+        };
+        if OPENERS.contains(&kw.as_str()) {
+            open.push((i, kw));
+        } else if let Some(closing) = kw.strip_prefix('/') {
+            if let Some((start, _)) = open.pop_if(|(_, k)| k == closing) {
+                node_ref.open_index = Some(start);
+                let (line, column) = (node_ref.line, node_ref.column);
+                drop(node_ref);
+                let mut opener = nodes[start].borrow_mut(py);
+                opener.close_index = Some(i);
+                opener.close_line = Some(line);
+                opener.close_column = Some(column);
+            }
+        } else if kw != "interpolation"
+            && kw != "html"
+            && let Some((start, _)) = open.last()
+        {
+            node_ref.open_index = Some(*start);
+        }
+    }
+}
+
+type Span = (usize, usize, usize, usize);
+
+fn span_of(node: &ParsedNode) -> Span {
+    (node.line, node.column, node.end_line, node.end_column)
+}
+
+// Errors for block markers `pair_blocks` left unpaired: openers with no
+// closer and closers with no opener. A closer met while a different block
+// is open (`{$if}...{/for}`) is a mismatch: both errors list the other
+// marker's span in `related`.
+pub(crate) fn unmatched_blocks(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    out: &mut Vec<Diagnostic>,
+) {
+    // Openers still open at the current sibling, innermost last.
+    let mut open: Vec<usize> = Vec::new();
+    // (opener index, closer span) of each mismatch.
+    let mut mismatched: Vec<(usize, Span)> = Vec::new();
+    // (opener index, index in `out`) of each unclosed-block error.
+    let mut unclosed: Vec<(usize, usize)> = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let node = node.borrow(py);
+        unmatched_blocks(py, &node.children, out);
+        let Some(kw) = node.block_keyword.as_deref().filter(|_| node.is_block) else {
+            continue;
+        };
+        if OPENERS.contains(&kw) {
+            open.push(i);
+            if node.close_index.is_none() {
+                unclosed.push((i, out.len()));
+                out.push(
+                    Diagnostic::error(
+                        "unclosed-block",
+                        format!("'{{${}}}' block is never closed with '{{/{}}}'", kw, kw),
+                        node.line,
+                        node.column,
+                    )
+                    .spanning(
+                        (node.end_line, node.end_column),
+                        (node.start_byte, node.end_byte),
+                    ),
+                );
+            }
+            continue;
+        }
+        let Some(closing) = kw.strip_prefix('/') else {
+            continue;
+        };
+        if let Some(start) = node.open_index {
+            if let Some(at) = open.iter().rposition(|&o| o == start) {
+                open.truncate(at);
+            }
+            continue;
+        }
+        let innermost = open.last().map(|&o| (o, nodes[o].borrow(py)));
+        let mut d = match &innermost {
+            Some((_, opener)) => Diagnostic::error(
+                "unmatched-block-close",
+                format!(
+                    "'{{/{}}}' does not match the open '{{${}}}' at line {}",
+                    closing,
+                    opener.block_keyword.as_deref().unwrap_or(""),
+                    opener.line
+                ),
+                node.line,
+                node.column,
+            ),
+            None => Diagnostic::error(
+                "unmatched-block-close",
+                format!("'{{/{}}}' has no open '{{${}}}' to close", closing, closing),
+                node.line,
+                node.column,
+            ),
+        }
+        .spanning(
+            (node.end_line, node.end_column),
+            (node.start_byte, node.end_byte),
+        );
+        if let Some((o, opener)) = innermost {
+            d.related.push(span_of(&opener));
+            if !mismatched.iter().any(|&(m, _)| m == o) {
+                mismatched.push((o, span_of(&node)));
+            }
+        }
+        out.push(d);
+    }
+    for (opener, at) in unclosed {
+        if let Some(&(_, closer)) = mismatched.iter().find(|&&(o, _)| o == opener) {
+            out[at].related.push(closer);
+        }
+    }
+}
+
+// Branch keywords that continue the innermost open block.
+pub(crate) const BRANCHES: &[&str] = &[
+    "elif", "else", "except", "finally", "then", "catch", "case", "default",
+];
+
+struct Frame {
+    opener: Py<ParsedNode>,
+    keyword: String,
+    // Where body nodes currently go: the opener or its latest branch.
+    current: Py<ParsedNode>,
+}
+
+// `resolved=True` shape: nest block bodies under their markers. Sibling
+// indices no longer apply, so `open_index`/`close_index` are cleared.
+pub(crate) fn resolve_blocks(py: Python<'_>, nodes: Vec<Py<ParsedNode>>) -> Vec<Py<ParsedNode>> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    for node in nodes {
+        let (is_block, kw) = {
+            let mut n = node.borrow_mut(py);
+            n.open_index = None;
+            n.close_index = None;
+            let children = std::mem::take(&mut n.children);
+            n.children = resolve_blocks(py, children);
+            (n.is_block, n.block_keyword.clone().unwrap_or_default())
+        };
+        if is_block && let Some(closing) = kw.strip_prefix('/') {
+            if stack.last().is_some_and(|f| f.keyword == closing) {
+                stack.pop();
+                continue;
+            }
+        } else if is_block
+            && BRANCHES.contains(&kw.as_str())
+            && let Some(frame) = stack.last_mut()
+        {
+            frame
+                .opener
+                .borrow_mut(py)
+                .children
+                .push(node.clone_ref(py));
+            frame.current = node;
+            continue;
+        }
+        let opens = is_block && OPENERS.contains(&kw.as_str());
+        match stack.last() {
+            Some(frame) => frame
+                .current
+                .borrow_mut(py)
+                .children
+                .push(node.clone_ref(py)),
+            None => roots.push(node.clone_ref(py)),
+        }
+        if opens {
+            stack.push(Frame {
+                opener: node.clone_ref(py),
+                keyword: kw,
+                current: node,
+            });
+        }
+    }
+    roots
+}
+
+fn is_branch_of_if(py: Python<'_>, node: &Py<ParsedNode>) -> bool {
+    let node = node.borrow(py);
+    node.is_block && matches!(node.block_keyword.as_deref(), Some("elif" | "else"))
+}
+
+// Fill `branches` of every resolved `{$if}`: the opener's own body up to its
+// first elif/else, then one branch per branch node.
+pub(crate) fn attach_branches(py: Python<'_>, nodes: &[Py<ParsedNode>]) -> PyResult<()> {
+    for node in nodes {
+        let children: Vec<Py<ParsedNode>> = node
+            .borrow(py)
+            .children
+            .iter()
+            .map(|c| c.clone_ref(py))
+            .collect();
+        attach_branches(py, &children)?;
+
+        let mut n = node.borrow_mut(py);
+        if !n.is_block || n.block_keyword.as_deref() != Some("if") {
+            continue;
+        }
+        let split = children
+            .iter()
+            .position(|c| is_branch_of_if(py, c))
+            .unwrap_or(children.len());
+        let mut branches = vec![Py::new(
+            py,
+            Branch {
+                keyword: "if".to_string(),
+                condition: n.expression.clone(),
+                children: children[..split].iter().map(|c| c.clone_ref(py)).collect(),
+                line: n.line,
+                column: n.column,
+            },
+        )?];
+        for child in &children[split..] {
+            if !is_branch_of_if(py, child) {
+                continue;
+            }
+            let c = child.borrow(py);
+            branches.push(Py::new(
+                py,
+                Branch {
+                    keyword: c.block_keyword.clone().unwrap_or_default(),
+                    condition: c.expression.clone(),
+                    children: c.children.iter().map(|g| g.clone_ref(py)).collect(),
+                    line: c.line,
+                    column: c.column,
+                },
+            )?);
+        }
+        n.branches = branches;
+    }
+    Ok(())
+}
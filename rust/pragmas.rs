@@ -0,0 +1,127 @@
+// Suppression pragmas: `{# pywire: disable=rule,... #}` and `!nolint rule ...`
+// (no rules means every rule). A pragma above the first line of markup covers
+// the whole file; anywhere else it covers the next element, or the element it
+// trails on the same line, including that element's children. Only warnings
+// can be suppressed; errors always surface.
+
+use crate::ParsedNode;
+use crate::diagnostics::Diagnostic;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+struct Pragma {
+    // Empty means every rule.
+    rules: Vec<String>,
+    first_line: usize,
+    last_line: usize,
+}
+
+impl Pragma {
+    fn covers(&self, code: &str, line: usize) -> bool {
+        (self.first_line..=self.last_line).contains(&line)
+            && (self.rules.is_empty() || self.rules.iter().any(|r| r == code))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Suppressions(Vec<Pragma>);
+
+fn rule_list(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|r| !r.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Rules of the pragma on `line`, and whether it trails other markup.
+fn scan_line(line: &str) -> Option<(Vec<String>, bool)> {
+    let trimmed = line.trim();
+    if let Some(rest) = trimmed.strip_prefix("!nolint")
+        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+    {
+        return Some((rule_list(rest), false));
+    }
+    let start = line.find("{#")?;
+    let body = &line[start + 2..];
+    let body = body[..body.find("#}")?].trim();
+    let rest = body.strip_prefix("pywire:")?.trim();
+    let rules = match rest.strip_prefix("disable")?.trim_start() {
+        "" => Vec::new(),
+        r => rule_list(r.strip_prefix('=')?),
+    };
+    Some((rules, !line[..start].trim().is_empty()))
+}
+
+// Last line of the elements starting on `line`.
+fn extent(py: Python<'_>, nodes: &[Py<ParsedNode>], line: usize) -> Option<usize> {
+    let mut end = None;
+    for node in nodes {
+        let node = node.borrow(py);
+        if node.line == line {
+            end = end.max(Some(node.close_line.unwrap_or(node.line)));
+        }
+        end = end.max(extent(py, &node.children, line));
+    }
+    end
+}
+
+impl Suppressions {
+    pub(crate) fn scan(py: Python<'_>, source: &str, template: &[Py<ParsedNode>]) -> Self {
+        let lines: Vec<&str> = source.lines().collect();
+        let found: Vec<(usize, Vec<String>, bool)> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| scan_line(l).map(|(rules, trailing)| (i + 1, rules, trailing)))
+            .collect();
+        let is_content = |i: usize| {
+            let t = lines[i].trim();
+            !t.is_empty() && !t.starts_with('!') && scan_line(lines[i]).is_none_or(|(_, tr)| tr)
+        };
+        let first_content = (0..lines.len())
+            .find(|&i| is_content(i))
+            .map_or(usize::MAX, |i| i + 1);
+
+        let pragmas = found
+            .into_iter()
+            .filter_map(|(line, rules, trailing)| {
+                if line < first_content {
+                    return Some(Pragma {
+                        rules,
+                        first_line: 1,
+                        last_line: usize::MAX,
+                    });
+                }
+                let target = if trailing {
+                    line
+                } else {
+                    (line..lines.len()).find(|&i| is_content(i))? + 1
+                };
+                let last_line = extent(py, template, target).unwrap_or(target).max(target);
+                Some(Pragma {
+                    rules,
+                    first_line: target,
+                    last_line,
+                })
+            })
+            .collect();
+        Suppressions(pragmas)
+    }
+
+    pub(crate) fn is_suppressed(&self, code: &str, line: usize) -> bool {
+        self.0.iter().any(|p| p.covers(code, line))
+    }
+
+    // Drop suppressed warnings, returning how many were dropped per code.
+    pub(crate) fn apply(&self, diagnostics: &mut Vec<Diagnostic>) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        diagnostics.retain(|d| {
+            let drop = d.severity == "warning" && self.is_suppressed(&d.code, d.line);
+            if drop {
+                *counts.entry(d.code.clone()).or_insert(0) += 1;
+            }
+            !drop
+        });
+        counts
+    }
+}
@@ -0,0 +1,418 @@
+use crate::feed::feed_format;
+use crate::html::is_void;
+use crate::kinds::{AttrKind, class_toggle, event_spec, style_property};
+use crate::provider::{PyProvider, Value, ValueProvider};
+use crate::routes::{RouteEntry, route_entries};
+use crate::serializer::HtmlSerializer;
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// One file written by `prerender`.
+#[pyclass(frozen)]
+pub struct PrerenderedPage {
+    #[pyo3(get)]
+    pub route: RouteEntry,
+    #[pyo3(get)]
+    pub file: PathBuf,
+    #[pyo3(get)]
+    pub size: usize,
+    #[pyo3(get)]
+    pub etag: Option<String>,
+}
+
+#[pymethods]
+impl PrerenderedPage {
+    fn __repr__(&self) -> String {
+        format!(
+            "PrerenderedPage(path={:?}, file={:?})",
+            self.route.path, self.file
+        )
+    }
+}
+
+const URL_ATTRIBUTES: &[&str] = &["src", "href", "poster", "action", "data"];
+
+// Renders parsed nodes, pulling dynamic values from a `ValueProvider`.
+pub(crate) struct Renderer<'a, 'py, P: ValueProvider> {
+    pub(crate) py: Python<'py>,
+    pub(crate) values: P,
+    // What is being rendered, for error messages: "route '/about'".
+    pub(crate) label: String,
+    pub(crate) assets: &'a HashMap<String, String>,
+    pub(crate) out: HtmlSerializer,
+    // `!feed` documents: no void elements.
+    pub(crate) xml: bool,
+}
+
+// Python literals that can be folded without calling back into Python.
+fn fold_literal(expr: &str) -> Option<String> {
+    let expr = expr.trim();
+    for q in ['"', '\''] {
+        if let Some(inner) = expr.strip_prefix(q).and_then(|e| e.strip_suffix(q))
+            && !inner.contains([q, '\\'])
+        {
+            return Some(inner.to_string());
+        }
+    }
+    if !expr.is_empty()
+        && expr.parse::<f64>().is_ok()
+        && expr
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'.' || b == b'-')
+    {
+        return Some(expr.to_string());
+    }
+    None
+}
+
+// Static attribute values are stored as written; decode their character
+// references so the serializer's escaping does not apply twice.
+pub(crate) fn decode_attr(value: &str) -> String {
+    crate::entities::decode(value, true)
+}
+
+fn braced(value: &str) -> Option<&str> {
+    value.strip_prefix('{').and_then(|v| v.strip_suffix('}'))
+}
+
+// Index of the block marker that ends the branch starting after `start`, and
+// the keyword found there (elif/else at the same depth, or the closing marker).
+fn branch_end(py: Python<'_>, nodes: &[Py<ParsedNode>], start: usize) -> (usize, String) {
+    let mut depth = 0;
+    for (i, node) in nodes.iter().enumerate().skip(start + 1) {
+        let node = node.borrow(py);
+        if !node.is_block {
+            continue;
+        }
+        let kw = node.block_keyword.as_deref().unwrap_or("");
+        match kw {
+            "if" | "for" | "try" | "await" => depth += 1,
+            _ if kw.starts_with('/') => {
+                if depth == 0 {
+                    return (i, kw.to_string());
+                }
+                depth -= 1;
+            }
+            "elif" | "else" if depth == 0 => return (i, kw.to_string()),
+            _ => {}
+        }
+    }
+    (nodes.len(), String::new())
+}
+
+impl<P: ValueProvider> Renderer<'_, '_, P> {
+    fn value(&mut self, expr: &str) -> PyResult<Option<String>> {
+        if let Some(folded) = fold_literal(expr) {
+            return Ok(Some(folded));
+        }
+        Ok(self.values.value(expr)?.into_text())
+    }
+
+    fn truthy(&mut self, expr: &str) -> PyResult<bool> {
+        self.values.truthy(expr)
+    }
+
+    // Fold `class:name={cond}` and `style:prop={value}` into the `class` and
+    // `style` attributes. A None or False style value drops the property.
+    fn conditionals(
+        &mut self,
+        node: &ParsedNode,
+        attrs: &mut Vec<(String, Option<String>)>,
+    ) -> PyResult<()> {
+        let mut classes = Vec::new();
+        for entry in node.attributes.conditionals(AttrKind::ClassToggle) {
+            if self.truthy(&entry.expression)? {
+                classes.push(entry.name);
+            }
+        }
+        let mut styles = Vec::new();
+        for entry in node.attributes.conditionals(AttrKind::StyleProperty) {
+            let value = match fold_literal(&entry.expression) {
+                Some(folded) => folded,
+                None => match self.values.value(&entry.expression)? {
+                    Value::None | Value::Bool(false) => continue,
+                    value => value.into_text().unwrap_or_default(),
+                },
+            };
+            styles.push(format!("{}: {}", entry.name, value));
+        }
+        for (name, extra, sep) in [
+            ("class", classes.join(" "), " "),
+            ("style", styles.join("; "), "; "),
+        ] {
+            if extra.is_empty() {
+                continue;
+            }
+            match attrs.iter_mut().find(|(n, _)| n == name) {
+                Some((_, Some(value))) if !value.trim().is_empty() => {
+                    let base = value.trim_end().trim_end_matches(';').to_string();
+                    *value = format!("{}{}{}", base, sep, extra);
+                }
+                Some((_, value)) => *value = Some(extra),
+                None => attrs.push((name.to_string(), Some(extra))),
+            }
+        }
+        Ok(())
+    }
+
+    // Render the `{$for}` loop starting at `nodes[start]` once per item, or
+    // its `{$else}` branch if there are none. Returns the index past the
+    // closing marker, or None if the provider cannot bind loop targets.
+    fn render_loop(&mut self, nodes: &[Py<ParsedNode>], start: usize) -> PyResult<Option<usize>> {
+        let py = self.py;
+        let expr = nodes[start]
+            .borrow(py)
+            .expression
+            .clone()
+            .unwrap_or_default();
+        let (target, iterable) = expr.split_once(" in ").unwrap_or(("", &expr));
+        let targets: Vec<&str> = target
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        let Some(count) = self.values.enter_loop(&targets, iterable)? else {
+            return Ok(None);
+        };
+        let (end, kw) = branch_end(py, nodes, start);
+        let close = if kw == "else" {
+            branch_end(py, nodes, end).0
+        } else {
+            end
+        };
+        let mut rendered = Ok(());
+        for index in 0..count {
+            rendered = self
+                .values
+                .bind_item(index)
+                .and_then(|()| self.render_nodes(&nodes[start + 1..end.min(nodes.len())]));
+            if rendered.is_err() {
+                break;
+            }
+        }
+        self.values.exit_loop();
+        rendered?;
+        if count == 0 && kw == "else" {
+            self.render_nodes(&nodes[end + 1..close.min(nodes.len())])?;
+        }
+        Ok(Some(close + 1))
+    }
+
+    pub(crate) fn render_nodes(&mut self, nodes: &[Py<ParsedNode>]) -> PyResult<()> {
+        let py = self.py;
+        let mut i = 0;
+        while i < nodes.len() {
+            let is_loop = {
+                let node = nodes[i].borrow(py);
+                node.is_block && node.block_keyword.as_deref() == Some("for")
+            };
+            if is_loop && let Some(next) = self.render_loop(nodes, i)? {
+                i = next;
+                continue;
+            }
+            let node = nodes[i].borrow(py);
+            if node.is_block && node.block_keyword.as_deref() == Some("if") {
+                // Walk the if/elif/else chain, rendering the first true branch.
+                let mut cond = node.expression.clone();
+                let mut start = i;
+                let mut rendered = false;
+                loop {
+                    let (end, kw) = branch_end(py, nodes, start);
+                    let take = !rendered
+                        && match &cond {
+                            Some(c) => self.truthy(c)?,
+                            None => true,
+                        };
+                    if take {
+                        self.render_nodes(&nodes[start + 1..end])?;
+                        rendered = true;
+                    }
+                    if kw.starts_with('/') || end >= nodes.len() {
+                        i = end + 1;
+                        break;
+                    }
+                    cond = nodes[end].borrow(py).expression.clone();
+                    if kw == "else" {
+                        cond = None;
+                    }
+                    start = end;
+                }
+                continue;
+            }
+            self.render_node(&node)?;
+            i += 1;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn render_node(&mut self, node: &ParsedNode) -> PyResult<()> {
+        if node.is_block {
+            return match node.block_keyword.as_deref() {
+                Some("interpolation") => {
+                    let expr = node.expression.as_deref().unwrap_or("");
+                    if let Some(text) = self.value(expr)? {
+                        self.out.text(&text);
+                    }
+                    Ok(())
+                }
+                Some(kw) => Err(PyValueError::new_err(format!(
+                    "Cannot prerender '{{${}}}' block at line {} of {}",
+                    kw.trim_start_matches('/'),
+                    node.line,
+                    self.label
+                ))),
+                None => Ok(()),
+            };
+        }
+        let Some(tag) = node.tag.as_deref() else {
+            if let Some(text) = &node.text_content {
+                self.out.raw(text);
+            }
+            return Ok(());
+        };
+
+        if let Some(Some(cond)) = node.attributes.get("$if")
+            && !self.truthy(braced(cond).unwrap_or(cond))?
+        {
+            return Ok(());
+        }
+
+        let mut attrs = Vec::new();
+        for (name, raw) in node.attributes.iter() {
+            if name.starts_with('$')
+                || event_spec(name).is_some()
+                || class_toggle(name).is_some()
+                || style_property(name).is_some()
+                || name.starts_with("__pw")
+            {
+                continue;
+            }
+            let value = match raw.as_deref().and_then(braced) {
+                Some(expr) => match fold_literal(expr) {
+                    Some(folded) => Some(folded),
+                    None => match self.values.value(expr)? {
+                        Value::Text(text) => Some(text),
+                        Value::Bool(true) => {
+                            attrs.push((name.clone(), None));
+                            continue;
+                        }
+                        Value::None | Value::Bool(false) => continue,
+                    },
+                },
+                None => raw.as_deref().map(decode_attr),
+            };
+            let value = match value {
+                Some(v) if URL_ATTRIBUTES.contains(&name.as_str()) => {
+                    Some(self.assets.get(&v).cloned().unwrap_or(v))
+                }
+                other => other,
+            };
+            attrs.push((name.clone(), value));
+        }
+        self.conditionals(node, &mut attrs)?;
+
+        self.out.start_tag(tag, Some(attrs), false);
+        if is_void(tag) && !self.xml {
+            return Ok(());
+        }
+        self.render_nodes(&node.children)?;
+        self.out.end_tag(tag);
+        Ok(())
+    }
+}
+
+// "/" -> index.html, "/about" -> about/index.html, "/feed.xml" -> feed.xml
+fn output_file(out_dir: &Path, route_path: &str) -> PathBuf {
+    let rel = route_path.trim_matches('/');
+    let mut file = out_dir.to_path_buf();
+    for part in rel.split('/').filter(|p| !p.is_empty() && *p != "..") {
+        file.push(part);
+    }
+    if rel.is_empty() || !rel.rsplit('/').next().unwrap_or("").contains('.') {
+        file.push("index.html");
+    }
+    file
+}
+
+/// Render every fully static route of `documents` to an HTML file under
+/// `out_dir`.
+///
+/// Literal expressions are folded in Rust; everything else is passed to
+/// `resolver(expression, route)`, whose result is rendered with `str()`
+/// (None renders nothing; booleans toggle attributes). `{$if}` chains are
+/// evaluated the same way. `asset_map` rewrites src/href-style attribute
+/// values, e.g. to fingerprinted asset URLs. `xhtml=True` writes
+/// well-formed XHTML (see `HtmlSerializer`). `!feed` documents are always
+/// written as XML, with an XML declaration unless the template has one.
+#[pyfunction]
+#[pyo3(signature = (documents, resolver, out_dir, asset_map=None, xhtml=false))]
+pub fn prerender(
+    py: Python<'_>,
+    documents: Vec<PyRef<'_, ParsedDocument>>,
+    resolver: Bound<'_, PyAny>,
+    out_dir: PathBuf,
+    asset_map: Option<HashMap<String, String>>,
+    xhtml: bool,
+) -> PyResult<Vec<PrerenderedPage>> {
+    let assets = asset_map.unwrap_or_default();
+    let mut pages = Vec::new();
+    for doc in &documents {
+        for route in route_entries(py, doc)? {
+            if !route.dynamic_reasons.is_empty() {
+                continue;
+            }
+            let is_feed = matches!(feed_format(&doc.directives), Ok(Some(_)));
+            let mut renderer = Renderer {
+                py,
+                values: PyProvider::new(&resolver, Bound::new(py, route.clone())?.into_any()),
+                label: format!("route '{}'", route.path),
+                assets: &assets,
+                out: HtmlSerializer::new(true, xhtml && !is_feed, is_feed),
+                xml: is_feed,
+            };
+            let declared = doc.template.iter().any(|n| {
+                let n = n.borrow(py);
+                n.tag.is_none()
+                    && n.text_content
+                        .as_deref()
+                        .is_some_and(|t| t.contains("<?xml"))
+            });
+            let starts_with_html = doc
+                .template
+                .iter()
+                .find(|n| n.borrow(py).tag.is_some())
+                .is_some_and(|n| n.borrow(py).tag.as_deref() == Some("html"));
+            // A written doctype is rendered as a text node.
+            let has_doctype = doc.template.iter().any(|n| n.borrow(py).is_doctype);
+            if is_feed && !declared {
+                renderer
+                    .out
+                    .raw("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            } else if starts_with_html && !is_feed && !has_doctype {
+                renderer.out.raw("<!DOCTYPE html>");
+            }
+            renderer.render_nodes(&doc.template)?;
+            let body = renderer.out.finish_bytes()?;
+            let etag = renderer.out.etag();
+
+            let file = output_file(&out_dir, &route.path);
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file, &body)?;
+            pages.push(PrerenderedPage {
+                file,
+                size: body.len(),
+                etag,
+                route,
+            });
+        }
+    }
+    Ok(pages)
+}
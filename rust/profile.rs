@@ -0,0 +1,138 @@
+use crate::ParsedNode;
+use pyo3::prelude::*;
+use std::time::Instant;
+
+/// Size of one element subtree in a `ProfileReport`.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct SubtreeCost {
+    #[pyo3(get)]
+    pub tag: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub nodes: usize,
+    #[pyo3(get)]
+    pub expressions: usize,
+}
+
+#[pymethods]
+impl SubtreeCost {
+    fn __repr__(&self) -> String {
+        format!(
+            "SubtreeCost(<{}> at {}:{}, nodes={}, expressions={})",
+            self.tag, self.line, self.column, self.nodes, self.expressions
+        )
+    }
+}
+
+/// Result of `profile`: wall time per phase plus the largest subtrees.
+#[pyclass(frozen)]
+pub struct ProfileReport {
+    #[pyo3(get)]
+    pub parse_seconds: f64,
+    #[pyo3(get)]
+    pub map_seconds: f64,
+    #[pyo3(get)]
+    pub analysis_seconds: f64,
+    #[pyo3(get)]
+    pub nodes: usize,
+    #[pyo3(get)]
+    pub expressions: usize,
+    #[pyo3(get)]
+    pub subtrees: Vec<SubtreeCost>,
+}
+
+#[pymethods]
+impl ProfileReport {
+    #[getter]
+    fn total_seconds(&self) -> f64 {
+        self.parse_seconds + self.map_seconds + self.analysis_seconds
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ProfileReport(parse={:.3}ms, map={:.3}ms, analysis={:.3}ms, nodes={})",
+            self.parse_seconds * 1e3,
+            self.map_seconds * 1e3,
+            self.analysis_seconds * 1e3,
+            self.nodes
+        )
+    }
+}
+
+// Expressions evaluated by a node itself: its block expression and any
+// `{...}` attribute values.
+fn own_expressions(node: &ParsedNode) -> usize {
+    let attrs = node
+        .attributes
+        .values()
+        .filter(|v| v.as_deref().is_some_and(|v| v.contains('{')))
+        .count();
+    attrs + usize::from(node.expression.is_some())
+}
+
+// Returns (nodes, expressions) of `nodes`, recording every element subtree.
+fn measure(py: Python<'_>, nodes: &[Py<ParsedNode>], out: &mut Vec<SubtreeCost>) -> (usize, usize) {
+    let mut total = (0, 0);
+    for node in nodes {
+        let node = node.borrow(py);
+        let (child_nodes, child_exprs) = measure(py, &node.children, out);
+        let cost = (1 + child_nodes, own_expressions(&node) + child_exprs);
+        if let Some(tag) = &node.tag {
+            out.push(SubtreeCost {
+                tag: tag.clone(),
+                line: node.line,
+                column: node.column,
+                nodes: cost.0,
+                expressions: cost.1,
+            });
+        }
+        total.0 += cost.0;
+        total.1 += cost.1;
+    }
+    total
+}
+
+/// Time each phase of compiling `source` (tree-sitter parse, mapping, and
+/// the route/meta/image analyses) and list the `top` largest element subtrees
+/// by expression count, then node count.
+#[pyfunction]
+#[pyo3(signature = (source, top=10))]
+pub fn profile(py: Python<'_>, source: &str, top: usize) -> PyResult<ProfileReport> {
+    let started = Instant::now();
+    let tree = crate::parse_tree(source)?;
+    let parse_seconds = started.elapsed().as_secs_f64();
+
+    let started = Instant::now();
+    let doc = crate::parse_with_tree(py, source, Some(&tree), &Default::default())?;
+    let map_seconds = started.elapsed().as_secs_f64();
+
+    let mut subtrees = Vec::new();
+    let (nodes, expressions) = measure(py, &doc.template, &mut subtrees);
+
+    let doc = Py::new(py, doc)?;
+    let started = Instant::now();
+    crate::routes::route_entries(py, &doc.borrow(py))?;
+    crate::meta::extract_meta(py, doc.borrow(py));
+    crate::assets::analyze_images(py, doc.borrow(py));
+    let analysis_seconds = started.elapsed().as_secs_f64();
+
+    subtrees.sort_by(|a, b| {
+        (b.expressions, b.nodes)
+            .cmp(&(a.expressions, a.nodes))
+            .then((a.line, a.column).cmp(&(b.line, b.column)))
+    });
+    subtrees.truncate(top);
+
+    Ok(ProfileReport {
+        parse_seconds,
+        map_seconds,
+        analysis_seconds,
+        nodes,
+        expressions,
+        subtrees,
+    })
+}
@@ -0,0 +1,240 @@
+use crate::diagnostics::Diagnostic;
+use crate::islands::IslandManifest;
+use crate::props::ComponentSignature;
+use crate::routes::{RouteEntry, route_entries};
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const TEMPLATE_EXTENSION: &str = "wire";
+
+/// Parsed templates of a whole project, keyed by path relative to the project
+/// root (with `/` separators). Cross-template analyses (route tables,
+/// component usage, link hints, ...) run over an index instead of re-parsing
+/// every file.
+#[pyclass]
+#[derive(Default)]
+pub struct ProjectIndex {
+    pub(crate) files: BTreeMap<String, Py<ParsedDocument>>,
+}
+
+// Component name declared by `!component Name` (or the older
+// `!component 'path' as Name` form).
+fn declared_component(doc: &ParsedDocument) -> Option<String> {
+    let content = doc
+        .directives
+        .iter()
+        .find(|d| d.name == "component")?
+        .content
+        .as_deref()?;
+    let name = match content.rsplit_once(" as ") {
+        Some((_, name)) => name,
+        None => content,
+    };
+    Some(name.trim().to_string()).filter(|n| !n.is_empty())
+}
+
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.split('.').next().unwrap_or(name)
+}
+
+pub(crate) fn is_component_tag(tag: &str) -> bool {
+    tag.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+pub(crate) fn collect_component_tags(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    out: &mut Vec<String>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(tag) = node.tag.as_deref()
+            && is_component_tag(tag)
+            && !out.iter().any(|t| t == tag)
+        {
+            out.push(tag.to_string());
+        }
+        collect_component_tags(py, &node.children, out);
+    }
+}
+
+impl ProjectIndex {
+    pub(crate) fn component_map(&self, py: Python<'_>) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::new();
+        for (path, doc) in &self.files {
+            let doc = doc.borrow(py);
+            let name = declared_component(&doc).or_else(|| {
+                let stem = file_stem(path);
+                is_component_tag(stem).then(|| stem.to_string())
+            });
+            if let Some(name) = name {
+                map.entry(name).or_insert_with(|| path.clone());
+            }
+        }
+        map
+    }
+
+    pub(crate) fn route_table(&self, py: Python<'_>) -> PyResult<Vec<RouteEntry>> {
+        let mut routes = Vec::new();
+        for (path, doc) in &self.files {
+            for mut route in route_entries(py, &doc.borrow(py))? {
+                route.file = Some(path.clone());
+                routes.push(route);
+            }
+        }
+        Ok(routes)
+    }
+
+    // Component files used by `path`, transitively, in discovery order.
+    pub(crate) fn component_closure(&self, py: Python<'_>, path: &str) -> Vec<String> {
+        let components = self.component_map(py);
+        let mut seen: Vec<String> = Vec::new();
+        let mut queue = vec![path.to_string()];
+        while let Some(current) = queue.pop() {
+            let Some(doc) = self.files.get(&current) else {
+                continue;
+            };
+            let mut tags = Vec::new();
+            collect_component_tags(py, &doc.borrow(py).template, &mut tags);
+            for tag in tags {
+                if let Some(file) = components.get(&tag)
+                    && file != path
+                    && !seen.contains(file)
+                {
+                    seen.push(file.clone());
+                    queue.push(file.clone());
+                }
+            }
+        }
+        seen
+    }
+
+    fn scan(&mut self, py: Python<'_>, root: &Path, dir: &Path) -> PyResult<()> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.') {
+                    self.scan(py, root, &path)?;
+                }
+            } else if path.extension().is_some_and(|e| e == TEMPLATE_EXTENSION) {
+                let rel = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let source = std::fs::read_to_string(&path)?;
+                self.add(py, rel, source)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ProjectIndex {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every `.wire` file under `root`.
+    #[staticmethod]
+    fn from_directory(py: Python<'_>, root: std::path::PathBuf) -> PyResult<Self> {
+        let mut index = Self::default();
+        index.scan(py, &root, &root)?;
+        Ok(index)
+    }
+
+    /// Parse `source` and store it under `path`, replacing any previous entry.
+    fn add(&mut self, py: Python<'_>, path: String, source: String) -> PyResult<()> {
+        let doc = crate::parse_with(py, &source, &Default::default())?;
+        self.files.insert(path, Py::new(py, doc)?);
+        Ok(())
+    }
+
+    fn remove(&mut self, path: &str) -> bool {
+        self.files.remove(path).is_some()
+    }
+
+    fn get(&self, py: Python<'_>, path: &str) -> PyResult<Py<ParsedDocument>> {
+        self.files
+            .get(path)
+            .map(|d| d.clone_ref(py))
+            .ok_or_else(|| PyKeyError::new_err(path.to_string()))
+    }
+
+    fn paths(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Component tag name -> defining file.
+    fn components(&self, py: Python<'_>) -> BTreeMap<String, String> {
+        self.component_map(py)
+    }
+
+    /// Routes of every template, with `file` set.
+    fn routes(&self, py: Python<'_>) -> PyResult<Vec<RouteEntry>> {
+        self.route_table(py)
+    }
+
+    /// Check every component usage against the component's `!props(...)`:
+    /// "prop-type-mismatch" for literal values that cannot match a builtin
+    /// annotation (a string attribute for an `int` prop, ...) and
+    /// "missing-prop" for required props left out. Returns file -> errors
+    /// for files with problems.
+    fn check_props(&self, py: Python<'_>) -> BTreeMap<String, Vec<Diagnostic>> {
+        self.prop_report(py)
+    }
+
+    /// Check event contracts: "undeclared-emit" for `$emit` of an event the
+    /// component's `!emits` leaves out, "unused-emit" warnings for declared
+    /// events never emitted, and "unknown-event" for `@event` subscriptions
+    /// on a component that does not declare the event. Components without
+    /// `!emits` are not checked. Returns file -> diagnostics.
+    fn check_events(&self, py: Python<'_>) -> BTreeMap<String, Vec<Diagnostic>> {
+        self.event_report(py)
+    }
+
+    /// Signature (props, slots, emitted events) of every component.
+    fn component_signatures(&self, py: Python<'_>) -> Vec<ComponentSignature> {
+        self.signatures(py)
+    }
+
+    /// Island manifest of the page at `path`, with component invocations
+    /// resolved against the components' own templates: a static component
+    /// is only an island if the page passes it wires or handlers.
+    fn islands(&self, py: Python<'_>, path: &str) -> PyResult<IslandManifest> {
+        self.island_manifest(py, path)
+    }
+
+    /// Components as a Custom Elements Manifest (`custom-elements.json`),
+    /// as plain dicts ready for `json.dumps`. Each component is a custom
+    /// element named from `prefix` and its name (`UserCard` ->
+    /// `pw-user-card`), with its props as attributes and fields, its events
+    /// and its slots (the default slot is named "").
+    #[pyo3(signature = (prefix="pw"))]
+    fn custom_elements_manifest<'py>(
+        &self,
+        py: Python<'py>,
+        prefix: &str,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        self.manifest(py, prefix)
+    }
+
+    fn __len__(&self) -> usize {
+        self.files.len()
+    }
+
+    fn __contains__(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+}
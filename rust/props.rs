@@ -0,0 +1,499 @@
+// Component interfaces: `!props(...)` declarations checked against the
+// attributes passed at every usage, and per-component signatures (props,
+// slots, emitted events) for stub generation.
+
+use crate::ParsedNode;
+use crate::diagnostics::Diagnostic;
+use crate::emits;
+use crate::kinds::{AttrKind, event_spec};
+use crate::project::{ProjectIndex, is_component_tag};
+use crate::routes::free_identifiers;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::BTreeMap;
+
+// Split `src` on `sep` outside brackets, string literals and `#` comments.
+pub(crate) fn split_top(src: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut comment = false;
+    let mut start = 0;
+    for (i, c) in src.char_indices() {
+        if comment {
+            comment = c != '\n';
+            continue;
+        }
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '#' => comment = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ if c == sep && depth == 0 => {
+                    parts.push(&src[start..i]);
+                    start = i + c.len_utf8();
+                }
+                _ => {}
+            },
+        }
+    }
+    parts.push(&src[start..]);
+    parts
+}
+
+// `line` split into code and the text of a trailing `#` comment.
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '#' => {
+                return (&line[..i], Some(line[i..].trim_start_matches('#').trim()));
+            }
+            None => {}
+        }
+    }
+    (line, None)
+}
+
+// Parse the content of a `!props(...)` directive.
+// `*args`, `**kwargs` and the `/` marker are skipped. `#` comments on the
+// lines above a parameter, or after it on the same line, become its `doc`.
+pub(crate) fn parse_props(content: &str) -> Option<Vec<PropSignature>> {
+    let inner = content.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let mut props: Vec<PropSignature> = Vec::new();
+    for (i, segment) in split_top(inner, ',').into_iter().enumerate() {
+        let mut code = String::new();
+        let mut docs = Vec::new();
+        for (j, line) in segment.split('\n').enumerate() {
+            let (line_code, comment) = split_comment(line);
+            match comment {
+                // `a: int,  # doc` puts the comment after the comma.
+                Some(text) if i > 0 && j == 0 && line_code.trim().is_empty() => {
+                    if let Some(prev) = props.last_mut()
+                        && prev.doc.is_none()
+                    {
+                        prev.doc = Some(text.to_string());
+                    }
+                }
+                Some(text) => docs.push(text),
+                None => {}
+            }
+            code.push_str(line_code);
+            code.push(' ');
+        }
+        let param = code.trim();
+        if param.is_empty() || param.starts_with('*') || param == "/" {
+            continue;
+        }
+        let (decl, default) = match split_top(param, '=').as_slice() {
+            [decl, default] => (*decl, Some(default.trim().to_string())),
+            _ => (param, None),
+        };
+        let (name, annotation) = match decl.split_once(':') {
+            Some((name, ty)) => (name, Some(ty.trim().to_string())),
+            None => (decl, None),
+        };
+        props.push(PropSignature {
+            name: name.trim().to_string(),
+            annotation,
+            default,
+            doc: (!docs.is_empty()).then(|| docs.join(" ")),
+        });
+    }
+    Some(props)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ValueType {
+    Str,
+    Int,
+    Float,
+    Bool,
+    None,
+}
+
+impl ValueType {
+    fn name(self) -> &'static str {
+        match self {
+            ValueType::Str => "str",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::Bool => "bool",
+            ValueType::None => "None",
+        }
+    }
+
+    // Type of a Python literal expression; None when it is not a literal.
+    fn of_literal(expr: &str) -> Option<Self> {
+        let expr = expr.trim();
+        let quoted = expr.len() >= 2
+            && ((expr.starts_with('"') && expr.ends_with('"'))
+                || (expr.starts_with('\'') && expr.ends_with('\'')));
+        match expr {
+            _ if quoted => Some(ValueType::Str),
+            "True" | "False" => Some(ValueType::Bool),
+            "None" => Some(ValueType::None),
+            _ if expr.parse::<i64>().is_ok() => Some(ValueType::Int),
+            _ if expr.parse::<f64>().is_ok() => Some(ValueType::Float),
+            _ => None,
+        }
+    }
+
+    // Value passed by an attribute; None when only known at runtime.
+    fn of_attribute(name: &str, value: Option<&str>) -> Option<Self> {
+        match AttrKind::classify(name, value) {
+            AttrKind::Static => Some(ValueType::Str),
+            AttrKind::Boolean => Some(ValueType::Bool),
+            AttrKind::Reactive => {
+                let v = value?.trim();
+                Self::of_literal(&v[1..v.len() - 1])
+            }
+            _ => None,
+        }
+    }
+}
+
+// Whether a `value` fits `annotation`. Only builtin scalar types are checked;
+// anything else is assumed compatible.
+fn accepts(annotation: &str, value: ValueType) -> bool {
+    let mut allowed = Vec::new();
+    let mut unknown = false;
+    let annotation = annotation.trim_matches(['"', '\'']);
+    let (annotation, optional) = match annotation
+        .strip_prefix("Optional[")
+        .and_then(|a| a.strip_suffix(']'))
+    {
+        Some(inner) => (inner, true),
+        None => (annotation, false),
+    };
+    for part in split_top(annotation, '|') {
+        match part.trim() {
+            "str" => allowed.push(ValueType::Str),
+            "int" => allowed.extend([ValueType::Int, ValueType::Bool]),
+            "float" => allowed.extend([ValueType::Float, ValueType::Int, ValueType::Bool]),
+            "bool" => allowed.push(ValueType::Bool),
+            "None" => allowed.push(ValueType::None),
+            _ => unknown = true,
+        }
+    }
+    if optional {
+        allowed.push(ValueType::None);
+    }
+    unknown || allowed.contains(&value)
+}
+
+fn check_usages(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    specs: &BTreeMap<String, (String, Vec<PropSignature>)>,
+    out: &mut Vec<Diagnostic>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(tag) = node.tag.as_deref().filter(|t| is_component_tag(t))
+            && let Some((file, spec)) = specs.get(tag)
+        {
+            let parsed = node.attributes.parsed();
+            // `bind:value={x}` passes `value` too.
+            let passed = |prop: &str| {
+                parsed.iter().any(|a| {
+                    !a.is_spread && (a.name == prop || a.bind_target.as_deref() == Some(prop))
+                })
+            };
+            for (name, value) in &node.attributes {
+                let Some(prop) = spec.iter().find(|p| &p.name == name) else {
+                    continue;
+                };
+                let (Some(annotation), Some(ty)) = (
+                    prop.annotation.as_deref(),
+                    ValueType::of_attribute(name, value.as_deref()),
+                ) else {
+                    continue;
+                };
+                if !accepts(annotation, ty) {
+                    out.push(Diagnostic::error(
+                        "prop-type-mismatch",
+                        format!(
+                            "<{}> prop '{}' expects {} but is given {} (declared in {})",
+                            tag,
+                            name,
+                            annotation,
+                            ty.name(),
+                            file
+                        ),
+                        node.line,
+                        node.column,
+                    ));
+                }
+            }
+            if !parsed.iter().any(|a| a.is_spread) {
+                for prop in spec.iter().filter(|p| p.default.is_none()) {
+                    if !passed(&prop.name) {
+                        out.push(Diagnostic::error(
+                            "missing-prop",
+                            format!(
+                                "<{}> is missing required prop '{}' (declared in {})",
+                                tag, prop.name, file
+                            ),
+                            node.line,
+                            node.column,
+                        ));
+                    }
+                }
+            }
+        }
+        check_usages(py, &node.children, specs, out);
+    }
+}
+
+impl ProjectIndex {
+    // Prop diagnostics per file, for files with at least one.
+    pub(crate) fn prop_report(&self, py: Python<'_>) -> BTreeMap<String, Vec<Diagnostic>> {
+        let _span = tracing::info_span!("analyze", pass = "props").entered();
+        let mut specs = BTreeMap::new();
+        for (tag, file) in self.component_map(py) {
+            let doc = self.files[&file].borrow(py);
+            let spec = doc
+                .directives
+                .iter()
+                .find(|d| d.name == "props")
+                .and_then(|d| parse_props(d.content.as_deref()?));
+            if let Some(spec) = spec {
+                specs.insert(tag, (file, spec));
+            }
+        }
+        let mut report = BTreeMap::new();
+        for (path, doc) in &self.files {
+            let doc = doc.borrow(py);
+            let mut out = Vec::new();
+            check_usages(py, &doc.template, &specs, &mut out);
+            if !out.is_empty() {
+                report.insert(path.clone(), out);
+            }
+        }
+        report
+    }
+}
+
+/// One parameter of a component's `!props(name: type = default, ...)`.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct PropSignature {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub annotation: Option<String>,
+    #[pyo3(get)]
+    pub default: Option<String>,
+    #[pyo3(get)]
+    pub doc: Option<String>,
+}
+
+#[pymethods]
+impl PropSignature {
+    #[getter]
+    fn required(&self) -> bool {
+        self.default.is_none()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PropSignature({})", self.name)
+    }
+}
+
+/// An event a component emits, either declared with `!emits` and raised with
+/// `$emit(...)` (`prop` is None) or through a callback prop (`on_select`, or
+/// any prop annotated `Callable[...]`). `triggers` are the DOM events whose
+/// handlers in the component raise it.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct EventSignature {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub prop: Option<String>,
+    #[pyo3(get)]
+    pub triggers: Vec<String>,
+}
+
+#[pymethods]
+impl EventSignature {
+    fn __repr__(&self) -> String {
+        match &self.prop {
+            Some(prop) => format!("EventSignature({} via {})", self.name, prop),
+            None => format!("EventSignature({})", self.name),
+        }
+    }
+}
+
+/// Public interface of one component file: its docstring, props, slots
+/// ("default" for an unnamed `<slot>`) and emitted events, for stub,
+/// validator or documentation generation.
+#[pyclass(frozen)]
+pub struct ComponentSignature {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub file: String,
+    #[pyo3(get)]
+    pub doc: Option<String>,
+    #[pyo3(get)]
+    pub props: Vec<PropSignature>,
+    #[pyo3(get)]
+    pub slots: Vec<String>,
+    #[pyo3(get)]
+    pub events: Vec<EventSignature>,
+}
+
+#[pymethods]
+impl ComponentSignature {
+    /// Plain dict/list/str form, ready for `json.dumps`.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("file", &self.file)?;
+        dict.set_item("doc", &self.doc)?;
+        let props = PyList::empty(py);
+        for p in &self.props {
+            let prop = PyDict::new(py);
+            prop.set_item("name", &p.name)?;
+            prop.set_item("annotation", &p.annotation)?;
+            prop.set_item("default", &p.default)?;
+            prop.set_item("required", p.required())?;
+            prop.set_item("doc", &p.doc)?;
+            props.append(prop)?;
+        }
+        dict.set_item("props", props)?;
+        dict.set_item("slots", &self.slots)?;
+        let events = PyList::empty(py);
+        for e in &self.events {
+            let event = PyDict::new(py);
+            event.set_item("name", &e.name)?;
+            event.set_item("prop", &e.prop)?;
+            event.set_item("triggers", &e.triggers)?;
+            events.append(event)?;
+        }
+        dict.set_item("events", events)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ComponentSignature({} in {})", self.name, self.file)
+    }
+}
+
+// Event name of a callback prop, if it is one.
+fn event_name(prop: &PropSignature) -> Option<String> {
+    if let Some(event) = prop.name.strip_prefix("on_") {
+        return Some(event.to_string());
+    }
+    let callable = prop
+        .annotation
+        .as_deref()
+        .is_some_and(|a| a.trim_start_matches("typing.").starts_with("Callable"));
+    callable.then(|| prop.name.clone())
+}
+
+// Slot names and `(dom event, handler source)` pairs of a component template.
+fn inventory(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    slots: &mut Vec<String>,
+    handlers: &mut Vec<(String, String)>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if node.tag.as_deref() == Some("slot") {
+            let name = match node.attributes.get("name") {
+                Some(Some(name)) => name.clone(),
+                _ => "default".to_string(),
+            };
+            if !slots.contains(&name) {
+                slots.push(name);
+            }
+        }
+        for (name, value) in &node.attributes {
+            if let (Some(event), Some(value)) = (event_spec(name), value) {
+                let event = event.split('.').next().unwrap_or(event);
+                handlers.push((event.to_string(), value.clone()));
+            }
+        }
+        inventory(py, &node.children, slots, handlers);
+    }
+}
+
+impl ProjectIndex {
+    pub(crate) fn signatures(&self, py: Python<'_>) -> Vec<ComponentSignature> {
+        let mut out = Vec::new();
+        for (tag, file) in self.component_map(py) {
+            let doc = self.files[&file].borrow(py);
+            let props = doc
+                .directives
+                .iter()
+                .find(|d| d.name == "props")
+                .and_then(|d| parse_props(d.content.as_deref()?))
+                .unwrap_or_default();
+            let mut slots = Vec::new();
+            let mut handlers = Vec::new();
+            inventory(py, &doc.template, &mut slots, &mut handlers);
+            let mut events: Vec<EventSignature> = props
+                .iter()
+                .filter_map(|prop| {
+                    let name = event_name(prop)?;
+                    let mut triggers: Vec<String> = Vec::new();
+                    for (event, handler) in &handlers {
+                        let calls = free_identifiers(handler).contains(&prop.name.as_str());
+                        if calls && !triggers.contains(event) {
+                            triggers.push(event.clone());
+                        }
+                    }
+                    Some(EventSignature {
+                        name,
+                        prop: Some(prop.name.clone()),
+                        triggers,
+                    })
+                })
+                .collect();
+            let emitted = emits::usages(py, &doc);
+            for (name, _) in emits::declared(&doc) {
+                if events.iter().any(|e| e.name == name) {
+                    continue;
+                }
+                let mut triggers: Vec<String> = Vec::new();
+                for usage in emitted.iter().filter(|u| u.name == name) {
+                    if let Some(t) = &usage.trigger
+                        && !triggers.contains(t)
+                    {
+                        triggers.push(t.clone());
+                    }
+                }
+                events.push(EventSignature {
+                    name,
+                    prop: None,
+                    triggers,
+                });
+            }
+            out.push(ComponentSignature {
+                name: tag,
+                doc: doc.docstring.clone(),
+                file,
+                props,
+                slots,
+                events,
+            });
+        }
+        out
+    }
+}
@@ -0,0 +1,271 @@
+// Where the renderer gets dynamic values from. Rendering only needs to know
+// whether a value is missing, a boolean or something to print, so providers
+// answer with a `Value` rather than a Python object; `PyProvider` is the
+// implementation backed by a Python callable.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict};
+
+// A resolved expression, as far as rendering is concerned.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    // Renders nothing; drops an attribute.
+    None,
+    // Toggles an attribute; prints as `True`/`False` in text.
+    Bool(bool),
+    Text(String),
+}
+
+impl Value {
+    pub(crate) fn into_text(self) -> Option<String> {
+        match self {
+            Value::None => None,
+            Value::Bool(true) => Some("True".to_string()),
+            Value::Bool(false) => Some("False".to_string()),
+            Value::Text(text) => Some(text),
+        }
+    }
+}
+
+pub(crate) trait ValueProvider {
+    // Value of `expr` in the current scope.
+    fn value(&mut self, expr: &str) -> PyResult<Value>;
+
+    // Truthiness of `expr`, by the provider's own rules (Python's for
+    // `PyProvider`, where `[]` and `0` are false).
+    fn truthy(&mut self, expr: &str) -> PyResult<bool>;
+
+    // Start a `{$for}` over `iterable` and return its item count, or None if
+    // this provider cannot bind loop targets. Each item is then selected with
+    // `bind_item` before its body renders; `exit_loop` restores the scope.
+    fn enter_loop(&mut self, targets: &[&str], iterable: &str) -> PyResult<Option<usize>>;
+
+    fn bind_item(&mut self, index: usize) -> PyResult<()>;
+
+    fn exit_loop(&mut self);
+}
+
+struct OpenLoop<'py> {
+    // The scope outside the loop.
+    scope: Bound<'py, PyDict>,
+    items: Vec<Bound<'py, PyAny>>,
+    targets: Vec<String>,
+}
+
+// Calls `resolver(expression, context)`. A dict context is a namespace that
+// loops bind their targets in (a copy per item); any other context, such as
+// a route, leaves loops unsupported.
+pub(crate) struct PyProvider<'a, 'py> {
+    resolver: &'a Bound<'py, PyAny>,
+    context: Bound<'py, PyAny>,
+    loops: Vec<OpenLoop<'py>>,
+}
+
+impl<'a, 'py> PyProvider<'a, 'py> {
+    pub(crate) fn new(resolver: &'a Bound<'py, PyAny>, context: Bound<'py, PyAny>) -> Self {
+        PyProvider {
+            resolver,
+            context,
+            loops: Vec::new(),
+        }
+    }
+
+    fn resolve(&self, expr: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.resolver.call1((expr.trim(), &self.context))
+    }
+}
+
+impl ValueProvider for PyProvider<'_, '_> {
+    fn value(&mut self, expr: &str) -> PyResult<Value> {
+        let value = self.resolve(expr)?;
+        if value.is_none() {
+            Ok(Value::None)
+        } else if value.is_instance_of::<PyBool>() {
+            Ok(Value::Bool(value.is_truthy()?))
+        } else {
+            Ok(Value::Text(value.str()?.to_string()))
+        }
+    }
+
+    fn truthy(&mut self, expr: &str) -> PyResult<bool> {
+        self.resolve(expr)?.is_truthy()
+    }
+
+    fn enter_loop(&mut self, targets: &[&str], iterable: &str) -> PyResult<Option<usize>> {
+        let Ok(scope) = self.context.downcast::<PyDict>().cloned() else {
+            return Ok(None);
+        };
+        let items: Vec<Bound<'_, PyAny>> = self
+            .resolve(iterable)?
+            .try_iter()?
+            .collect::<PyResult<_>>()?;
+        let count = items.len();
+        let targets = targets.iter().map(|t| t.to_string()).collect();
+        self.loops.push(OpenLoop {
+            scope,
+            items,
+            targets,
+        });
+        Ok(Some(count))
+    }
+
+    fn bind_item(&mut self, index: usize) -> PyResult<()> {
+        let Some(open) = self.loops.last() else {
+            return Ok(());
+        };
+        let inner = open.scope.copy()?;
+        let item = &open.items[index];
+        if let [name] = &open.targets[..] {
+            inner.set_item(name, item)?;
+        } else {
+            for (name, value) in open.targets.iter().zip(item.try_iter()?) {
+                inner.set_item(name, value?)?;
+            }
+        }
+        self.context = inner.into_any();
+        Ok(())
+    }
+
+    fn exit_loop(&mut self) {
+        if let Some(open) = self.loops.pop() {
+            self.context = open.scope.into_any();
+        }
+    }
+}
+
+// What a provider answered for one expression.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Answer {
+    Value(Value),
+    Truthy(bool),
+    Loop(Option<usize>),
+}
+
+// One provider call of a recorded render, in call order.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TraceEvent {
+    pub(crate) expr: String,
+    pub(crate) answer: Answer,
+}
+
+// Wraps a provider and, when `events` is Some, records every answer it
+// gives so the render can be replayed without the original values.
+pub(crate) struct Recorder<P> {
+    inner: P,
+    pub(crate) events: Option<Vec<TraceEvent>>,
+}
+
+impl<P: ValueProvider> Recorder<P> {
+    pub(crate) fn new(inner: P, record: bool) -> Self {
+        Recorder {
+            inner,
+            events: record.then(Vec::new),
+        }
+    }
+
+    fn note(&mut self, expr: &str, answer: Answer) {
+        if let Some(events) = &mut self.events {
+            events.push(TraceEvent {
+                expr: expr.trim().to_string(),
+                answer,
+            });
+        }
+    }
+}
+
+impl<P: ValueProvider> ValueProvider for Recorder<P> {
+    fn value(&mut self, expr: &str) -> PyResult<Value> {
+        let value = self.inner.value(expr)?;
+        self.note(expr, Answer::Value(value.clone()));
+        Ok(value)
+    }
+
+    fn truthy(&mut self, expr: &str) -> PyResult<bool> {
+        let truthy = self.inner.truthy(expr)?;
+        self.note(expr, Answer::Truthy(truthy));
+        Ok(truthy)
+    }
+
+    fn enter_loop(&mut self, targets: &[&str], iterable: &str) -> PyResult<Option<usize>> {
+        let count = self.inner.enter_loop(targets, iterable)?;
+        self.note(iterable, Answer::Loop(count));
+        Ok(count)
+    }
+
+    fn bind_item(&mut self, index: usize) -> PyResult<()> {
+        self.inner.bind_item(index)
+    }
+
+    fn exit_loop(&mut self) {
+        self.inner.exit_loop();
+    }
+}
+
+// Answers from recorded events instead of evaluating anything. Fails as
+// soon as the render asks for something else than was recorded, which
+// means the template changed since.
+pub(crate) struct Replay<'e> {
+    events: &'e [TraceEvent],
+    pos: usize,
+}
+
+impl<'e> Replay<'e> {
+    pub(crate) fn new(events: &'e [TraceEvent]) -> Self {
+        Replay { events, pos: 0 }
+    }
+
+    fn next(&mut self, expr: &str) -> PyResult<&'e Answer> {
+        let expr = expr.trim();
+        match self.events.get(self.pos) {
+            Some(event) if event.expr == expr => {
+                self.pos += 1;
+                Ok(&event.answer)
+            }
+            Some(event) => Err(PyValueError::new_err(format!(
+                "Trace diverges at '{}': recorded '{}'",
+                expr, event.expr
+            ))),
+            None => Err(PyValueError::new_err(format!(
+                "Trace diverges at '{}': no more recorded values",
+                expr
+            ))),
+        }
+    }
+
+    fn mismatch(expr: &str) -> PyErr {
+        PyValueError::new_err(format!(
+            "Trace diverges at '{}': recorded a different kind of use",
+            expr.trim()
+        ))
+    }
+}
+
+impl ValueProvider for Replay<'_> {
+    fn value(&mut self, expr: &str) -> PyResult<Value> {
+        match self.next(expr)? {
+            Answer::Value(value) => Ok(value.clone()),
+            _ => Err(Self::mismatch(expr)),
+        }
+    }
+
+    fn truthy(&mut self, expr: &str) -> PyResult<bool> {
+        match self.next(expr)? {
+            Answer::Truthy(truthy) => Ok(*truthy),
+            _ => Err(Self::mismatch(expr)),
+        }
+    }
+
+    fn enter_loop(&mut self, _targets: &[&str], iterable: &str) -> PyResult<Option<usize>> {
+        match self.next(iterable)? {
+            Answer::Loop(count) => Ok(*count),
+            _ => Err(Self::mismatch(iterable)),
+        }
+    }
+
+    fn bind_item(&mut self, _index: usize) -> PyResult<()> {
+        Ok(())
+    }
+
+    fn exit_loop(&mut self) {}
+}
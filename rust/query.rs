@@ -0,0 +1,665 @@
+// CSS selector queries over parsed templates and plain HTML strings.
+//
+// Both inputs are flattened into the same element arena so one matcher serves
+// tests, lints and tooling. Supported syntax: type, `*`, `#id`, `.class`,
+// `[attr]`, `[attr=v]` (also `~=`, `^=`, `$=`, `*=`), descendant and `>`
+// combinators, and `,` lists. Attribute names may use template prefixes
+// (`[@click]`, `[$if]`).
+
+use crate::attrs::Attributes;
+use crate::html::{HtmlNode, parse_html};
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::exceptions::{PyAssertionError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+/// An element returned by `select` / `assert_contains`.
+///
+/// `text` is the element's descendant text with whitespace collapsed
+/// (interpolations appear as `{expr}`). `line`/`column` are set for template
+/// input only.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct QueryMatch {
+    #[pyo3(get)]
+    pub tag: String,
+    #[pyo3(get)]
+    pub attributes: Attributes,
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub line: Option<usize>,
+    #[pyo3(get)]
+    pub column: Option<usize>,
+}
+
+#[pymethods]
+impl QueryMatch {
+    fn __repr__(&self) -> String {
+        format!("QueryMatch({})", describe(&self.element()))
+    }
+}
+
+impl QueryMatch {
+    fn element(&self) -> Element {
+        let mut attrs: Vec<(String, Option<String>)> = self
+            .attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        attrs.sort();
+        Element {
+            tag: self.tag.clone(),
+            attrs,
+            parent: None,
+            text: self.text.clone(),
+            position: self.line.zip(self.column),
+        }
+    }
+}
+
+pub(crate) struct Element {
+    pub tag: String,
+    pub attrs: Vec<(String, Option<String>)>,
+    pub parent: Option<usize>,
+    pub text: String,
+    pub position: Option<(usize, usize)>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+
+    fn to_match(&self) -> QueryMatch {
+        QueryMatch {
+            tag: self.tag.clone(),
+            attributes: self.attrs.iter().cloned().collect(),
+            text: self.text.clone(),
+            line: self.position.map(|p| p.0),
+            column: self.position.map(|p| p.1),
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// Elements in document order; parents always precede their children.
+pub(crate) fn html_elements(nodes: &[HtmlNode]) -> Vec<Element> {
+    fn walk(nodes: &[HtmlNode], parent: Option<usize>, out: &mut Vec<Element>) -> String {
+        let mut text = String::new();
+        for node in nodes {
+            match node {
+                HtmlNode::Text(t) => text.push_str(&decode_entities(t)),
+                HtmlNode::Element(el) => {
+                    let index = out.len();
+                    out.push(Element {
+                        tag: el.tag.clone(),
+                        attrs: el.attrs.clone(),
+                        parent,
+                        text: String::new(),
+                        position: None,
+                    });
+                    let inner = walk(&el.children, Some(index), out);
+                    text.push(' ');
+                    text.push_str(&inner);
+                    text.push(' ');
+                    out[index].text = collapse_whitespace(&inner);
+                }
+                HtmlNode::Comment(_) | HtmlNode::Doctype(_) => {}
+            }
+        }
+        text
+    }
+    let mut out = Vec::new();
+    walk(nodes, None, &mut out);
+    out
+}
+
+pub(crate) fn template_elements(py: Python<'_>, nodes: &[Py<ParsedNode>]) -> Vec<Element> {
+    fn walk(
+        py: Python<'_>,
+        nodes: &[Py<ParsedNode>],
+        parent: Option<usize>,
+        out: &mut Vec<Element>,
+    ) -> String {
+        let mut text = String::new();
+        for node in nodes {
+            let node = node.borrow(py);
+            if node.is_block {
+                if node.block_keyword.as_deref() == Some("interpolation") {
+                    text.push('{');
+                    text.push_str(node.expression.as_deref().unwrap_or("").trim());
+                    text.push('}');
+                }
+                text.push_str(&walk(py, &node.children, parent, out));
+                continue;
+            }
+            let Some(tag) = &node.tag else {
+                if !node.is_comment
+                    && let Some(t) = &node.text_content
+                {
+                    text.push_str(&decode_entities(t));
+                }
+                continue;
+            };
+            let index = out.len();
+            let attrs: Vec<(String, Option<String>)> = node
+                .attributes
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            out.push(Element {
+                tag: tag.clone(),
+                attrs,
+                parent,
+                text: String::new(),
+                position: Some((node.line, node.column)),
+            });
+            let inner = walk(py, &node.children, Some(index), out);
+            text.push(' ');
+            text.push_str(&inner);
+            text.push(' ');
+            out[index].text = collapse_whitespace(&inner);
+        }
+        text
+    }
+    let mut out = Vec::new();
+    walk(py, nodes, None, &mut out);
+    out
+}
+
+// `document_or_html` arguments accept a ParsedDocument or an HTML string.
+fn elements_of(py: Python<'_>, target: &Bound<'_, PyAny>) -> PyResult<Vec<Element>> {
+    if let Ok(doc) = target.downcast::<ParsedDocument>() {
+        return Ok(template_elements(py, &doc.borrow().template));
+    }
+    if let Ok(html) = target.extract::<String>() {
+        return Ok(html_elements(&parse_html(&html)));
+    }
+    Err(PyTypeError::new_err(format!(
+        "expected a ParsedDocument or an HTML string, got {}",
+        target.get_type().name()?
+    )))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AttrOp {
+    Exists,
+    Equals,
+    Includes,
+    Prefix,
+    Suffix,
+    Substring,
+}
+
+struct AttrTest {
+    name: String,
+    op: AttrOp,
+    value: String,
+}
+
+impl AttrTest {
+    fn matches(&self, el: &Element) -> bool {
+        let Some(actual) = el.attr(&self.name) else {
+            return false;
+        };
+        let v = self.value.as_str();
+        match self.op {
+            AttrOp::Exists => true,
+            AttrOp::Equals => actual == v,
+            AttrOp::Includes => actual.split_whitespace().any(|t| t == v),
+            AttrOp::Prefix => !v.is_empty() && actual.starts_with(v),
+            AttrOp::Suffix => !v.is_empty() && actual.ends_with(v),
+            AttrOp::Substring => !v.is_empty() && actual.contains(v),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<AttrTest>,
+}
+
+impl Compound {
+    fn matches(&self, el: &Element) -> bool {
+        self.score(el) == self.weight()
+    }
+
+    // Number of satisfied simple selectors, for nearest-match ranking.
+    fn score(&self, el: &Element) -> usize {
+        let classes = el.attr("class").unwrap_or("");
+        let tag = self
+            .tag
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case(&el.tag));
+        let id = self.id.is_some() && self.id.as_deref() == el.attr("id");
+        usize::from(tag)
+            + usize::from(id)
+            + self
+                .classes
+                .iter()
+                .filter(|c| classes.split_whitespace().any(|t| t == c.as_str()))
+                .count()
+            + self.attrs.iter().filter(|a| a.matches(el)).count()
+    }
+
+    fn weight(&self) -> usize {
+        usize::from(self.tag.is_some())
+            + usize::from(self.id.is_some())
+            + self.classes.len()
+            + self.attrs.len()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+// A complex selector; each compound but the first carries the combinator
+// linking it to the previous one.
+struct Complex(Vec<(Combinator, Compound)>);
+
+pub(crate) struct Selector(Vec<Complex>);
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '$' | '@' | '.')
+}
+
+struct SelectorParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl SelectorParser<'_> {
+    fn error(&self, msg: &str) -> PyErr {
+        PyValueError::new_err(format!(
+            "invalid selector {:?} at offset {}: {}",
+            self.src, self.pos, msg
+        ))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos;
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+        self.pos > start
+    }
+
+    // Identifiers stop at `.` except inside attribute brackets.
+    fn ident(&mut self, allow_dot: bool) -> PyResult<String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !is_ident_char(c) || (c == '.' && !allow_dot) || (c == ':' && !allow_dot) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        if self.pos == start {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    fn attr_test(&mut self) -> PyResult<AttrTest> {
+        self.skip_whitespace();
+        let name = self.ident(true)?;
+        self.skip_whitespace();
+        let op = match self.peek() {
+            Some(']') => {
+                self.pos += 1;
+                return Ok(AttrTest {
+                    name,
+                    op: AttrOp::Exists,
+                    value: String::new(),
+                });
+            }
+            Some('=') => AttrOp::Equals,
+            Some(c) => {
+                let op = match c {
+                    '~' => AttrOp::Includes,
+                    '^' => AttrOp::Prefix,
+                    '$' => AttrOp::Suffix,
+                    '*' => AttrOp::Substring,
+                    _ => return Err(self.error("expected an attribute operator")),
+                };
+                self.pos += 1;
+                if self.peek() != Some('=') {
+                    return Err(self.error("expected '='"));
+                }
+                op
+            }
+            None => return Err(self.error("unterminated attribute selector")),
+        };
+        self.pos += 1;
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(q @ ('"' | '\'')) => {
+                let body = &self.src[self.pos + 1..];
+                let end = body
+                    .find(q)
+                    .ok_or_else(|| self.error("unterminated string"))?;
+                self.pos += end + 2;
+                body[..end].to_string()
+            }
+            _ => {
+                let body = &self.src[self.pos..];
+                let end = body
+                    .find(|c: char| c == ']' || c.is_whitespace())
+                    .unwrap_or(body.len());
+                if end == 0 {
+                    return Err(self.error("expected an attribute value"));
+                }
+                self.pos += end;
+                body[..end].to_string()
+            }
+        };
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            return Err(self.error("expected ']'"));
+        }
+        self.pos += 1;
+        Ok(AttrTest { name, op, value })
+    }
+
+    fn compound(&mut self) -> PyResult<Compound> {
+        let mut compound = Compound::default();
+        let start = self.pos;
+        match self.peek() {
+            Some('*') => self.pos += 1,
+            Some(c) if is_ident_char(c) && c != '.' && c != ':' => {
+                compound.tag = Some(self.ident(false)?);
+            }
+            _ => {}
+        }
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    compound.id = Some(self.ident(false)?);
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    compound.classes.push(self.ident(false)?);
+                }
+                Some('[') => {
+                    self.pos += 1;
+                    let test = self.attr_test()?;
+                    compound.attrs.push(test);
+                }
+                Some(':') => return Err(self.error("pseudo-classes are not supported")),
+                _ => break,
+            }
+        }
+        if self.pos == start {
+            return Err(self.error("expected a selector"));
+        }
+        Ok(compound)
+    }
+
+    fn parse(mut self) -> PyResult<Selector> {
+        let mut list = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let mut parts = vec![(Combinator::Descendant, self.compound()?)];
+            loop {
+                let spaced = self.skip_whitespace();
+                let combinator = match self.peek() {
+                    None | Some(',') => break,
+                    Some('>') => {
+                        self.pos += 1;
+                        self.skip_whitespace();
+                        Combinator::Child
+                    }
+                    _ if spaced => Combinator::Descendant,
+                    _ => return Err(self.error("unexpected character")),
+                };
+                parts.push((combinator, self.compound()?));
+            }
+            list.push(Complex(parts));
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            } else {
+                return Ok(Selector(list));
+            }
+        }
+    }
+}
+
+impl Selector {
+    pub(crate) fn parse(src: &str) -> PyResult<Self> {
+        SelectorParser { src, pos: 0 }.parse()
+    }
+
+    // (ids, classes and attributes, types) of the most specific selector in
+    // the list, for cascade order.
+    pub(crate) fn specificity(&self) -> (usize, usize, usize) {
+        self.0
+            .iter()
+            .map(|c| {
+                c.0.iter().fold((0, 0, 0), |(a, b, t), (_, part)| {
+                    (
+                        a + usize::from(part.id.is_some()),
+                        b + part.classes.len() + part.attrs.len(),
+                        t + usize::from(part.tag.is_some()),
+                    )
+                })
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn matches(&self, elements: &[Element], index: usize) -> bool {
+        self.0
+            .iter()
+            .any(|c| matches_at(elements, index, &c.0, c.0.len() - 1))
+    }
+
+    // Best partial score of the rightmost compounds against `el`.
+    fn partial_score(&self, el: &Element) -> usize {
+        self.0
+            .iter()
+            .map(|c| c.0.last().map_or(0, |(_, compound)| compound.score(el)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn matches_at(
+    elements: &[Element],
+    index: usize,
+    parts: &[(Combinator, Compound)],
+    k: usize,
+) -> bool {
+    let (combinator, compound) = &parts[k];
+    if !compound.matches(&elements[index]) {
+        return false;
+    }
+    if k == 0 {
+        return true;
+    }
+    let mut parent = elements[index].parent;
+    match combinator {
+        Combinator::Child => parent.is_some_and(|p| matches_at(elements, p, parts, k - 1)),
+        Combinator::Descendant => {
+            while let Some(p) = parent {
+                if matches_at(elements, p, parts, k - 1) {
+                    return true;
+                }
+                parent = elements[p].parent;
+            }
+            false
+        }
+    }
+}
+
+/// Elements of a ParsedDocument or an HTML string matching a CSS selector,
+/// in document order.
+#[pyfunction]
+pub fn select(
+    py: Python<'_>,
+    document_or_html: Bound<'_, PyAny>,
+    selector: &str,
+) -> PyResult<Vec<QueryMatch>> {
+    let selector = Selector::parse(selector)?;
+    let elements = elements_of(py, &document_or_html)?;
+    Ok((0..elements.len())
+        .filter(|&i| selector.matches(&elements, i))
+        .map(|i| elements[i].to_match())
+        .collect())
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}...", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+fn describe(el: &Element) -> String {
+    let mut out = format!("<{}", el.tag);
+    crate::html::write_attrs(&mut out, &el.attrs);
+    out.push('>');
+    out.push_str(&truncate(&el.text, 40));
+    out.push_str("</");
+    out.push_str(&el.tag);
+    out.push('>');
+    if let Some((line, _)) = el.position {
+        out.push_str(&format!(" (line {})", line));
+    }
+    out
+}
+
+struct Expectation {
+    text: Option<String>,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+impl Expectation {
+    // Why `el` does not meet the expectation; empty if it does.
+    fn mismatches(&self, el: &Element) -> Vec<String> {
+        let mut out = Vec::new();
+        if let Some(text) = &self.text
+            && !el.text.contains(text.as_str())
+        {
+            out.push(format!("text {:?} does not contain {:?}", el.text, text));
+        }
+        for (name, expected) in &self.attrs {
+            let problem = match (el.attr(name), expected) {
+                (None, _) => Some(format!("missing attribute {}", name)),
+                (Some(_), None) => None,
+                (Some(a), Some(e)) if name == "class" => (!e
+                    .split_whitespace()
+                    .all(|t| a.split_whitespace().any(|c| c == t)))
+                .then(|| format!("class={:?}, expected tokens {:?}", a, e)),
+                (Some(a), Some(e)) => {
+                    (a != e).then(|| format!("{}={:?}, expected {:?}", name, a, e))
+                }
+            };
+            out.extend(problem);
+        }
+        out
+    }
+}
+
+/// Assert that a ParsedDocument or HTML string contains an element matching
+/// `selector`, optionally with `text` (whitespace-insensitive substring of its
+/// text) and `attrs` (name -> value, None for presence only; `class` checks
+/// tokens). Returns the first matching element.
+///
+/// On failure raises AssertionError listing the nearest candidates and what
+/// differs for each.
+#[pyfunction]
+#[pyo3(signature = (document_or_html, selector, text=None, attrs=None))]
+pub fn assert_contains(
+    py: Python<'_>,
+    document_or_html: Bound<'_, PyAny>,
+    selector: &str,
+    text: Option<&str>,
+    attrs: Option<HashMap<String, Option<String>>>,
+) -> PyResult<QueryMatch> {
+    let parsed = Selector::parse(selector)?;
+    let elements = elements_of(py, &document_or_html)?;
+    let mut attrs: Vec<(String, Option<String>)> = attrs.unwrap_or_default().into_iter().collect();
+    attrs.sort();
+    let expectation = Expectation {
+        text: text.map(collapse_whitespace),
+        attrs,
+    };
+
+    let mut candidates = Vec::new();
+    for (i, el) in elements.iter().enumerate() {
+        let selected = parsed.matches(&elements, i);
+        let mismatches = expectation.mismatches(el);
+        if selected && mismatches.is_empty() {
+            return Ok(el.to_match());
+        }
+        // Full selector matches rank above partial ones, then fewest misses.
+        let score = if selected {
+            1000
+        } else {
+            parsed.partial_score(el) * 10
+        };
+        let score = score.saturating_sub(mismatches.len());
+        if score > 0 {
+            candidates.push((score, i, selected, mismatches));
+        }
+    }
+
+    let mut message = format!("no element matches {:?}", selector);
+    if let Some(text) = &expectation.text {
+        message.push_str(&format!(" with text {:?}", text));
+    }
+    if !expectation.attrs.is_empty() {
+        let attrs: Vec<String> = expectation
+            .attrs
+            .iter()
+            .map(|(k, v)| match v {
+                Some(v) => format!("{}={:?}", k, v),
+                None => k.clone(),
+            })
+            .collect();
+        message.push_str(&format!(" with attrs [{}]", attrs.join(", ")));
+    }
+    if candidates.is_empty() {
+        message.push_str(&format!(" ({} elements searched)", elements.len()));
+        return Err(PyAssertionError::new_err(message));
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    message.push_str("\nNearest matches:");
+    for (_, i, selected, mut mismatches) in candidates.into_iter().take(3) {
+        if !selected {
+            mismatches.insert(0, "does not match selector".to_string());
+        }
+        message.push_str(&format!(
+            "\n  {}: {}",
+            describe(&elements[i]),
+            mismatches.join("; ")
+        ));
+    }
+    Err(PyAssertionError::new_err(message))
+}
@@ -0,0 +1,249 @@
+// Reactive statements in the frontmatter, in two spellings:
+//
+//     $: total = price * qty
+//
+//     @reactive
+//     def total():
+//         return price * qty
+//
+// Each is reported with the names it reads and writes so the runtime can wire
+// up recomputation from the parse alone instead of tracing at import time.
+
+use crate::routes::free_identifiers;
+use pyo3::prelude::*;
+
+pub(crate) const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// A `$:` statement or `@reactive` function in the frontmatter.
+///
+/// `kind` is "label" or "decorator". `writes` are the assigned names (the
+/// function name for decorators); `reads` are the free names it uses, in
+/// first-use order.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ReactiveStatement {
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub source: String,
+    #[pyo3(get)]
+    pub reads: Vec<String>,
+    #[pyo3(get)]
+    pub writes: Vec<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl ReactiveStatement {
+    fn __repr__(&self) -> String {
+        format!(
+            "ReactiveStatement({} at {}:{}: {:?} <- {:?})",
+            self.kind, self.line, self.column, self.writes, self.reads
+        )
+    }
+}
+
+fn push_unique(out: &mut Vec<String>, name: &str) {
+    if !out.iter().any(|n| n == name) {
+        out.push(name.to_string());
+    }
+}
+
+fn names(src: &str, skip: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    for ident in free_identifiers(src) {
+        if !KEYWORDS.contains(&ident) && !skip.iter().any(|s| s == ident) {
+            push_unique(&mut out, ident);
+        }
+    }
+    out
+}
+
+// Byte offsets of top-level assignment `=` signs in `stmt`, and whether the
+// last one is augmented (`+=`, ...).
+pub(crate) fn assignments(stmt: &str) -> (Vec<usize>, bool) {
+    let bytes = stmt.as_bytes();
+    let mut found = Vec::new();
+    let mut augmented = false;
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match quote {
+            Some(_) if c == b'\\' => i += 1,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                b'\'' | b'"' => quote = Some(c),
+                b'#' => break,
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                b'=' if depth == 0 => {
+                    let prev = i.checked_sub(1).map(|p| bytes[p]);
+                    let next = bytes.get(i + 1).copied();
+                    if next == Some(b'=') {
+                        i += 1;
+                    } else if !matches!(prev, Some(b'=' | b'!' | b'<' | b'>' | b':')) {
+                        augmented = matches!(
+                            prev,
+                            Some(b'+' | b'-' | b'*' | b'/' | b'%' | b'&' | b'|' | b'^' | b'@')
+                        );
+                        found.push(i);
+                    }
+                }
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+    (found, augmented)
+}
+
+// Names assigned by `stmt`, given its top-level `=` offsets.
+pub(crate) fn assigned(stmt: &str, eqs: &[usize], out: &mut Vec<String>) {
+    let mut start = 0;
+    for &eq in eqs {
+        let lhs = stmt[start..eq].trim_end_matches(|c: char| "+-*/%&|^@<>".contains(c));
+        targets(lhs, out);
+        start = eq + 1;
+    }
+}
+
+// Base name of each comma-separated assignment target (`a.b`, `a[i]` -> `a`).
+fn targets(lhs: &str, out: &mut Vec<String>) {
+    for target in lhs.split(',') {
+        let target = target
+            .trim()
+            .trim_start_matches(['(', '[', '*'])
+            .trim_start();
+        let end = target
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(target.len());
+        let name = &target[..end];
+        if !name.is_empty() && !KEYWORDS.contains(&name) {
+            push_unique(out, name);
+        }
+    }
+}
+
+fn label_statement(stmt: &str) -> (Vec<String>, Vec<String>) {
+    let (eqs, augmented) = assignments(stmt);
+    let Some(&last) = eqs.last() else {
+        return (names(stmt, &[]), Vec::new());
+    };
+    let mut writes = Vec::new();
+    assigned(stmt, &eqs, &mut writes);
+    let mut reads = if augmented {
+        writes.clone()
+    } else {
+        Vec::new()
+    };
+    for name in names(&stmt[last + 1..], &[]) {
+        push_unique(&mut reads, &name);
+    }
+    (reads, writes)
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_reactive_decorator(line: &str) -> bool {
+    let Some(rest) = line.trim().strip_prefix('@') else {
+        return false;
+    };
+    let name = rest.split('(').next().unwrap_or("").trim();
+    name.rsplit('.').next() == Some("reactive")
+}
+
+// Reads of a decorated function: free names of the body minus its parameters
+// and local assignments.
+fn function_statement(def: &str, body: &str) -> Option<(String, Vec<String>)> {
+    let rest = def
+        .trim_start()
+        .strip_prefix("async ")
+        .unwrap_or(def.trim_start());
+    let rest = rest.strip_prefix("def ")?.trim_start();
+    let (name, rest) = rest.split_once('(')?;
+    let params = rest.rsplit_once(')').map_or("", |(p, _)| p);
+    let mut local: Vec<String> = params
+        .split(',')
+        .filter_map(|p| {
+            let p = p.trim().trim_start_matches('*');
+            let end = p.find([':', '=', ' ']).unwrap_or(p.len());
+            (!p[..end].is_empty()).then(|| p[..end].to_string())
+        })
+        .collect();
+    for line in body.lines() {
+        assigned(line, &assignments(line).0, &mut local);
+    }
+    Some((name.trim().to_string(), names(body, &local)))
+}
+
+// Statements in the Python `code` starting at `first_line` of the file.
+pub(crate) fn scan(code: &str, first_line: usize, out: &mut Vec<ReactiveStatement>) {
+    let lines: Vec<&str> = code.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let column = indent(line);
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("$:") {
+            let mut stmt = rest.trim().to_string();
+            let start = i;
+            // Keep bracketed continuations together.
+            while stmt.matches(['(', '[', '{']).count() > stmt.matches([')', ']', '}']).count()
+                && i + 1 < lines.len()
+            {
+                i += 1;
+                stmt.push('\n');
+                stmt.push_str(lines[i].trim());
+            }
+            let (reads, writes) = label_statement(&stmt);
+            out.push(ReactiveStatement {
+                kind: "label".to_string(),
+                source: stmt,
+                reads,
+                writes,
+                line: first_line + start,
+                column,
+            });
+        } else if is_reactive_decorator(line) {
+            let start = i;
+            let mut def = i + 1;
+            while def < lines.len() && lines[def].trim_start().starts_with('@') {
+                def += 1;
+            }
+            let mut end = def + 1;
+            while end < lines.len()
+                && (lines[end].trim().is_empty() || indent(lines[end]) > indent(lines[def]))
+            {
+                end += 1;
+            }
+            if def < lines.len() {
+                let body = lines[def + 1..end].join("\n");
+                if let Some((name, reads)) = function_statement(lines[def], &body) {
+                    out.push(ReactiveStatement {
+                        kind: "decorator".to_string(),
+                        source: lines[start..end].join("\n").trim_end().to_string(),
+                        reads,
+                        writes: vec![name],
+                        line: first_line + start,
+                        column,
+                    });
+                }
+            }
+            i = end.saturating_sub(1);
+        }
+        i += 1;
+    }
+}
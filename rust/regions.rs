@@ -0,0 +1,221 @@
+// Region addresses. A region is a block (`{$if}`, `{$for}`, `{$try}`,
+// `{$await}`) or an element with dynamic attributes or interpolated
+// children. Each gets a path of segments derived from its own content rather
+// than its position, so inserting unrelated siblings does not move it:
+//
+//     for.1c9e02[item.id]/li.77a0b4
+//
+// Segments are `<tag or keyword>.<hash>` (`<tag>#<id>` with a static id),
+// with `~N` for the Nth identical sibling. A `{$for}` segment carries the
+// loop key expression in brackets: the `key=` clause of `{$for x in xs,
+// key=expr}`, else the loop target. The runtime substitutes key values and
+// prefixes the file to form the full address.
+//
+// Interpolations directly inside a region are addressed below it as
+// `text.<hash>`, so an update replaces only the dynamic text node instead
+// of the whole text content of the element.
+
+use crate::ParsedNode;
+use crate::kinds::AttrKind;
+use crate::pairing::OPENERS;
+use crate::wires::split_loop_key;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// One run of text inside a region, in document order. `kind` is "static"
+/// (`text` as written) or "dynamic" (an interpolation of `expression`,
+/// addressed by `address`).
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct TextPart {
+    #[pyo3(get)]
+    pub kind: &'static str,
+    #[pyo3(get)]
+    pub text: Option<String>,
+    #[pyo3(get)]
+    pub expression: Option<String>,
+    #[pyo3(get)]
+    pub address: Option<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl TextPart {
+    fn __repr__(&self) -> String {
+        match (&self.expression, &self.address) {
+            (Some(expr), Some(address)) => format!("TextPart({{{}}} at {})", expr, address),
+            _ => format!("TextPart({:?})", self.text.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+fn short_hash(parts: &[&str]) -> String {
+    let joined = parts.join("\u{1f}");
+    format!("{:06x}", xxh3_64(joined.as_bytes()) & 0xff_ffff)
+}
+
+fn is_dynamic(py: Python<'_>, node: &ParsedNode) -> bool {
+    let dynamic_attr = node.attributes.parsed().iter().any(|attr| {
+        matches!(
+            attr.kind,
+            AttrKind::Reactive
+                | AttrKind::Shorthand
+                | AttrKind::Spread
+                | AttrKind::ClassToggle
+                | AttrKind::StyleProperty
+        )
+    });
+    dynamic_attr
+        || node.children.iter().any(|c| {
+            let c = c.borrow(py);
+            c.is_block && c.block_keyword.as_deref() == Some("interpolation")
+        })
+}
+
+// Segment name of `node`, or None if it is not a region.
+fn segment(py: Python<'_>, node: &ParsedNode) -> Option<String> {
+    if node.is_block {
+        let keyword = node.block_keyword.as_deref()?;
+        if !OPENERS.contains(&keyword) {
+            return None;
+        }
+        let expr = node.expression.as_deref().unwrap_or("");
+        let mut name = format!("{}.{}", keyword, short_hash(&[keyword, expr]));
+        if keyword == "for" {
+            let (head, key) = split_loop_key(expr);
+            let target = head.split(" in ").next().unwrap_or("").trim();
+            name.push_str(&format!("[{}]", key.unwrap_or(target)));
+        }
+        return Some(name);
+    }
+    let tag = node.tag.as_deref()?;
+    if !is_dynamic(py, node) {
+        return None;
+    }
+    if let Some(Some(id)) = node.attributes.get("id")
+        && !id.contains('{')
+    {
+        return Some(format!("{}#{}", tag, id));
+    }
+    let mut attrs: Vec<String> = node
+        .attributes
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v.as_deref().unwrap_or("")))
+        .collect();
+    attrs.sort();
+    let mut parts = vec![tag];
+    parts.extend(attrs.iter().map(String::as_str));
+    let exprs: Vec<String> = node
+        .children
+        .iter()
+        .filter_map(|c| c.borrow(py).expression.clone())
+        .collect();
+    parts.extend(exprs.iter().map(String::as_str));
+    Some(format!("{}.{}", tag, short_hash(&parts)))
+}
+
+fn join(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+// Assign `region` to every region in `nodes` under `parent`. In the flat
+// shape a block's body follows it as siblings up to `close_index`, so open
+// blocks are tracked on a stack; resolved bodies are plain children.
+pub(crate) fn assign_regions(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    parent: &str,
+    seen: &mut HashMap<String, usize>,
+) {
+    let mut open: Vec<(usize, String)> = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        open.retain(|(close, _)| *close > i);
+        let scope = open
+            .last()
+            .map_or(parent, |(_, path)| path.as_str())
+            .to_string();
+        let (name, close) = {
+            let n = node.borrow(py);
+            (segment(py, &n), n.close_index)
+        };
+        let path = match name {
+            Some(name) => {
+                let count = seen.entry(join(&scope, &name)).or_insert(0);
+                *count += 1;
+                let name = match *count {
+                    1 => name,
+                    n => format!("{}~{}", name, n),
+                };
+                let path = join(&scope, &name);
+                node.borrow_mut(py).region = Some(path.clone());
+                path
+            }
+            None => scope,
+        };
+        if let Some(close) = close {
+            open.push((close, path.clone()));
+        }
+        let children: Vec<Py<ParsedNode>> = node
+            .borrow(py)
+            .children
+            .iter()
+            .map(|c| c.clone_ref(py))
+            .collect();
+        assign_regions(py, &children, &path, seen);
+    }
+}
+
+// Text runs of the children of `node`, split at its interpolations. Empty
+// unless `node` is a region with an interpolated child.
+pub(crate) fn text_parts(py: Python<'_>, node: &ParsedNode) -> Vec<TextPart> {
+    let Some(region) = &node.region else {
+        return Vec::new();
+    };
+    let mut parts = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for child in &node.children {
+        let child = child.borrow(py);
+        let part = match child.block_keyword.as_deref() {
+            Some("interpolation") => {
+                let expr = child.expression.clone().unwrap_or_default();
+                let name = format!("text.{}", short_hash(&["text", &expr]));
+                let count = seen.entry(name.clone()).or_insert(0);
+                *count += 1;
+                let name = match *count {
+                    1 => name,
+                    n => format!("{}~{}", name, n),
+                };
+                TextPart {
+                    kind: "dynamic",
+                    text: None,
+                    expression: Some(expr),
+                    address: Some(join(region, &name)),
+                    line: child.line,
+                    column: child.column,
+                }
+            }
+            None if child.tag.is_none() && !child.is_comment => TextPart {
+                kind: "static",
+                text: child.text_content.clone(),
+                expression: None,
+                address: None,
+                line: child.line,
+                column: child.column,
+            },
+            _ => continue,
+        };
+        parts.push(part);
+    }
+    if parts.iter().all(|p| p.kind == "static") {
+        return Vec::new();
+    }
+    parts
+}
@@ -0,0 +1,472 @@
+use crate::errors::PyWireDirectiveError;
+use crate::kinds::event_spec;
+use crate::{ParsedDirective, ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+
+/// Cache policy declared with `!cache max-age=60 vary=session`.
+///
+/// Durations are in seconds. `vary` lists request facets the cached response
+/// depends on (e.g. "session", "cookie:theme"), which the framework folds into
+/// its cache key and Vary header.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, Default)]
+pub struct CachePolicy {
+    #[pyo3(get)]
+    pub max_age: Option<u64>,
+    #[pyo3(get)]
+    pub s_maxage: Option<u64>,
+    #[pyo3(get)]
+    pub stale_while_revalidate: Option<u64>,
+    #[pyo3(get)]
+    pub vary: Vec<String>,
+    #[pyo3(get)]
+    pub public: bool,
+    #[pyo3(get)]
+    pub private: bool,
+    #[pyo3(get)]
+    pub no_store: bool,
+    #[pyo3(get)]
+    pub immutable: bool,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl CachePolicy {
+    /// Value for the Cache-Control response header.
+    fn header(&self) -> String {
+        let mut parts = Vec::new();
+        if self.no_store {
+            parts.push("no-store".to_string());
+        }
+        if self.public {
+            parts.push("public".to_string());
+        }
+        if self.private {
+            parts.push("private".to_string());
+        }
+        if let Some(v) = self.max_age {
+            parts.push(format!("max-age={}", v));
+        }
+        if let Some(v) = self.s_maxage {
+            parts.push(format!("s-maxage={}", v));
+        }
+        if let Some(v) = self.stale_while_revalidate {
+            parts.push(format!("stale-while-revalidate={}", v));
+        }
+        if self.immutable {
+            parts.push("immutable".to_string());
+        }
+        parts.join(", ")
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CachePolicy({:?}, vary={:?})", self.header(), self.vary)
+    }
+}
+
+fn parse_seconds(key: &str, value: Option<&str>) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("'{}' needs a value", key))?;
+    value
+        .parse()
+        .map_err(|_| format!("'{}' expects a number of seconds, got '{}'", key, value))
+}
+
+pub(crate) fn parse_cache_policy(content: &str) -> Result<CachePolicy, String> {
+    let mut policy = CachePolicy::default();
+    for token in content.split_whitespace() {
+        let (key, value) = match token.split_once('=') {
+            Some((k, v)) => (k, Some(v)),
+            None => (token, None),
+        };
+        match key {
+            "max-age" => policy.max_age = Some(parse_seconds(key, value)?),
+            "s-maxage" => policy.s_maxage = Some(parse_seconds(key, value)?),
+            "stale-while-revalidate" => {
+                policy.stale_while_revalidate = Some(parse_seconds(key, value)?)
+            }
+            "vary" => {
+                let value = value.ok_or("'vary' needs a value")?;
+                policy.vary.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .map(String::from),
+                );
+            }
+            "public" | "private" | "no-store" | "immutable" if value.is_none() => match key {
+                "public" => policy.public = true,
+                "private" => policy.private = true,
+                "no-store" => policy.no_store = true,
+                _ => policy.immutable = true,
+            },
+            _ => return Err(format!("Unknown cache option '{}'", token)),
+        }
+    }
+    if policy.public && policy.private {
+        return Err("'public' and 'private' are mutually exclusive".to_string());
+    }
+    Ok(policy)
+}
+
+pub(crate) fn document_cache_policy(
+    directives: &[ParsedDirective],
+) -> PyResult<Option<CachePolicy>> {
+    let mut found: Option<CachePolicy> = None;
+    for d in directives.iter().filter(|d| d.name == "cache") {
+        if found.is_some() {
+            return Err(PyWireDirectiveError::new_err(format!(
+                "Duplicate !cache directive at line {}",
+                d.line
+            )));
+        }
+        let mut policy = parse_cache_policy(d.content.as_deref().unwrap_or("")).map_err(|e| {
+            PyWireDirectiveError::new_err(format!("Invalid !cache at line {}: {}", d.line, e))
+        })?;
+        policy.line = d.line;
+        policy.column = d.column;
+        found = Some(policy);
+    }
+    Ok(found)
+}
+
+// Minimal reader for the Python literals `!path` accepts: a string, or a dict
+// of string keys to string values.
+struct LiteralReader<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl LiteralReader<'_> {
+    fn skip_ws(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.src[self.pos..].starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.skip_ws();
+        let quote = self.src[self.pos..].chars().next()?;
+        if quote != '\'' && quote != '"' {
+            return None;
+        }
+        let mut out = String::new();
+        let mut chars = self.src[self.pos + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next()?.1 {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    other => out.push(other),
+                },
+                c if c == quote => {
+                    self.pos += 1 + i + 1;
+                    return Some(out);
+                }
+                c => out.push(c),
+            }
+        }
+        None
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_ws();
+        self.pos == self.src.len()
+    }
+}
+
+// Route name -> path pairs for a `!path` directive, in declaration order.
+pub(crate) fn parse_path_routes(content: &str) -> Option<Vec<(String, String)>> {
+    let mut r = LiteralReader {
+        src: content,
+        pos: 0,
+    };
+    let mut routes = Vec::new();
+    if r.eat('{') {
+        while !r.eat('}') {
+            let name = r.string()?;
+            if !r.eat(':') {
+                return None;
+            }
+            routes.push((name, r.string()?));
+            if !r.eat(',') {
+                if !r.eat('}') {
+                    return None;
+                }
+                break;
+            }
+        }
+    } else {
+        routes.push(("main".to_string(), r.string()?));
+    }
+    r.at_end().then_some(routes)
+}
+
+/// One entry of a template's route table.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct RouteEntry {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub path: String,
+    /// Template file, when the entry comes from a `ProjectIndex`.
+    #[pyo3(get)]
+    pub file: Option<String>,
+    #[pyo3(get)]
+    pub cache_policy: Option<CachePolicy>,
+    #[pyo3(get)]
+    pub line: usize,
+    /// Why the route cannot be pre-rendered; empty for fully static routes.
+    #[pyo3(get)]
+    pub dynamic_reasons: Vec<String>,
+}
+
+#[pymethods]
+impl RouteEntry {
+    /// True when the route has no wires, request data or parameters and can
+    /// be rendered to a file at build time.
+    #[getter]
+    fn is_static(&self) -> bool {
+        self.dynamic_reasons.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RouteEntry(name={:?}, path={:?}, static={})",
+            self.name,
+            self.path,
+            self.dynamic_reasons.is_empty()
+        )
+    }
+}
+
+const REQUEST_NAMES: &[&str] = &[
+    "request", "params", "query", "session", "cookies", "headers",
+];
+
+// Free identifiers in a Python snippet, skipping string literals, comments and
+// attribute names (`x.request`). Wire reads keep their `$` prefix. Names in
+// f-string replacement fields count as reads.
+pub(crate) fn free_identifiers(src: &str) -> Vec<&str> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    // The string starting at `i` has an `f` prefix.
+    let mut fstring = false;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if c == b'"' || c == b'\'' {
+            let quote = if bytes[i..].starts_with(&[c; 3]) {
+                3
+            } else {
+                1
+            };
+            let body_start = i + quote;
+            i = body_start;
+            while i < bytes.len() && !bytes[i..].starts_with(&[c; 3][..quote]) {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            let body_end = i.min(bytes.len());
+            if std::mem::take(&mut fstring) {
+                fstring_fields(&src[body_start..body_end], &mut out);
+            }
+            i += quote;
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'$' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            let word = &src[start..i];
+            if matches!(bytes.get(i), Some(b'"' | b'\''))
+                && word.len() <= 2
+                && word.bytes().all(|b| b"rRbBuUfF".contains(&b))
+            {
+                // String prefix, not a name.
+                fstring = word.contains(['f', 'F']);
+                continue;
+            }
+            let after_dot = src[..start].trim_end().ends_with('.');
+            if !after_dot && i - start > usize::from(c == b'$') {
+                out.push(word);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+// Free identifiers of the replacement fields in f-string `body` (the text
+// between the quotes), including fields nested in format specs
+// (`{x:{width}}`).
+fn fstring_fields<'a>(body: &'a str, out: &mut Vec<&'a str>) {
+    let bytes = body.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'{' {
+            i += 1;
+            continue;
+        }
+        if bytes.get(i + 1) == Some(&b'{') {
+            i += 2;
+            continue;
+        }
+        let start = i + 1;
+        let (mut depth, mut quote, mut expr_end) = (0, None, None);
+        let mut j = start;
+        while j < bytes.len() {
+            let b = bytes[j];
+            match quote {
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None => match b {
+                    b'"' | b'\'' => quote = Some(b),
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' if depth > 0 => depth -= 1,
+                    b'}' => break,
+                    // `!r` conversion or `:spec`; not `!=`.
+                    b'!' if depth == 0 && bytes.get(j + 1) != Some(&b'=') => {
+                        expr_end.get_or_insert(j);
+                    }
+                    b':' if depth == 0 => {
+                        expr_end.get_or_insert(j);
+                    }
+                    _ => {}
+                },
+            }
+            j += 1;
+        }
+        let expr_end = expr_end.unwrap_or(j);
+        out.extend(free_identifiers(&body[start..expr_end]));
+        fstring_fields(&body[expr_end..j], out);
+        i = j + 1;
+    }
+}
+
+fn note_identifiers(src: &str, reasons: &mut Vec<String>) {
+    for ident in free_identifiers(src) {
+        let reason = if ident == "wire" || ident.starts_with('$') {
+            "wire state".to_string()
+        } else if REQUEST_NAMES.contains(&ident) {
+            format!("request data ({})", ident)
+        } else {
+            continue;
+        };
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+}
+
+fn note_template(py: Python<'_>, nodes: &[Py<ParsedNode>], reasons: &mut Vec<String>) {
+    for node in nodes {
+        let node = node.borrow(py);
+        for (name, value) in &node.attributes {
+            if event_spec(name).is_some() && !reasons.iter().any(|r| r == "event handler") {
+                reasons.push("event handler".to_string());
+            }
+            if let Some(value) = value
+                && value.starts_with('{')
+            {
+                note_identifiers(value, reasons);
+            }
+        }
+        if let Some(expr) = &node.expression {
+            note_identifiers(expr, reasons);
+        }
+        note_template(py, &node.children, reasons);
+    }
+}
+
+// Reasons a document's output depends on more than its own source.
+pub(crate) fn document_dynamic_reasons(py: Python<'_>, doc: &ParsedDocument) -> Vec<String> {
+    let mut reasons = Vec::new();
+    note_identifiers(&doc.python_code, &mut reasons);
+    note_template(py, &doc.template, &mut reasons);
+    reasons
+}
+
+pub(crate) fn route_entries(py: Python<'_>, doc: &ParsedDocument) -> PyResult<Vec<RouteEntry>> {
+    let _span = tracing::info_span!("analyze", pass = "routes").entered();
+    let doc_reasons = document_dynamic_reasons(py, doc);
+    let mut entries = Vec::new();
+    for d in doc.directives.iter().filter(|d| d.name == "path") {
+        let routes = d
+            .content
+            .as_deref()
+            .and_then(parse_path_routes)
+            .ok_or_else(|| {
+                PyWireDirectiveError::new_err(format!(
+                    "!path at line {} must be a string or a dict of strings",
+                    d.line
+                ))
+            })?;
+        entries.extend(routes.into_iter().map(|(name, path)| {
+            let mut dynamic_reasons = doc_reasons.clone();
+            if path.contains([':', '{', '[', '*']) {
+                dynamic_reasons.insert(0, "route parameter".to_string());
+            }
+            RouteEntry {
+                name,
+                path,
+                file: None,
+                cache_policy: doc.cache_policy.clone(),
+                line: d.line,
+                dynamic_reasons,
+            }
+        }));
+    }
+    Ok(entries)
+}
+
+/// Routes declared by a parsed template's `!path` directive(s), each carrying
+/// the template's cache policy and static-ness, so routing, cache headers and
+/// static export can be set up without importing the compiled page.
+#[pyfunction]
+pub fn extract_routes(
+    py: Python<'_>,
+    document: PyRef<'_, ParsedDocument>,
+) -> PyResult<Vec<RouteEntry>> {
+    route_entries(py, &document)
+}
+
+/// sitemap.xml listing the static routes among `routes`, under `base_url`.
+#[pyfunction]
+pub fn sitemap_xml(routes: Vec<PyRef<'_, RouteEntry>>, base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    let mut seen: Vec<&str> = Vec::new();
+    for route in routes.iter().filter(|r| r.dynamic_reasons.is_empty()) {
+        if seen.contains(&route.path.as_str()) {
+            continue;
+        }
+        seen.push(&route.path);
+        let mut loc = Vec::new();
+        crate::serializer::escape_text_into(&mut loc, &format!("{}{}", base, route.path));
+        out.push_str("  <url><loc>");
+        out.push_str(&String::from_utf8_lossy(&loc));
+        out.push_str("</loc></url>\n");
+    }
+    out.push_str("</urlset>\n");
+    out
+}
@@ -0,0 +1,277 @@
+// Loop body specialization. A `{$for}` whose body is plain markup (elements,
+// text and interpolations, no nested blocks, components or handlers) is
+// compiled once into a row template: the static markup between expressions,
+// and one slot per expression. Rendering N items is then N rounds of
+// evaluating the slots and splicing the values in, instead of N walks of the
+// body's subtree.
+
+use crate::ParsedNode;
+use crate::attrs::unbrace;
+use crate::chunks::EXCLUDED_TAGS;
+use crate::html::is_void;
+use crate::kinds::AttrKind;
+use crate::prerender::decode_attr;
+use crate::routes::free_identifiers;
+use crate::serializer::{escape_attr_into, escape_text_into};
+use crate::wires::split_loop_key;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBool;
+
+/// One expression of a row template. `kind` is "text" (an interpolation),
+/// "value" (part of a quoted attribute value) or "attribute" (a whole
+/// `name={expr}` attribute, dropped for None/False and bare for True).
+/// `per_item` is False when the expression does not read the loop target,
+/// so it can be evaluated once for the whole loop.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct RowSlot {
+    #[pyo3(get)]
+    pub kind: &'static str,
+    #[pyo3(get)]
+    pub expression: String,
+    #[pyo3(get)]
+    pub attribute: Option<String>,
+    #[pyo3(get)]
+    pub per_item: bool,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+}
+
+#[pymethods]
+impl RowSlot {
+    fn __repr__(&self) -> String {
+        format!("RowSlot({} {{{}}})", self.kind, self.expression)
+    }
+}
+
+/// Precompiled body of a `{$for target in iterable}` loop. `parts` is the
+/// static markup around the `slots`, one more part than slots:
+/// `parts[0] + slot[0] + parts[1] + ... + parts[-1]`.
+#[pyclass(frozen)]
+pub struct RowTemplate {
+    #[pyo3(get)]
+    pub target: String,
+    #[pyo3(get)]
+    pub iterable: String,
+    #[pyo3(get)]
+    pub parts: Vec<String>,
+    #[pyo3(get)]
+    pub slots: Vec<RowSlot>,
+}
+
+#[pymethods]
+impl RowTemplate {
+    /// Render one row per item of `rows`, each a sequence holding the value
+    /// of every slot in order. None renders as nothing.
+    fn render(&self, rows: &Bound<'_, PyAny>) -> PyResult<String> {
+        let mut out = Vec::new();
+        for row in rows.try_iter()? {
+            let values: Vec<Bound<'_, PyAny>> = row?.try_iter()?.collect::<PyResult<_>>()?;
+            if values.len() != self.slots.len() {
+                return Err(PyValueError::new_err(format!(
+                    "row has {} values, the template has {} slots",
+                    values.len(),
+                    self.slots.len()
+                )));
+            }
+            out.extend_from_slice(self.parts[0].as_bytes());
+            for ((slot, value), part) in self.slots.iter().zip(&values).zip(&self.parts[1..]) {
+                write_slot(&mut out, slot, value)?;
+                out.extend_from_slice(part.as_bytes());
+            }
+        }
+        String::from_utf8(out).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RowTemplate(for {} in {}, slots={})",
+            self.target,
+            self.iterable,
+            self.slots.len()
+        )
+    }
+}
+
+fn write_slot(out: &mut Vec<u8>, slot: &RowSlot, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    if value.is_none() {
+        return Ok(());
+    }
+    match slot.kind {
+        "attribute" => {
+            if value.is_instance_of::<PyBool>() && !value.is_truthy()? {
+                return Ok(());
+            }
+            out.push(b' ');
+            out.extend_from_slice(slot.attribute.as_deref().unwrap_or("").as_bytes());
+            if !value.is_instance_of::<PyBool>() {
+                out.extend_from_slice(b"=\"");
+                escape_attr_into(out, &value.str()?.to_string());
+                out.push(b'"');
+            }
+        }
+        "value" => escape_attr_into(out, &value.str()?.to_string()),
+        _ => escape_text_into(out, &value.str()?.to_string()),
+    }
+    Ok(())
+}
+
+struct Builder<'a> {
+    targets: &'a [&'a str],
+    parts: Vec<String>,
+    current: Vec<u8>,
+    slots: Vec<RowSlot>,
+}
+
+impl Builder<'_> {
+    fn raw(&mut self, text: &str) {
+        self.current.extend_from_slice(text.as_bytes());
+    }
+
+    fn slot(
+        &mut self,
+        kind: &'static str,
+        expr: &str,
+        attribute: Option<&str>,
+        at: (usize, usize),
+    ) {
+        let part = std::mem::take(&mut self.current);
+        self.parts.push(String::from_utf8_lossy(&part).into_owned());
+        let per_item = free_identifiers(expr)
+            .iter()
+            .any(|ident| self.targets.contains(ident));
+        self.slots.push(RowSlot {
+            kind,
+            expression: expr.trim().to_string(),
+            attribute: attribute.map(str::to_string),
+            per_item,
+            line: at.0,
+            column: at.1,
+        });
+    }
+
+    // Append `node`; None if it cannot be specialized.
+    fn node(&mut self, py: Python<'_>, node: &ParsedNode) -> Option<()> {
+        if node.is_block {
+            if node.block_keyword.as_deref() != Some("interpolation") {
+                return None;
+            }
+            let expr = node.expression.as_deref()?;
+            self.slot("text", expr, None, (node.line, node.column));
+            return Some(());
+        }
+        let Some(tag) = node.tag.as_deref() else {
+            self.raw(node.text_content.as_deref().unwrap_or(""));
+            return Some(());
+        };
+        if EXCLUDED_TAGS.contains(&tag) || node.component_name.is_some() {
+            return None;
+        }
+        self.raw(&format!("<{}", tag));
+        for attr in node.attributes.parsed() {
+            let at = (attr.line, attr.column);
+            match attr.kind {
+                AttrKind::Static if attr.has_interpolation => {
+                    self.raw(&format!(" {}=\"", attr.name));
+                    for segment in &attr.segments {
+                        if segment.is_expression {
+                            let expr = unbrace(&segment.text);
+                            self.slot("value", &expr, None, (segment.line, segment.column));
+                        } else {
+                            escape_attr_into(&mut self.current, &decode_attr(&segment.text));
+                        }
+                    }
+                    self.raw("\"");
+                }
+                AttrKind::Static | AttrKind::Boolean => {
+                    self.raw(&format!(" {}", attr.name));
+                    if let Some(value) = &attr.value {
+                        self.raw("=\"");
+                        escape_attr_into(&mut self.current, &decode_attr(value));
+                        self.raw("\"");
+                    }
+                }
+                AttrKind::Reactive | AttrKind::Shorthand => {
+                    let expr = unbrace(attr.value.as_deref()?);
+                    self.slot("attribute", &expr, Some(&attr.name), at);
+                }
+                _ => return None,
+            }
+        }
+        self.raw(">");
+        if is_void(tag) {
+            return Some(());
+        }
+        for child in &node.children {
+            self.node(py, &child.borrow(py))?;
+        }
+        self.raw(&format!("</{}>", tag));
+        Some(())
+    }
+}
+
+// Row template of a loop over `expr` ("item in items", optionally followed
+// by ", key=...") with body `body`.
+fn compile(py: Python<'_>, expr: &str, body: &[Py<ParsedNode>]) -> Option<RowTemplate> {
+    let (target, iterable) = split_loop_key(expr).0.split_once(" in ")?;
+    let target = target.trim();
+    let names: Vec<&str> = target
+        .split([',', '(', ')'])
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect();
+    let mut builder = Builder {
+        targets: &names,
+        parts: Vec::new(),
+        current: Vec::new(),
+        slots: Vec::new(),
+    };
+    for node in body {
+        builder.node(py, &node.borrow(py))?;
+    }
+    let last = std::mem::take(&mut builder.current);
+    builder
+        .parts
+        .push(String::from_utf8_lossy(&last).into_owned());
+    Some(RowTemplate {
+        target: target.to_string(),
+        iterable: iterable.trim().to_string(),
+        parts: builder.parts,
+        slots: builder.slots,
+    })
+}
+
+// Set `row_template` on every specializable `{$for}` opener in `nodes`. A
+// loop's body is its following siblings up to `close_index`, or its children
+// once resolved.
+pub(crate) fn assign_row_templates(py: Python<'_>, nodes: &[Py<ParsedNode>]) -> PyResult<()> {
+    for (i, node) in nodes.iter().enumerate() {
+        let template = {
+            let n = node.borrow(py);
+            match (n.block_keyword.as_deref(), &n.expression) {
+                (Some("for"), Some(expr)) => {
+                    let body = match n.close_index {
+                        Some(close) if close > i => &nodes[i + 1..close],
+                        _ => &n.children[..],
+                    };
+                    compile(py, expr, body)
+                }
+                _ => None,
+            }
+        };
+        if let Some(template) = template {
+            node.borrow_mut(py).row_template = Some(Py::new(py, template)?);
+        }
+        let children: Vec<_> = node
+            .borrow(py)
+            .children
+            .iter()
+            .map(|c| c.clone_ref(py))
+            .collect();
+        assign_row_templates(py, &children)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Computes the minimal, ordered set of regions to re-render after wire changes.
+///
+/// Regions form a tree (a region's output contains its child regions), and each
+/// wire subscribes a set of regions. A dirty region whose ancestor is also dirty
+/// is skipped because re-rendering the ancestor already covers it.
+#[pyclass]
+#[derive(Default)]
+pub struct RegionScheduler {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+    parents: Vec<Option<u32>>,
+    alive: Vec<bool>,
+    subscriptions: HashMap<String, Vec<u32>>,
+}
+
+impl RegionScheduler {
+    fn id(&self, region: &str) -> PyResult<u32> {
+        self.ids
+            .get(region)
+            .copied()
+            .ok_or_else(|| PyKeyError::new_err(format!("Unknown region '{}'", region)))
+    }
+
+    fn has_dirty_ancestor(
+        &self,
+        region: u32,
+        dirty: &HashSet<u32>,
+        memo: &mut HashMap<u32, bool>,
+    ) -> bool {
+        let mut chain = Vec::new();
+        let mut current = self.parents[region as usize];
+        let mut result = false;
+        while let Some(p) = current {
+            if let Some(&known) = memo.get(&p) {
+                result = known;
+                break;
+            }
+            if dirty.contains(&p) {
+                result = true;
+                break;
+            }
+            chain.push(p);
+            current = self.parents[p as usize];
+        }
+        // Regions on the walked chain are not dirty themselves, so they share the answer.
+        for p in chain {
+            memo.insert(p, result);
+        }
+        result
+    }
+}
+
+#[pymethods]
+impl RegionScheduler {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a region (in document order) under an optional parent region.
+    /// Re-adding a region moves it; moving it under itself or one of its own
+    /// descendants raises ValueError.
+    #[pyo3(signature = (region, parent=None))]
+    fn add_region(&mut self, region: String, parent: Option<&str>) -> PyResult<()> {
+        let parent_id = parent.map(|p| self.id(p)).transpose()?;
+        if let Some(&existing) = self.ids.get(&region) {
+            let mut current = parent_id;
+            while let Some(c) = current {
+                if c == existing {
+                    return Err(PyValueError::new_err(format!(
+                        "Region '{}' cannot be nested inside itself",
+                        region
+                    )));
+                }
+                current = self.parents[c as usize];
+            }
+            self.parents[existing as usize] = parent_id;
+            self.alive[existing as usize] = true;
+            return Ok(());
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(region.clone(), id);
+        self.names.push(region);
+        self.parents.push(parent_id);
+        self.alive.push(true);
+        Ok(())
+    }
+
+    /// Remove a region and every region nested inside it.
+    fn remove_region(&mut self, region: &str) -> PyResult<()> {
+        let root = self.id(region)?;
+        let removed: HashSet<u32> = (0..self.names.len() as u32)
+            .filter(|&id| self.alive[id as usize])
+            .filter(|&id| {
+                let mut current = Some(id);
+                while let Some(c) = current {
+                    if c == root {
+                        return true;
+                    }
+                    current = self.parents[c as usize];
+                }
+                false
+            })
+            .collect();
+        for &id in &removed {
+            self.alive[id as usize] = false;
+            self.ids.remove(&self.names[id as usize]);
+        }
+        for regions in self.subscriptions.values_mut() {
+            regions.retain(|r| !removed.contains(r));
+        }
+        Ok(())
+    }
+
+    /// Record that `region` reads `wire`.
+    fn subscribe(&mut self, wire: String, region: &str) -> PyResult<()> {
+        let id = self.id(region)?;
+        let regions = self.subscriptions.entry(wire).or_default();
+        if !regions.contains(&id) {
+            regions.push(id);
+        }
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, wire: &str, region: &str) -> PyResult<()> {
+        let id = self.id(region)?;
+        if let Some(regions) = self.subscriptions.get_mut(wire) {
+            regions.retain(|&r| r != id);
+        }
+        Ok(())
+    }
+
+    /// Regions to re-render for a batch of changed wires, outermost-only and in document order.
+    fn schedule(&self, changed_wires: Vec<String>) -> Vec<String> {
+        let mut dirty = HashSet::new();
+        for wire in &changed_wires {
+            if let Some(regions) = self.subscriptions.get(wire) {
+                dirty.extend(regions.iter().copied().filter(|&r| self.alive[r as usize]));
+            }
+        }
+
+        let mut memo = HashMap::new();
+        let mut selected: Vec<u32> = dirty
+            .iter()
+            .copied()
+            .filter(|&r| !self.has_dirty_ancestor(r, &dirty, &mut memo))
+            .collect();
+        selected.sort_unstable();
+        selected
+            .into_iter()
+            .map(|r| self.names[r as usize].clone())
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.ids.len()
+    }
+}
@@ -0,0 +1,456 @@
+// Selective re-rendering. Given the values a page rendered with and the
+// values it now has, only regions reading a changed name are rendered, and
+// of those only the ones whose markup actually differs are sent. A region's
+// reads cover everything inside it, so a region with unchanged reads never
+// contains a changed one and the walk does not descend into it.
+//
+// A render can be recorded: every answer the resolver gave is kept with the
+// template source, so the same payload can be serialized again offline.
+
+use crate::kinds::AttrKind;
+use crate::prerender::Renderer;
+use crate::provider::{Answer, PyProvider, Recorder, Replay, TraceEvent, Value, ValueProvider};
+use crate::serializer::{HtmlSerializer, escape_attr_into};
+use crate::snapshot::{dumps_state, loads_state};
+use crate::wires::expression_reads;
+use crate::{ParseOptions, ParsedDocument, ParsedNode, columns, parse_with};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PyDict};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Result of `render_dirty`. `payload` holds one
+/// `<template data-pw-region="ADDRESS">...</template>` part per region in
+/// `regions`, in document order. `changed` are the names whose values
+/// differ between the snapshots; `unchanged` the regions reading one of
+/// them whose markup came out byte-identical and was left out.
+#[pyclass(frozen)]
+pub struct RegionUpdate {
+    #[pyo3(get)]
+    pub payload: Py<PyBytes>,
+    #[pyo3(get)]
+    pub regions: Vec<String>,
+    #[pyo3(get)]
+    pub changed: Vec<String>,
+    #[pyo3(get)]
+    pub unchanged: Vec<String>,
+    /// Recorded inputs, with `record=True`.
+    #[pyo3(get)]
+    pub trace: Option<Py<RenderTrace>>,
+}
+
+#[pymethods]
+impl RegionUpdate {
+    fn __repr__(&self) -> String {
+        format!("RegionUpdate(regions={:?})", self.regions)
+    }
+}
+
+// Names whose value was added, removed or compares unequal. A comparison
+// that raises counts as a change.
+fn changed_names(
+    previous: &Bound<'_, PyDict>,
+    current: &Bound<'_, PyDict>,
+) -> PyResult<Vec<String>> {
+    let mut names = Vec::new();
+    for (key, value) in current.iter() {
+        let same = match previous.get_item(&key)? {
+            Some(old) => old.eq(&value).unwrap_or(false),
+            None => false,
+        };
+        if !same {
+            names.push(key.str()?.to_string());
+        }
+    }
+    for key in previous.keys() {
+        if !current.contains(&key)? {
+            names.push(key.str()?.to_string());
+        }
+    }
+    Ok(names)
+}
+
+// Every name read by `nodes` and their descendants: interpolations, block
+// markers and attribute expressions. Event handlers do not render.
+fn collect_reads(py: Python<'_>, nodes: &[Py<ParsedNode>], reads: &mut HashSet<String>) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(info) = &node.expression_info {
+            reads.extend(info.names.iter().cloned());
+        }
+        for attr in node.attributes.parsed() {
+            if attr.kind == AttrKind::Event {
+                continue;
+            }
+            for segment in attr.segments.iter().filter(|s| s.is_expression) {
+                reads.extend(
+                    expression_reads(&segment.text)
+                        .into_iter()
+                        .map(String::from),
+                );
+            }
+        }
+        for kind in [AttrKind::ClassToggle, AttrKind::StyleProperty] {
+            for entry in node.attributes.conditionals(kind) {
+                reads.extend(
+                    expression_reads(&entry.expression)
+                        .into_iter()
+                        .map(String::from),
+                );
+            }
+        }
+        collect_reads(py, &node.children, reads);
+    }
+}
+
+// Outermost regions in `nodes` reading a changed name, as (address, nodes
+// to render). A flat block's region spans its siblings up to `close_index`.
+fn dirty_regions(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    changed: &HashSet<String>,
+    out: &mut Vec<(String, Vec<Py<ParsedNode>>)>,
+) {
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = nodes[i].borrow(py);
+        let end = node.close_index.map_or(i + 1, |close| close + 1);
+        let span = &nodes[i..end.min(nodes.len())];
+        match &node.region {
+            Some(region) => {
+                let mut reads = HashSet::new();
+                collect_reads(py, span, &mut reads);
+                if !reads.is_disjoint(changed) {
+                    out.push((
+                        region.clone(),
+                        span.iter().map(|n| n.clone_ref(py)).collect(),
+                    ));
+                }
+            }
+            None => dirty_regions(py, &node.children, changed, out),
+        }
+        i = end;
+    }
+}
+
+// Render `nodes` for `region`, handing the provider back with the markup.
+fn render<P: ValueProvider>(
+    py: Python<'_>,
+    values: P,
+    region: &str,
+    nodes: &[Py<ParsedNode>],
+) -> PyResult<(Vec<u8>, P)> {
+    let assets = HashMap::new();
+    let mut renderer = Renderer {
+        py,
+        values,
+        label: format!("region '{}'", region),
+        assets: &assets,
+        out: HtmlSerializer::new(false, false, false),
+        xml: false,
+    };
+    renderer.render_nodes(nodes)?;
+    let html = renderer.out.finish_bytes()?;
+    Ok((html, renderer.values))
+}
+
+#[derive(Default)]
+struct Parts {
+    payload: Vec<u8>,
+    regions: Vec<String>,
+    unchanged: Vec<String>,
+}
+
+impl Parts {
+    // Frame `html` for `region`, unless it is what the region rendered
+    // before.
+    fn push(&mut self, region: String, html: &[u8], before: Option<&[u8]>) {
+        if before == Some(html) {
+            self.unchanged.push(region);
+            return;
+        }
+        self.payload
+            .extend_from_slice(b"<template data-pw-region=\"");
+        escape_attr_into(&mut self.payload, &region);
+        self.payload.extend_from_slice(b"\">");
+        self.payload.extend_from_slice(html);
+        self.payload.extend_from_slice(b"</template>");
+        self.regions.push(region);
+    }
+
+    fn finish(
+        self,
+        py: Python<'_>,
+        changed: Vec<String>,
+        trace: Option<RenderTrace>,
+    ) -> PyResult<RegionUpdate> {
+        Ok(RegionUpdate {
+            payload: PyBytes::new(py, &self.payload).unbind(),
+            regions: self.regions,
+            changed,
+            unchanged: self.unchanged,
+            trace: trace.map(|trace| Py::new(py, trace)).transpose()?,
+        })
+    }
+}
+
+// Format of `RenderTrace.to_bytes()`, a state snapshot (see `dumps_state`)
+// of `(TRACE_TAG, source, changed, [(address, current, previous)])` where
+// each event is `(expression, kind, answer)`.
+const TRACE_TAG: &str = "pywire-render-trace";
+const TRACE_VERSION: u64 = 1;
+
+type RawEvent<'py> = (String, String, Bound<'py, PyAny>);
+type RawRegion<'py> = (String, Vec<RawEvent<'py>>, Option<Vec<RawEvent<'py>>>);
+
+struct RecordedRegion {
+    address: String,
+    current: Vec<TraceEvent>,
+    // None if the region failed to render with the previous values.
+    previous: Option<Vec<TraceEvent>>,
+}
+
+/// The values a `render_dirty(..., record=True)` call pulled from its
+/// resolver, region by region, with the template source. `replay(trace)`
+/// serializes the same payload again without the values or the resolver;
+/// `to_bytes()` and `RenderTrace.from_bytes()` carry a trace elsewhere, e.g.
+/// from a production log to a debugging session.
+#[pyclass(frozen)]
+pub struct RenderTrace {
+    source: Arc<str>,
+    #[pyo3(get)]
+    changed: Vec<String>,
+    renders: Vec<RecordedRegion>,
+}
+
+fn event_to_py<'py>(py: Python<'py>, event: &TraceEvent) -> PyResult<Bound<'py, PyAny>> {
+    let (kind, answer) = match &event.answer {
+        Answer::Value(Value::None) => ("value", py.None().into_bound(py)),
+        Answer::Value(Value::Bool(b)) => ("value", PyBool::new(py, *b).to_owned().into_any()),
+        Answer::Value(Value::Text(text)) => ("value", text.into_pyobject(py)?.into_any()),
+        Answer::Truthy(b) => ("truthy", PyBool::new(py, *b).to_owned().into_any()),
+        Answer::Loop(count) => ("loop", count.into_pyobject(py)?),
+    };
+    Ok((event.expr.as_str(), kind, answer)
+        .into_pyobject(py)?
+        .into_any())
+}
+
+fn event_from_py((expr, kind, answer): RawEvent<'_>) -> PyResult<TraceEvent> {
+    let answer = match kind.as_str() {
+        "value" if answer.is_none() => Answer::Value(Value::None),
+        "value" if answer.is_instance_of::<PyBool>() => {
+            Answer::Value(Value::Bool(answer.extract()?))
+        }
+        "value" => Answer::Value(Value::Text(answer.extract()?)),
+        "truthy" => Answer::Truthy(answer.extract()?),
+        "loop" => Answer::Loop(answer.extract()?),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown event kind '{}' in render trace",
+                other
+            )));
+        }
+    };
+    Ok(TraceEvent { expr, answer })
+}
+
+#[pymethods]
+impl RenderTrace {
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Addresses of the regions rendered, in document order.
+    #[getter]
+    fn regions(&self) -> Vec<String> {
+        self.renders.iter().map(|r| r.address.clone()).collect()
+    }
+
+    /// Compact binary form of the trace.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let events = |events: &[TraceEvent]| -> PyResult<Vec<Bound<'py, PyAny>>> {
+            events.iter().map(|e| event_to_py(py, e)).collect()
+        };
+        let mut renders = Vec::with_capacity(self.renders.len());
+        for render in &self.renders {
+            let previous = render.previous.as_deref().map(events).transpose()?;
+            renders.push((render.address.as_str(), events(&render.current)?, previous));
+        }
+        let trace = (TRACE_TAG, &*self.source, &self.changed, renders).into_pyobject(py)?;
+        dumps_state(py, trace.as_any(), TRACE_VERSION)
+    }
+
+    /// Restore a trace written by `to_bytes()`.
+    #[staticmethod]
+    fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<RenderTrace> {
+        let (tag, source, changed, renders): (String, String, Vec<String>, Vec<RawRegion<'_>>) =
+            loads_state(py, data, Some(TRACE_VERSION))?.extract()?;
+        if tag != TRACE_TAG {
+            return Err(PyValueError::new_err("Not a pywire render trace"));
+        }
+        let events = |raw: Vec<RawEvent<'_>>| -> PyResult<Vec<TraceEvent>> {
+            raw.into_iter().map(event_from_py).collect()
+        };
+        let mut recorded = Vec::with_capacity(renders.len());
+        for (address, current, previous) in renders {
+            recorded.push(RecordedRegion {
+                address,
+                current: events(current)?,
+                previous: previous.map(events).transpose()?,
+            });
+        }
+        Ok(RenderTrace {
+            source: Arc::from(source),
+            changed,
+            renders: recorded,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RenderTrace(regions={:?})", self.regions())
+    }
+}
+
+/// Render the regions of `document` affected by a change from the
+/// `previous` values to the `current` ones, framed as one multi-part
+/// payload keyed by region address (see `RegionUpdate`).
+///
+/// A region is affected when anything inside it reads a name whose value
+/// changed; only the outermost affected region is rendered. It is rendered
+/// with both snapshots and left out when the output is byte-identical.
+/// Expressions are evaluated by `resolver(expression, namespace)`, by
+/// default Python's `eval` with the values as globals; `{$for}` targets are
+/// bound in the namespace. Snapshots must hold copies of mutable values, or
+/// in-place changes compare equal. Raises ValueError for blocks that cannot
+/// be rendered outside the page (`{$try}`, `{$await}`, ...).
+///
+/// With `record=True` the result carries a `RenderTrace` of every resolved
+/// value, for `replay`.
+#[pyfunction]
+#[pyo3(signature = (document, previous, current, resolver=None, record=false))]
+pub fn render_dirty<'py>(
+    py: Python<'py>,
+    document: PyRef<'_, ParsedDocument>,
+    previous: Bound<'py, PyDict>,
+    current: Bound<'py, PyDict>,
+    resolver: Option<Bound<'py, PyAny>>,
+    record: bool,
+) -> PyResult<RegionUpdate> {
+    document.raise_for_errors(py)?;
+    let resolver = match resolver {
+        Some(resolver) => resolver,
+        None => py.import("builtins")?.getattr("eval")?,
+    };
+    let changed = changed_names(&previous, &current)?;
+    let mut dirty = Vec::new();
+    dirty_regions(
+        py,
+        &document.template,
+        &changed.iter().cloned().collect(),
+        &mut dirty,
+    );
+
+    let provider = |values: &Bound<'py, PyDict>| -> PyResult<_> {
+        Ok(Recorder::new(
+            PyProvider::new(&resolver, values.copy()?.into_any()),
+            record,
+        ))
+    };
+    let mut parts = Parts::default();
+    let mut renders = Vec::new();
+    for (region, nodes) in dirty {
+        let (html, now) = render(py, provider(&current)?, &region, &nodes)?;
+        let before = render(py, provider(&previous)?, &region, &nodes).ok();
+        parts.push(region.clone(), &html, before.as_ref().map(|(b, _)| &b[..]));
+        if let Some(events) = now.events {
+            renders.push(RecordedRegion {
+                address: region,
+                current: events,
+                previous: before.and_then(|(_, then)| then.events),
+            });
+        }
+    }
+    let trace = record.then(|| RenderTrace {
+        source: document.source.clone(),
+        changed: changed.clone(),
+        renders,
+    });
+    parts.finish(py, changed, trace)
+}
+
+// Nodes of the region at `address`: the node carrying it and, for a flat
+// block, its body and closing marker.
+fn region_span(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    address: &str,
+) -> Option<Vec<Py<ParsedNode>>> {
+    for (i, node) in nodes.iter().enumerate() {
+        let node = node.borrow(py);
+        if node.region.as_deref() == Some(address) {
+            let end = node.close_index.map_or(i + 1, |close| close + 1);
+            return Some(
+                nodes[i..end.min(nodes.len())]
+                    .iter()
+                    .map(|n| n.clone_ref(py))
+                    .collect(),
+            );
+        }
+        if let Some(span) = region_span(py, &node.children, address) {
+            return Some(span);
+        }
+    }
+    None
+}
+
+fn replay_trace(py: Python<'_>, trace: &RenderTrace) -> PyResult<RegionUpdate> {
+    let options = ParseOptions {
+        tab_width: columns::DEFAULT_TAB_WIDTH,
+        ..Default::default()
+    };
+    let doc = parse_with(py, &trace.source, &options)?;
+    doc.raise_for_errors(py)?;
+    let mut parts = Parts::default();
+    for recorded in &trace.renders {
+        let Some(nodes) = region_span(py, &doc.template, &recorded.address) else {
+            return Err(PyValueError::new_err(format!(
+                "Region '{}' is not in the traced template",
+                recorded.address
+            )));
+        };
+        let (html, _) = render(
+            py,
+            Replay::new(&recorded.current),
+            &recorded.address,
+            &nodes,
+        )?;
+        let before = match &recorded.previous {
+            Some(events) => Some(render(py, Replay::new(events), &recorded.address, &nodes)?.0),
+            None => None,
+        };
+        parts.push(recorded.address.clone(), &html, before.as_deref());
+    }
+    parts.finish(py, trace.changed.clone(), None)
+}
+
+/// Serialize the payload of a recorded `render_dirty` call again from its
+/// `RenderTrace` (or the trace's `to_bytes()`), using the recorded values
+/// instead of evaluating anything. The template is re-parsed from the
+/// traced source with default options. Raises ValueError if the render
+/// asks for values in a different order than recorded.
+#[pyfunction]
+pub fn replay(py: Python<'_>, trace: &Bound<'_, PyAny>) -> PyResult<RegionUpdate> {
+    if let Ok(trace) = trace.downcast::<RenderTrace>() {
+        return replay_trace(py, trace.get());
+    }
+    if let Ok(data) = trace.extract::<Vec<u8>>() {
+        return replay_trace(py, &RenderTrace::from_bytes(py, &data)?);
+    }
+    Err(PyTypeError::new_err(format!(
+        "expected a RenderTrace or bytes, got {}",
+        trace.get_type().name()?
+    )))
+}
@@ -0,0 +1,177 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Tags outgoing region updates with a per-region sequence number, starting
+/// at 1, so the receiving side can tell stale frames from new ones.
+#[pyclass]
+#[derive(Default)]
+pub struct RegionSequencer {
+    counters: HashMap<String, u64>,
+}
+
+#[pymethods]
+impl RegionSequencer {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sequence number for the next update of `region`.
+    fn next(&mut self, region: String) -> u64 {
+        let counter = self.counters.entry(region).or_default();
+        *counter += 1;
+        *counter
+    }
+
+    /// Last number handed out for `region`; 0 if it has none yet.
+    fn current(&self, region: &str) -> u64 {
+        self.counters.get(region).copied().unwrap_or(0)
+    }
+
+    /// Forget the counter of one region, or of every region.
+    #[pyo3(signature = (region=None))]
+    fn reset(&mut self, region: Option<&str>) {
+        match region {
+            Some(region) => {
+                self.counters.remove(region);
+            }
+            None => self.counters.clear(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct RegionState {
+    applied: u64,
+    waiting: BTreeMap<u64, Py<PyAny>>,
+}
+
+impl RegionState {
+    // Move out every buffered update that directly follows `applied`.
+    fn drain_ready(&mut self, region: &str, out: &mut Vec<(String, u64, Py<PyAny>)>) {
+        while let Some(entry) = self.waiting.first_entry() {
+            if *entry.key() != self.applied + 1 {
+                break;
+            }
+            let (seq, payload) = entry.remove_entry();
+            self.applied = seq;
+            out.push((region.to_string(), seq, payload));
+        }
+    }
+
+    // Release everything buffered in order, giving up on the gaps.
+    fn drain_all(&mut self, region: &str, out: &mut Vec<(String, u64, Py<PyAny>)>) {
+        for (seq, payload) in std::mem::take(&mut self.waiting) {
+            self.applied = seq;
+            out.push((region.to_string(), seq, payload));
+        }
+    }
+}
+
+/// Consumer side of `RegionSequencer`: puts region updates back in sequence
+/// order and drops duplicates and frames older than what was already applied.
+///
+/// Each region expects sequence 1 first. An update that arrives ahead of a
+/// gap is held back until the gap fills, `flush()` is called, or more than
+/// `max_pending` updates are waiting for the region, at which point the
+/// missing frames are treated as lost.
+#[pyclass]
+pub struct ReorderBuffer {
+    regions: HashMap<String, RegionState>,
+    max_pending: usize,
+    #[pyo3(get)]
+    dropped: usize,
+}
+
+#[pymethods]
+impl ReorderBuffer {
+    #[new]
+    #[pyo3(signature = (max_pending=32))]
+    fn new(max_pending: usize) -> PyResult<Self> {
+        if max_pending == 0 {
+            return Err(PyValueError::new_err("max_pending must be at least 1"));
+        }
+        Ok(ReorderBuffer {
+            regions: HashMap::new(),
+            max_pending,
+            dropped: 0,
+        })
+    }
+
+    /// Accept one update and return the `(region, seq, payload)` updates now
+    /// ready to apply, in order. Stale and duplicate updates return `[]`.
+    fn push(
+        &mut self,
+        region: String,
+        seq: u64,
+        payload: Py<PyAny>,
+    ) -> Vec<(String, u64, Py<PyAny>)> {
+        let state = self.regions.entry(region.clone()).or_default();
+        if seq <= state.applied || state.waiting.contains_key(&seq) {
+            self.dropped += 1;
+            return Vec::new();
+        }
+        state.waiting.insert(seq, payload);
+        let mut out = Vec::new();
+        state.drain_ready(&region, &mut out);
+        if state.waiting.len() > self.max_pending {
+            state.drain_all(&region, &mut out);
+        }
+        out
+    }
+
+    /// Release held-back updates of one region (or all regions) without
+    /// waiting for the missing ones, e.g. after a timeout.
+    #[pyo3(signature = (region=None))]
+    fn flush(&mut self, region: Option<&str>) -> Vec<(String, u64, Py<PyAny>)> {
+        let mut out = Vec::new();
+        match region {
+            Some(region) => {
+                if let Some(state) = self.regions.get_mut(region) {
+                    state.drain_all(region, &mut out);
+                }
+            }
+            None => {
+                let mut names: Vec<String> = self.regions.keys().cloned().collect();
+                names.sort();
+                for name in names {
+                    if let Some(state) = self.regions.get_mut(&name) {
+                        state.drain_all(&name, &mut out);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Mark `region` as current up to `seq`, e.g. after a full re-render, so
+    /// only later updates are accepted. Returns held-back updates that are
+    /// now ready.
+    fn resync(&mut self, region: String, seq: u64) -> Vec<(String, u64, Py<PyAny>)> {
+        let state = self.regions.entry(region.clone()).or_default();
+        state.applied = seq;
+        let later = state.waiting.split_off(&(seq + 1));
+        self.dropped += state.waiting.len();
+        state.waiting = later;
+        let mut out = Vec::new();
+        state.drain_ready(&region, &mut out);
+        out
+    }
+
+    /// Highest sequence number applied for `region`; 0 if none.
+    fn applied(&self, region: &str) -> u64 {
+        self.regions.get(region).map_or(0, |s| s.applied)
+    }
+
+    /// Number of updates held back across all regions.
+    #[getter]
+    fn pending(&self) -> usize {
+        self.regions.values().map(|s| s.waiting.len()).sum()
+    }
+
+    /// Drop all state for a region, e.g. when it is removed from the page.
+    fn forget(&mut self, region: &str) {
+        self.regions.remove(region);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::html::is_void;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use xxhash_rust::xxh3::Xxh3;
+
+pub(crate) fn escape_text_into(out: &mut Vec<u8>, text: &str) {
+    let mut last = 0;
+    for (i, b) in text.bytes().enumerate() {
+        let rep: &[u8] = match b {
+            b'&' => b"&amp;",
+            b'<' => b"&lt;",
+            b'>' => b"&gt;",
+            _ => continue,
+        };
+        out.extend_from_slice(&text.as_bytes()[last..i]);
+        out.extend_from_slice(rep);
+        last = i + 1;
+    }
+    out.extend_from_slice(&text.as_bytes()[last..]);
+}
+
+pub(crate) fn escape_attr_into(out: &mut Vec<u8>, value: &str) {
+    let mut last = 0;
+    for (i, b) in value.bytes().enumerate() {
+        let rep: &[u8] = match b {
+            b'&' => b"&amp;",
+            b'<' => b"&lt;",
+            b'"' => b"&quot;",
+            _ => continue,
+        };
+        out.extend_from_slice(&value.as_bytes()[last..i]);
+        out.extend_from_slice(rep);
+        last = i + 1;
+    }
+    out.extend_from_slice(&value.as_bytes()[last..]);
+}
+
+// Region ids end up inside comments and attribute values, so keep them to a
+// conservative alphabet rather than escaping.
+pub(crate) fn validate_region_id(region_id: &str) -> PyResult<()> {
+    let ok = !region_id.is_empty()
+        && !region_id.contains("--")
+        && region_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.' | '/'));
+    if ok {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "Invalid region id '{}'",
+            region_id
+        )))
+    }
+}
+
+pub(crate) fn format_etag(digest: u128) -> String {
+    format!("\"{:032x}\"", digest)
+}
+
+const XHTML_NS: &str = "http://www.w3.org/1999/xhtml";
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+const MATHML_NS: &str = "http://www.w3.org/1998/Math/MathML";
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+// Default namespace declared on the root element and on embedded `<svg>`
+// and `<math>` in XHTML mode.
+fn default_namespace(tag: &str, root: bool) -> Option<&'static str> {
+    match tag.to_ascii_lowercase().as_str() {
+        "svg" => Some(SVG_NS),
+        "math" => Some(MATHML_NS),
+        _ if root => Some(XHTML_NS),
+        _ => None,
+    }
+}
+
+pub(crate) const PLACEHOLDER_OPEN: &str = "pw-defer:";
+pub(crate) const PLACEHOLDER_CLOSE: &str = "/pw-defer:";
+
+/// Output buffer for rendered HTML, written to by generated render code.
+///
+/// `take()` drains what has been written so far, so a response can be
+/// streamed chunk by chunk; `finish()` returns the remainder. With
+/// `etag=True` every drained chunk is also fed to an xxh3-128 hasher, so a
+/// strong ETag for the full body is available once the output is finished.
+///
+/// With `xhtml=True` the output is well-formed XML: void elements are
+/// self-closed, boolean attributes get their name as value (`hidden="hidden"`),
+/// and the XHTML namespace is declared on the first element (SVG and MathML
+/// on `<svg>`/`<math>`, `xmlns:xlink` where `xlink:` attributes are used).
+/// `xml=True` is the same without HTML rules, for feeds and other XML: no
+/// element is void and no namespace is implied.
+#[pyclass]
+#[derive(Default)]
+pub struct HtmlSerializer {
+    buf: Vec<u8>,
+    xhtml: bool,
+    xml: bool,
+    root_written: bool,
+    placeholders: Vec<String>,
+    open_placeholders: Vec<String>,
+    written: usize,
+    hasher: Option<Box<Xxh3>>,
+    etag: Option<String>,
+}
+
+impl HtmlSerializer {
+    pub(crate) fn write_raw(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    // `xmlns` declarations the element needs and does not carry itself.
+    fn write_namespaces(&mut self, tag: &str, attrs: &[(String, Option<String>)]) {
+        let has = |name: &str| attrs.iter().any(|(n, _)| n == name);
+        let root = !std::mem::replace(&mut self.root_written, true);
+        let mut declare = Vec::new();
+        if let Some(ns) = default_namespace(tag, root)
+            && !has("xmlns")
+        {
+            declare.push(("xmlns", ns));
+        }
+        if attrs.iter().any(|(n, _)| n.starts_with("xlink:")) && !has("xmlns:xlink") {
+            declare.push(("xmlns:xlink", XLINK_NS));
+        }
+        for (name, ns) in declare {
+            self.buf.push(b' ');
+            self.buf.extend_from_slice(name.as_bytes());
+            self.buf.extend_from_slice(b"=\"");
+            self.buf.extend_from_slice(ns.as_bytes());
+            self.buf.push(b'"');
+        }
+    }
+
+    fn well_formed(&self) -> bool {
+        self.xhtml || self.xml
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<u8> {
+        self.written += self.buf.len();
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(&self.buf);
+        }
+        std::mem::take(&mut self.buf)
+    }
+
+    pub(crate) fn finish_bytes(&mut self) -> PyResult<Vec<u8>> {
+        if let Some(open) = self.open_placeholders.last() {
+            return Err(PyValueError::new_err(format!(
+                "Placeholder for region '{}' was never closed",
+                open
+            )));
+        }
+        let span = tracing::info_span!("serialize", bytes = tracing::field::Empty).entered();
+        let out = self.drain();
+        span.record("bytes", out.len());
+        if let Some(hasher) = self.hasher.take() {
+            self.etag = Some(format_etag(hasher.digest128()));
+        }
+        Ok(out)
+    }
+}
+
+#[pymethods]
+impl HtmlSerializer {
+    #[new]
+    #[pyo3(signature = (etag=false, xhtml=false, xml=false))]
+    pub(crate) fn new(etag: bool, xhtml: bool, xml: bool) -> Self {
+        HtmlSerializer {
+            hasher: etag.then(|| Box::new(Xxh3::new())),
+            xhtml,
+            xml,
+            ..Self::default()
+        }
+    }
+
+    #[getter]
+    fn xhtml(&self) -> bool {
+        self.xhtml
+    }
+
+    #[getter]
+    fn xml(&self) -> bool {
+        self.xml
+    }
+
+    #[pyo3(signature = (tag, attrs=None, self_closing=false))]
+    pub(crate) fn start_tag(
+        &mut self,
+        tag: &str,
+        attrs: Option<Vec<(String, Option<String>)>>,
+        self_closing: bool,
+    ) {
+        let attrs = attrs.unwrap_or_default();
+        self.buf.push(b'<');
+        self.buf.extend_from_slice(tag.as_bytes());
+        if self.xhtml {
+            self.write_namespaces(tag, &attrs);
+        }
+        for (name, value) in &attrs {
+            self.buf.push(b' ');
+            self.buf.extend_from_slice(name.as_bytes());
+            // XML has no valueless attributes.
+            let value = value
+                .as_deref()
+                .or(self.well_formed().then_some(name.as_str()));
+            if let Some(v) = value {
+                self.buf.extend_from_slice(b"=\"");
+                escape_attr_into(&mut self.buf, v);
+                self.buf.push(b'"');
+            }
+        }
+        let self_closing = self_closing || (self.xhtml && is_void(tag));
+        self.buf
+            .extend_from_slice(if self_closing { b" />" } else { b">" });
+    }
+
+    /// Write a closing tag. In XHTML mode, closing a void element writes
+    /// nothing, since `start_tag` already closed it.
+    pub(crate) fn end_tag(&mut self, tag: &str) {
+        if self.xhtml && is_void(tag) {
+            return;
+        }
+        self.buf.extend_from_slice(b"</");
+        self.buf.extend_from_slice(tag.as_bytes());
+        self.buf.push(b'>');
+    }
+
+    /// Write escaped text content.
+    pub(crate) fn text(&mut self, text: &str) {
+        escape_text_into(&mut self.buf, text);
+    }
+
+    /// Write trusted markup verbatim.
+    pub(crate) fn raw(&mut self, html: &str) {
+        self.write_raw(html.as_bytes());
+    }
+
+    pub(crate) fn comment(&mut self, text: &str) {
+        self.buf.extend_from_slice(b"<!--");
+        // Split every `--` run, however long: `---` becomes `- - -`.
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '-' && escaped.ends_with('-') {
+                escaped.push(' ');
+            }
+            escaped.push(c);
+        }
+        self.buf.extend_from_slice(escaped.as_bytes());
+        // `--->` is not a well-formed XML comment end.
+        if self.well_formed() && text.ends_with('-') {
+            self.buf.push(b' ');
+        }
+        self.buf.extend_from_slice(b"-->");
+    }
+
+    /// Start a deferred region: its fallback content goes between this marker
+    /// and `close_placeholder`, and is swapped out when the region's payload
+    /// arrives later in the stream.
+    fn open_placeholder(&mut self, region_id: &str) -> PyResult<()> {
+        validate_region_id(region_id)?;
+        if self.placeholders.iter().any(|p| p == region_id) {
+            return Err(PyValueError::new_err(format!(
+                "Duplicate placeholder for region '{}'",
+                region_id
+            )));
+        }
+        self.buf.extend_from_slice(b"<!--");
+        self.buf.extend_from_slice(PLACEHOLDER_OPEN.as_bytes());
+        self.buf.extend_from_slice(region_id.as_bytes());
+        self.buf.extend_from_slice(b"-->");
+        self.placeholders.push(region_id.to_string());
+        self.open_placeholders.push(region_id.to_string());
+        Ok(())
+    }
+
+    fn close_placeholder(&mut self, region_id: &str) -> PyResult<()> {
+        if self.open_placeholders.last().map(String::as_str) != Some(region_id) {
+            return Err(PyValueError::new_err(format!(
+                "close_placeholder('{}') does not match the innermost open placeholder",
+                region_id
+            )));
+        }
+        self.open_placeholders.pop();
+        self.buf.extend_from_slice(b"<!--");
+        self.buf.extend_from_slice(PLACEHOLDER_CLOSE.as_bytes());
+        self.buf.extend_from_slice(region_id.as_bytes());
+        self.buf.extend_from_slice(b"-->");
+        Ok(())
+    }
+
+    /// Region ids of every placeholder emitted so far, in document order.
+    #[getter]
+    fn placeholders(&self) -> Vec<String> {
+        self.placeholders.clone()
+    }
+
+    /// Drain and return everything written since the last `take()`.
+    fn take<'py>(&mut self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.drain())
+    }
+
+    /// Return the remaining output. Fails if a placeholder was left open.
+    fn finish<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &self.finish_bytes()?))
+    }
+
+    /// Like `finish()`, but also returns the ETag of the complete body
+    /// (None unless the serializer was created with `etag=True`).
+    fn finish_with_etag<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyBytes>, Option<String>)> {
+        let out = self.finish_bytes()?;
+        Ok((PyBytes::new(py, &out), self.etag.clone()))
+    }
+
+    /// Quoted strong ETag of the full output, available after `finish()`.
+    #[getter]
+    pub(crate) fn etag(&self) -> Option<String> {
+        self.etag.clone()
+    }
+
+    /// Total bytes produced so far, including already-drained chunks.
+    fn __len__(&self) -> usize {
+        self.written + self.buf.len()
+    }
+}
+
+/// Strong ETag (quoted xxh3-128 hex digest) for an already-rendered body.
+#[pyfunction]
+pub fn content_etag(py: Python<'_>, data: &[u8]) -> String {
+    py.detach(|| format_etag(xxhash_rust::xxh3::xxh3_128(data)))
+}
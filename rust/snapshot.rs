@@ -0,0 +1,295 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{
+    PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple,
+};
+
+// Binary layout: b"PWS" | format version (u8) | schema version (varint) | value
+const MAGIC: &[u8; 3] = b"PWS";
+const FORMAT_VERSION: u8 = 1;
+pub(crate) const MAX_DEPTH: usize = 256;
+
+pub(crate) const TAG_NONE: u8 = 0;
+pub(crate) const TAG_FALSE: u8 = 1;
+pub(crate) const TAG_TRUE: u8 = 2;
+pub(crate) const TAG_INT: u8 = 3;
+pub(crate) const TAG_BIGINT: u8 = 4;
+pub(crate) const TAG_FLOAT: u8 = 5;
+pub(crate) const TAG_STR: u8 = 6;
+pub(crate) const TAG_BYTES: u8 = 7;
+pub(crate) const TAG_LIST: u8 = 8;
+pub(crate) const TAG_TUPLE: u8 = 9;
+pub(crate) const TAG_DICT: u8 = 10;
+pub(crate) const TAG_SET: u8 = 11;
+pub(crate) const TAG_FROZENSET: u8 = 12;
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_len_prefixed(out: &mut Vec<u8>, tag: u8, bytes: &[u8]) {
+    out.push(tag);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn encode_value(
+    out: &mut Vec<u8>,
+    obj: &Bound<'_, PyAny>,
+    depth: usize,
+) -> PyResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err(format!(
+            "State snapshot exceeds maximum nesting depth of {} (cyclic structure?)",
+            MAX_DEPTH
+        )));
+    }
+
+    if obj.is_none() {
+        out.push(TAG_NONE);
+    } else if let Ok(b) = obj.downcast::<PyBool>() {
+        out.push(if b.is_true() { TAG_TRUE } else { TAG_FALSE });
+    } else if obj.is_instance_of::<PyInt>() {
+        match obj.extract::<i64>() {
+            Ok(v) => {
+                out.push(TAG_INT);
+                // zigzag keeps small negative numbers short
+                write_varint(out, ((v << 1) ^ (v >> 63)) as u64);
+            }
+            Err(_) => {
+                let text = obj.str()?.to_string();
+                write_len_prefixed(out, TAG_BIGINT, text.as_bytes());
+            }
+        }
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        out.push(TAG_FLOAT);
+        out.extend_from_slice(&f.value().to_le_bytes());
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        write_len_prefixed(out, TAG_STR, s.to_str()?.as_bytes());
+    } else if let Ok(b) = obj.downcast::<PyBytes>() {
+        write_len_prefixed(out, TAG_BYTES, b.as_bytes());
+    } else if let Ok(list) = obj.downcast::<PyList>() {
+        out.push(TAG_LIST);
+        write_varint(out, list.len() as u64);
+        for item in list.iter() {
+            encode_value(out, &item, depth + 1)?;
+        }
+    } else if let Ok(tuple) = obj.downcast::<PyTuple>() {
+        out.push(TAG_TUPLE);
+        write_varint(out, tuple.len() as u64);
+        for item in tuple.iter() {
+            encode_value(out, &item, depth + 1)?;
+        }
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        out.push(TAG_DICT);
+        write_varint(out, dict.len() as u64);
+        for (key, value) in dict.iter() {
+            encode_value(out, &key, depth + 1)?;
+            encode_value(out, &value, depth + 1)?;
+        }
+    } else if let Ok(set) = obj.downcast::<PySet>() {
+        out.push(TAG_SET);
+        write_varint(out, set.len() as u64);
+        for item in set.iter() {
+            encode_value(out, &item, depth + 1)?;
+        }
+    } else if let Ok(set) = obj.downcast::<PyFrozenSet>() {
+        out.push(TAG_FROZENSET);
+        write_varint(out, set.len() as u64);
+        for item in set.iter() {
+            encode_value(out, &item, depth + 1)?;
+        }
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "Cannot snapshot value of type '{}'",
+            obj.get_type().name()?
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) struct Reader<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn truncated() -> PyErr {
+        PyValueError::new_err("Truncated state snapshot")
+    }
+
+    pub(crate) fn byte(&mut self) -> PyResult<u8> {
+        let b = *self.data.get(self.pos).ok_or_else(Self::truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> PyResult<&'a [u8]> {
+        if self.data.len() - self.pos < n {
+            return Err(Self::truncated());
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    pub(crate) fn varint(&mut self) -> PyResult<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            value |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(PyValueError::new_err("Malformed varint in state snapshot"))
+    }
+
+    // Container lengths can never exceed the remaining bytes (every item takes at
+    // least one), which keeps hostile payloads from forcing huge allocations.
+    pub(crate) fn len(&mut self) -> PyResult<usize> {
+        let n = self.varint()? as usize;
+        if n > self.data.len() - self.pos {
+            return Err(Self::truncated());
+        }
+        Ok(n)
+    }
+
+    fn str(&mut self) -> PyResult<&'a str> {
+        let n = self.len()?;
+        std::str::from_utf8(self.take(n)?)
+            .map_err(|_| PyValueError::new_err("Invalid UTF-8 in state snapshot"))
+    }
+}
+
+pub(crate) fn decode_value<'py>(
+    py: Python<'py>,
+    reader: &mut Reader<'_>,
+    depth: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err(
+            "State snapshot exceeds maximum nesting depth",
+        ));
+    }
+
+    let tag = reader.byte()?;
+    let value = match tag {
+        TAG_NONE => py.None().into_bound(py),
+        TAG_FALSE => PyBool::new(py, false).to_owned().into_any(),
+        TAG_TRUE => PyBool::new(py, true).to_owned().into_any(),
+        TAG_INT => {
+            let raw = reader.varint()?;
+            let v = ((raw >> 1) as i64) ^ -((raw & 1) as i64);
+            v.into_pyobject(py)?.into_any()
+        }
+        TAG_BIGINT => {
+            let text = reader.str()?;
+            py.get_type::<PyInt>().call1((text,))?
+        }
+        TAG_FLOAT => {
+            let bytes: [u8; 8] = reader.take(8)?.try_into().unwrap();
+            PyFloat::new(py, f64::from_le_bytes(bytes)).into_any()
+        }
+        TAG_STR => PyString::new(py, reader.str()?).into_any(),
+        TAG_BYTES => {
+            let n = reader.len()?;
+            PyBytes::new(py, reader.take(n)?).into_any()
+        }
+        TAG_LIST | TAG_TUPLE | TAG_SET | TAG_FROZENSET => {
+            let n = reader.len()?;
+            let mut items = Vec::with_capacity(n);
+            for _ in 0..n {
+                items.push(decode_value(py, reader, depth + 1)?);
+            }
+            match tag {
+                TAG_LIST => PyList::new(py, items)?.into_any(),
+                TAG_TUPLE => PyTuple::new(py, items)?.into_any(),
+                TAG_SET => PySet::new(py, &items)?.into_any(),
+                _ => PyFrozenSet::new(py, &items)?.into_any(),
+            }
+        }
+        TAG_DICT => {
+            let n = reader.len()?;
+            let dict = PyDict::new(py);
+            for _ in 0..n {
+                let key = decode_value(py, reader, depth + 1)?;
+                let value = decode_value(py, reader, depth + 1)?;
+                dict.set_item(key, value)?;
+            }
+            dict.into_any()
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown tag {} in state snapshot",
+                other
+            )));
+        }
+    };
+    Ok(value)
+}
+
+fn read_header(reader: &mut Reader<'_>) -> PyResult<u64> {
+    if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+        return Err(PyValueError::new_err("Not a pywire state snapshot"));
+    }
+    let format = reader.byte()?;
+    if format != FORMAT_VERSION {
+        return Err(PyValueError::new_err(format!(
+            "Unsupported state snapshot format version {} (expected {})",
+            format, FORMAT_VERSION
+        )));
+    }
+    reader.varint()
+}
+
+/// Serialize nested builtin state (dict/list/tuple/set/scalars) into a versioned binary snapshot.
+#[pyfunction]
+#[pyo3(signature = (state, schema_version=0))]
+pub fn dumps_state<'py>(
+    py: Python<'py>,
+    state: &Bound<'py, PyAny>,
+    schema_version: u64,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut out = Vec::with_capacity(64);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    write_varint(&mut out, schema_version);
+    encode_value(&mut out, state, 0)?;
+    Ok(PyBytes::new(py, &out))
+}
+
+/// Restore a snapshot produced by `dumps_state`. Only builtin types are ever constructed.
+#[pyfunction]
+#[pyo3(signature = (data, schema_version=None))]
+pub fn loads_state<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    schema_version: Option<u64>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut reader = Reader { data, pos: 0 };
+    let found = read_header(&mut reader)?;
+    if let Some(expected) = schema_version
+        && found != expected
+    {
+        return Err(PyValueError::new_err(format!(
+            "State snapshot schema version {} does not match expected {}",
+            found, expected
+        )));
+    }
+    let value = decode_value(py, &mut reader, 0)?;
+    if reader.pos != data.len() {
+        return Err(PyValueError::new_err("Trailing bytes after state snapshot"));
+    }
+    Ok(value)
+}
+
+/// Read the schema version stored in a snapshot header without decoding the payload.
+#[pyfunction]
+pub fn snapshot_schema_version(data: &[u8]) -> PyResult<u64> {
+    read_header(&mut Reader { data, pos: 0 })
+}
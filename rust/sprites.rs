@@ -0,0 +1,308 @@
+use crate::ParsedNode;
+use crate::assets::offset_of;
+use crate::html::is_void;
+use crate::project::ProjectIndex;
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+// Attributes that stay on each usage instead of moving into the symbol.
+const USAGE_ATTRIBUTES: &[&str] = &[
+    "class",
+    "width",
+    "height",
+    "style",
+    "id",
+    "role",
+    "aria-hidden",
+    "aria-label",
+    "focusable",
+];
+
+/// An inline `<svg>` repeated often enough to move into the sprite sheet.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct SpriteSymbol {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub markup: String,
+    /// `(file, line, column)` of every usage.
+    #[pyo3(get)]
+    pub occurrences: Vec<(String, usize, usize)>,
+    #[pyo3(get)]
+    pub saved_bytes: usize,
+    key: String,
+}
+
+#[pymethods]
+impl SpriteSymbol {
+    fn __repr__(&self) -> String {
+        format!(
+            "SpriteSymbol(id={:?}, occurrences={})",
+            self.id,
+            self.occurrences.len()
+        )
+    }
+}
+
+/// Result of `extract_sprites`: the symbols to hoist, in first-use order.
+#[pyclass(frozen)]
+pub struct SpritePlan {
+    #[pyo3(get)]
+    pub symbols: Vec<SpriteSymbol>,
+}
+
+#[pymethods]
+impl SpritePlan {
+    /// Hidden `<svg>` holding every symbol, to include once per page.
+    fn sprite(&self) -> String {
+        let mut out = String::from(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" style=\"display:none\" aria-hidden=\"true\">",
+        );
+        for symbol in &self.symbols {
+            out.push_str(&symbol.markup);
+        }
+        out.push_str("</svg>");
+        out
+    }
+
+    fn __len__(&self) -> usize {
+        self.symbols.len()
+    }
+}
+
+fn write_attr(out: &mut String, name: &str, value: &Option<String>) {
+    out.push(' ');
+    out.push_str(name);
+    if let Some(v) = value {
+        out.push_str("=\"");
+        out.push_str(v);
+        out.push('"');
+    }
+}
+
+// Canonical markup of a fully static subtree; None if anything is dynamic.
+fn canonical(py: Python<'_>, node: &ParsedNode, out: &mut String) -> Option<()> {
+    if node.is_block {
+        return None;
+    }
+    let Some(tag) = node.tag.as_deref() else {
+        let text = node.text_content.as_deref().unwrap_or("");
+        out.push_str(text.trim());
+        return Some(());
+    };
+    let mut names: Vec<&String> = node.attributes.keys().collect();
+    names.sort();
+    out.push('<');
+    out.push_str(tag);
+    for name in names {
+        let value = &node.attributes[name];
+        if name.starts_with(['$', '@', ':']) || name.starts_with("on:") || name.starts_with("__pw")
+        {
+            return None;
+        }
+        if value.as_deref().is_some_and(|v| v.contains('{')) {
+            return None;
+        }
+        write_attr(out, name, value);
+    }
+    out.push('>');
+    if !is_void(tag) {
+        for child in &node.children {
+            canonical(py, &child.borrow(py), out)?;
+        }
+        out.push_str("</");
+        out.push_str(tag);
+        out.push('>');
+    }
+    Some(())
+}
+
+// Dedup key of an `<svg>`: its viewBox plus canonical children. Usage-level
+// attributes (class, size, ...) are left out so differently styled copies of
+// the same icon share a symbol.
+pub(crate) fn svg_key(py: Python<'_>, node: &ParsedNode) -> Option<String> {
+    if node.tag.as_deref() != Some("svg") {
+        return None;
+    }
+    let mut key = String::new();
+    let mut names: Vec<&String> = node.attributes.keys().collect();
+    names.sort();
+    for name in names {
+        if name == "xmlns" || USAGE_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        let value = &node.attributes[name];
+        if name.starts_with(['$', '@', ':'])
+            || name.starts_with("on:")
+            || name.starts_with("__pw")
+            || value.as_deref().is_some_and(|v| v.contains('{'))
+        {
+            return None;
+        }
+        write_attr(&mut key, name, value);
+    }
+    key.push('>');
+    for child in &node.children {
+        canonical(py, &child.borrow(py), &mut key)?;
+    }
+    Some(key)
+}
+
+pub(crate) fn symbol_id(key: &str) -> String {
+    format!("pw-icon-{:08x}", xxh3_64(key.as_bytes()) as u32)
+}
+
+fn collect_svgs(
+    py: Python<'_>,
+    file: &str,
+    nodes: &[Py<ParsedNode>],
+    found: &mut BTreeMap<String, SpriteSymbol>,
+    order: &mut Vec<String>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(key) = svg_key(py, &node) {
+            let symbol = found.entry(key.clone()).or_insert_with(|| {
+                order.push(key.clone());
+                let (attrs, body) = key.split_once('>').unwrap_or(("", ""));
+                let id = symbol_id(&key);
+                SpriteSymbol {
+                    markup: format!("<symbol id=\"{}\"{}>{}</symbol>", id, attrs, body),
+                    id,
+                    occurrences: Vec::new(),
+                    saved_bytes: 0,
+                    key: key.clone(),
+                }
+            });
+            symbol
+                .occurrences
+                .push((file.to_string(), node.line, node.column));
+            continue;
+        }
+        collect_svgs(py, file, &node.children, found, order);
+    }
+}
+
+/// Find inline `<svg>` icons repeated at least `min_occurrences` times across
+/// the project. Only fully static SVGs are considered.
+#[pyfunction]
+#[pyo3(signature = (index, min_occurrences=2))]
+pub fn extract_sprites(
+    py: Python<'_>,
+    index: PyRef<'_, ProjectIndex>,
+    min_occurrences: usize,
+) -> SpritePlan {
+    let mut found = BTreeMap::new();
+    let mut order = Vec::new();
+    for (file, doc) in &index.files {
+        collect_svgs(py, file, &doc.borrow(py).template, &mut found, &mut order);
+    }
+    let symbols = order
+        .into_iter()
+        .filter_map(|key| found.remove(&key))
+        .filter(|s| s.occurrences.len() >= min_occurrences.max(1))
+        .map(|mut s| {
+            let usage = format!("<use href=\"#{}\"></use>", s.id).len();
+            s.saved_bytes = (s.key.len().saturating_sub(usage)) * s.occurrences.len();
+            s
+        })
+        .collect();
+    SpritePlan { symbols }
+}
+
+// End offset (exclusive) of the `<svg ...>...</svg>` element starting at `start`.
+fn svg_end(source: &str, start: usize) -> Option<usize> {
+    let lower = source[start..].to_ascii_lowercase();
+    let mut depth = 0usize;
+    let mut pos = 0;
+    loop {
+        let open = lower[pos..].find("<svg").map(|i| i + pos);
+        let close = lower[pos..].find("</svg").map(|i| i + pos)?;
+        match open {
+            Some(o) if o < close => {
+                depth += 1;
+                pos = o + 4;
+            }
+            _ => {
+                depth = depth.checked_sub(1)?;
+                pos = close + 5;
+                if depth == 0 {
+                    return lower[pos..].find('>').map(|g| start + pos + g + 1);
+                }
+            }
+        }
+    }
+}
+
+struct Usage {
+    line: usize,
+    column: usize,
+    id: String,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+fn collect_usages(
+    py: Python<'_>,
+    nodes: &[Py<ParsedNode>],
+    ids: &BTreeMap<String, String>,
+    out: &mut Vec<Usage>,
+) {
+    for node in nodes {
+        let node = node.borrow(py);
+        if let Some(key) = svg_key(py, &node) {
+            if let Some(id) = ids.get(&key) {
+                let mut attrs: Vec<(String, Option<String>)> = node
+                    .attributes
+                    .iter()
+                    .filter(|(k, _)| USAGE_ATTRIBUTES.contains(&k.as_str()))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                attrs.sort();
+                out.push(Usage {
+                    line: node.line,
+                    column: node.column,
+                    id: id.clone(),
+                    attrs,
+                });
+            }
+            continue;
+        }
+        collect_usages(py, &node.children, ids, out);
+    }
+}
+
+/// Replace inline copies of the plan's symbols in template `source` with
+/// `<svg ...><use href="#id"></use></svg>`, keeping usage attributes such as
+/// class and size.
+#[pyfunction]
+pub fn rewrite_sprites(
+    py: Python<'_>,
+    source: String,
+    plan: PyRef<'_, SpritePlan>,
+) -> PyResult<String> {
+    let ids: BTreeMap<String, String> = plan
+        .symbols
+        .iter()
+        .map(|s| (s.key.clone(), s.id.clone()))
+        .collect();
+    let doc = crate::parse_with(py, &source, &Default::default())?;
+    let mut usages = Vec::new();
+    collect_usages(py, &doc.template, &ids, &mut usages);
+
+    let mut out = source.clone();
+    for usage in usages.into_iter().rev() {
+        let start = offset_of(&source, usage.line, usage.column);
+        let Some(end) = svg_end(&source, start) else {
+            continue;
+        };
+        let mut replacement = String::from("<svg");
+        for (name, value) in &usage.attrs {
+            write_attr(&mut replacement, name, value);
+        }
+        replacement.push_str(&format!("><use href=\"#{}\"></use></svg>", usage.id));
+        out.replace_range(start..end, &replacement);
+    }
+    Ok(out)
+}
@@ -0,0 +1,71 @@
+use crate::serializer::validate_region_id;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+pub(crate) const STREAM_END_MARKER: &[u8] = b"<!--pw-stream-end-->";
+
+/// Frames late-arriving region payloads for a streamed (chunked) response.
+///
+/// The page shell is sent first with `<!--pw-defer:ID-->` placeholders; each
+/// region then follows as `<template data-pw-fill="ID">...</template>`, which
+/// the client swaps into the matching placeholder. Regions may be filled in
+/// any order, each exactly once.
+#[pyclass]
+pub struct StreamEncoder {
+    pending: Vec<String>,
+}
+
+#[pymethods]
+impl StreamEncoder {
+    #[new]
+    fn new(placeholders: Vec<String>) -> PyResult<Self> {
+        for id in &placeholders {
+            validate_region_id(id)?;
+        }
+        Ok(StreamEncoder {
+            pending: placeholders,
+        })
+    }
+
+    /// Frame the payload for one deferred region.
+    fn fill<'py>(
+        &mut self,
+        py: Python<'py>,
+        region_id: &str,
+        payload: &[u8],
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let Some(pos) = self.pending.iter().position(|p| p == region_id) else {
+            return Err(PyValueError::new_err(format!(
+                "Region '{}' has no pending placeholder",
+                region_id
+            )));
+        };
+        self.pending.remove(pos);
+
+        let mut out = Vec::with_capacity(payload.len() + region_id.len() + 48);
+        out.extend_from_slice(b"<template data-pw-fill=\"");
+        out.extend_from_slice(region_id.as_bytes());
+        out.extend_from_slice(b"\">");
+        out.extend_from_slice(payload);
+        out.extend_from_slice(b"</template>");
+        Ok(PyBytes::new(py, &out))
+    }
+
+    /// Region ids still waiting for a payload.
+    #[getter]
+    fn pending(&self) -> Vec<String> {
+        self.pending.clone()
+    }
+
+    /// Final frame closing the stream. Fails if any region was never filled.
+    fn finish<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        if !self.pending.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "Stream finished with unfilled regions: {}",
+                self.pending.join(", ")
+            )));
+        }
+        Ok(PyBytes::new(py, STREAM_END_MARKER))
+    }
+}
@@ -0,0 +1,231 @@
+// Strict publishing profiles: templates restricted to an element and
+// attribute allowlist, without inline scripts, and with a CSS size budget.
+// Components are not checked here; each component's own template is.
+
+use crate::diagnostics::Diagnostic;
+use crate::kinds::AttrKind;
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+
+// Allowlists of the `amp()` preset, whitespace-separated.
+const AMP_ELEMENTS: &str = "\
+    html head body title meta link style script noscript template slot a abbr address \
+    article aside b blockquote br button caption cite code dd del details dfn div dl dt \
+    em fieldset figcaption figure footer form h1 h2 h3 h4 h5 h6 header hr i input ins \
+    kbd label legend li main mark nav ol option p pre q s section select small span \
+    strong sub summary sup table tbody td textarea tfoot th thead time tr u ul amp-*";
+
+const AMP_ATTRIBUTES: &str = "\
+    id class style title lang dir hidden role tabindex href target rel src srcset sizes \
+    alt width height layout name content charset type async custom-element \
+    custom-template action action-xhr method value placeholder for disabled checked \
+    selected required colspan rowspan datetime open amp data-* aria-*";
+
+/// Restrictions for `check_profile`. `elements` and `attributes` are
+/// allowlists; an entry ending in `*` allows a prefix (`"data-*"`).
+/// Attributes are checked on every element, template directives (`$if`)
+/// excepted. `max_css_bytes` limits `<style>` content plus `style`
+/// attributes.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct StrictProfile {
+    #[pyo3(get)]
+    pub elements: Vec<String>,
+    #[pyo3(get)]
+    pub attributes: Vec<String>,
+    #[pyo3(get)]
+    pub allow_inline_scripts: bool,
+    #[pyo3(get)]
+    pub max_css_bytes: Option<usize>,
+}
+
+#[pymethods]
+impl StrictProfile {
+    #[new]
+    #[pyo3(signature = (elements, attributes, allow_inline_scripts=false, max_css_bytes=None))]
+    fn new(
+        elements: Vec<String>,
+        attributes: Vec<String>,
+        allow_inline_scripts: bool,
+        max_css_bytes: Option<usize>,
+    ) -> Self {
+        StrictProfile {
+            elements,
+            attributes,
+            allow_inline_scripts,
+            max_css_bytes,
+        }
+    }
+
+    /// An AMP-like profile: common HTML plus `amp-*` elements, no inline
+    /// scripts or event handlers, and at most 75,000 bytes of CSS.
+    #[staticmethod]
+    fn amp() -> Self {
+        StrictProfile {
+            elements: AMP_ELEMENTS
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            attributes: AMP_ATTRIBUTES
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            allow_inline_scripts: false,
+            max_css_bytes: Some(75_000),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StrictProfile(elements={}, attributes={}, allow_inline_scripts={}, max_css_bytes={:?})",
+            self.elements.len(),
+            self.attributes.len(),
+            self.allow_inline_scripts,
+            self.max_css_bytes
+        )
+    }
+}
+
+fn allowed(list: &[String], name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    list.iter().any(|entry| match entry.strip_suffix('*') {
+        Some(prefix) => name.starts_with(&prefix.to_ascii_lowercase()),
+        None => entry.eq_ignore_ascii_case(&name),
+    })
+}
+
+struct Checker<'a> {
+    profile: &'a StrictProfile,
+    css_bytes: usize,
+    css_reported: bool,
+    out: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn error(&mut self, code: &str, message: String, node: &ParsedNode) {
+        self.out.push(
+            Diagnostic::error(code, message, node.line, node.column).spanning(
+                (node.end_line, node.end_column),
+                (node.start_byte, node.end_byte),
+            ),
+        );
+    }
+
+    fn add_css(&mut self, bytes: usize, node: &ParsedNode) {
+        self.css_bytes += bytes;
+        if let Some(max) = self.profile.max_css_bytes
+            && self.css_bytes > max
+            && !self.css_reported
+        {
+            self.css_reported = true;
+            self.error(
+                "profile-css-size",
+                format!("CSS exceeds the profile's {} byte limit", max),
+                node,
+            );
+        }
+    }
+
+    fn check(&mut self, py: Python<'_>, nodes: &[Py<ParsedNode>]) {
+        for node in nodes {
+            let node = node.borrow(py);
+            if let Some(tag) = node.tag.as_deref()
+                && node.component_name.is_none()
+            {
+                self.element(py, tag, &node);
+            }
+            self.check(py, &node.children);
+        }
+    }
+
+    fn element(&mut self, py: Python<'_>, tag: &str, node: &ParsedNode) {
+        if !allowed(&self.profile.elements, tag) {
+            self.error(
+                "profile-element",
+                format!("<{}> is not allowed by the profile", tag),
+                node,
+            );
+        }
+        let inline_script = tag.eq_ignore_ascii_case("script")
+            && (!node.attributes.contains_key("src") || !node.children.is_empty());
+        if inline_script && !self.profile.allow_inline_scripts {
+            self.error(
+                "profile-inline-script",
+                "inline <script> is not allowed by the profile".to_string(),
+                node,
+            );
+        }
+        if tag.eq_ignore_ascii_case("style") {
+            let bytes = node
+                .children
+                .iter()
+                .filter_map(|c| c.borrow(py).text_content.as_ref().map(String::len))
+                .sum();
+            self.add_css(bytes, node);
+        }
+
+        for attr in node.attributes.parsed() {
+            // Checked under the name they render as: `{href}` as `href`,
+            // `bind:value` as `value`, `class:x` / `style:x` as `class` /
+            // `style`. A spread's names are unknown until render time.
+            let name = match attr.kind {
+                AttrKind::Spread | AttrKind::Directive => continue,
+                AttrKind::Bind => attr.bind_target.as_deref().unwrap_or(&attr.name),
+                AttrKind::ClassToggle => "class",
+                AttrKind::StyleProperty => "style",
+                _ => attr.name.as_str(),
+            };
+            if name.starts_with('$') {
+                continue;
+            }
+            let handler =
+                attr.kind == AttrKind::Event || name.to_ascii_lowercase().starts_with("on");
+            if handler && !self.profile.allow_inline_scripts {
+                self.error(
+                    "profile-inline-script",
+                    format!(
+                        "event handler '{}' is not allowed by the profile",
+                        attr.name
+                    ),
+                    node,
+                );
+                continue;
+            }
+            if !handler && !allowed(&self.profile.attributes, name) {
+                self.error(
+                    "profile-attribute",
+                    format!(
+                        "attribute '{}' on <{}> is not allowed by the profile",
+                        name, tag
+                    ),
+                    node,
+                );
+            }
+            if attr.name == "style"
+                && let Some(value) = attr.dict_value()
+            {
+                self.add_css(value.len(), node);
+            }
+        }
+    }
+}
+
+/// Check `document` against a strict publishing profile, e.g.
+/// `StrictProfile.amp()`. Reports "profile-element", "profile-attribute",
+/// "profile-inline-script" and "profile-css-size" errors, in document order.
+#[pyfunction]
+pub fn check_profile(
+    py: Python<'_>,
+    document: PyRef<'_, ParsedDocument>,
+    profile: PyRef<'_, StrictProfile>,
+) -> Vec<Diagnostic> {
+    let _span = tracing::info_span!("analyze", pass = "profile").entered();
+    let mut checker = Checker {
+        profile: &profile,
+        css_bytes: 0,
+        css_reported: false,
+        out: Vec::new(),
+    };
+    checker.check(py, &document.template);
+    checker.out
+}
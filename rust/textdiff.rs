@@ -0,0 +1,202 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Replace `delete` units at `offset` (in the old text) with `insert`.
+///
+/// Edits are returned in ascending offset order and never overlap, so applying
+/// them back-to-front (e.g. with `CharacterData.replaceData`) keeps every
+/// offset valid.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit {
+    #[pyo3(get)]
+    pub offset: usize,
+    #[pyo3(get)]
+    pub delete: usize,
+    #[pyo3(get)]
+    pub insert: String,
+}
+
+#[pymethods]
+impl TextEdit {
+    fn __repr__(&self) -> String {
+        format!(
+            "TextEdit(offset={}, delete={}, insert={:?})",
+            self.offset, self.delete, self.insert
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// Myers' O(ND) diff. Returns None when the edit distance exceeds `max_cost`,
+// in which case callers fall back to a whole replacement.
+pub(crate) fn myers<T: PartialEq>(a: &[T], b: &[T], max_cost: usize) -> Option<Vec<Op>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = ((n + m) as usize).min(max_cost) as isize;
+    let offset = max + 1;
+    let mut v = vec![0isize; (2 * max + 3) as usize];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max {
+        // Only diagonals -(d-1)..=d-1 are read when backtracking through step
+        // d, so keep just that window: O(D^2) memory instead of O((N+M)·D).
+        let (lo, hi) = ((offset - d + 1) as usize, (offset + d) as usize);
+        trace.push(v[lo..hi.max(lo)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m, d));
+            }
+        }
+    }
+    None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize, d_end: isize) -> Vec<Op> {
+    let mut ops = Vec::with_capacity((n + m) as usize);
+    let (mut x, mut y) = (n, m);
+    for d in (0..=d_end).rev() {
+        // trace[d] is the window -(d-1)..=d-1 of V as it stood before step d
+        let window = &trace[d as usize];
+        let v = |k: isize| window[(k + d - 1) as usize];
+        let k = x - y;
+        let (prev_x, prev_y, is_insert) = if d == 0 {
+            (0, 0, false)
+        } else if k == -d || (k != d && v(k - 1) < v(k + 1)) {
+            let px = v(k + 1);
+            (px, px - (k + 1), true)
+        } else {
+            let px = v(k - 1);
+            (px, px - (k - 1), false)
+        };
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            ops.push(if is_insert { Op::Insert } else { Op::Delete });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    ops
+}
+
+fn unit_len(c: char, utf16: bool) -> usize {
+    if utf16 { c.len_utf16() } else { 1 }
+}
+
+pub(crate) fn diff_text(old: &str, new: &str, max_cost: usize, utf16: bool) -> Vec<TextEdit> {
+    let a: Vec<char> = old.chars().collect();
+    let b: Vec<char> = new.chars().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+    if a_mid.is_empty() && b_mid.is_empty() {
+        return Vec::new();
+    }
+
+    let base: usize = a[..prefix].iter().map(|&c| unit_len(c, utf16)).sum();
+    let ops = match myers(a_mid, b_mid, max_cost) {
+        Some(ops) => ops,
+        None => {
+            return vec![TextEdit {
+                offset: base,
+                delete: a_mid.iter().map(|&c| unit_len(c, utf16)).sum(),
+                insert: b_mid.iter().collect(),
+            }];
+        }
+    };
+
+    // Group runs of deletes/inserts between equal stretches into single edits.
+    let mut edits = Vec::new();
+    let (mut ai, mut bi, mut pos) = (0usize, 0usize, base);
+    let mut current: Option<TextEdit> = None;
+    for op in ops {
+        match op {
+            Op::Equal => {
+                if let Some(edit) = current.take() {
+                    pos += edit.delete;
+                    edits.push(edit);
+                }
+                pos += unit_len(a_mid[ai], utf16);
+                ai += 1;
+                bi += 1;
+            }
+            Op::Delete => {
+                let edit = current.get_or_insert_with(|| TextEdit {
+                    offset: pos,
+                    delete: 0,
+                    insert: String::new(),
+                });
+                edit.delete += unit_len(a_mid[ai], utf16);
+                ai += 1;
+            }
+            Op::Insert => {
+                let edit = current.get_or_insert_with(|| TextEdit {
+                    offset: pos,
+                    delete: 0,
+                    insert: String::new(),
+                });
+                edit.insert.push(b_mid[bi]);
+                bi += 1;
+            }
+        }
+    }
+    edits.extend(current);
+    edits
+}
+
+/// Minimal edits turning `old` into `new`, for patching large text nodes in place.
+///
+/// Offsets count UTF-16 code units (what the DOM uses) unless `unit="char"`.
+/// When the edit distance exceeds `max_cost` a single replacement of the
+/// differing middle section is returned instead.
+#[pyfunction]
+#[pyo3(signature = (old, new, max_cost=2000, unit="utf16"))]
+pub fn text_diff(
+    py: Python<'_>,
+    old: &str,
+    new: &str,
+    max_cost: usize,
+    unit: &str,
+) -> PyResult<Vec<TextEdit>> {
+    let utf16 = match unit {
+        "utf16" => true,
+        "char" => false,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown unit '{}' (expected 'utf16' or 'char')",
+                other
+            )));
+        }
+    };
+    Ok(py.detach(|| diff_text(old, new, max_cost, utf16)))
+}
@@ -0,0 +1,181 @@
+// `tracing` integration. Spans (parse, map, analyze, serialize) are always
+// compiled in; they cost a relaxed atomic load until a Python hook is set with
+// `set_trace_hook`, which installs a subscriber forwarding every closed span.
+
+use crate::ParsedNode;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HOOK: Mutex<Option<Py<PyAny>>> = Mutex::new(None);
+static INSTALL: Once = Once::new();
+
+enum FieldValue {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+struct SpanTiming {
+    start: Instant,
+    fields: Vec<(&'static str, FieldValue)>,
+}
+
+impl SpanTiming {
+    fn set(&mut self, field: &Field, value: FieldValue) {
+        match self
+            .fields
+            .iter_mut()
+            .find(|(name, _)| *name == field.name())
+        {
+            Some(slot) => slot.1 = value,
+            None => self.fields.push((field.name(), value)),
+        }
+    }
+}
+
+impl Visit for SpanTiming {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, FieldValue::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, FieldValue::UInt(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, FieldValue::Float(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, FieldValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, FieldValue::Str(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, FieldValue::Str(format!("{:?}", value)));
+    }
+}
+
+struct PythonLayer;
+
+impl PythonLayer {
+    fn emit(name: &str, parent: Option<&str>, timing: SpanTiming) {
+        let elapsed = timing.start.elapsed().as_secs_f64();
+        Python::attach(|py| {
+            let Some(hook) = HOOK
+                .lock()
+                .ok()
+                .and_then(|h| h.as_ref().map(|h| h.clone_ref(py)))
+            else {
+                return;
+            };
+            let result = (|| -> PyResult<()> {
+                let fields = PyDict::new(py);
+                if let Some(parent) = parent {
+                    fields.set_item("parent", parent)?;
+                }
+                for (key, value) in timing.fields {
+                    match value {
+                        FieldValue::Int(v) => fields.set_item(key, v)?,
+                        FieldValue::UInt(v) => fields.set_item(key, v)?,
+                        FieldValue::Float(v) => fields.set_item(key, v)?,
+                        FieldValue::Bool(v) => fields.set_item(key, v)?,
+                        FieldValue::Str(v) => fields.set_item(key, v)?,
+                    }
+                }
+                hook.call1(py, (name, elapsed, fields))?;
+                Ok(())
+            })();
+            // A failing hook must not break parsing; report it like a
+            // failing __del__ would.
+            if let Err(e) = result {
+                e.write_unraisable(py, None);
+            }
+        });
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for PythonLayer {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // Re-check `enabled` on every use so the hook can be toggled.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, _metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut timing = SpanTiming {
+            start: Instant::now(),
+            fields: Vec::new(),
+        };
+        attrs.record(&mut timing);
+        span.extensions_mut().insert(timing);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>()
+        {
+            values.record(timing);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<SpanTiming>() else {
+            return;
+        };
+        let parent = span.parent().map(|p| p.name());
+        Self::emit(span.name(), parent, timing);
+    }
+}
+
+// Total number of nodes in a mapped tree, for span fields.
+pub(crate) fn count_nodes(py: Python<'_>, nodes: &[Py<ParsedNode>]) -> usize {
+    nodes
+        .iter()
+        .map(|n| 1 + count_nodes(py, &n.borrow(py).children))
+        .sum()
+}
+
+/// Forward parser spans to `hook(name, seconds, fields)` as they close.
+///
+/// Spans are `parse`, `map`, `analyze` (with a `pass` field) and `serialize`;
+/// `fields` holds counts such as `nodes` or `bytes` plus the enclosing span
+/// name as `parent`. Pass None to switch tracing off again.
+#[pyfunction]
+#[pyo3(signature = (hook=None))]
+pub fn set_trace_hook(hook: Option<Py<PyAny>>) {
+    INSTALL.call_once(|| {
+        // Fails only if a subscriber is already installed in this module.
+        let _ = tracing::subscriber::set_global_default(Registry::default().with(PythonLayer));
+    });
+    let enabled = hook.is_some();
+    if let Ok(mut slot) = HOOK.lock() {
+        *slot = hook;
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
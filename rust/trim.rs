@@ -0,0 +1,66 @@
+// Jinja-style whitespace control: `{$if cond -}`, `{- /if}`, `{-expr-}`. A
+// `-` just inside the opening brace trims whitespace before the node, one
+// just inside the closing brace trims whitespace after it.
+
+use crate::ParsedNode;
+use pyo3::prelude::*;
+
+// Split the markers off the text between a node's braces: `- /if` ->
+// `(true, "/if", false)`. A leading `-` only counts when followed by
+// whitespace, `$` or `/`, or when there is a trailing marker, so `{-x}` stays
+// a negation.
+pub(crate) fn split_markers(inner: &str) -> (bool, &str, bool) {
+    let mut body = inner.trim();
+    let after = body.ends_with('-') && !body.ends_with("--");
+    if after {
+        body = body[..body.len() - 1].trim_end();
+    }
+    let before = body.strip_prefix('-').is_some_and(|rest| {
+        after || rest.starts_with(|c: char| c.is_whitespace() || c == '$' || c == '/')
+    });
+    if before {
+        body = body[1..].trim_start();
+    }
+    (before, body, after)
+}
+
+fn trim_text(py: Python<'_>, node: &Py<ParsedNode>, trim: fn(&str) -> &str) {
+    let mut node = node.borrow_mut(py);
+    if node.tag.is_none()
+        && !node.is_block
+        && !node.is_raw
+        && !node.is_comment
+        && let Some(text) = &node.text_content
+    {
+        node.text_content = Some(trim(text).to_string());
+    }
+}
+
+// Trim the text siblings next to marked nodes, at every level, and drop text
+// nodes left empty. Runs before block pairing, which indexes siblings.
+pub(crate) fn apply_markers(py: Python<'_>, nodes: Vec<Py<ParsedNode>>) -> Vec<Py<ParsedNode>> {
+    for node in &nodes {
+        let mut n = node.borrow_mut(py);
+        let children = std::mem::take(&mut n.children);
+        n.children = apply_markers(py, children);
+    }
+    for i in 0..nodes.len() {
+        let (before, after) = {
+            let n = nodes[i].borrow(py);
+            (n.trim_before, n.trim_after)
+        };
+        if before && i > 0 {
+            trim_text(py, &nodes[i - 1], str::trim_end);
+        }
+        if after && i + 1 < nodes.len() {
+            trim_text(py, &nodes[i + 1], str::trim_start);
+        }
+    }
+    nodes
+        .into_iter()
+        .filter(|node| {
+            let n = node.borrow(py);
+            n.is_block || n.is_comment || n.tag.is_some() || n.text_content.as_deref() != Some("")
+        })
+        .collect()
+}
@@ -0,0 +1,184 @@
+// Opt-in Unicode hygiene for template expressions: NFC-normalize them and
+// flag invisible or confusable characters, which compile fine but make
+// `{total}` and `{total}` different names.
+
+use crate::ParsedNode;
+use crate::assets::offset_of;
+use crate::diagnostics::Diagnostic;
+use pyo3::prelude::*;
+use unicode_normalization::{UnicodeNormalization, is_nfc};
+
+const INVISIBLE: &[(char, &str)] = &[
+    ('\u{00A0}', "NO-BREAK SPACE"),
+    ('\u{00AD}', "SOFT HYPHEN"),
+    ('\u{180E}', "MONGOLIAN VOWEL SEPARATOR"),
+    ('\u{200B}', "ZERO WIDTH SPACE"),
+    ('\u{200C}', "ZERO WIDTH NON-JOINER"),
+    ('\u{200D}', "ZERO WIDTH JOINER"),
+    ('\u{200E}', "LEFT-TO-RIGHT MARK"),
+    ('\u{200F}', "RIGHT-TO-LEFT MARK"),
+    ('\u{202A}', "LEFT-TO-RIGHT EMBEDDING"),
+    ('\u{202B}', "RIGHT-TO-LEFT EMBEDDING"),
+    ('\u{202C}', "POP DIRECTIONAL FORMATTING"),
+    ('\u{202D}', "LEFT-TO-RIGHT OVERRIDE"),
+    ('\u{202E}', "RIGHT-TO-LEFT OVERRIDE"),
+    ('\u{2060}', "WORD JOINER"),
+    ('\u{2066}', "LEFT-TO-RIGHT ISOLATE"),
+    ('\u{2067}', "RIGHT-TO-LEFT ISOLATE"),
+    ('\u{2068}', "FIRST STRONG ISOLATE"),
+    ('\u{2069}', "POP DIRECTIONAL ISOLATE"),
+    ('\u{FEFF}', "ZERO WIDTH NO-BREAK SPACE"),
+];
+
+// Cyrillic and Greek letters that render like Latin ones.
+const CONFUSABLES: &[(char, char)] = &[
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('ԁ', 'd'),
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('Н', 'H'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('ο', 'o'),
+    ('ν', 'v'),
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+];
+
+fn lookalike(c: char) -> Option<char> {
+    // Fullwidth ASCII block.
+    if ('\u{FF01}'..='\u{FF5E}').contains(&c) {
+        return char::from_u32(c as u32 - 0xFEE0);
+    }
+    CONFUSABLES
+        .iter()
+        .find(|(from, _)| *from == c)
+        .map(|(_, to)| *to)
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    (line, offset - before.rfind('\n').map_or(0, |i| i + 1))
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    // Flag suspicious characters in `expr`, located in the source at or
+    // after the node's start.
+    fn scan(&mut self, node: &ParsedNode, expr: &str) {
+        if expr.is_ascii() {
+            return;
+        }
+        let start = offset_of(self.source, node.line, node.column);
+        let base = self
+            .source
+            .get(start..)
+            .and_then(|rest| rest.find(expr))
+            .map(|i| start + i);
+        let mut quote = None;
+        for (i, c) in expr.char_indices() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                None if c == '"' || c == '\'' => quote = Some(c),
+                _ => {}
+            }
+            let (line, column) = match base {
+                Some(base) => line_column(self.source, base + i),
+                None => (node.line, node.column),
+            };
+            if let Some((_, name)) = INVISIBLE.iter().find(|(ch, _)| *ch == c) {
+                self.diagnostics.push(Diagnostic::warning(
+                    "invisible-character",
+                    format!("invisible U+{:04X} {} in expression", c as u32, name),
+                    line,
+                    column,
+                ));
+            } else if quote.is_none()
+                && let Some(ascii) = lookalike(c)
+            {
+                self.diagnostics.push(Diagnostic::warning(
+                    "confusable-character",
+                    format!(
+                        "'{}' (U+{:04X}) in expression looks like '{}'",
+                        c, c as u32, ascii
+                    ),
+                    line,
+                    column,
+                ));
+            }
+        }
+    }
+}
+
+fn normalized(value: &str) -> Option<String> {
+    (!is_nfc(value)).then(|| value.nfc().collect())
+}
+
+// The `normalize_unicode=True` pass over a mapped template.
+pub(crate) fn normalize_expressions(
+    py: Python<'_>,
+    source: &str,
+    nodes: &[Py<ParsedNode>],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for node in nodes {
+        let mut node = node.borrow_mut(py);
+        let mut checker = Checker {
+            source,
+            diagnostics: &mut *diagnostics,
+        };
+        if let Some(expr) = node.expression.clone() {
+            checker.scan(&node, &expr);
+            if let Some(nfc) = normalized(&expr) {
+                node.expression = Some(nfc);
+            }
+        }
+        let mut names: Vec<String> = node
+            .attributes
+            .iter()
+            .filter(|(_, v)| v.as_deref().is_some_and(|v| v.contains('{')))
+            .map(|(k, _)| k.clone())
+            .collect();
+        names.sort();
+        for name in names {
+            let value = node.attributes[&name].clone().unwrap_or_default();
+            checker.scan(&node, &value);
+            if let Some(nfc) = normalized(&value) {
+                node.attributes.insert(name, Some(nfc));
+            }
+        }
+        normalize_expressions(py, source, &node.children, diagnostics);
+    }
+}
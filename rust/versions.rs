@@ -0,0 +1,156 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Source of `VersionStore` identities, so a snapshot can name its store.
+static NEXT_STORE: AtomicU64 = AtomicU64::new(0);
+
+/// Per-wire version counters plus a global logical clock.
+///
+/// Every bump advances the clock and stamps the wire with it, so "did any of
+/// these wires change since snapshot X" is a single pass comparing stamps
+/// against the clock value captured in the snapshot.
+#[pyclass]
+pub struct VersionStore {
+    // Identity stamped on snapshots; wire ids only mean something here.
+    store: u64,
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+    versions: Vec<u64>,
+    stamps: Vec<u64>,
+    clock: u64,
+}
+
+/// The clock value and wire set captured by `VersionStore.snapshot`.
+#[pyclass(frozen)]
+pub struct VersionSnapshot {
+    #[pyo3(get)]
+    pub clock: u64,
+    store: u64,
+    wires: Vec<u32>,
+}
+
+impl VersionStore {
+    fn intern(&mut self, wire: &str) -> u32 {
+        if let Some(&id) = self.ids.get(wire) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(wire.to_string(), id);
+        self.names.push(wire.to_string());
+        self.versions.push(0);
+        self.stamps.push(0);
+        id
+    }
+
+    fn check_owner(&self, snapshot: &VersionSnapshot) -> PyResult<()> {
+        if snapshot.store != self.store {
+            return Err(PyValueError::new_err(
+                "Snapshot was taken from a different VersionStore",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl VersionStore {
+    #[new]
+    fn new() -> Self {
+        VersionStore {
+            store: NEXT_STORE.fetch_add(1, Ordering::Relaxed),
+            ids: HashMap::new(),
+            names: Vec::new(),
+            versions: Vec::new(),
+            stamps: Vec::new(),
+            clock: 0,
+        }
+    }
+
+    /// Record a change to `wire` and return its new version.
+    fn bump(&mut self, wire: &str) -> u64 {
+        let id = self.intern(wire) as usize;
+        self.clock += 1;
+        self.versions[id] += 1;
+        self.stamps[id] = self.clock;
+        self.versions[id]
+    }
+
+    fn bump_many(&mut self, wires: Vec<String>) {
+        for wire in &wires {
+            self.bump(wire);
+        }
+    }
+
+    /// Current version of `wire` (0 if it has never changed).
+    fn version(&self, wire: &str) -> u64 {
+        self.ids
+            .get(wire)
+            .map_or(0, |&id| self.versions[id as usize])
+    }
+
+    #[getter]
+    fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Capture the current clock for a set of wires (typically a memoized region's inputs).
+    fn snapshot(&mut self, wires: Vec<String>) -> VersionSnapshot {
+        VersionSnapshot {
+            clock: self.clock,
+            store: self.store,
+            wires: wires.iter().map(|w| self.intern(w)).collect(),
+        }
+    }
+
+    /// True if any wire captured in `snapshot` has changed since it was taken.
+    /// Raises ValueError for a snapshot of another store.
+    fn changed_since(&self, snapshot: PyRef<'_, VersionSnapshot>) -> PyResult<bool> {
+        self.check_owner(&snapshot)?;
+        Ok(snapshot
+            .wires
+            .iter()
+            .any(|&id| self.stamps[id as usize] > snapshot.clock))
+    }
+
+    /// The wires captured in `snapshot` that have changed since it was taken.
+    /// Raises ValueError for a snapshot of another store.
+    fn changed_wires(&self, snapshot: PyRef<'_, VersionSnapshot>) -> PyResult<Vec<String>> {
+        self.check_owner(&snapshot)?;
+        Ok(snapshot
+            .wires
+            .iter()
+            .filter(|&&id| self.stamps[id as usize] > snapshot.clock)
+            .map(|&id| self.names[id as usize].clone())
+            .collect())
+    }
+
+    /// True if any of `wires` changed after the given clock value.
+    fn any_changed_after(&self, wires: Vec<String>, clock: u64) -> bool {
+        wires.iter().any(|w| {
+            self.ids
+                .get(w)
+                .is_some_and(|&id| self.stamps[id as usize] > clock)
+        })
+    }
+
+    fn __len__(&self) -> usize {
+        self.names.len()
+    }
+}
+
+#[pymethods]
+impl VersionSnapshot {
+    fn __len__(&self) -> usize {
+        self.wires.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "VersionSnapshot(clock={}, wires={})",
+            self.clock,
+            self.wires.len()
+        )
+    }
+}
@@ -0,0 +1,684 @@
+// State analyses combining the frontmatter with template references:
+// - wire candidates: plain variables the template renders and that change
+//   after initialization. Without `wire(...)` those changes never reach the
+//   page, which is easy to miss.
+// - unused wires and template names that nothing defines.
+
+use crate::diagnostics::Diagnostic;
+use crate::frontmatter::module_names;
+use crate::kinds::AttrKind;
+use crate::reactive::{KEYWORDS, assigned, assignments};
+use crate::routes::free_identifiers;
+use crate::{ParsedDocument, ParsedNode};
+use pyo3::prelude::*;
+
+// Methods that mutate lists, dicts and sets in place.
+const MUTATORS: &[&str] = &[
+    "append",
+    "extend",
+    "insert",
+    "pop",
+    "remove",
+    "clear",
+    "update",
+    "add",
+    "discard",
+    "setdefault",
+    "sort",
+    "reverse",
+    "popitem",
+];
+
+/// A frontmatter variable that should probably be a wire.
+///
+/// `line`/`column` locate its initial assignment and `fix` is the suggested
+/// replacement line. `references` and `mutations` are `(line, column)` pairs.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct WireSuggestion {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub fix: String,
+    #[pyo3(get)]
+    pub references: Vec<(usize, usize)>,
+    #[pyo3(get)]
+    pub mutations: Vec<(usize, usize)>,
+}
+
+#[pymethods]
+impl WireSuggestion {
+    fn __repr__(&self) -> String {
+        format!(
+            "WireSuggestion({} at {}:{})",
+            self.name, self.line, self.column
+        )
+    }
+}
+
+struct Candidate {
+    name: String,
+    line: usize,
+    column: usize,
+    value: String,
+    references: Vec<(usize, usize)>,
+    mutations: Vec<(usize, usize)>,
+}
+
+// `name = value` or `name: Type = value` with a single plain target.
+pub(crate) fn simple_assignment(line: &str) -> Option<(&str, &str)> {
+    let (eqs, augmented) = assignments(line);
+    if eqs.len() != 1 || augmented {
+        return None;
+    }
+    // `name: Type = value` annotates the target.
+    let target = &line[..eqs[0]];
+    let name = target
+        .split_once(':')
+        .map_or(target, |(name, _)| name)
+        .trim();
+    let value = line[eqs[0] + 1..].trim();
+    let is_ident = !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit());
+    (is_ident && !KEYWORDS.contains(&name)).then_some((name, value))
+}
+
+// Constructor of a wire declaration's value: `wire` in `wire(0)` or
+// `pywire.wire(0)`, `WireList` in `WireList([])`.
+pub(crate) fn wire_constructor(value: &str) -> Option<&str> {
+    let (callee, _) = value.split_once('(')?;
+    let name = callee.trim().rsplit('.').next()?;
+    let is_class = name
+        .strip_prefix("Wire")
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_alphanumeric()));
+    (name == "wire" || is_class).then_some(name)
+}
+
+fn is_wire_call(value: &str) -> bool {
+    wire_constructor(value).is_some()
+}
+
+// `(name, line)` of the frontmatter's top-level `name = wire(...)`.
+pub(crate) fn declared_wires(doc: &ParsedDocument) -> Vec<(&str, usize)> {
+    doc.python_code
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.starts_with(char::is_whitespace))
+        .filter_map(|(i, text)| {
+            let (name, value) = simple_assignment(text)?;
+            is_wire_call(value).then_some((name, doc.python_line + i))
+        })
+        .collect()
+}
+
+// Whether `line` mutates `name` in place (`name.append(...)`, `del name[k]`).
+fn mutates_in_place(line: &str, name: &str) -> bool {
+    let bytes = line.as_bytes();
+    line.match_indices(name).any(|(i, _)| {
+        let before = i.checked_sub(1).map(|b| bytes[b]);
+        if before.is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'.') {
+            return false;
+        }
+        let rest = &line[i + name.len()..];
+        if let Some(call) = rest.strip_prefix('.') {
+            let method = call.split('(').next().unwrap_or("");
+            return call.len() > method.len() && MUTATORS.contains(&method);
+        }
+        rest.starts_with('[') && line[..i].trim_end().ends_with("del")
+    })
+}
+
+// Record `text` at `at` as a mutation of every candidate it changes, other
+// than its own initial assignment.
+fn note_mutations(candidates: &mut [Candidate], at: (usize, usize), text: &str) {
+    let mut written = Vec::new();
+    assigned(text, &assignments(text).0, &mut written);
+    for c in candidates.iter_mut() {
+        if (c.line, c.column) == at {
+            continue;
+        }
+        if written.contains(&c.name) || mutates_in_place(text, &c.name) {
+            c.mutations.push(at);
+        }
+    }
+}
+
+// `class:` and `style:` attributes, whose valueless form reads a variable.
+fn is_conditional(kind: AttrKind) -> bool {
+    matches!(kind, AttrKind::ClassToggle | AttrKind::StyleProperty)
+}
+
+fn walk(py: Python<'_>, nodes: &[Py<ParsedNode>], candidates: &mut [Candidate]) {
+    for node in nodes {
+        let node = node.borrow(py);
+        let at = (node.line, node.column);
+        let mut reads: Vec<&str> = Vec::new();
+        for attr in node.attributes.parsed() {
+            if attr.is_shorthand {
+                reads.push(&attr.name);
+            }
+            if is_conditional(attr.kind) {
+                reads.extend(free_identifiers(
+                    attr.expression.as_deref().unwrap_or_default(),
+                ));
+                continue;
+            }
+            let Some(value) = attr.dict_value().filter(|v| v.starts_with('{')) else {
+                continue;
+            };
+            if attr.kind == AttrKind::Event {
+                note_mutations(candidates, at, value.trim_matches(['{', '}']));
+            } else if attr.kind == AttrKind::Bind
+                && let Some(expr) = &attr.expression
+            {
+                // A binding reads its target and writes user input back.
+                reads.extend(free_identifiers(value));
+                note_mutations(candidates, at, &format!("{} = None", expr));
+            } else {
+                reads.extend(free_identifiers(value));
+            }
+        }
+        if let Some(expr) = &node.expression {
+            reads.extend(free_identifiers(expr));
+        }
+        for c in candidates.iter_mut() {
+            if reads.contains(&c.name.as_str()) && !c.references.contains(&at) {
+                c.references.push(at);
+            }
+        }
+        walk(py, &node.children, candidates);
+    }
+}
+
+/// Suggest frontmatter variables of `document` that should be wires: those
+/// referenced in the template and reassigned or mutated after their initial
+/// assignment. Silence one with a `wire-candidate` suppression pragma.
+#[pyfunction]
+pub fn suggest_wires(py: Python<'_>, document: PyRef<'_, ParsedDocument>) -> Vec<WireSuggestion> {
+    let _span = tracing::info_span!("analyze", pass = "wires").entered();
+    let doc = &*document;
+    let lines: Vec<(usize, &str)> = doc
+        .python_code
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (doc.python_line + i, l))
+        .collect();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for &(line, text) in &lines {
+        if text.starts_with(char::is_whitespace) {
+            continue;
+        }
+        if let Some((name, value)) = simple_assignment(text)
+            && !is_wire_call(value)
+            && !candidates.iter().any(|c| c.name == name)
+        {
+            candidates.push(Candidate {
+                name: name.to_string(),
+                line,
+                column: 0,
+                value: value.to_string(),
+                references: Vec::new(),
+                mutations: Vec::new(),
+            });
+        }
+    }
+    for &(line, text) in &lines {
+        let column = text.len() - text.trim_start().len();
+        note_mutations(&mut candidates, (line, column), text.trim_start());
+    }
+    walk(py, &doc.template, &mut candidates);
+
+    candidates
+        .into_iter()
+        .filter(|c| !c.references.is_empty() && !c.mutations.is_empty())
+        .filter(|c| !doc.suppressions.is_suppressed("wire-candidate", c.line))
+        .map(|c| WireSuggestion {
+            message: format!(
+                "'{}' is rendered in the template and changed after it is set; \
+                 wrap it in wire() so updates reach the page",
+                c.name
+            ),
+            fix: format!("{} = wire({})", c.name, c.value),
+            name: c.name,
+            line: c.line,
+            column: c.column,
+            references: c.references,
+            mutations: c.mutations,
+        })
+        .collect()
+}
+
+// Names Python provides without a definition in the frontmatter: every name
+// in `dir(builtins)` (Python 3.11, with `site` loaded), plus
+// `PythonFinalizationError` from 3.13.
+pub(crate) const BUILTINS: &[&str] = &[
+    "ArithmeticError",
+    "AssertionError",
+    "AttributeError",
+    "BaseException",
+    "BaseExceptionGroup",
+    "BlockingIOError",
+    "BrokenPipeError",
+    "BufferError",
+    "BytesWarning",
+    "ChildProcessError",
+    "ConnectionAbortedError",
+    "ConnectionError",
+    "ConnectionRefusedError",
+    "ConnectionResetError",
+    "DeprecationWarning",
+    "EOFError",
+    "Ellipsis",
+    "EncodingWarning",
+    "EnvironmentError",
+    "Exception",
+    "ExceptionGroup",
+    "False",
+    "FileExistsError",
+    "FileNotFoundError",
+    "FloatingPointError",
+    "FutureWarning",
+    "GeneratorExit",
+    "IOError",
+    "ImportError",
+    "ImportWarning",
+    "IndentationError",
+    "IndexError",
+    "InterruptedError",
+    "IsADirectoryError",
+    "KeyError",
+    "KeyboardInterrupt",
+    "LookupError",
+    "MemoryError",
+    "ModuleNotFoundError",
+    "NameError",
+    "None",
+    "NotADirectoryError",
+    "NotImplemented",
+    "NotImplementedError",
+    "OSError",
+    "OverflowError",
+    "PendingDeprecationWarning",
+    "PermissionError",
+    "ProcessLookupError",
+    "PythonFinalizationError",
+    "RecursionError",
+    "ReferenceError",
+    "ResourceWarning",
+    "RuntimeError",
+    "RuntimeWarning",
+    "StopAsyncIteration",
+    "StopIteration",
+    "SyntaxError",
+    "SyntaxWarning",
+    "SystemError",
+    "SystemExit",
+    "TabError",
+    "TimeoutError",
+    "True",
+    "TypeError",
+    "UnboundLocalError",
+    "UnicodeDecodeError",
+    "UnicodeEncodeError",
+    "UnicodeError",
+    "UnicodeTranslateError",
+    "UnicodeWarning",
+    "UserWarning",
+    "ValueError",
+    "Warning",
+    "ZeroDivisionError",
+    "__build_class__",
+    "__debug__",
+    "__doc__",
+    "__import__",
+    "__loader__",
+    "__name__",
+    "__package__",
+    "__spec__",
+    "abs",
+    "aiter",
+    "all",
+    "anext",
+    "any",
+    "ascii",
+    "bin",
+    "bool",
+    "breakpoint",
+    "bytearray",
+    "bytes",
+    "callable",
+    "chr",
+    "classmethod",
+    "compile",
+    "complex",
+    "copyright",
+    "credits",
+    "delattr",
+    "dict",
+    "dir",
+    "divmod",
+    "enumerate",
+    "eval",
+    "exec",
+    "exit",
+    "filter",
+    "float",
+    "format",
+    "frozenset",
+    "getattr",
+    "globals",
+    "hasattr",
+    "hash",
+    "help",
+    "hex",
+    "id",
+    "input",
+    "int",
+    "isinstance",
+    "issubclass",
+    "iter",
+    "len",
+    "license",
+    "list",
+    "locals",
+    "map",
+    "max",
+    "memoryview",
+    "min",
+    "next",
+    "object",
+    "oct",
+    "open",
+    "ord",
+    "pow",
+    "print",
+    "property",
+    "quit",
+    "range",
+    "repr",
+    "reversed",
+    "round",
+    "set",
+    "setattr",
+    "slice",
+    "sorted",
+    "staticmethod",
+    "str",
+    "sum",
+    "super",
+    "tuple",
+    "type",
+    "vars",
+    "zip",
+];
+
+// Names the runtime injects into every page.
+const RUNTIME_NAMES: &[&str] = &[
+    "self", "wire", "request", "params", "query", "session", "cookies", "headers",
+];
+
+// Byte offset of `ident` (a slice of `src`) within `src`.
+pub(crate) fn offset_in(src: &str, ident: &str) -> usize {
+    ident.as_ptr() as usize - src.as_ptr() as usize
+}
+
+// Free names read by expression `src`, leaving out keyword arguments and
+// names bound by comprehensions and lambdas inside it.
+pub(crate) fn expression_reads(src: &str) -> Vec<&str> {
+    let idents = free_identifiers(src);
+    let mut bound: Vec<&str> = Vec::new();
+    let mut binding = false;
+    for &ident in &idents {
+        match ident {
+            "for" => binding = true,
+            "in" => binding = false,
+            _ if binding => bound.push(ident),
+            _ => {}
+        }
+    }
+    for (i, _) in src.match_indices("lambda") {
+        let params = &src[i + 6..];
+        let params = &params[..params.find(':').unwrap_or(params.len())];
+        bound.extend(free_identifiers(params));
+    }
+    idents
+        .into_iter()
+        .filter(|ident| {
+            let rest = src[offset_in(src, ident) + ident.len()..].trim_start();
+            let kwarg = rest.starts_with('=') && !rest.starts_with("==");
+            !kwarg && !bound.contains(ident) && !KEYWORDS.contains(ident)
+        })
+        .collect()
+}
+
+// Part of statement `line` that is read: everything after the last plain
+// assignment `=`, or all of it.
+fn statement_reads_src(line: &str) -> &str {
+    match assignments(line).0.last() {
+        Some(&last) => &line[last + 1..],
+        None => line,
+    }
+}
+
+// Names bound at module level by frontmatter line `text`.
+pub(crate) fn definitions(text: &str, out: &mut Vec<String>) {
+    let first = |s: &str| {
+        let end = s
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(s.len());
+        s[..end].to_string()
+    };
+    let stmt = text.strip_prefix("async ").unwrap_or(text);
+    if let Some(rest) = stmt
+        .strip_prefix("def ")
+        .or_else(|| stmt.strip_prefix("class "))
+    {
+        out.push(first(rest.trim_start()));
+    } else if let Some(rest) = stmt.strip_prefix("import ") {
+        for part in rest.split(',') {
+            let part = part.trim();
+            let name = part.rsplit_once(" as ").map_or(part, |(_, alias)| alias);
+            out.push(first(name.trim()));
+        }
+    } else if let Some((_, names)) = stmt
+        .strip_prefix("from ")
+        .and_then(|rest| rest.split_once(" import "))
+    {
+        for part in names.trim_matches(['(', ')', ' ']).split(',') {
+            let part = part.trim();
+            let name = part.rsplit_once(" as ").map_or(part, |(_, alias)| alias);
+            out.push(first(name.trim()));
+        }
+    } else {
+        assigned(text, &assignments(text).0, out);
+    }
+}
+
+#[derive(Default)]
+struct TemplateRefs {
+    reads: Vec<(String, (usize, usize))>,
+    bound: Vec<String>,
+}
+
+// Split the `, key=expr` clause off a loop expression (`item in items,
+// key=item.id`), as the Python parser does.
+pub(crate) fn split_loop_key(expr: &str) -> (&str, Option<&str>) {
+    match expr.rsplit_once(", key=") {
+        Some((head, key)) => (head, Some(key.trim())),
+        None => (expr, None),
+    }
+}
+
+// Names bound by a block marker: `{$for x in ...}`, `{$except E as e}`,
+// `{$then value}` and `{$catch err}`.
+pub(crate) fn block_bindings(keyword: &str, expr: &str, out: &mut Vec<String>) -> Option<usize> {
+    let (names, reads_from) = match keyword {
+        "for" => {
+            let at = expr.find(" in ")?;
+            (&expr[..at], at + 4)
+        }
+        "except" => {
+            let at = expr.rfind(" as ")?;
+            (&expr[at + 4..], 0)
+        }
+        "then" | "catch" => (expr, expr.len()),
+        "case" => {
+            let at = expr.find(" if ").map_or(expr.len(), |at| at + 4);
+            out.extend(pattern_captures(&expr[..at]).map(str::to_string));
+            return Some(at);
+        }
+        _ => return Some(0),
+    };
+    out.extend(free_identifiers(names).into_iter().map(str::to_string));
+    Some(reads_from)
+}
+
+// Names a `{$case}` pattern captures: bare identifiers that are not class
+// patterns, dotted values, keyword-pattern keys or the `_` wildcard.
+fn pattern_captures(pattern: &str) -> impl Iterator<Item = &str> {
+    free_identifiers(pattern).into_iter().filter(move |ident| {
+        let rest = pattern[offset_in(pattern, ident) + ident.len()..].trim_start();
+        let kwarg = rest.starts_with('=') && !rest.starts_with("==");
+        !kwarg && !rest.starts_with(['(', '.']) && *ident != "_" && !KEYWORDS.contains(ident)
+    })
+}
+
+fn collect_refs(py: Python<'_>, nodes: &[Py<ParsedNode>], refs: &mut TemplateRefs) {
+    for node in nodes {
+        let node = node.borrow(py);
+        let at = (node.line, node.column);
+        let note = |reads: &mut Vec<_>, src: &str| {
+            for ident in expression_reads(src) {
+                reads.push((ident.to_string(), at));
+            }
+        };
+        for attr in node.attributes.parsed() {
+            if attr.is_shorthand {
+                note(&mut refs.reads, &attr.name);
+                continue;
+            }
+            if is_conditional(attr.kind) {
+                note(
+                    &mut refs.reads,
+                    attr.expression.as_deref().unwrap_or_default(),
+                );
+                continue;
+            }
+            let Some(value) = attr.dict_value().filter(|v| v.starts_with('{')) else {
+                continue;
+            };
+            let value = value.trim_start_matches('{').trim_end_matches('}');
+            if attr.kind == AttrKind::Event {
+                note(&mut refs.reads, statement_reads_src(value));
+            } else {
+                note(&mut refs.reads, value);
+            }
+        }
+        if let Some(expr) = &node.expression {
+            let keyword = node.block_keyword.as_deref().unwrap_or("");
+            let from = block_bindings(keyword, expr, &mut refs.bound).unwrap_or(0);
+            note(&mut refs.reads, &expr[from..]);
+        }
+        collect_refs(py, &node.children, refs);
+    }
+}
+
+// "undefined-name" warnings at template reads of names that neither the
+// frontmatter (`defined`), `!const` declarations, the template itself nor
+// Python or the runtime define.
+fn undefined_names(refs: &TemplateRefs, defined: &[String], constants: &[&str]) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    let mut reported: Vec<(&str, (usize, usize))> = Vec::new();
+    for (name, at) in &refs.reads {
+        let bare = name.trim_start_matches('$');
+        let known = defined.iter().chain(&refs.bound).any(|d| d == bare)
+            || constants.contains(&bare)
+            || BUILTINS.contains(&bare)
+            || RUNTIME_NAMES.contains(&bare);
+        if known || reported.contains(&(bare, *at)) {
+            continue;
+        }
+        reported.push((bare, *at));
+        out.push(Diagnostic::warning(
+            "undefined-name",
+            format!("'{}' is not defined in the frontmatter", bare),
+            at.0,
+            at.1,
+        ));
+    }
+    out
+}
+
+// `parse(..., validate_names=True)`: "undefined-name" warnings for
+// `template`, before suppression pragmas are applied.
+pub(crate) fn validate_names(
+    py: Python<'_>,
+    python_code: &str,
+    template: &[Py<ParsedNode>],
+    constants: &[&str],
+) -> Vec<Diagnostic> {
+    let mut refs = TemplateRefs::default();
+    collect_refs(py, template, &mut refs);
+    undefined_names(&refs, &module_names(python_code), constants)
+}
+
+/// Check how `document` uses its state. Reports "unused-wire" warnings at
+/// wires declared in the frontmatter that neither the template nor other
+/// frontmatter code reads, and "undefined-name" warnings at template
+/// references to names nothing defines. Suppression pragmas apply.
+#[pyfunction]
+pub fn check_state(py: Python<'_>, document: PyRef<'_, ParsedDocument>) -> Vec<Diagnostic> {
+    let _span = tracing::info_span!("analyze", pass = "state").entered();
+    let doc = &*document;
+    let wires = declared_wires(doc);
+    let mut frontmatter_reads = Vec::new();
+    for (i, text) in doc.python_code.lines().enumerate() {
+        let line = doc.python_line + i;
+        for ident in expression_reads(statement_reads_src(text.trim())) {
+            frontmatter_reads.push((ident, line));
+        }
+    }
+    let mut refs = TemplateRefs::default();
+    collect_refs(py, &doc.template, &mut refs);
+
+    let mut out = Vec::new();
+    for &(name, line) in &wires {
+        let read = refs
+            .reads
+            .iter()
+            .any(|(n, _)| n.trim_start_matches('$') == name)
+            || frontmatter_reads
+                .iter()
+                .any(|&(n, l)| n.trim_start_matches('$') == name && l != line);
+        if !read {
+            out.push(Diagnostic::warning(
+                "unused-wire",
+                format!(
+                    "wire '{}' is never read by the template or other wires",
+                    name
+                ),
+                line,
+                0,
+            ));
+        }
+    }
+    let constants: Vec<&str> = doc
+        .constants
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    out.extend(undefined_names(
+        &refs,
+        &module_names(&doc.python_code),
+        &constants,
+    ));
+    out.retain(|d| !doc.suppressions.is_suppressed(&d.code, d.line));
+    out
+}
@@ -95,6 +95,7 @@ setup(
             "pywire._pywire_parser",
             path="Cargo.toml",
             binding=Binding.PyO3,
+            features=["pyo3/extension-module", "compression", "parallel"],
         )
     ],
     cmdclass={
@@ -0,0 +1,101 @@
+"""Parser benchmarks for `pywire bench`."""
+
+import platform
+import statistics
+import time
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Any, Dict, Iterable, List
+
+from pywire import __version__, _pywire_parser
+
+# Bumped when the layout of the JSON report changes.
+REPORT_VERSION = 1
+
+MODES = {"parse": False, "parse_resolved": True}
+
+
+@dataclass
+class Regression:
+    name: str
+    mode: str
+    baseline_ns: int
+    current_ns: int
+
+    @property
+    def percent(self) -> float:
+        return (self.current_ns / self.baseline_ns - 1) * 100
+
+
+def collect_templates(paths: Iterable[Path]) -> List[Path]:
+    """Expand files and directories (searched for *.wire) into a sorted list."""
+    found = set()
+    for path in paths:
+        if path.is_dir():
+            found.update(path.rglob("*.wire"))
+        else:
+            found.add(path)
+    return sorted(found)
+
+
+def _time(source: str, resolved: bool, iterations: int) -> List[int]:
+    samples = []
+    for _ in range(iterations):
+        started = time.perf_counter_ns()
+        _pywire_parser.parse(source, lenient=True, resolved=resolved)
+        samples.append(time.perf_counter_ns() - started)
+    return samples
+
+
+def run_benchmarks(
+    files: List[Path], iterations: int = 50, warmup: int = 5
+) -> Dict[str, Any]:
+    """Time lenient parsing of each file, flat and resolved.
+
+    Returns a JSON-ready report; entries are keyed by file name and mode so
+    reports from different runs can be compared with `compare_reports`.
+    """
+    benchmarks = []
+    for path in files:
+        source = path.read_text("utf-8")
+        for mode, resolved in MODES.items():
+            _time(source, resolved, warmup)
+            samples = _time(source, resolved, iterations)
+            benchmarks.append(
+                {
+                    "name": path.as_posix(),
+                    "mode": mode,
+                    "bytes": len(source.encode("utf-8")),
+                    "iterations": iterations,
+                    "min_ns": min(samples),
+                    "median_ns": int(statistics.median(samples)),
+                    "mean_ns": int(statistics.fmean(samples)),
+                }
+            )
+    return {
+        "version": REPORT_VERSION,
+        "pywire": __version__,
+        "python": platform.python_version(),
+        "benchmarks": benchmarks,
+    }
+
+
+def compare_reports(
+    current: Dict[str, Any], baseline: Dict[str, Any], max_regression: float
+) -> List[Regression]:
+    """Benchmarks whose median is more than `max_regression` percent slower
+    than in `baseline`. Entries missing from either report are ignored."""
+    if baseline.get("version") != REPORT_VERSION:
+        version = baseline.get("version")
+        raise ValueError(f"Report version {version!r} is not {REPORT_VERSION}")
+    before = {(b["name"], b["mode"]): b["median_ns"] for b in baseline["benchmarks"]}
+    regressions = []
+    for entry in current["benchmarks"]:
+        previous = before.get((entry["name"], entry["mode"]))
+        if not previous:
+            continue
+        if entry["median_ns"] > previous * (1 + max_regression / 100):
+            regressions.append(
+                Regression(entry["name"], entry["mode"], previous, entry["median_ns"])
+            )
+    return regressions
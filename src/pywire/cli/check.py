@@ -0,0 +1,37 @@
+"""Template checks for `pywire check`."""
+
+from pathlib import Path
+from typing import List
+
+from pywire import _pywire_parser
+
+
+def check_template(path: Path) -> List[str]:
+    """One `path:line:column: severity: message [code]` line per diagnostic."""
+    doc = _pywire_parser.parse(path.read_text("utf-8"), lenient=True)
+    return [
+        f"{path}:{d.line}:{d.column}: {d.severity}: {d.message} [{d.code}]"
+        for d in doc.diagnostics
+    ]
+
+
+def has_errors(lines: List[str]) -> bool:
+    return any(": error: " in line for line in lines)
+
+
+def profile_template(path: Path, top: int = 5) -> List[str]:
+    """Time per phase for one template, then its `top` most expensive
+    element subtrees (by expressions, then nodes)."""
+    report = _pywire_parser.profile(path.read_text("utf-8"), top)
+    lines = [
+        f"{path}: parse {report.parse_seconds * 1e3:.2f}ms, "
+        f"map {report.map_seconds * 1e3:.2f}ms, "
+        f"analysis {report.analysis_seconds * 1e3:.2f}ms "
+        f"({report.nodes} nodes, {report.expressions} expressions)"
+    ]
+    for subtree in report.subtrees:
+        lines.append(
+            f"  <{subtree.tag}> at {subtree.line}:{subtree.column}: "
+            f"{subtree.nodes} nodes, {subtree.expressions} expressions"
+        )
+    return lines
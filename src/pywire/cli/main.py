@@ -53,7 +53,7 @@ click.rich_click.COMMAND_GROUPS = {
     "pywire": [
         {
             "name": "Commands",
-            "commands": ["dev", "run", "build"],
+            "commands": ["dev", "run", "build", "check", "bench"],
         }
     ]
 }
@@ -347,5 +347,117 @@ def run(
     )
 
 
+@cli.command()
+@click.argument("paths", nargs=-1, type=click.Path(exists=True, path_type=Path))
+@click.option(
+    "--profile",
+    is_flag=True,
+    help="Also time parse, map and analysis and list the most expensive subtrees.",
+)
+@click.option("--top", default=5, type=int, help="Subtrees listed per template with --profile.")
+def check(paths: tuple[Path, ...], profile: bool, top: int) -> None:
+    """Check templates for errors and warnings.
+
+    PATHS are .wire files or directories to search for them (default:
+    pages). Exits with status 1 when any template has an error.
+    """
+    from pywire.cli.bench import collect_templates
+    from pywire.cli.check import check_template, has_errors, profile_template
+
+    files = collect_templates(paths or (Path("pages"),))
+    if not files:
+        raise click.UsageError("No .wire templates found to check.")
+
+    failed = False
+    for path in files:
+        lines = check_template(path)
+        failed = failed or has_errors(lines)
+        for line in lines:
+            click.echo(line)
+        if profile:
+            try:
+                for line in profile_template(path, top):
+                    click.echo(line)
+            except Exception as e:
+                click.echo(f"{path}: could not profile: {e}", err=True)
+    if failed:
+        sys.exit(1)
+
+
+@cli.command()
+@click.argument("paths", nargs=-1, type=click.Path(exists=True, path_type=Path))
+@click.option("--iterations", default=50, type=int, help="Timed parses per template")
+@click.option(
+    "--json",
+    "json_out",
+    default=None,
+    help="Write the report as JSON to this file ('-' for stdout).",
+)
+@click.option(
+    "--baseline",
+    default=None,
+    type=click.Path(exists=True, dir_okay=False, path_type=Path),
+    help="JSON report of an earlier run to compare against.",
+)
+@click.option(
+    "--max-regression",
+    default=10.0,
+    type=float,
+    help="Percent slowdown over the baseline that fails the run.",
+)
+def bench(
+    paths: tuple[Path, ...],
+    iterations: int,
+    json_out: Optional[str],
+    baseline: Optional[Path],
+    max_regression: float,
+) -> None:
+    """Benchmark the template parser.
+
+    PATHS are .wire files or directories to search for them (default:
+    benches/corpus in a pywire checkout, otherwise pages). Exits with status
+    1 when a template got slower than --baseline allows.
+    """
+    import json
+
+    from pywire.cli.bench import collect_templates, compare_reports, run_benchmarks
+
+    if not paths:
+        corpus = Path("benches/corpus")
+        paths = (corpus if corpus.is_dir() else Path("pages"),)
+    files = collect_templates(paths)
+    if not files:
+        raise click.UsageError("No .wire templates found to benchmark.")
+
+    report = run_benchmarks(files, iterations=iterations)
+
+    if json_out == "-":
+        click.echo(json.dumps(report, indent=2))
+    else:
+        if json_out:
+            Path(json_out).write_text(json.dumps(report, indent=2) + "\n", "utf-8")
+        for entry in report["benchmarks"]:
+            console.print(
+                f"[cyan]{entry['name']}[/] {entry['mode']}: "
+                f"{entry['median_ns'] / 1e3:.1f}µs median, "
+                f"{entry['min_ns'] / 1e3:.1f}µs min ({entry['bytes']} bytes)"
+            )
+
+    if baseline:
+        try:
+            previous = json.loads(baseline.read_text("utf-8"))
+            regressions = compare_reports(report, previous, max_regression)
+        except (ValueError, KeyError) as e:
+            raise click.UsageError(f"Invalid baseline report {baseline}: {e}")
+        for r in regressions:
+            click.echo(
+                f"Regression: {r.name} {r.mode} {r.baseline_ns / 1e3:.1f}µs -> "
+                f"{r.current_ns / 1e3:.1f}µs (+{r.percent:.1f}%)",
+                err=True,
+            )
+        if regressions:
+            sys.exit(1)
+
+
 if __name__ == "__main__":
     cli()
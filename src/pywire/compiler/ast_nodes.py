@@ -187,6 +187,34 @@ class FinallyAttribute(SpecialAttribute):
         return "FinallyAttribute()"
 
 
+@dataclass
+class MatchAttribute(SpecialAttribute):
+    """{$match subject} marker."""
+
+    subject: str
+
+    def __str__(self) -> str:
+        return f"MatchAttribute(subject={self.subject})"
+
+
+@dataclass
+class CaseAttribute(SpecialAttribute):
+    """{$case pattern} marker; the pattern may end in an `if` guard."""
+
+    pattern: str
+
+    def __str__(self) -> str:
+        return f"CaseAttribute(pattern={self.pattern})"
+
+
+@dataclass
+class DefaultAttribute(SpecialAttribute):
+    """{$default} marker: the wildcard arm of a {$match}."""
+
+    def __str__(self) -> str:
+        return "DefaultAttribute()"
+
+
 @dataclass
 class AwaitAttribute(SpecialAttribute):
     """{$await expression} marker."""
@@ -302,7 +330,7 @@ class ReactiveAttribute(SpecialAttribute):
 @dataclass
 class SpreadAttribute(SpecialAttribute):
     """
-    {**attrs} (preprocessed to __pywire_spread__="{**attrs}")
+    {**attrs}
     Represents a spread of attributes.
     """
 
@@ -339,6 +367,26 @@ class TemplateNode(ASTNode):
     children: List["TemplateNode"] = field(default_factory=list)
     text_content: Optional[str] = None
     is_raw: bool = False
+    # Exact source range, when the node came from the Rust parser
+    end_line: Optional[int] = None
+    end_column: Optional[int] = None
+    start_byte: Optional[int] = None
+    end_byte: Optional[int] = None
+    # Set when the tag names a component (`<Card>`), as classified by the parser
+    component_name: Optional[str] = None
+    # <slot>: the slot it defines; other elements: the slot they fill
+    is_slot: bool = False
+    slot_name: Optional[str] = None
+    # `<!-- ... -->`; text_content holds the comment as written
+    is_comment: bool = False
+    # `<!DOCTYPE ...>`; text_content holds the declaration as written
+    is_doctype: bool = False
+    # Interpolated `attributes` split into text and InterpolationNode parts
+    attribute_segments: Dict[str, List[Union[str, "InterpolationNode"]]] = field(
+        default_factory=dict
+    )
+    # <template shadowrootmode>: "open" or "closed"; its content is a shadow tree
+    shadow_root_mode: Optional[str] = None
 
     def __str__(self) -> str:
         if self.tag:
@@ -9,21 +9,23 @@ from pywire.compiler.exceptions import PyWireSyntaxError
 
 
 class EventAttributeParser(AttributeParser):
-    """Parses @event attributes (click, submit, etc.)."""
+    """Parses @event / on:event attributes (click, submit, etc.)."""
 
     PREFIX = "@"
+    PREFIXES = ("@", "on:")
     PATTERN = re.compile(r"^@(\w+)$")
 
     def can_parse(self, attr_name: str) -> bool:
-        """Check if attribute starts with @."""
-        return attr_name.startswith(self.PREFIX)
+        """Check if attribute starts with @ or on:."""
+        return attr_name.startswith(self.PREFIXES)
 
     def parse(
         self, attr_name: str, attr_value: str, line: int, col: int
     ) -> Optional[EventAttribute]:
         """Parse @click.prevent.stop={handler_name} attribute."""
-        # Remove @ prefix
-        full_event = attr_name[1:]
+        # Remove @ / on: prefix
+        prefix = next(p for p in self.PREFIXES if attr_name.startswith(p))
+        full_event = attr_name[len(prefix) :]
         parts = full_event.split(".")
         event_type = parts[0]
         modifiers = [m for m in parts[1:] if m]
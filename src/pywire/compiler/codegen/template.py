@@ -9,7 +9,9 @@ from typing import Any, Dict, List, Optional, Set, Tuple, Union, cast
 
 from pywire.compiler.ast_nodes import (
     AwaitAttribute,
+    CaseAttribute,
     CatchAttribute,
+    DefaultAttribute,
     ElifAttribute,
     ElseAttribute,
     EventAttribute,
@@ -19,6 +21,7 @@ from pywire.compiler.ast_nodes import (
     IfAttribute,
     InterpolationNode,
     KeyAttribute,
+    MatchAttribute,
     ReactiveAttribute,
     ShowAttribute,
     TemplateNode,
@@ -58,6 +61,8 @@ class TemplateCodegen:
         self._region_counter = 0
         self.region_renderers: Dict[str, str] = {}
         self._expr_id_counter = 0
+        # Declarative shadow roots enclosing the node being generated
+        self._shadow_depth = 0
 
     def generate_render_method(
         self,
@@ -1017,6 +1022,28 @@ class TemplateCodegen:
             node.end_col_offset = template_node.column + 1  # type: ignore
         return node
 
+    def _parse_case(self, pattern: str, line: int) -> ast.match_case:
+        """Parse a {$case} pattern (with optional `if` guard) into a match arm."""
+        try:
+            stmt = ast.parse(f"match _:\n case {pattern}:\n  pass").body[0]
+        except SyntaxError as e:
+            from pywire.compiler.exceptions import PyWireSyntaxError
+
+            raise PyWireSyntaxError(
+                f"Invalid {{$case}} pattern '{pattern}': {e.msg}", line=line
+            )
+        return cast(ast.Match, stmt).cases[0]
+
+    def _pattern_captures(self, pattern: ast.pattern) -> Set[str]:
+        """Names a match pattern binds."""
+        names: Set[str] = set()
+        for sub in ast.walk(pattern):
+            if isinstance(sub, (ast.MatchAs, ast.MatchStar)) and sub.name:
+                names.add(sub.name)
+            elif isinstance(sub, ast.MatchMapping) and sub.rest:
+                names.add(sub.rest)
+        return names
+
     def _add_node(
         self,
         node: TemplateNode,
@@ -1098,7 +1125,10 @@ class TemplateCodegen:
             new_attrs = [a for a in node.special_attributes if a is not for_attr]
 
             # Check if we should split children for for-else
-            if node.tag == "template" or (not node.tag and not node.text_content):
+            # A declarative shadow root keeps its <template> wrapper
+            if (node.tag == "template" and not node.shadow_root_mode) or (
+                not node.tag and not node.text_content
+            ):
                 current_body = for_body
                 prev_child = None
                 for child in node.children:
@@ -1806,9 +1836,105 @@ class TemplateCodegen:
             )
             return
 
+        # 2c. Handle $match
+        match_attr = next(
+            (a for a in node.special_attributes if isinstance(a, MatchAttribute)), None
+        )
+        if match_attr:
+            # Partition children into (match_case, body_nodes) arms; anything
+            # before the first arm is whitespace (checked by the parser).
+            arms: List[Tuple[ast.match_case, List[TemplateNode]]] = []
+            prev_child = None
+            for child in node.children:
+                case_attr = next(
+                    (
+                        a
+                        for a in child.special_attributes
+                        if isinstance(a, CaseAttribute)
+                    ),
+                    None,
+                )
+                default_attr = next(
+                    (
+                        a
+                        for a in child.special_attributes
+                        if isinstance(a, DefaultAttribute)
+                    ),
+                    None,
+                )
+
+                if case_attr or default_attr:
+                    pattern = case_attr.pattern if case_attr else "_"
+                    arms.append((self._parse_case(pattern, child.line), []))
+                    prev_child = None  # Reset gap tracking for new arm
+                elif arms:
+                    current_arm_nodes = arms[-1][1]
+                    if prev_child and child.line == prev_child.line:
+                        end_line, end_col = self._get_node_end_pos(prev_child)
+                        if child.column > end_col:
+                            current_arm_nodes.append(
+                                TemplateNode(
+                                    tag=None,
+                                    text_content=" " * (child.column - end_col),
+                                    line=child.line,
+                                    column=end_col,
+                                )
+                            )
+                    current_arm_nodes.append(child)
+                    prev_child = child
+
+            cases: List[ast.match_case] = []
+            for arm, arm_nodes in arms:
+                # Names captured by the pattern are locals of the arm body
+                arm_locals = local_vars.copy()
+                arm_locals.update(self._pattern_captures(arm.pattern))
+                if arm.guard is not None:
+                    arm.guard = self._transform_expr(
+                        ast.unparse(arm.guard),
+                        arm_locals,
+                        known_globals,
+                        known_imports,
+                        line_offset=node.line,
+                        cached=False,
+                    )
+                arm_body: List[ast.stmt] = []
+                for b_node in arm_nodes:
+                    self._add_node(
+                        b_node,
+                        arm_body,
+                        arm_locals,
+                        bound_var,
+                        layout_id,
+                        known_methods,
+                        known_globals,
+                        known_imports,
+                        async_methods,
+                        component_map,
+                        scope_id,
+                        parts_var=parts_var,
+                        wire_vars=wire_vars,
+                    )
+                arm.body = arm_body if arm_body else [ast.Pass()]
+                cases.append(arm)
+
+            subject = self._transform_expr(
+                match_attr.subject,
+                local_vars,
+                known_globals,
+                known_imports,
+                line_offset=node.line,
+                cached=False,
+            )
+            if cases:
+                match_stmt = ast.Match(subject=subject, cases=cases)
+                self._set_line(match_stmt, node)
+                body.append(match_stmt)
+            return
+
         # --- Handle <slot> ---
-        if node.tag == "slot":
-            slot_name = node.attributes.get("name", "default")
+        # Inside a declarative shadow root <slot> is native and emitted as is
+        if node.tag == "slot" and not self._shadow_depth:
+            slot_name = node.slot_name or node.attributes.get("name", "default")
             is_head_slot = "$head" in node.attributes
 
             default_renderer_arg: ast.expr = ast.Constant(value=None)
@@ -1888,12 +2014,12 @@ class TemplateCodegen:
             return
 
         if node.tag and (
-            (component_map and node.tag in component_map) or node.tag[0].isupper()
+            (component_map and node.tag in component_map) or node.component_name
         ):
             cls_name = (
                 component_map[node.tag]
                 if component_map and node.tag in component_map
-                else node.tag
+                else cast(str, node.component_name)
             )
 
             # Prepare arguments (kwargs)
@@ -1980,9 +2106,9 @@ class TemplateCodegen:
                         )
                     else:
                         # String interpolation
-                        parts = self.interpolation_parser.parse(
-                            v, node.line, node.column
-                        )
+                        parts = node.attribute_segments.get(
+                            k
+                        ) or self.interpolation_parser.parse(v, node.line, node.column)
                         current_concat: Optional[ast.expr] = None
                         for part in parts:
                             term: ast.expr
@@ -2198,7 +2324,9 @@ class TemplateCodegen:
                 # Note: child is TemplateNode. attributes dict.
                 # If element:
                 child_slot_name: Optional[str] = None
-                if child.tag and "slot" in child.attributes:
+                if child.slot_name and not child.is_slot:
+                    child_slot_name = child.slot_name
+                elif child.tag and "slot" in child.attributes:
                     child_slot_name = child.attributes["slot"]
                     # Remove slot attribute? Optional but cleaner.
 
@@ -2658,7 +2786,9 @@ class TemplateCodegen:
             # Static attrs
             for k, v in node.attributes.items():
                 if "{" in v and "}" in v:
-                    parts = self.interpolation_parser.parse(v, node.line, node.column)
+                    parts = node.attribute_segments.get(
+                        k
+                    ) or self.interpolation_parser.parse(v, node.line, node.column)
                     current_concat = None
                     for part in parts:
                         if isinstance(part, str):
@@ -3347,6 +3477,8 @@ class TemplateCodegen:
                 )
             )
 
+            shadow = 1 if node.shadow_root_mode else 0
+            self._shadow_depth += shadow
             prev_child = None
             for child in node.children:
                 # Add whitespace if there is a gap between this child and the previous one
@@ -3370,6 +3502,7 @@ class TemplateCodegen:
                     wire_vars=wire_vars,
                 )
                 prev_child = child
+            self._shadow_depth -= shadow
 
             if node.tag.lower() not in self.VOID_ELEMENTS:
                 body.append(
@@ -9,9 +9,12 @@ from pywire import _pywire_parser as pywire_parser
 
 from pywire.compiler.ast_nodes import (
     AwaitAttribute,
+    CaseAttribute,
     CatchAttribute,
+    DefaultAttribute,
     ElifAttribute,
     ElseAttribute,
+    EventAttribute,
     ExceptAttribute,
     FieldValidationRules,
     FinallyAttribute,
@@ -19,6 +22,7 @@ from pywire.compiler.ast_nodes import (
     FormValidationSchema,
     IfAttribute,
     InterpolationNode,
+    MatchAttribute,
     ParsedPyWire,
     ReactiveAttribute,
     SpecialAttribute,
@@ -51,7 +55,10 @@ class BlockMarkerAttribute(SpecialAttribute):
 class PyWireParser:
     """Main parser orchestrator."""
 
-    def __init__(self) -> None:
+    def __init__(self, dev_annotations: bool = False) -> None:
+        # Stamp data-pw-src="file:line" onto elements for the devtools extension
+        self.dev_annotations = dev_annotations
+
         # Directive registry
         self.directive_parsers: List[DirectiveParser] = [
             PathDirectiveParser(),
@@ -82,10 +89,17 @@ class PyWireParser:
     def parse(self, content: str, file_path: str = "") -> ParsedPyWire:
         """Parse PyWire content using tree-sitter-pywire."""
         try:
-            doc = pywire_parser.parse(content)
+            annotate_file = (file_path or "<template>") if self.dev_annotations else None
+            doc = pywire_parser.parse(content, annotate_file, fold_constants=True)
         except Exception as e:
             raise PyWireSyntaxError(f"Parser error: {str(e)}", file_path=file_path)
 
+        error = next((d for d in doc.diagnostics if d.severity == "error"), None)
+        if error is not None:
+            raise PyWireSyntaxError(
+                error.message, file_path=file_path, line=error.line, column=error.column
+            )
+
         directives = []
         for d in doc.directives:
             parsed_d = self._map_rust_directive(d, file_path)
@@ -93,7 +107,7 @@ class PyWireParser:
                 directives.append(parsed_d)
 
         template_nodes = []
-        for n in doc.template:
+        for n in self._without_examples(doc.template):
             template_nodes.append(self._map_node(n))
 
         # Reconstruct block hierarchy from flat list
@@ -115,6 +129,15 @@ class PyWireParser:
                     line=e.lineno or 1,
                 )
 
+        if doc.constants:
+            # `!const` values stay available to the expressions that were not
+            # folded at parse time
+            constants = [
+                ast.parse(f"{name} = {expr}").body[0] for name, expr in doc.constants
+            ]
+            python_ast = python_ast or ast.Module(body=[], type_ignores=[])
+            python_ast.body[:0] = constants
+
         return ParsedPyWire(
             directives=directives,
             template=template_nodes,
@@ -136,7 +159,7 @@ class PyWireParser:
     def _map_node(self, rn: Any) -> TemplateNode:
         """Map a Rust ParsedNode to a PyWire TemplateNode."""
         # Clean attributes
-        regular_attrs, special_attrs = self._parse_attributes(rn.attributes)
+        regular_attrs, special_attrs = self._parse_attributes(rn.parsed_attributes)
 
         node = TemplateNode(
             tag=rn.tag,
@@ -144,7 +167,21 @@ class PyWireParser:
             special_attributes=special_attrs,
             line=rn.line,
             column=rn.column,
-            is_raw=rn.is_raw,
+            # Comments and doctypes are emitted verbatim, braces and all
+            is_raw=rn.is_raw or rn.is_comment or rn.is_doctype,
+            end_line=rn.end_line,
+            end_column=rn.end_column,
+            start_byte=rn.start_byte,
+            end_byte=rn.end_byte,
+            component_name=rn.component_name,
+            is_slot=rn.is_slot,
+            slot_name=rn.slot_name,
+            is_comment=rn.is_comment,
+            is_doctype=rn.is_doctype,
+            shadow_root_mode=rn.shadow_root_mode,
+            attribute_segments=self._attribute_segments(
+                rn.parsed_attributes, regular_attrs
+            ),
         )
 
         if rn.text_content:
@@ -169,7 +206,7 @@ class PyWireParser:
                 self._handle_rust_block(rn, node)
 
         # Map children
-        for child in rn.children:
+        for child in self._without_examples(rn.children):
             node.children.append(self._map_node(child))
 
         # Reconstruct block hierarchy for children if any
@@ -182,7 +219,7 @@ class PyWireParser:
             submit_attr = None
             model_attr = None
             for attr in node.special_attributes:
-                from pywire.compiler.ast_nodes import EventAttribute, ModelAttribute
+                from pywire.compiler.ast_nodes import ModelAttribute
 
                 if isinstance(attr, EventAttribute) and attr.event_type == "submit":
                     submit_attr = attr
@@ -199,6 +236,19 @@ class PyWireParser:
 
         return node
 
+    def _without_examples(self, nodes: List[Any]) -> List[Any]:
+        """Drop {$example} blocks; they are for gallery tools, not rendering."""
+        kept = []
+        skip_to = -1
+        for i, rn in enumerate(nodes):
+            if i <= skip_to:
+                continue
+            if rn.is_block and rn.block_keyword == "example":
+                skip_to = rn.close_index if rn.close_index is not None else len(nodes)
+                continue
+            kept.append(rn)
+        return kept
+
     def _structure_hierarchy(self, nodes: List[TemplateNode]) -> List[TemplateNode]:
         """Convert linear sequence of block/end-block nodes into a tree."""
         roots: List[TemplateNode] = []
@@ -210,6 +260,7 @@ class PyWireParser:
             ForAttribute,
             TryAttribute,
             AwaitAttribute,
+            MatchAttribute,
         )
 
         for node in nodes:
@@ -225,6 +276,7 @@ class PyWireParser:
                 if stack:
                     block_node = stack.pop()
                     self._validate_block_root(block_node)
+                    self._validate_match_arms(block_node)
                 # Discard the closing node itself
                 continue
 
@@ -342,6 +394,32 @@ class PyWireParser:
                     name="$finally", value="", line=rn.line, column=rn.column
                 )
             )
+        elif kw == "match":
+            node.special_attributes.append(
+                MatchAttribute(
+                    name="$match",
+                    value="",
+                    subject=expr,
+                    line=rn.line,
+                    column=rn.column,
+                )
+            )
+        elif kw == "case":
+            node.special_attributes.append(
+                CaseAttribute(
+                    name="$case",
+                    value="",
+                    pattern=expr,
+                    line=rn.line,
+                    column=rn.column,
+                )
+            )
+        elif kw == "default":
+            node.special_attributes.append(
+                DefaultAttribute(
+                    name="$default", value="", line=rn.line, column=rn.column
+                )
+            )
         elif kw == "await":
             node.special_attributes.append(
                 AwaitAttribute(
@@ -431,6 +509,35 @@ class PyWireParser:
                         line=node.line,
                     )
 
+    def _validate_match_arms(self, node: TemplateNode) -> None:
+        """Validate that a {$match} body is only {$case}/{$default} arms."""
+        if not any(isinstance(a, MatchAttribute) for a in node.special_attributes):
+            return
+
+        seen_arm = False
+        seen_default = False
+        for c in node.children:
+            if any(isinstance(a, CaseAttribute) for a in c.special_attributes):
+                if seen_default:
+                    raise PyWireSyntaxError(
+                        "{$default} must be the last arm of a {$match}",
+                        line=c.line,
+                        column=c.column,
+                    )
+                seen_arm = True
+            elif any(isinstance(a, DefaultAttribute) for a in c.special_attributes):
+                seen_arm = seen_default = True
+            elif not seen_arm and (
+                c.tag
+                or (c.text_content and c.text_content.strip())
+                or c.special_attributes
+            ):
+                raise PyWireSyntaxError(
+                    "Content inside {$match} must be in a {$case} or {$default} arm",
+                    line=c.line,
+                    column=c.column,
+                )
+
     def _extract_form_validation_schema(
         self, form_node: TemplateNode
     ) -> FormValidationSchema:
@@ -584,33 +691,99 @@ class PyWireParser:
                 nodes.append(node)
         return nodes
 
+    def _attribute_segments(
+        self, attrs: List[Any], regular: dict
+    ) -> Dict[str, List[Union[str, InterpolationNode]]]:
+        """Interpolated regular attributes as text/InterpolationNode parts.
+
+        Segments come from the Rust parser, with source positions; edge
+        whitespace is dropped to match the stripped value in ``regular``.
+        """
+        out: Dict[str, List[Union[str, InterpolationNode]]] = {}
+        for attr in attrs:
+            if attr.name not in regular or not attr.has_interpolation:
+                continue
+            parts: List[Union[str, InterpolationNode]] = [
+                InterpolationNode(
+                    expression=seg.text, line=seg.line, column=seg.column
+                )
+                if seg.is_expression
+                else seg.text
+                for seg in attr.segments
+            ]
+            if isinstance(parts[0], str):
+                parts[0] = parts[0].lstrip()
+            if isinstance(parts[-1], str):
+                parts[-1] = parts[-1].rstrip()
+            out[attr.name] = [p for p in parts if p != ""]
+        return out
+
+    def _event_attribute(self, attr: Any) -> EventAttribute:
+        """Map a Rust event ``ParsedAttribute`` (``@click``, ``on:click``)."""
+        braced = attr.value is not None and attr.value.strip().startswith("{")
+        if attr.value is None or (attr.quote is None and not braced):
+            raise PyWireSyntaxError(
+                f"Event handler for '{attr.name}' must be wrapped in brackets or quotes: "
+                f'{attr.name}={{expr}} or {attr.name}="expr"',
+                line=attr.line,
+            )
+        return EventAttribute(
+            name=attr.name,
+            value=attr.value,
+            event_type=attr.event,
+            handler_name=attr.handler_expr,
+            modifiers=list(attr.modifiers),
+            line=attr.line,
+            column=attr.column,
+        )
+
     def _parse_attributes(
-        self, attrs: Dict[str, Any]
+        self, attrs: List[Any]
     ) -> Tuple[dict, List[Union[SpecialAttribute, InterpolationNode]]]:
-        """Separate regular attrs from special ones."""
+        """Separate regular attrs from special ones.
+
+        ``attrs`` are the Rust ``ParsedAttribute`` objects of a node.
+        """
         regular = {}
         special: List[Union[SpecialAttribute, InterpolationNode]] = []
 
-        for name, value in attrs.items():
-            # Unescape special characters encoded for lxml compatibility
-            if name.startswith("__pw_on_"):
-                name = "@" + name[len("__pw_on_") :]
-            elif name.startswith("__pw_dir_"):
-                name = "$" + name[len("__pw_dir_") :]
-            elif name.startswith("__pw_sh_"):
-                # Shorthand binding: __pw_sh_attr -> {attr}
-                attr_name = name[len("__pw_sh_") :]
+        for attr in attrs:
+            if attr.is_spread:
+                # Spread attributes {**props}; the last one wins
+                special = [s for s in special if not isinstance(s, SpreadAttribute)]
+                special.append(
+                    SpreadAttribute(
+                        name="__pywire_spread__",
+                        value=attr.raw_value,
+                        expr=attr.value,
+                        line=attr.line,
+                        column=attr.column,
+                    )
+                )
+                continue
+            if attr.is_shorthand:
+                # Shorthand binding {attr} -> attr={attr}
                 special.append(
                     ReactiveAttribute(
-                        name=attr_name,
-                        value=f"{{{attr_name}}}",
-                        expr=attr_name,
-                        line=0,
-                        column=0,
+                        name=attr.name,
+                        value=attr.raw_value,
+                        expr=attr.value,
+                        line=attr.line,
+                        column=attr.column,
                     )
                 )
                 continue
 
+            if attr.event is not None:
+                special.append(self._event_attribute(attr))
+                continue
+
+            name, value = attr.name, attr.value
+            # Unescape special characters encoded for lxml compatibility
+            if name.startswith("__pw_on_"):
+                name = "@" + name[len("__pw_on_") :]
+            elif name.startswith("__pw_dir_"):
+                name = "$" + name[len("__pw_dir_") :]
             elif name == "$permanent":
                 regular["data-pywire-permanent"] = "true"
                 continue
@@ -624,54 +797,25 @@ class PyWireParser:
             parsed = False
             for parser in self.attribute_parsers:
                 if parser.can_parse(name):
-                    attr = parser.parse(name, str(value), 0, 0)
-                    if attr:
-                        special.append(attr)
+                    parsed_attr = parser.parse(name, str(value), 0, 0)
+                    if parsed_attr:
+                        special.append(parsed_attr)
                     parsed = True
                     break
 
             if not parsed:
-                # Removed direct check for {attr} here as lxml breaks on it.
-                # Handled via preprocessing __pw_sh_.
-
                 # Check for reactive value syntax: attr="{expr}"
                 val_str = str(value).strip()
                 if val_str.startswith("{") and val_str.endswith("}"):
-                    # Treat as reactive attribute
-                    # Exclude special internal attr for spread
-                    if name == "__pywire_spread__":
-                        # Spread attributes {**props}
-                        special.append(
-                            SpreadAttribute(
-                                name=name,
-                                value=val_str,
-                                expr=val_str[3:-1].strip(),  # Strip {** and }
-                                line=0,
-                                column=0,
-                            )
-                        )
-                    elif name.startswith("__pw_sh_"):
-                        # Shorthand attributes {attr}
-                        real_name = name[8:]
-                        special.append(
-                            ReactiveAttribute(
-                                name=real_name,
-                                value=val_str,
-                                expr=val_str[1:-1].strip(),  # Strip { and }
-                                line=0,
-                                column=0,
-                            )
-                        )
-                    else:
-                        special.append(
-                            ReactiveAttribute(
-                                name=name,
-                                value=val_str,
-                                expr=val_str[1:-1],
-                                line=0,
-                                column=0,
-                            )
+                    special.append(
+                        ReactiveAttribute(
+                            name=name,
+                            value=val_str,
+                            expr=val_str[1:-1],
+                            line=0,
+                            column=0,
                         )
+                    )
                 else:
                     regular[name] = val_str
 
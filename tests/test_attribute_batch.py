@@ -0,0 +1,27 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestAttributeBatch(unittest.TestCase):
+    def element(self, source: str):
+        (node,) = pywire_parser.parse(source).template
+        return node
+
+    def test_dynamic_attributes_are_batched(self) -> None:
+        node = self.element(
+            '<input type="range" value={level} title="Level {level}" class:hot={level > 8} />'
+        )
+        self.assertEqual(node.attribute_batch, ["value", "title", "class"])
+
+    def test_class_and_style_entries_patch_one_attribute(self) -> None:
+        node = self.element("<p class:a={x} class:b={y} style:color={c}>x</p>")
+        self.assertEqual(node.attribute_batch, ["class", "style"])
+
+    def test_single_dynamic_attribute_is_not_batched(self) -> None:
+        node = self.element('<input type="range" value={level} @input={update} />')
+        self.assertEqual(node.attribute_batch, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,36 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestAttributeOrder(unittest.TestCase):
+    def test_source_order(self) -> None:
+        doc = pywire_parser.parse(
+            '<div z="1" {**props} class="x" a={b} hidden></div>'
+        )
+        (div,) = doc.template
+        self.assertEqual(
+            list(div.attributes),
+            ["z", "__pywire_spread__", "class", "a", "hidden"],
+        )
+
+    def test_dict_access(self) -> None:
+        doc = pywire_parser.parse('<a href="/" title="t">x</a>')
+        (a,) = doc.template
+        self.assertIsInstance(a.attributes, dict)
+        self.assertEqual(a.attributes["href"], "/")
+        self.assertEqual(a.attributes.get("missing"), None)
+        self.assertIn("title", a.attributes)
+
+    def test_repeated_attribute_keeps_first_position(self) -> None:
+        doc = pywire_parser.parse('<p class="a" id="p" class="b">x</p>')
+        (p,) = doc.template
+        self.assertEqual(list(p.attributes.items()), [("class", "b"), ("id", "p")])
+
+    def test_select_preserves_order(self) -> None:
+        (match,) = pywire_parser.select(pywire_parser.parse('<p z="1" a="2">x</p>'), "p")
+        self.assertEqual(list(match.attributes), ["z", "a"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,53 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestAttributeSegments(unittest.TestCase):
+    def attr(self, source: str, index: int = 0):
+        (node,) = pywire_parser.parse(source).template
+        return node.parsed_attributes[index]
+
+    def test_literal_and_expression_segments(self) -> None:
+        source = '<a class="btn {variant} large">x</a>'
+        attr = self.attr(source)
+        self.assertTrue(attr.has_interpolation)
+        self.assertEqual(
+            [(s.text, s.is_expression) for s in attr.segments],
+            [("btn ", False), ("variant", True), (" large", False)],
+        )
+        expr = attr.segments[1]
+        self.assertEqual(source[expr.start_byte : expr.end_byte], "{variant}")
+        self.assertEqual((expr.line, expr.column), (1, 14))
+
+    def test_static_value_is_one_literal(self) -> None:
+        attr = self.attr('<a href="/home">x</a>')
+        self.assertFalse(attr.has_interpolation)
+        self.assertEqual([s.text for s in attr.segments], ["/home"])
+
+    def test_nested_braces_and_strings(self) -> None:
+        attr = self.attr("<a title=\"{ {'k': '}'}['k'] }!\">x</a>")
+        self.assertEqual(
+            [(s.text, s.is_expression) for s in attr.segments],
+            [("{'k': '}'}['k']", True), ("!", False)],
+        )
+
+    def test_unclosed_and_empty_braces_are_literal(self) -> None:
+        attr = self.attr('<a title="a {} b {c">x</a>')
+        self.assertFalse(attr.has_interpolation)
+        self.assertEqual([s.text for s in attr.segments], ["a {} b {c"])
+
+    def test_multiline_positions(self) -> None:
+        attr = self.attr('<a title="one\n  {two}">x</a>')
+        expr = attr.segments[1]
+        self.assertEqual((expr.line, expr.column), (2, 2))
+
+    def test_shorthand_is_one_expression(self) -> None:
+        attr = self.attr("<input {value}>")
+        (seg,) = attr.segments
+        self.assertTrue(seg.is_expression)
+        self.assertEqual(seg.text, "value")
+
+
+if __name__ == "__main__":
+    unittest.main()
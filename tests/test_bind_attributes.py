@@ -0,0 +1,59 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def attribute(source, name):
+    doc = pywire_parser.parse(source)
+    return next(a for a in doc.template[0].parsed_attributes if a.name == name)
+
+
+def codes(source):
+    return [d.code for d in pywire_parser.parse(source).diagnostics]
+
+
+class TestBindAttributes(unittest.TestCase):
+    def test_bind_kind_and_fields(self) -> None:
+        attr = attribute("<input bind:value={form.name} />", "bind:value")
+        self.assertEqual(attr.kind, pywire_parser.AttrKind.BIND)
+        self.assertEqual(attr.bind_target, "value")
+        self.assertEqual(attr.expression, "form.name")
+        self.assertIsNone(attr.event)
+
+    def test_other_targets(self) -> None:
+        attr = attribute("<input type=checkbox bind:checked={ done } />", "bind:checked")
+        self.assertEqual((attr.bind_target, attr.expression), ("checked", "done"))
+
+    def test_plain_attributes_have_no_binding(self) -> None:
+        attr = attribute("<input value={name} />", "value")
+        self.assertEqual(attr.kind, pywire_parser.AttrKind.REACTIVE)
+        self.assertIsNone(attr.bind_target)
+        self.assertIsNone(attr.expression)
+
+    def test_assignable_targets_are_accepted(self) -> None:
+        for expr in ("name", "form.name", "rows[i].value", "grid[r][c]"):
+            with self.subTest(expr=expr):
+                self.assertNotIn("invalid-bind", codes(f"<input bind:value={{{expr}}} />"))
+
+    def test_invalid_bindings_are_reported(self) -> None:
+        for source in (
+            '<input bind:value="name" />',
+            "<input bind:value />",
+            "<input bind:value={name.upper()} />",
+            "<input bind:value={a + b} />",
+        ):
+            with self.subTest(source=source):
+                self.assertIn("invalid-bind", codes(source))
+
+    def test_binding_is_not_a_component_prop(self) -> None:
+        doc = pywire_parser.parse("<Field bind:value={name} label='Name' />")
+        self.assertEqual([p.name for p in doc.template[0].props], ["label"])
+
+    def test_binding_counts_as_a_write(self) -> None:
+        doc = pywire_parser.parse("---\nname = ''\n---\n<input bind:value={name} />\n")
+        (suggestion,) = pywire_parser.suggest_wires(doc)
+        self.assertEqual(suggestion.name, "name")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestBlockPairing(unittest.TestCase):
+    def test_if_chain_links_to_opener(self) -> None:
+        doc = pywire_parser.parse("{$if a}<p>a</p>{$elif b}<p>b</p>{$else}c{/if}")
+        nodes = doc.template
+        kws = [n.block_keyword for n in nodes]
+        opener = kws.index("if")
+        closer = kws.index("/if")
+        self.assertEqual(nodes[opener].close_index, closer)
+        self.assertEqual(nodes[closer].open_index, opener)
+        self.assertEqual(nodes[kws.index("elif")].open_index, opener)
+        self.assertEqual(nodes[kws.index("else")].open_index, opener)
+        self.assertEqual(nodes[opener].close_line, nodes[closer].line)
+        self.assertEqual(nodes[opener].close_column, nodes[closer].column)
+
+    def test_nested_blocks(self) -> None:
+        doc = pywire_parser.parse("<ul>{$for x in xs}{$if x}<li>{x}</li>{/if}{/for}</ul>")
+        ul = doc.template[0]
+        kws = [n.block_keyword for n in ul.children]
+        self.assertEqual(ul.children[kws.index("for")].close_index, kws.index("/for"))
+        self.assertEqual(ul.children[kws.index("if")].close_index, kws.index("/if"))
+
+    def test_element_close_span(self) -> None:
+        doc = pywire_parser.parse("<div>\n  <br>\n</div>")
+        div = doc.template[0]
+        self.assertEqual((div.close_line, div.close_column), (3, 0))
+        br = next(c for c in div.children if c.tag == "br")
+        self.assertIsNone(br.close_line)
+
+    def test_mismatched_close_is_unpaired(self) -> None:
+        doc = pywire_parser.parse("{$if a}x{/for}")
+        self.assertTrue(all(n.open_index is None for n in doc.template))
+        self.assertIsNone(doc.template[0].close_index)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,37 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestClassTokens(unittest.TestCase):
+    def test_static_directive_and_expression_classes(self) -> None:
+        index = pywire_parser.ProjectIndex()
+        index.add(
+            "pages/index.wire",
+            '<div class="card  p-4 md:flex">\n'
+            '    <button class:opacity-50={disabled} class={"btn-primary" if main else "btn"}>Go</button>\n'
+            '    <span class="badge {extra}">x</span>\n'
+            "</div>\n",
+        )
+        result = pywire_parser.extract_class_tokens(index)
+        for token in ["card", "p-4", "md:flex", "opacity-50", "btn-primary", "btn", "badge"]:
+            self.assertIn(token, result)
+        self.assertEqual(result.tokens, sorted(result.tokens))
+
+        exprs = {(e.attribute, e.expression) for e in result.expressions}
+        self.assertEqual(
+            exprs,
+            {
+                ("class:opacity-50", "disabled"),
+                ("class", '"btn-primary" if main else "btn"'),
+                ("class", "extra"),
+            },
+        )
+        button = next(e for e in result.expressions if e.literals)
+        self.assertEqual(button.file, "pages/index.wire")
+        self.assertEqual(button.line, 2)
+        self.assertEqual(button.literals, ["btn-primary", "btn"])
+
+
+if __name__ == "__main__":
+    unittest.main()
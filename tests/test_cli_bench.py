@@ -0,0 +1,45 @@
+import json
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire.cli.bench import collect_templates, compare_reports, run_benchmarks
+
+CORPUS = Path(__file__).parent.parent / "benches" / "corpus"
+
+
+def report(median_ns: int, name: str = "a.wire") -> dict:
+    entry = {"name": name, "mode": "parse", "bytes": 1, "iterations": 1}
+    return {"version": 1, "benchmarks": [{**entry, "median_ns": median_ns}]}
+
+
+class TestBench(unittest.TestCase):
+    def test_collects_corpus(self) -> None:
+        names = [p.name for p in collect_templates([CORPUS])]
+        self.assertEqual(names, ["errors.wire", "huge.wire", "loops.wire", "small.wire"])
+
+    def test_report_is_json_ready(self) -> None:
+        with tempfile.TemporaryDirectory() as tmp:
+            page = Path(tmp) / "page.wire"
+            page.write_text("<p>{x}</p>\n")
+            result = json.loads(json.dumps(run_benchmarks([page], iterations=3, warmup=0)))
+        self.assertEqual(result["version"], 1)
+        modes = [(b["mode"], b["iterations"]) for b in result["benchmarks"]]
+        self.assertEqual(modes, [("parse", 3), ("parse_resolved", 3)])
+        for entry in result["benchmarks"]:
+            self.assertLessEqual(entry["min_ns"], entry["median_ns"])
+
+    def test_regression_gate(self) -> None:
+        (r,) = compare_reports(report(1200), report(1000), max_regression=10)
+        self.assertEqual((r.name, r.baseline_ns, r.current_ns), ("a.wire", 1000, 1200))
+        self.assertAlmostEqual(r.percent, 20.0)
+        self.assertEqual(compare_reports(report(1050), report(1000), 10), [])
+        self.assertEqual(compare_reports(report(9000, "b.wire"), report(1000), 10), [])
+
+    def test_rejects_other_report_versions(self) -> None:
+        with self.assertRaises(ValueError):
+            compare_reports(report(1), {**report(1), "version": 0}, 10)
+
+
+if __name__ == "__main__":
+    unittest.main()
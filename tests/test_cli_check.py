@@ -0,0 +1,40 @@
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire.cli.check import check_template, has_errors, profile_template
+
+
+class TestCheck(unittest.TestCase):
+    def setUp(self) -> None:
+        self._tmp = tempfile.TemporaryDirectory()
+        self.root = Path(self._tmp.name)
+
+    def tearDown(self) -> None:
+        self._tmp.cleanup()
+
+    def write(self, source: str) -> Path:
+        path = self.root / "page.wire"
+        path.write_text(source)
+        return path
+
+    def test_clean_template(self) -> None:
+        lines = check_template(self.write("<p>{x}</p>\n"))
+        self.assertFalse(has_errors(lines))
+
+    def test_errors_are_reported_with_position(self) -> None:
+        path = self.write("<div>\n  {$if x}\n</div>\n")
+        lines = check_template(path)
+        self.assertTrue(has_errors(lines))
+        self.assertTrue(lines[0].startswith(f"{path}:"))
+
+    def test_profile(self) -> None:
+        path = self.write("<ul>{$for x in xs}<li>{x}</li>{/for}</ul>\n")
+        lines = profile_template(path, top=1)
+        self.assertIn("ms", lines[0])
+        self.assertEqual(len(lines), 2)
+        self.assertIn("<ul> at 1:0", lines[1])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,94 @@
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestCodemod(unittest.TestCase):
+    def setUp(self) -> None:
+        self._tmp = tempfile.TemporaryDirectory()
+        self.root = Path(self._tmp.name)
+
+    def tearDown(self) -> None:
+        self._tmp.cleanup()
+
+    def write(self, name: str, source: str) -> Path:
+        path = self.root / name
+        path.write_text(source)
+        return path
+
+    def test_rename_component(self) -> None:
+        source = '<div>\n  <Card  title="a">\n    <Cards />\n  </Card>\n</div>\n'
+        a = self.write("a.wire", source)
+        b = self.write("b.wire", "<p>none</p>\n")
+        results = pywire_parser.codemod(
+            [a, b], "rename-component", {"from": "Card", "to": "Panel"}
+        )
+        self.assertEqual([r.path for r in results], [a, b])
+        self.assertTrue(results[0].changed)
+        self.assertEqual(results[0].edits, 2)
+        self.assertEqual(results[0].skipped, 0)
+        self.assertEqual(
+            a.read_text(),
+            '<div>\n  <Panel  title="a">\n    <Cards />\n  </Panel>\n</div>\n',
+        )
+        self.assertIn("-  <Card  title=\"a\">", results[0].diff)
+        self.assertFalse(results[1].changed)
+        self.assertEqual(results[1].diff, "")
+
+    def test_convert_directives(self) -> None:
+        path = self.write(
+            "list.wire",
+            "<ul>\n  <li $for={item in items} $key={item.id} class=\"row\">{item}</li>\n"
+            "  <p $if={empty}>Nothing</p>\n</ul>\n",
+        )
+        pywire_parser.codemod([path], "convert-directives")
+        self.assertEqual(
+            path.read_text(),
+            "<ul>\n  {$for item in items, key=item.id}<li class=\"row\">{item}</li>{/for}\n"
+            "  {$if empty}<p>Nothing</p>{/if}\n</ul>\n",
+        )
+
+    def test_add_loop_keys(self) -> None:
+        path = self.write(
+            "loops.wire",
+            "{$for user in users}<p>{user}</p>{/for}\n"
+            "{$for k, v in pairs, key=k}<p>{v}</p>{/for}\n",
+        )
+        (result,) = pywire_parser.codemod(
+            [path], "add-loop-keys", {"key": "{}.id"}, write=False
+        )
+        self.assertTrue(result.changed)
+        self.assertIn("+{$for user in users, key=user.id}<p>", result.diff)
+        # Preview only
+        self.assertIn("{$for user in users}", path.read_text())
+
+    def test_script_transform(self) -> None:
+        path = self.write("page.wire", "<h1>Old</h1>\n")
+        calls = []
+
+        def script(p, source):
+            calls.append(p)
+            return source.replace("Old", "New")
+
+        (result,) = pywire_parser.codemod([path], script)
+        self.assertEqual(calls, [path])
+        self.assertIsNone(result.edits)
+        self.assertEqual(result.skipped, 0)
+        self.assertEqual(path.read_text(), "<h1>New</h1>\n")
+
+    def test_errors(self) -> None:
+        missing = self.root / "missing.wire"
+        (result,) = pywire_parser.codemod([missing], "convert-directives")
+        self.assertIsNotNone(result.error)
+        self.assertFalse(result.changed)
+
+        with self.assertRaises(ValueError):
+            pywire_parser.codemod([], "no-such-transform")
+        with self.assertRaises(ValueError):
+            pywire_parser.codemod([], "rename-component", {"from": "A"})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,34 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestCommentNodes(unittest.TestCase):
+    def test_comment_preserved(self) -> None:
+        doc = pywire_parser.parse(
+            "<!--[if IE]> {legacy} <![endif]-->\n<div><!-- pw:keep --><p>x</p></div>"
+        )
+        top, _, div = doc.template
+        self.assertTrue(top.is_comment)
+        self.assertEqual(top.kind, pywire_parser.NodeKind.COMMENT)
+        self.assertEqual(top.text_content, "<!--[if IE]> {legacy} <![endif]-->")
+        comment, p = div.children
+        self.assertTrue(comment.is_comment)
+        self.assertEqual(comment.text_content, "<!-- pw:keep -->")
+        self.assertFalse(p.is_comment)
+
+    def test_strip_comments(self) -> None:
+        doc = pywire_parser.parse(
+            "<!-- a --><div><!-- b --><p>x</p></div>", strip_comments=True
+        )
+        (div,) = doc.template
+        self.assertEqual([c.tag for c in div.children], ["p"])
+
+    def test_comment_between_text(self) -> None:
+        doc = pywire_parser.parse("<p>a<!-- b -->c</p>")
+        (p,) = doc.template
+        self.assertEqual([c.is_comment for c in p.children], [False, True, False])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,70 @@
+import asyncio
+import unittest
+from types import SimpleNamespace
+from typing import Any
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def render(source: str, **state: Any) -> str:
+    namespace: dict = {}
+    exec(pywire_parser.compile(source).code, namespace)
+    return asyncio.run(namespace["_render_template"](SimpleNamespace(**state)))
+
+
+class TestCompile(unittest.TestCase):
+    def test_markup_and_interpolation(self) -> None:
+        html = render('<p class="lead">Hi {name}</p>', name="<Ada>")
+        self.assertEqual(html, '<p class="lead">Hi &lt;Ada&gt;</p>')
+
+    def test_reactive_attributes(self) -> None:
+        source = "<input value={v} disabled={off} aria-busy={busy}>"
+        html = render(source, v=3, off=False, busy=True)
+        self.assertEqual(html, '<input value="3" aria-busy="true">')
+
+    def test_if_chain(self) -> None:
+        source = "{$if n > 1}many{$elif n}one{$else}none{/if}"
+        self.assertEqual([render(source, n=n) for n in (0, 1, 5)], ["none", "one", "many"])
+
+    def test_for_else(self) -> None:
+        source = "{$for item in items}<li>{item.upper()}</li>{$else}empty{/for}"
+        self.assertEqual(render(source, items=["a", "b"]), "<li>A</li><li>B</li>")
+        self.assertEqual(render(source, items=[]), "empty")
+
+    def test_keyed_for(self) -> None:
+        source = "{$for item in items, key=item.id}<li>{item.name}</li>{/for}"
+        items = [SimpleNamespace(id=1, name="a"), SimpleNamespace(id=2, name="b")]
+        self.assertEqual(render(source, items=items), "<li>a</li><li>b</li>")
+
+    def test_builtins_and_imports_are_not_rewritten(self) -> None:
+        source = "---\nimport math\n---\n<p>{len(xs)} {math.floor(x)}</p>"
+        self.assertEqual(render(source, xs=[1, 2], x=2.5), "<p>2 2</p>")
+
+    def test_frontmatter_names_shadow_builtins(self) -> None:
+        source = '---\nid = 7\ntype = "primary"\n---\n<p>{id} {type} {len(id_list)}</p>'
+        self.assertEqual(render(source, id=7, type="primary", id_list=[1]), "<p>7 primary 1</p>")
+        code = pywire_parser.compile(source).code
+        self.assertIn("self.id", code)
+        self.assertIn("self.type", code)
+
+    def test_static_character_references(self) -> None:
+        html = render('<img alt="Don&#39;t {name}">', name="Ada")
+        self.assertEqual(html, '<img alt="Don\'t Ada">')
+
+    def test_line_map(self) -> None:
+        source = "<ul>\n  {$for x in xs}\n    <li>{x}</li>\n  {/for}\n</ul>\n"
+        compiled = pywire_parser.compile(source)
+        lines = compiled.code.splitlines()
+        (loop,) = [i + 1 for i, line in enumerate(lines) if "async for" in line]
+        self.assertEqual(compiled.template_line(loop), 2)
+        self.assertIsNone(compiled.template_line(1))
+
+    def test_unsupported_constructs(self) -> None:
+        with self.assertRaises(NotImplementedError):
+            pywire_parser.compile("<button @click={save}>Save</button>")
+        with self.assertRaises(NotImplementedError):
+            pywire_parser.compile('<p>{f"{n} items"}</p>')
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,64 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+BUTTON = """{#
+  A clickable button.
+
+  Renders its default slot as the label.
+#}
+!component Button
+!props(
+    # Visual style of the button
+    variant: str = "primary",
+    size: int = 2,  # Font size in rem
+    disabled: bool = False,
+)
+
+<button class={variant}><slot></slot></button>
+"""
+
+
+class TestComponentDocs(unittest.TestCase):
+    def test_leading_comment_docstring(self) -> None:
+        doc = pywire_parser.parse(BUTTON)
+        self.assertEqual(
+            doc.docstring,
+            "A clickable button.\n\nRenders its default slot as the label.",
+        )
+
+    def test_doc_directive_wins(self) -> None:
+        doc = pywire_parser.parse('!doc "Shows a badge."\n{# not this #}\n<span></span>\n')
+        self.assertEqual(doc.docstring, "Shows a badge.")
+
+    def test_comment_opening_template_section(self) -> None:
+        doc = pywire_parser.parse("!path '/'\n\n{# Home page. #}\n<h1>Hi</h1>\n")
+        self.assertEqual(doc.docstring, "Home page.")
+
+    def test_pragma_is_not_a_docstring(self) -> None:
+        doc = pywire_parser.parse("{# pywire: disable #}\n<p>x</p>\n")
+        self.assertIsNone(doc.docstring)
+
+    def test_no_docstring(self) -> None:
+        self.assertIsNone(pywire_parser.parse("<p>{# later #}</p>\n").docstring)
+
+    def test_prop_docs(self) -> None:
+        index = pywire_parser.ProjectIndex()
+        index.add("components/Button.wire", BUTTON)
+        (sig,) = index.component_signatures()
+        self.assertTrue(sig.doc.startswith("A clickable button."))
+        docs = {p.name: p.doc for p in sig.props}
+        self.assertEqual(
+            docs,
+            {
+                "variant": "Visual style of the button",
+                "size": "Font size in rem",
+                "disabled": None,
+            },
+        )
+        self.assertEqual(sig.props[0].default, '"primary"')
+        self.assertEqual(sig.to_dict()["props"][1]["doc"], "Font size in rem")
+
+
+if __name__ == "__main__":
+    unittest.main()
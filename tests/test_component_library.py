@@ -0,0 +1,45 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+LIBRARY = """=== Badge ===
+<span class="badge">{label}</span>
+
+=== Avatar ===
+!props(src: str)
+<img src={src}>
+"""
+
+
+class TestComponentLibrary(unittest.TestCase):
+    def test_components_in_file_order(self) -> None:
+        components = pywire_parser.parse_library(LIBRARY)
+        self.assertEqual(list(components), ["Badge", "Avatar"])
+        self.assertEqual(components["Avatar"].line, 5)
+
+    def test_offsets_slice_component_body(self) -> None:
+        badge = pywire_parser.parse_library(LIBRARY)["Badge"]
+        body = LIBRARY[badge.start_byte : badge.end_byte]
+        self.assertEqual(body.strip(), '<span class="badge">{label}</span>')
+
+    def test_positions_refer_to_library_file(self) -> None:
+        avatar = pywire_parser.parse_library(LIBRARY)["Avatar"]
+        (img,) = [n for n in avatar.document.template if n.tag == "img"]
+        self.assertEqual(img.line, 6)
+        self.assertEqual([d.name for d in avatar.document.directives], ["props"])
+
+    def test_duplicate_name_rejected(self) -> None:
+        with self.assertRaises(pywire_parser.PyWireParseError) as ctx:
+            pywire_parser.parse_library("=== A ===\n<p/>\n=== A ===\n<b/>\n")
+        self.assertEqual(ctx.exception.line, 3)
+
+    def test_content_before_first_fence_rejected(self) -> None:
+        with self.assertRaises(pywire_parser.PyWireParseError):
+            pywire_parser.parse_library("<p>stray</p>\n=== A ===\n<b/>\n")
+
+    def test_empty_library(self) -> None:
+        self.assertEqual(dict(pywire_parser.parse_library("\n")), {})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,46 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+NodeKind = pywire_parser.NodeKind
+AttrKind = pywire_parser.AttrKind
+
+
+class TestComponentNodes(unittest.TestCase):
+    def test_component_kind(self) -> None:
+        doc = pywire_parser.parse(
+            '<Card title="Hi" open count={n + 1} {user} {**extra} @close={hide} $show>'
+            "<p>Body</p></Card>"
+        )
+        (card,) = doc.template
+        self.assertEqual(card.kind, NodeKind.COMPONENT)
+        self.assertEqual(card.component_name, "Card")
+        self.assertEqual([c.tag for c in card.children], ["p"])
+
+        props = {p.name: p for p in card.props}
+        self.assertEqual(sorted(props), ["**", "count", "open", "title", "user"])
+        self.assertEqual(props["title"].kind, AttrKind.STATIC)
+        self.assertEqual(props["title"].value, "Hi")
+        self.assertEqual(props["open"].kind, AttrKind.BOOLEAN)
+        self.assertIsNone(props["open"].value)
+        self.assertEqual(props["count"].kind, AttrKind.REACTIVE)
+        self.assertEqual(props["count"].value, "n + 1")
+        self.assertEqual(props["user"].kind, AttrKind.SHORTHAND)
+        self.assertEqual(props["user"].value, "user")
+        self.assertEqual(props["**"].kind, AttrKind.SPREAD)
+        self.assertEqual(props["**"].value, "extra")
+
+    def test_dotted_component(self) -> None:
+        (node,) = pywire_parser.parse("<ui.Button />").template
+        self.assertEqual(node.kind, NodeKind.COMPONENT)
+        self.assertEqual(node.component_name, "ui.Button")
+
+    def test_html_element(self) -> None:
+        (node,) = pywire_parser.parse('<div title="x"></div>').template
+        self.assertEqual(node.kind, NodeKind.ELEMENT)
+        self.assertIsNone(node.component_name)
+        self.assertEqual(node.props, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,57 @@
+import json
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PICKER = """!component Picker
+!props(items: list, selected: Optional[str] = None, on_select: Callable[[str], None] = None, on_close=None)
+
+<div class="picker">
+  <slot name="header"></slot>
+  {$for item in items}
+    <button @click={on_select(item)}>{item}</button>
+  {/for}
+  <button @click.prevent={on_close()}>x</button>
+  <slot></slot>
+</div>
+"""
+
+
+class TestComponentSignatures(unittest.TestCase):
+    def setUp(self) -> None:
+        self.index = pywire_parser.ProjectIndex()
+        self.index.add("components/Picker.wire", PICKER)
+        self.index.add("pages/index.wire", "<Picker items={[]} />\n")
+
+    def test_one_signature_per_component(self) -> None:
+        (sig,) = self.index.component_signatures()
+        self.assertEqual((sig.name, sig.file), ("Picker", "components/Picker.wire"))
+
+    def test_props(self) -> None:
+        (sig,) = self.index.component_signatures()
+        items, selected, *_ = sig.props
+        self.assertEqual((items.name, items.annotation, items.required), ("items", "list", True))
+        self.assertEqual((selected.annotation, selected.default), ("Optional[str]", "None"))
+        self.assertFalse(selected.required)
+
+    def test_slots(self) -> None:
+        (sig,) = self.index.component_signatures()
+        self.assertEqual(sig.slots, ["header", "default"])
+
+    def test_events(self) -> None:
+        (sig,) = self.index.component_signatures()
+        events = {e.name: (e.prop, e.triggers) for e in sig.events}
+        self.assertEqual(
+            events,
+            {"select": ("on_select", ["click"]), "close": ("on_close", ["click"])},
+        )
+
+    def test_to_dict_is_json_ready(self) -> None:
+        (sig,) = self.index.component_signatures()
+        data = json.loads(json.dumps(sig.to_dict()))
+        self.assertEqual(data["slots"], ["header", "default"])
+        self.assertTrue(data["props"][0]["required"])
+
+
+if __name__ == "__main__":
+    unittest.main()
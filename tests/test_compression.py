@@ -0,0 +1,80 @@
+import gzip
+import hashlib
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+try:
+    import brotli
+except ImportError:  # pragma: no cover - optional test dependency
+    brotli = None
+
+
+@unittest.skipUnless(
+    hasattr(pywire_parser, "Compressor"), "built without the compression feature"
+)
+class TestCompression(unittest.TestCase):
+    BODY = b"<ul>" + b"".join(b"<li>item %d</li>" % i for i in range(200)) + b"</ul>"
+
+    def test_gzip_round_trip(self) -> None:
+        compressor = pywire_parser.Compressor("gzip")
+        self.assertEqual(compressor.encoding, "gzip")
+        packed = compressor.compress(self.BODY)
+        self.assertLess(len(packed), len(self.BODY))
+        self.assertEqual(gzip.decompress(packed), self.BODY)
+
+    @unittest.skipIf(brotli is None, "brotli not installed")
+    def test_brotli_reuse_across_responses(self) -> None:
+        compressor = pywire_parser.Compressor("br", level=9)
+        for _ in range(3):
+            self.assertEqual(brotli.decompress(compressor.compress(self.BODY)), self.BODY)
+
+    def test_compress_serializer_output(self) -> None:
+        out = pywire_parser.HtmlSerializer(etag=True)
+        out.raw(self.BODY.decode())
+        packed = pywire_parser.Compressor("gzip").compress_output(out)
+        self.assertEqual(gzip.decompress(packed), self.BODY)
+        self.assertEqual(out.etag, pywire_parser.content_etag(self.BODY))
+
+    def test_shared_dictionary_shrinks_output(self) -> None:
+        plain = pywire_parser.Compressor("br").compress(self.BODY)
+        shared = pywire_parser.Compressor("br", dictionary=self.BODY).compress(self.BODY)
+        self.assertLess(len(shared), len(plain))
+
+    def test_shared_dictionary_round_trip(self) -> None:
+        compressor = pywire_parser.Compressor("br", dictionary=self.BODY)
+        self.assertEqual(compressor.encoding, "dcb")
+        packed = compressor.compress(self.BODY)
+        self.assertEqual(packed[:4], b"\xffDCB")
+        self.assertEqual(packed[4:36], hashlib.sha256(self.BODY).digest())
+        self.assertEqual(compressor.decompress(packed), self.BODY)
+        other = pywire_parser.Compressor("br", dictionary=b"<p>other</p>")
+        with self.assertRaises(ValueError):
+            other.decompress(packed)
+
+    def test_decompress_without_dictionary(self) -> None:
+        for encoding in ("gzip", "br"):
+            compressor = pywire_parser.Compressor(encoding)
+            self.assertEqual(compressor.encoding, encoding)
+            self.assertEqual(compressor.decompress(compressor.compress(self.BODY)), self.BODY)
+
+    def test_shared_between_threads(self) -> None:
+        from concurrent.futures import ThreadPoolExecutor
+
+        compressor = pywire_parser.Compressor("br", dictionary=self.BODY[:500])
+        bodies = [self.BODY + b"<p>%d</p>" % i for i in range(32)]
+        with ThreadPoolExecutor(8) as pool:
+            packed = list(pool.map(compressor.compress, bodies))
+        self.assertEqual([compressor.decompress(p) for p in packed], bodies)
+
+    def test_invalid_options(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.Compressor("zstd")
+        with self.assertRaises(ValueError):
+            pywire_parser.Compressor("gzip", level=12)
+        with self.assertRaises(ValueError):
+            pywire_parser.Compressor("gzip", dictionary=b"x")
+
+
+if __name__ == "__main__":
+    unittest.main()
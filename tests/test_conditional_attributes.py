@@ -0,0 +1,61 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def node(source):
+    return pywire_parser.parse(source).template[0]
+
+
+def codes(source):
+    return [d.code for d in pywire_parser.parse(source).diagnostics]
+
+
+class TestConditionalAttributes(unittest.TestCase):
+    def test_kinds(self) -> None:
+        p = node("<p class:active={on} style:color={c}>x</p>")
+        attrs = {a.name: a for a in p.parsed_attributes}
+        self.assertEqual(attrs["class:active"].kind, pywire_parser.AttrKind.CLASS_TOGGLE)
+        self.assertEqual(attrs["style:color"].kind, pywire_parser.AttrKind.STYLE_PROPERTY)
+        self.assertEqual(attrs["class:active"].expression, "on")
+
+    def test_conditional_entries(self) -> None:
+        p = node(
+            '<p class="item" class:active={i == selected} class:done={ item.done } '
+            "style:color={c}>x</p>"
+        )
+        self.assertEqual(
+            [(c.name, c.expression) for c in p.conditional_classes],
+            [("active", "i == selected"), ("done", "item.done")],
+        )
+        self.assertEqual([(s.name, s.expression) for s in p.conditional_styles], [("color", "c")])
+
+    def test_shorthand_uses_the_name(self) -> None:
+        p = node("<p class:active style:width>x</p>")
+        classes = [(c.name, c.expression) for c in p.conditional_classes]
+        styles = [(s.name, s.expression) for s in p.conditional_styles]
+        self.assertEqual(classes, [("active", "active")])
+        self.assertEqual(styles, [("width", "width")])
+        self.assertNotIn("invalid-conditional-attribute", codes("<p class:active>x</p>"))
+
+    def test_plain_class_is_not_conditional(self) -> None:
+        p = node('<p class="a b">x</p>')
+        self.assertEqual(p.conditional_classes, [])
+        self.assertEqual(p.attributes["class"], "a b")
+
+    def test_literal_values_are_reported(self) -> None:
+        for source in ('<p class:active="yes">x</p>', '<p style:color="red">x</p>'):
+            with self.subTest(source=source):
+                self.assertIn("invalid-conditional-attribute", codes(source))
+
+    def test_conditionals_make_the_element_dynamic(self) -> None:
+        self.assertIsNotNone(node("<p class:active={on}>x</p>").region)
+        self.assertIsNone(node('<p class="static">x</p>').region)
+
+    def test_not_component_props(self) -> None:
+        card = node("<Card class:wide={wide} title='T' />")
+        self.assertEqual([p.name for p in card.props], ["title"])
+
+
+if __name__ == "__main__":
+    unittest.main()
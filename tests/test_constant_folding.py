@@ -0,0 +1,93 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def folded(source):
+    doc = pywire_parser.parse(source, fold_constants=True)
+    return doc, doc.template[0].children
+
+
+class TestConstantFolding(unittest.TestCase):
+    def test_literal_expressions_become_text(self) -> None:
+        _, (text,) = folded("<p>{'active' if True else 'x'}</p>")
+        self.assertFalse(text.is_block)
+        self.assertEqual(text.text_content, "active")
+        self.assertEqual(text.folded_from, "'active' if True else 'x'")
+
+    def test_const_directives(self) -> None:
+        doc, (text,) = folded(
+            '!const SITE = "Acme"\n!const YEAR = 2020 + 6\n<p>{SITE + " " + "(c)"} {YEAR}</p>\n'
+        )
+        self.assertEqual(doc.constants, [("SITE", '"Acme"'), ("YEAR", "2020 + 6")])
+        texts = [n.text_content for n in doc.template[-1].children if n.folded_from]
+        self.assertEqual(texts, ["Acme (c)", "2026"])
+
+    def test_values_are_escaped_like_the_runtime(self) -> None:
+        _, (text,) = folded("<p>{'<b>' + \"&\"}</p>")
+        self.assertEqual(text.text_content, "&lt;b&gt;&amp;")
+
+    def test_python_str_semantics(self) -> None:
+        for expr, expected in (
+            ("None", "None"),
+            ("1 == 1.0", "True"),
+            ("2.0", "2.0"),
+            ("0.5 + 1", "1.5"),
+            ("'' or 'fallback'", "fallback"),
+            ("not (1 < 2)", "False"),
+        ):
+            with self.subTest(expr=expr):
+                _, (text,) = folded(f"<p>{{{expr}}}</p>")
+                self.assertEqual(text.text_content, expected)
+
+    def test_dynamic_expressions_are_kept(self) -> None:
+        for expr in ("name", "'a'.upper()", "SITE if user else 'x'", "'{x}'", "1e-9"):
+            with self.subTest(expr=expr):
+                _, (node,) = folded(f"<p>{{{expr}}}</p>")
+                self.assertTrue(node.is_block)
+                self.assertIsNone(node.folded_from)
+
+    def test_bound_names_are_not_folded(self) -> None:
+        def interpolations(doc):
+            found = []
+            stack = list(doc.template)
+            while stack:
+                node = stack.pop(0)
+                if node.block_keyword == "interpolation":
+                    found.append(node.expression)
+                stack.extend(node.children)
+            return found
+
+        for resolved in (False, True):
+            with self.subTest(resolved=resolved):
+                doc = pywire_parser.parse(
+                    '!const item = "x"\n!const TITLE = "T"\n'
+                    "<ul>{$for item in items}<li>{item}</li>{/for}</ul><h1>{TITLE}</h1>\n",
+                    fold_constants=True,
+                    resolved=resolved,
+                )
+                self.assertEqual(interpolations(doc), ["item"])
+        doc = pywire_parser.parse(
+            '---\nSITE = load_site()\n---\n!const SITE = "Acme"\n<p>{SITE}</p>\n',
+            fold_constants=True,
+        )
+        self.assertEqual(interpolations(doc), ["SITE"])
+
+    def test_folded_elements_are_static(self) -> None:
+        doc, _ = folded("<p>{'x' if False else 'y'}</p>")
+        self.assertIsNone(doc.template[0].region)
+
+    def test_folding_is_opt_in(self) -> None:
+        doc = pywire_parser.parse("<p>{'a'}</p>")
+        self.assertTrue(doc.template[0].children[0].is_block)
+
+    def test_invalid_const(self) -> None:
+        for directive in ("!const 1x = 2", "!const X = load()", "!const X"):
+            with self.subTest(directive=directive):
+                doc = pywire_parser.parse(directive + "\n<p></p>\n")
+                self.assertEqual([d.code for d in doc.diagnostics], ["invalid-directive"])
+                self.assertEqual(doc.constants, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,69 @@
+import json
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PICKER = """!component UserPicker
+!props(items: list, selected: Optional[str] = None, on_select: Callable[[str], None] = None)
+
+<div class="picker">
+  <slot name="header"></slot>
+  {$for item in items}
+    <button @click={on_select(item)}>{item}</button>
+  {/for}
+  <slot></slot>
+</div>
+"""
+
+
+class TestCustomElementsManifest(unittest.TestCase):
+    def setUp(self) -> None:
+        self.index = pywire_parser.ProjectIndex()
+        self.index.add("components/UserPicker.wire", PICKER)
+        self.index.add("components/HTMLView.wire", "<div><slot></slot></div>\n")
+        self.index.add("pages/index.wire", "<UserPicker items={[]} />\n")
+
+    def declarations(self, **kwargs):
+        manifest = self.index.custom_elements_manifest(**kwargs)
+        return {
+            m["declarations"][0]["name"]: m["declarations"][0] for m in manifest["modules"]
+        }
+
+    def test_json_ready(self) -> None:
+        manifest = json.loads(json.dumps(self.index.custom_elements_manifest()))
+        self.assertEqual(manifest["schemaVersion"], "1.0.0")
+        paths = [m["path"] for m in manifest["modules"]]
+        self.assertEqual(paths, ["components/HTMLView.wire", "components/UserPicker.wire"])
+
+    def test_tag_names(self) -> None:
+        tags = {name: d["tagName"] for name, d in self.declarations().items()}
+        self.assertEqual(tags, {"UserPicker": "pw-user-picker", "HTMLView": "pw-html-view"})
+        tags = {name: d["tagName"] for name, d in self.declarations(prefix="acme").items()}
+        self.assertEqual(tags["UserPicker"], "acme-user-picker")
+
+    def test_attributes_and_members(self) -> None:
+        picker = self.declarations()["UserPicker"]
+        attrs = {a["name"]: a for a in picker["attributes"]}
+        self.assertEqual(sorted(attrs), ["items", "selected"])
+        self.assertEqual(attrs["items"]["type"], {"text": "list"})
+        self.assertNotIn("default", attrs["items"])
+        self.assertEqual(attrs["selected"]["default"], "None")
+        members = [m["name"] for m in picker["members"]]
+        self.assertEqual(members, ["items", "selected", "on_select"])
+
+    def test_events_and_slots(self) -> None:
+        picker = self.declarations()["UserPicker"]
+        self.assertEqual([e["name"] for e in picker["events"]], ["select"])
+        self.assertEqual([s["name"] for s in picker["slots"]], ["header", ""])
+
+    def test_exports(self) -> None:
+        manifest = self.index.custom_elements_manifest()
+        module = next(m for m in manifest["modules"] if m["path"].endswith("UserPicker.wire"))
+        kinds = {e["kind"]: e["name"] for e in module["exports"]}
+        self.assertEqual(
+            kinds, {"js": "UserPicker", "custom-element-definition": "pw-user-picker"}
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,46 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestDepGraph(unittest.TestCase):
+    def setUp(self) -> None:
+        self.graph = pywire_parser.DepGraph()
+        # count -> doubled -> summary, count -> summary
+        self.graph.add_edge("count", "doubled")
+        self.graph.add_edge("doubled", "summary")
+        self.graph.add_edge("count", "summary")
+        self.graph.add_edge("other", "footer")
+
+    def test_dirty_set(self) -> None:
+        self.assertEqual(
+            self.graph.dirty_set(["count"]), {"count", "doubled", "summary"}
+        )
+        self.assertEqual(self.graph.dirty_set(["missing"]), set())
+
+    def test_dirty_order_is_topological(self) -> None:
+        self.assertEqual(
+            self.graph.dirty_order(["count"]), ["count", "doubled", "summary"]
+        )
+
+    def test_remove_node(self) -> None:
+        self.assertEqual(self.graph.edge_count, 4)
+        self.graph.remove_node("doubled")
+        self.assertEqual(self.graph.edge_count, 2)
+        self.assertEqual(self.graph.dirty_order(["count"]), ["count", "summary"])
+        self.assertFalse(self.graph.has_node("doubled"))
+
+    def test_cycle_detection(self) -> None:
+        self.assertIsNone(self.graph.find_cycle())
+        self.graph.add_edge("summary", "count")
+        self.assertEqual(
+            self.graph.find_cycle(), ["count", "doubled", "summary", "count"]
+        )
+        with self.assertRaises(ValueError):
+            self.graph.dirty_order(["count"])
+        # Cycles outside the dirty subgraph don't matter.
+        self.assertEqual(self.graph.dirty_order(["other"]), ["other", "footer"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,24 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestDevAnnotations(unittest.TestCase):
+    SOURCE = "<main>\n    <p>Hello</p>\n    <Card title=\"x\" />\n</main>\n"
+
+    def test_elements_are_stamped(self) -> None:
+        doc = pywire_parser.parse(self.SOURCE, "pages/index.wire")
+        main = doc.template[0]
+        self.assertEqual(main.attributes["data-pw-src"], "pages/index.wire:1")
+        tags = [c for c in main.children if c.tag]
+        self.assertEqual(tags[0].attributes["data-pw-src"], "pages/index.wire:2")
+        # Components receive attributes as props and are left alone.
+        self.assertNotIn("data-pw-src", tags[1].attributes)
+
+    def test_off_by_default(self) -> None:
+        doc = pywire_parser.parse(self.SOURCE)
+        self.assertNotIn("data-pw-src", doc.template[0].attributes)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,33 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.parser import PyWireParser
+
+PAGE = "<!DOCTYPE html>\n<html>\n<body><p>x</p></body>\n</html>\n"
+
+
+class TestDoctypeNodes(unittest.TestCase):
+    def test_doctype_preserved(self) -> None:
+        doc = pywire_parser.parse(PAGE)
+        doctype = doc.template[0]
+        self.assertTrue(doctype.is_doctype)
+        self.assertIsNone(doctype.tag)
+        self.assertEqual(doctype.kind, pywire_parser.NodeKind.DOCTYPE)
+        self.assertEqual(doctype.text_content, "<!DOCTYPE html>")
+        html = next(n for n in doc.template if n.tag)
+        self.assertFalse(html.is_doctype)
+
+    def test_case_is_kept(self) -> None:
+        doc = pywire_parser.parse("<!doctype html>\n<p>x</p>\n")
+        self.assertEqual(doc.template[0].text_content, "<!doctype html>")
+
+    def test_compiler_keeps_doctype_verbatim(self) -> None:
+        parsed = PyWireParser().parse(PAGE, "page.wire")
+        doctype = parsed.template[0]
+        self.assertTrue(doctype.is_doctype)
+        self.assertTrue(doctype.is_raw)
+        self.assertEqual(doctype.text_content, "<!DOCTYPE html>")
+
+
+if __name__ == "__main__":
+    unittest.main()
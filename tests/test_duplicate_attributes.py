@@ -0,0 +1,40 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def duplicates(source):
+    doc = pywire_parser.parse(source)
+    return [d for d in doc.diagnostics if d.code == "duplicate-attribute"]
+
+
+class TestDuplicateAttributes(unittest.TestCase):
+    def test_last_value_wins_with_a_warning(self) -> None:
+        source = '<div class="a" class="b"></div>'
+        (w,) = duplicates(source)
+        self.assertEqual(w.severity, "warning")
+        self.assertIn("'class'", w.message)
+        (div,) = pywire_parser.parse(source).template
+        self.assertEqual(div.attributes["class"], "b")
+
+    def test_both_spans(self) -> None:
+        source = '<div\n  id="x"\n  id="y"></div>'
+        (w,) = duplicates(source)
+        self.assertEqual((w.line, w.column, w.end_line, w.end_column), (3, 2, 3, 8))
+        self.assertEqual(source[w.start_byte : w.end_byte], 'id="y"')
+        self.assertEqual(w.related, [(2, 2, 2, 8)])
+
+    def test_each_repeat_is_reported(self) -> None:
+        self.assertEqual(len(duplicates("<p title=a title=b title=c></p>")), 2)
+
+    def test_distinct_attributes_and_spreads(self) -> None:
+        source = "<input {**a} {**b} class:on={x} class='y' @click={go} @keyup={go}>"
+        self.assertEqual(duplicates(source), [])
+
+    def test_can_be_suppressed(self) -> None:
+        source = "{# pywire: disable=duplicate-attribute #}\n<p title=a title=b></p>\n"
+        self.assertEqual(duplicates(source), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
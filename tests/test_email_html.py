@@ -0,0 +1,70 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestEmailHtml(unittest.TestCase):
+    def test_inlines_styles(self) -> None:
+        result = pywire_parser.email_html(
+            "<style>p { color: red; margin: 0 } .lead { color: blue }"
+            " #x { font-size: 12px !important }</style>"
+            '<p class="lead" id="x" style="margin: 4px; font-size: 20px">Hi</p>'
+        )
+        self.assertEqual(
+            result.html,
+            '<p class="lead" id="x" style="color: blue; margin: 4px;'
+            ' font-size: 12px !important">Hi</p>',
+        )
+        self.assertEqual(result.warnings, [])
+
+    def test_inline_important_wins(self) -> None:
+        result = pywire_parser.email_html(
+            "<style>p { color: red !important; margin: 0 !important }</style>"
+            '<p style="color: blue !important; margin: 4px">Hi</p>'
+        )
+        self.assertEqual(
+            result.html,
+            '<p style="margin: 0 !important; color: blue !important">Hi</p>',
+        )
+
+    def test_keeps_media_queries_and_pseudo_classes(self) -> None:
+        result = pywire_parser.email_html(
+            "<head><style>a { color: red } a:hover { color: blue }"
+            " @media (max-width: 600px) { a { color: green } }</style></head>"
+            '<a href="/">x</a>'
+        )
+        self.assertEqual(
+            result.html,
+            "<head><style>a:hover{color: blue}"
+            "@media (max-width: 600px){a { color: green }}</style></head>"
+            '<a href="/" style="color: red">x</a>',
+        )
+
+    def test_strips_scripts_and_handlers(self) -> None:
+        result = pywire_parser.email_html(
+            '<button onclick="go()" data-on-click="save" type="button">Go</button>'
+            "<script>alert(1)</script>"
+        )
+        self.assertEqual(result.html, '<button type="button">Go</button>')
+
+    def test_tables(self) -> None:
+        result = pywire_parser.email_html('<div class="card"><p>x</p></div>', tables=True)
+        self.assertEqual(
+            result.html,
+            '<table role="presentation" width="100%" cellpadding="0" cellspacing="0"'
+            ' border="0"><tr><td class="card"><p>x</p></td></tr></table>',
+        )
+
+    def test_unsupported_css_warnings(self) -> None:
+        result = pywire_parser.email_html(
+            "<style>\n.row { display: flex }\n</style>"
+            '<div class="row" style="display: flex">a</div><div class="row">b</div>'
+        )
+        (warning,) = result.warnings
+        self.assertEqual(warning.code, "email-unsupported-css")
+        self.assertEqual(warning.line, 2)
+        self.assertIn("flexbox", warning.message)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,51 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+LIST = """!component ItemList
+!emits item_selected, refresh
+
+---
+def reload():
+    $emit("refresh")
+---
+<ul>
+  {$for item in items}
+    <li @click={$emit("item_selected", item)}>{item}</li>
+  {/for}
+</ul>
+"""
+
+
+def index_with(page: str, component: str = LIST):
+    index = pywire_parser.ProjectIndex()
+    index.add("components/ItemList.wire", component)
+    index.add("pages/index.wire", page)
+    return index
+
+
+class TestEmits(unittest.TestCase):
+    def test_valid_contract(self) -> None:
+        index = index_with("<ItemList @item_selected={pick} @refresh={load} />\n")
+        self.assertEqual(index.check_events(), {})
+
+    def test_unknown_subscription(self) -> None:
+        index = index_with("<ItemList @item_picked={pick} />\n")
+        (d,) = index.check_events()["pages/index.wire"]
+        self.assertEqual((d.code, d.line), ("unknown-event", 1))
+        self.assertIn("'item_picked'", d.message)
+
+    def test_undeclared_and_unused(self) -> None:
+        component = LIST.replace("!emits item_selected, refresh", "!emits refresh, closed")
+        index = index_with("<ItemList />\n", component)
+        codes = sorted(d.code for d in index.check_events()["components/ItemList.wire"])
+        self.assertEqual(codes, ["undeclared-emit", "unused-emit"])
+
+    def test_signature_lists_declared_events(self) -> None:
+        (sig,) = index_with("<ItemList />\n").component_signatures()
+        events = {e.name: (e.prop, e.triggers) for e in sig.events}
+        self.assertEqual(events, {"item_selected": (None, ["click"]), "refresh": (None, [])})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.attributes.events import EventAttributeParser
+
+
+class TestEventAttributes(unittest.TestCase):
+    def attrs(self, source: str) -> list:
+        (node,) = pywire_parser.parse(source).template
+        return node.parsed_attributes
+
+    def test_on_prefix_with_modifiers(self) -> None:
+        (attr,) = self.attrs("<button on:click.prevent.once={save}>x</button>")
+        self.assertEqual(attr.kind, pywire_parser.AttrKind.EVENT)
+        self.assertEqual(attr.event, "click")
+        self.assertEqual(attr.modifiers, ["prevent", "once"])
+        self.assertEqual(attr.handler_expr, "save")
+
+    def test_at_prefix(self) -> None:
+        (attr,) = self.attrs('<form @submit="{handle(form)}">x</form>')
+        self.assertEqual(attr.event, "submit")
+        self.assertEqual(attr.modifiers, [])
+        self.assertEqual(attr.handler_expr, "handle(form)")
+
+    def test_other_attributes_have_no_event(self) -> None:
+        (attr,) = self.attrs('<a href="/">x</a>')
+        self.assertIsNone(attr.event)
+        self.assertEqual(attr.modifiers, [])
+        self.assertIsNone(attr.handler_expr)
+
+    def test_python_parser_accepts_on_prefix(self) -> None:
+        parser = EventAttributeParser()
+        self.assertTrue(parser.can_parse("on:input.debounce.300ms"))
+        attr = parser.parse("on:input.debounce.300ms", "{search}", 1, 0)
+        self.assertEqual(attr.event_type, "input")
+        self.assertEqual(attr.modifiers, ["debounce", "300ms"])
+        self.assertEqual(attr.handler_name, "search")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,49 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.parser import PyWireParser
+
+SOURCE = """<button class="btn">{label}</button>
+{$example "Primary button"}
+<button class="btn primary">Save</button>
+{/example}
+<div>
+  {$example 'Disabled'}<button disabled>No</button>{/example}
+</div>
+"""
+
+
+class TestExampleBlocks(unittest.TestCase):
+    def test_keyword(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        (opener,) = [n for n in doc.template if n.block_keyword == "example"]
+        self.assertEqual(opener.block_keyword, "example")
+        self.assertEqual(opener.keyword, pywire_parser.BlockKeyword.EXAMPLE)
+        self.assertIsNotNone(opener.close_index)
+
+    def test_extract_examples(self) -> None:
+        examples = pywire_parser.extract_examples(SOURCE)
+        self.assertEqual([e.title for e in examples], ["Primary button", "Disabled"])
+        primary = examples[0]
+        self.assertEqual(primary.line, 2)
+        self.assertEqual(
+            primary.source.strip(), '<button class="btn primary">Save</button>'
+        )
+        self.assertIn("button", [n.tag for n in primary.children])
+        self.assertEqual(examples[1].source, "<button disabled>No</button>")
+
+    def test_examples_are_not_rendered(self) -> None:
+        parsed = PyWireParser().parse(SOURCE)
+        tags = [n.tag for n in parsed.template if n.tag]
+        self.assertEqual(tags, ["button", "div"])
+        (div,) = [n for n in parsed.template if n.tag == "div"]
+        self.assertEqual([c.tag for c in div.children if c.tag], [])
+
+    def test_resolved_nests_body(self) -> None:
+        doc = pywire_parser.parse(SOURCE, resolved=True)
+        (opener,) = [n for n in doc.template if n.block_keyword == "example"]
+        self.assertIn("button", [c.tag for c in opener.children])
+
+
+if __name__ == "__main__":
+    unittest.main()
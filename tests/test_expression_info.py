@@ -0,0 +1,57 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestAnalyzeExpression(unittest.TestCase):
+    def test_names_chains_and_subscripts(self) -> None:
+        info = pywire_parser.analyze_expression("user.profile.name.upper() + items[0] + count")
+        self.assertEqual(info.names, ["user", "items", "count"])
+        self.assertEqual(info.attribute_chains, ["user.profile.name"])
+        self.assertEqual(info.subscripts, ["items"])
+
+    def test_dotted_subscript_target(self) -> None:
+        info = pywire_parser.analyze_expression("user.tags[-1]")
+        self.assertEqual(info.subscripts, ["user.tags"])
+        self.assertEqual(info.attribute_chains, ["user.tags"])
+
+    def test_comprehension_variables_are_not_reads(self) -> None:
+        info = pywire_parser.analyze_expression("[t.title for t in todos if not t.done]")
+        self.assertEqual(info.names, ["todos"])
+        self.assertEqual(info.attribute_chains, [])
+
+    def test_strings_and_keyword_arguments(self) -> None:
+        info = pywire_parser.analyze_expression("fmt('a.b[0]', sep=sep)")
+        self.assertEqual(info.names, ["fmt", "sep"])
+        self.assertEqual(info.subscripts, [])
+
+    def test_fstring_fields_are_reads(self) -> None:
+        info = pywire_parser.analyze_expression(
+            "f'{count} of {total!r:>{width}} {{literal}}' + rf\"{path}\" + 'plain {x}'"
+        )
+        self.assertEqual(info.names, ["count", "total", "width", "path"])
+
+    def test_fstring_field_with_nested_quotes(self) -> None:
+        info = pywire_parser.analyze_expression('f"{user[\'name\']}: {a != b}"')
+        self.assertEqual(info.names, ["user", "a", "b"])
+
+
+class TestParsedExpressionInfo(unittest.TestCase):
+    def test_interpolations_carry_their_reads(self) -> None:
+        (p,) = pywire_parser.parse("<p>{user.name}</p>").template
+        (interp,) = p.children
+        self.assertEqual(interp.expression_info.attribute_chains, ["user.name"])
+
+    def test_loop_reads_only_the_iterable(self) -> None:
+        doc = pywire_parser.parse("{$for row in table.rows}{row}{/for}")
+        info = doc.template[0].expression_info
+        self.assertEqual(info.expression, "row in table.rows")
+        self.assertEqual(info.names, ["table"])
+
+    def test_text_has_none(self) -> None:
+        (p,) = pywire_parser.parse("<p>x</p>").template
+        self.assertIsNone(p.children[0].expression_info)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,39 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestExtractMeta(unittest.TestCase):
+    def test_static_and_expression_tags(self) -> None:
+        doc = pywire_parser.parse(
+            """!path '/posts/:slug'
+
+<head>
+    <title>{post.title} | Blog</title>
+    <meta name="description" content="Posts about things">
+    <meta property="og:title" content={post.title}>
+    <meta name="twitter:card" content="summary_large_image">
+    <meta name="viewport" content="width=device-width">
+</head>
+"""
+        )
+        meta = pywire_parser.extract_meta(doc)
+        self.assertEqual(
+            [t.key for t in meta.tags],
+            ["title", "description", "og:title", "twitter:card"],
+        )
+        self.assertEqual(meta.title.value, "{post.title} | Blog")
+        self.assertTrue(meta.title.is_expression)
+        self.assertFalse(meta.description.is_expression)
+        self.assertTrue(meta.get("og:title").is_expression)
+        self.assertEqual(meta.missing(), ["og:description", "og:image"])
+        self.assertEqual(meta.missing(["og:title"]), [])
+
+    def test_page_without_meta(self) -> None:
+        meta = pywire_parser.extract_meta(pywire_parser.parse("<p>hi</p>\n"))
+        self.assertEqual(len(meta), 0)
+        self.assertIsNone(meta.title)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,108 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+RSS = """!feed rss
+<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Blog</title>
+    <link>https://example.com/</link>
+    <description>Posts</description>
+    {$for post in posts}
+    <item>
+      <title>{post.title}</title>
+      <description><![CDATA[<p>{not interpolated}</p>]]></description>
+    </item>
+    {/for}
+  </channel>
+</rss>
+"""
+
+
+def codes(source):
+    return [d.code for d in pywire_parser.parse(source).diagnostics]
+
+
+def find(nodes, tag):
+    for node in nodes:
+        if node.tag == tag:
+            return node
+        found = find(node.children, tag)
+        if found is not None:
+            return found
+    return None
+
+
+class TestFeedTemplates(unittest.TestCase):
+    def test_valid_rss(self) -> None:
+        doc = pywire_parser.parse(RSS)
+        self.assertEqual(doc.feed, "rss")
+        self.assertEqual([d.code for d in doc.diagnostics if d.severity == "error"], [])
+
+    def test_link_keeps_its_content(self) -> None:
+        link = find(pywire_parser.parse(RSS).template, "link")
+        (text,) = link.children
+        self.assertEqual(text.text_content, "https://example.com/")
+
+    def test_cdata_is_verbatim(self) -> None:
+        description = find(find(pywire_parser.parse(RSS).template, "item").children, "description")
+        (text,) = description.children
+        self.assertTrue(text.is_raw)
+        self.assertEqual(text.text_content, "<![CDATA[<p>{not interpolated}</p>]]>")
+
+    def test_missing_channel_fields(self) -> None:
+        source = '!feed\n<rss version="2.0"><channel><title>x</title></channel></rss>\n'
+        self.assertEqual(codes(source).count("feed-missing-field"), 2)
+
+    def test_wrong_root(self) -> None:
+        self.assertIn("feed-root", codes("!feed atom\n<rss version=\"2.0\"></rss>\n"))
+
+    def test_atom_entry_fields(self) -> None:
+        source = (
+            "!feed atom\n<feed><id>x</id><title>t</title><updated>u</updated>"
+            "<entry><title>e</title></entry></feed>\n"
+        )
+        messages = [
+            d.message
+            for d in pywire_parser.parse(source).diagnostics
+            if d.code == "feed-missing-field"
+        ]
+        self.assertEqual(len(messages), 3)
+
+    def test_invalid_format(self) -> None:
+        self.assertIn("invalid-directive", codes("!feed json\n<rss></rss>\n"))
+
+    def test_every_entry_point_masks_feeds(self) -> None:
+        def link_text(doc):
+            return find(doc.template, "link").children[0].text_content
+
+        document = pywire_parser.Document(RSS)
+        many = pywire_parser.parse_many({"feed.wire": RSS})["feed.wire"]
+        library = pywire_parser.parse_library("=== Feed ===\n" + RSS)["Feed"].document
+        for doc in (document.reparse(), many, library):
+            self.assertEqual(link_text(doc), "https://example.com/")
+            self.assertEqual(codes(RSS), [d.code for d in doc.diagnostics])
+        document.edit(0, 0, 0, "")
+        self.assertEqual(link_text(document.reparse()), "https://example.com/")
+
+    def test_html_document_is_not_a_feed(self) -> None:
+        doc = pywire_parser.parse("<link rel=\"x\">\n")
+        self.assertIsNone(doc.feed)
+
+
+class TestXmlSerializer(unittest.TestCase):
+    def test_no_void_elements(self) -> None:
+        out = pywire_parser.HtmlSerializer(xml=True)
+        out.start_tag("link")
+        out.text("https://example.com/?a&b")
+        out.end_tag("link")
+        out.start_tag("enclosure", [("url", "a.mp3"), ("hidden", None)], self_closing=True)
+        self.assertEqual(
+            out.finish(),
+            b'<link>https://example.com/?a&amp;b</link><enclosure url="a.mp3" hidden="hidden" />',
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,73 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """!path '/items'
+---
+items = [1, 2]
+def label(i):
+        return f"#{i}"
+---
+<main>
+<ul>
+      {$for item in items}
+<li class="item"
+    data-id={item}>{label(item)}</li>
+{/for}
+    </ul>
+        <pre>
+  keep   this
+</pre>
+</main>
+"""
+
+EXPECTED = """!path '/items'
+---
+items = [1, 2]
+def label(i):
+        return f"#{i}"
+---
+<main>
+  <ul>
+    {$for item in items}
+      <li class="item"
+          data-id={item}>{label(item)}</li>
+    {/for}
+  </ul>
+  <pre>
+  keep   this
+</pre>
+</main>
+"""
+
+
+class TestFormat(unittest.TestCase):
+    def test_reindents_tags_and_blocks(self) -> None:
+        self.assertEqual(pywire_parser.format(SOURCE), EXPECTED)
+
+    def test_idempotent(self) -> None:
+        self.assertEqual(pywire_parser.format(EXPECTED), EXPECTED)
+
+    def test_branch_markers_align_with_the_opener(self) -> None:
+        source = "<div>\n{$if a}\n<b>a</b>\n{$else}\n<i>b</i>\n{/if}\n</div>\n"
+        self.assertEqual(
+            pywire_parser.format(source),
+            "<div>\n  {$if a}\n    <b>a</b>\n  {$else}\n    <i>b</i>\n  {/if}\n</div>\n",
+        )
+
+    def test_indent_options(self) -> None:
+        source = "<div>\n<p>x</p>\n</div>\n"
+        self.assertEqual(pywire_parser.format(source, indent=4), "<div>\n    <p>x</p>\n</div>\n")
+        tabbed = pywire_parser.format(source, use_tabs=True, indent=4)
+        self.assertEqual(tabbed, "<div>\n\t<p>x</p>\n</div>\n")
+
+    def test_whitespace_rules_still_apply(self) -> None:
+        self.assertEqual(pywire_parser.format("\r\n<p>x</p>   \r\n\n\n\n"), "<p>x</p>\n")
+
+    def test_syntax_errors_raise(self) -> None:
+        with self.assertRaises(Exception):
+            pywire_parser.format("<div>{$if x}</div>\n")
+
+
+if __name__ == "__main__":
+    unittest.main()
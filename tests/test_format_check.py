@@ -0,0 +1,59 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestFormatCheck(unittest.TestCase):
+    def test_formatted_source(self) -> None:
+        check = pywire_parser.format_check("<p>x</p>\n")
+        self.assertTrue(check.is_formatted)
+        self.assertTrue(check)
+        self.assertEqual(check.diff, "")
+
+    def test_trailing_whitespace_diff(self) -> None:
+        check = pywire_parser.format_check("<div>\n  <p>x</p>   \n</div>\n", "a.wire")
+        self.assertFalse(check.is_formatted)
+        self.assertEqual(
+            check.diff,
+            "--- a/a.wire\n+++ b/a.wire\n"
+            "@@ -1,3 +1,3 @@\n"
+            " <div>\n-  <p>x</p>   \n+  <p>x</p>\n </div>\n",
+        )
+
+    def test_missing_final_newline(self) -> None:
+        check = pywire_parser.format_check("<p>x</p>")
+        self.assertIn("\\ No newline at end of file", check.diff)
+        self.assertTrue(check.diff.endswith("+<p>x</p>\n"))
+
+    def test_blank_lines_and_line_endings(self) -> None:
+        source = "\n\n<a></a>\r\n\n\n\n\n<b></b>\r\n"
+        self.assertEqual(pywire_parser.format_source(source), "<a></a>\n\n\n<b></b>\n")
+
+    def test_pre_is_left_alone(self) -> None:
+        source = "<pre>\n  keep   \n\n\n\n</pre>\n"
+        self.assertTrue(pywire_parser.format_check(source).is_formatted)
+
+    def test_separate_hunks(self) -> None:
+        lines = [f"<p>{i}</p>" for i in range(20)]
+        lines[2] += " "
+        lines[17] += " "
+        diff = pywire_parser.format_check("\n".join(lines) + "\n").diff
+        self.assertEqual(diff.count("@@ -"), 2)
+        self.assertIn("@@ -1,6 +1,6 @@", diff)
+
+    def test_large_rewrite_is_one_hunk(self) -> None:
+        lines = [f"<p>{i}</p>" for i in range(3000)]
+        source = "<main>\n" + "\r\n".join(lines) + "\r\n</main>\n"
+        diff = pywire_parser.format_check(source, "big.wire").diff
+        self.assertEqual(diff.count("@@ -"), 1)
+        self.assertIn("@@ -1,3002 +1,3002 @@", diff)
+        self.assertEqual(diff.count("\n-<p>"), 3000)
+        self.assertEqual(diff.count("\n+<p>"), 3000)
+
+    def test_format_is_idempotent(self) -> None:
+        once = pywire_parser.format_source("  <p>x</p>  \n\n\n\n")
+        self.assertEqual(pywire_parser.format_source(once), once)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,71 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestFromHtml(unittest.TestCase):
+    def test_plain_page(self) -> None:
+        html = (
+            "<!DOCTYPE html>\n<html><body>\n<p class=\"a\">Use {braces} &amp; more</p>\n"
+            "<script>if (x) { go(); }</script>\n</body></html>\n"
+        )
+        wire = pywire_parser.from_html(html).wire
+        self.assertIn("<p class=\"a\">Use &#123;braces&#125; &amp; more</p>", wire)
+        self.assertIn("<script>if (x) { go(); }</script>", wire)
+        self.assertTrue(wire.startswith("<!DOCTYPE html>"))
+        self.assertNotIn("---", wire)
+
+    def test_lenient_input(self) -> None:
+        wire = pywire_parser.from_html("<ul><li>One<li>Two</ul>").wire
+        self.assertEqual(wire, "<ul><li>One</li><li>Two</li></ul>")
+
+    def test_detect_loops(self) -> None:
+        html = (
+            "<ul>\n"
+            '  <li><a href="/">Home</a></li>\n'
+            '  <li><a href="/docs">Docs &amp; API</a></li>\n'
+            '  <li><a href="/blog">Blog</a></li>\n'
+            "</ul>\n"
+        )
+        wire = pywire_parser.from_html(html, detect_loops=True).wire
+        self.assertEqual(
+            wire,
+            "---\n"
+            "li_items = [\n"
+            '    {"href": "/", "text": "Home"},\n'
+            '    {"href": "/docs", "text": "Docs & API"},\n'
+            '    {"href": "/blog", "text": "Blog"},\n'
+            "]\n"
+            "---\n"
+            "<ul>\n"
+            "  {$for item in li_items}\n"
+            '  <li><a href={item["href"]}>{item["text"]}</a></li>\n'
+            "  {/for}\n"
+            "</ul>\n",
+        )
+
+    def test_identical_or_short_runs_are_kept(self) -> None:
+        html = "<div><hr><hr><hr></div><p>a</p><p>b</p>"
+        self.assertEqual(pywire_parser.from_html(html, detect_loops=True).wire, html)
+
+    def test_framework_attributes_are_dropped(self) -> None:
+        html = (
+            '<div x-data="{open: false}">\n'
+            '  <button @click="open = !open" :class="{on: open}">Menu</button>\n'
+            "</div>\n"
+        )
+        result = pywire_parser.from_html(html)
+        self.assertEqual(
+            result.wire,
+            '<div x-data="&#123;open: false&#125;">\n  <button>Menu</button>\n</div>\n',
+        )
+        self.assertEqual(
+            [(d.code, d.severity, d.line) for d in result.diagnostics],
+            [("html-template-attribute", "warning", 2)] * 2,
+        )
+        self.assertIn("`@click`", result.diagnostics[0].message)
+        self.assertIn("`:class`", result.diagnostics[1].message)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,81 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestFromJinja(unittest.TestCase):
+    def test_expressions_and_filters(self) -> None:
+        result = pywire_parser.from_jinja(
+            "<p>{{ user.name|title }} ({{ items|length }})</p>{{ bio|safe }}"
+        )
+        self.assertEqual(
+            result.wire,
+            "<p>{(user.name).title()} ({len(items)})</p>{$html bio}",
+        )
+        self.assertEqual(result.converted, 3)
+        self.assertEqual(result.diagnostics, [])
+
+    def test_blocks(self) -> None:
+        result = pywire_parser.from_jinja(
+            "{% if user %}Hi{% elif guest %}Guest{% else %}?{% endif %}\n"
+            "{%- for x in xs -%}<li>{{ x|default('none') }}</li>{% endfor %}"
+        )
+        self.assertEqual(
+            result.wire,
+            "{$if user}Hi{$elif guest}Guest{$else}?{/if}\n"
+            "{- $for x in xs -}<li>{(x or 'none')}</li>{/for}",
+        )
+
+    def test_for_empty(self) -> None:
+        result = pywire_parser.from_jinja(
+            "{% for x in xs %}<li>{{ x }}</li>{% empty %}<p>None</p>{% endfor %}"
+        )
+        self.assertEqual(
+            result.wire,
+            "{$if xs}{$for x in xs}<li>{x}</li>{/for}{$else}<p>None</p>{/if}",
+        )
+
+    def test_layouts_and_includes(self) -> None:
+        result = pywire_parser.from_jinja(
+            '{% extends "base.html" %}{% set title = "Home" %}'
+            '{% block content %}{% include "partials/site_nav.html" %}{% endblock %}'
+        )
+        self.assertEqual(
+            result.wire,
+            '!layout "base.wire"\n'
+            '!component "partials/site_nav.wire" as SiteNav\n'
+            '---\ntitle = "Home"\n---\n'
+            '<template slot="content"><SiteNav /></template>',
+        )
+        self.assertEqual([d.code for d in result.diagnostics], ["jinja-include"])
+
+        base = pywire_parser.from_jinja("<main>{% block content %}{% endblock %}</main>")
+        self.assertEqual(base.wire, '<main><slot name="content"></slot></main>')
+
+    def test_todos(self) -> None:
+        result = pywire_parser.from_jinja(
+            "<a href=\"{% url 'home' %}\">{{ d|dictsort }}</a>\n"
+            "{% for x in xs if x %}{{ x }}{% endfor %}"
+        )
+        self.assertIn("{# TODO(jinja): {% url 'home' %} #}", result.wire)
+        self.assertIn("{# TODO(jinja): {{ d|dictsort }} #}", result.wire)
+        self.assertIn("{# TODO(jinja): {% endfor %} #}", result.wire)
+        self.assertNotIn("{/for}", result.wire)
+        lines = [d.line for d in result.diagnostics]
+        self.assertEqual(lines, [1, 1, 2, 2])
+        self.assertIn("need manual changes", result.report)
+
+    def test_literal_braces_and_comments(self) -> None:
+        result = pywire_parser.from_jinja(
+            "<p>{x}</p><script>if (a) { b() }</script>{# note #}"
+            "{% raw %}{{ keep }}{% endraw %}"
+        )
+        self.assertEqual(
+            result.wire,
+            "<p>&#123;x&#125;</p><script>if (a) { b() }</script>{# note #}"
+            "&#123;&#123; keep &#125;&#125;",
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,76 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """---
+import json
+import os.path as osp
+from pywire import wire, WireList
+from .models import (
+    User,  # the account
+    Team as Group,
+)
+
+count = wire(0)
+todos: list = WireList([])
+label = "x"
+
+
+async def load():
+    data = wire(None)
+    return data
+
+
+def helper():
+    pass
+---
+<p>{count}</p>
+"""
+
+
+class TestFrontmatterInfo(unittest.TestCase):
+    def setUp(self) -> None:
+        self.info = pywire_parser.frontmatter_info(pywire_parser.parse(SOURCE))
+
+    def test_names(self) -> None:
+        self.assertEqual(
+            self.info.names,
+            [
+                "json",
+                "osp",
+                "wire",
+                "WireList",
+                "User",
+                "Group",
+                "count",
+                "todos",
+                "label",
+                "load",
+                "helper",
+            ],
+        )
+
+    def test_wires(self) -> None:
+        wires = [(w.name, w.constructor, w.line) for w in self.info.wires]
+        self.assertEqual(wires, [("count", "wire", 10), ("todos", "WireList", 11)])
+
+    def test_imports(self) -> None:
+        imports = [(i.module, i.name, i.bound, i.line) for i in self.info.imports]
+        self.assertEqual(
+            imports,
+            [
+                ("json", None, "json", 2),
+                ("os.path", None, "osp", 3),
+                ("pywire", "wire", "wire", 4),
+                ("pywire", "WireList", "WireList", 4),
+                (".models", "User", "User", 5),
+                (".models", "Team", "Group", 5),
+            ],
+        )
+
+    def test_async_functions(self) -> None:
+        self.assertEqual(self.info.async_functions, ["load"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,48 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PAGE = """<header>
+  <h1>{user.profile.display_name}</h1>
+  <img alt={user.profile.display_name} src="{user.profile.avatar}" />
+</header>
+<p>Signed in as {user.profile.display_name}</p>
+"""
+
+
+class TestHoistingHints(unittest.TestCase):
+    def hints(self, source: str, **kwargs: int) -> list:
+        return pywire_parser.hoisting_hints(pywire_parser.parse(source), **kwargs)
+
+    def test_repeated_chain(self) -> None:
+        (hint,) = self.hints(PAGE)
+        self.assertEqual(hint.expression, "user.profile.display_name")
+        self.assertEqual(hint.count, 3)
+        self.assertEqual(hint.occurrences[0], (2, 6))
+        self.assertIsNone(hint.scope)
+
+    def test_min_count(self) -> None:
+        self.assertEqual(self.hints(PAGE, min_count=4), [])
+
+    def test_method_calls_are_not_hoisted(self) -> None:
+        hints = self.hints("<p>{user.name.upper()}</p><p>{user.name.title()}</p>\n")
+        self.assertEqual([h.expression for h in hints], ["user.name"])
+
+    def test_string_literals_are_ignored(self) -> None:
+        self.assertEqual(self.hints("<p>{'a.b'}</p><p>{'a.b'}</p>\n"), [])
+
+    def test_loop_target_scopes_the_hint(self) -> None:
+        source = (
+            "{$for row in rows}\n"
+            "  <td>{row.owner.name}</td><td title={row.owner.name}>{site.title}</td>\n"
+            "{/for}\n"
+            "<footer>{site.title}</footer>\n"
+        )
+        hints = {h.expression: h for h in self.hints(source)}
+        self.assertEqual(hints["row.owner.name"].scope, (1, 0))
+        self.assertEqual(hints["site.title"].count, 2)
+        self.assertIsNone(hints["site.title"].scope)
+
+
+if __name__ == "__main__":
+    unittest.main()
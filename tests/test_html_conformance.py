@@ -0,0 +1,43 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+# Pure-HTML inputs our lenient parser should read exactly like html5ever.
+CORPUS = [
+    '<div class="a" id=b><span>x</span> y</div>',
+    "<br><img src=x.png alt=''><input disabled><hr/>",
+    "<ul><li>a<li>b<li>c</ul>",
+    "<dl><dt>term<dd>definition<dt>next<dd>more</dl>",
+    "<select><option>a<option selected>b</select>",
+    "<p>one<p>two<div>block</div>",
+    "<script>if (a < b && c) { x('</p>') }</script><p>after</p>",
+    "<style>p > a { color: red }</style><textarea><b>raw</b></textarea>",
+    '<a href="/x?a=1&amp;b=2">link &amp; more</a>',
+    "<span>stray</div>end</span>",
+    "<template><li>row</li></template>",
+    "<svg width=16 height=16><path d='M0 0'/></svg>",
+]
+
+
+@unittest.skipUnless(
+    hasattr(pywire_parser, "html_divergences"), "built without the conformance feature"
+)
+class TestHtmlConformance(unittest.TestCase):
+    def test_corpus_matches_reference(self) -> None:
+        for html in CORPUS:
+            with self.subTest(html=html):
+                found = pywire_parser.html_divergences(html)
+                self.assertEqual([(d.kind, d.path) for d in found], [])
+
+    def test_divergence_is_structured(self) -> None:
+        (d,) = pywire_parser.html_divergences("<table><tr><td>1</td></tr></table>")
+        self.assertEqual((d.kind, d.path), ("tag", "/table[0]/tr[0]"))
+        self.assertEqual((d.ours, d.reference), ("<tr>", "<tbody>"))
+
+    def test_missing_and_extra_nodes(self) -> None:
+        kinds = {d.kind for d in pywire_parser.html_divergences("<p>a</p></br>")}
+        self.assertIn("missing", kinds)
+
+
+if __name__ == "__main__":
+    unittest.main()
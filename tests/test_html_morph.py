@@ -0,0 +1,151 @@
+import random
+import unittest
+from html.parser import HTMLParser
+from typing import Any, List
+
+from pywire import _pywire_parser as pywire_parser
+
+VOID = {"br", "img", "input", "hr", "meta", "link"}
+
+
+class Node:
+    def __init__(self, tag: Any = None, attrs: Any = None, text: Any = None) -> None:
+        self.tag = tag
+        self.attrs = dict(attrs or [])
+        self.text = text
+        self.children: List["Node"] = []
+
+    def html(self) -> str:
+        if self.tag is None:
+            return self.text
+        attrs = "".join(f' {k}="{v}"' for k, v in self.attrs.items())
+        if self.tag in VOID:
+            return f"<{self.tag}{attrs}>"
+        inner = "".join(c.html() for c in self.children)
+        return f"<{self.tag}{attrs}>{inner}</{self.tag}>"
+
+
+class TreeParser(HTMLParser):
+    def __init__(self) -> None:
+        super().__init__(convert_charrefs=False)
+        self.stack = [Node("#root")]
+
+    def handle_starttag(self, tag: str, attrs: Any) -> None:
+        node = Node(tag, attrs)
+        self.stack[-1].children.append(node)
+        if tag not in VOID:
+            self.stack.append(node)
+
+    def handle_endtag(self, tag: str) -> None:
+        self.stack.pop()
+
+    def handle_data(self, data: str) -> None:
+        self.stack[-1].children.append(Node(text=data))
+
+
+def parse(html: str) -> Node:
+    parser = TreeParser()
+    parser.feed(html)
+    return parser.stack[0]
+
+
+def apply_ops(root: Node, ops: list) -> None:
+    def at(path: list) -> Node:
+        node = root
+        for i in path:
+            node = node.children[i]
+        return node
+
+    for op in ops:
+        if op.kind == "set_attr":
+            at(op.path).attrs[op.name] = op.value
+        elif op.kind == "remove_attr":
+            del at(op.path).attrs[op.name]
+        elif op.kind == "set_attrs":
+            for name, value in op.attrs:
+                if value is None:
+                    del at(op.path).attrs[name]
+                else:
+                    at(op.path).attrs[name] = value
+        elif op.kind == "set_text":
+            at(op.path).text = op.value
+        elif op.kind == "replace":
+            parent = at(op.path[:-1])
+            parent.children[op.path[-1]] = parse(op.html).children[0]
+        elif op.kind == "reorder":
+            parent = at(op.path)
+            old = parent.children
+            inserted = dict(op.inserted)
+            parent.children = [
+                parse(inserted[i]).children[0] if src < 0 else old[src]
+                for i, src in enumerate(op.sources)
+            ]
+
+
+class TestHtmlMorph(unittest.TestCase):
+    def check(self, old: str, new: str) -> list:
+        ops = pywire_parser.morph_html(old, new)
+        root = parse(old)
+        apply_ops(root, ops)
+        self.assertEqual("".join(c.html() for c in root.children), new)
+        return ops
+
+    def test_identical(self) -> None:
+        self.assertEqual(self.check("<div><p>a</p></div>", "<div><p>a</p></div>"), [])
+
+    def test_attribute_and_text_patch(self) -> None:
+        ops = self.check(
+            '<div class="a"><span>1</span></div>', '<div class="b"><span>2</span></div>'
+        )
+        self.assertEqual([op.kind for op in ops], ["set_attr", "set_text"])
+        self.assertEqual(ops[1].path, [0, 0, 0])
+
+    def test_several_attribute_changes_are_one_op(self) -> None:
+        ops = self.check(
+            '<input type="range" value="1" title="1">', '<input type="range" value="2" max="9">'
+        )
+        self.assertEqual([op.kind for op in ops], ["set_attrs"])
+        self.assertEqual(ops[0].attrs, [("value", "2"), ("max", "9"), ("title", None)])
+
+    def test_keyed_elements_are_moved_not_recreated(self) -> None:
+        old = '<ul><li id="a">A</li><li id="b">B</li><li id="c">C</li></ul>'
+        new = '<ul><li id="c">C</li><li id="a">A</li><li id="b">B</li></ul>'
+        ops = self.check(old, new)
+        self.assertEqual(len(ops), 1)
+        self.assertEqual(ops[0].kind, "reorder")
+        self.assertEqual(ops[0].moved, [0])
+        self.assertEqual(ops[0].inserted, [])
+
+    def test_full_document_is_morphed_in_place(self) -> None:
+        old = "<html><head><title>A</title></head><body><main>x</main></body></html>"
+        new = "<html><head><title>B</title></head><body><main>y</main></body></html>"
+        ops = self.check(old, new)
+        self.assertTrue(all(op.kind == "set_text" for op in ops))
+
+    def test_multibyte_after_doctype_prefix(self) -> None:
+        # byte 9 falls inside the 'é'; used to panic slicing the str
+        ops = pywire_parser.morph_html("<!doctypé><p>a</p>", "<!doctypé><p>b</p>")
+        self.assertEqual([op.kind for op in ops], ["set_text"])
+
+    def test_random_fragments(self) -> None:
+        rng = random.Random(3)
+
+        def fragment(depth: int) -> str:
+            parts = []
+            for _ in range(rng.randint(0, 3)):
+                roll = rng.random()
+                if roll < 0.3 or depth > 2:
+                    parts.append(rng.choice(["x", "y", "z"]))
+                else:
+                    # no <p>: its implied-close rules differ from html.parser
+                    tag = rng.choice(["div", "span", "em"])
+                    attr = rng.choice(["", ' class="c"', f' id="k{rng.randint(0, 3)}"'])
+                    parts.append(f"<{tag}{attr}>{fragment(depth + 1)}</{tag}>")
+            return "".join(parts)
+
+        for _ in range(200):
+            self.check(fragment(0), fragment(0))
+
+
+if __name__ == "__main__":
+    unittest.main()
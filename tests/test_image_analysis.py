@@ -0,0 +1,51 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """<main>
+    <img src="/hero.jpg" alt="Hero">
+    <img src="/logo.png" width="64" height="64" loading="lazy" />
+    <img src={user.avatar}>
+</main>
+"""
+
+
+class TestImageAnalysis(unittest.TestCase):
+    def test_analyze_images(self) -> None:
+        images = pywire_parser.analyze_images(pywire_parser.parse(SOURCE))
+        self.assertEqual([i.src for i in images], ["/hero.jpg", "/logo.png", None])
+        self.assertEqual(images[0].missing, ["width", "height", "loading"])
+        self.assertEqual(images[1].missing, [])
+        self.assertFalse(images[2].is_static)
+        self.assertEqual(images[0].alt, "Hero")
+        self.assertEqual(images[0].line, 2)
+
+    def test_rewrite_images_injects_attributes(self) -> None:
+        def hook(info):
+            if not info.is_static or not info.missing:
+                return None
+            return {
+                "width": 1200,
+                "height": 630,
+                "loading": "lazy",
+                "alt": "ignored",
+                "srcset": "/hero-600.jpg 600w, /hero.jpg 1200w",
+            }
+
+        out = pywire_parser.rewrite_images(SOURCE, hook)
+        self.assertIn(
+            '<img src="/hero.jpg" alt="Hero" width="1200" height="630" loading="lazy"'
+            ' srcset="/hero-600.jpg 600w, /hero.jpg 1200w">',
+            out,
+        )
+        self.assertIn('<img src="/logo.png" width="64" height="64" loading="lazy" />', out)
+        self.assertIn("<img src={user.avatar}>", out)
+
+    def test_rewrite_images_multibyte_whitespace(self) -> None:
+        source = '<p><img src="/a.png" alt="a"\u00a0/></p>\n'
+        out = pywire_parser.rewrite_images(source, lambda info: {"width": 10})
+        self.assertEqual(out, '<p><img src="/a.png" alt="a" width="10"\u00a0/></p>\n')
+
+
+if __name__ == "__main__":
+    unittest.main()
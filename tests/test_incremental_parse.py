@@ -0,0 +1,50 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def tags(doc):
+    return [n.tag for n in doc.template if n.tag]
+
+
+class TestIncrementalParse(unittest.TestCase):
+    def test_initial_parse_matches_parse(self) -> None:
+        source = "<h1>a</h1><p>b</p>"
+        doc = pywire_parser.Document(source).reparse()
+        self.assertEqual(tags(doc), tags(pywire_parser.parse(source)))
+
+    def test_options_match_parse(self) -> None:
+        source = "<p>a</p><!-- note --><p>b</p>"
+        doc = pywire_parser.Document(source, strip_comments=True).reparse()
+        expected = pywire_parser.parse(source, strip_comments=True)
+        self.assertEqual(len(doc.template), len(expected.template))
+        self.assertLess(len(doc.template), len(pywire_parser.parse(source).template))
+
+    def test_edit_then_reparse(self) -> None:
+        document = pywire_parser.Document("<h1>a</h1><p>b</p>")
+        document.edit(11, 12, 13, "em")
+        self.assertEqual(document.source, "<h1>a</h1><em>b</p>")
+        document.edit(17, 18, 19, "em")
+        self.assertEqual(document.source, "<h1>a</h1><em>b</em>")
+        self.assertEqual(tags(document.reparse()), ["h1", "em"])
+
+    def test_insertion_shifts_positions(self) -> None:
+        document = pywire_parser.Document("<p>x</p>\n<b>y</b>")
+        document.edit(0, 0, 9, "<i>z</i>\n")
+        doc = document.reparse()
+        self.assertEqual(tags(doc), ["i", "p", "b"])
+        self.assertEqual(doc.template[-1].line, 3)
+        self.assertTrue(document.changed_ranges)
+
+    def test_invalid_edits_rejected(self) -> None:
+        document = pywire_parser.Document("<p>é</p>")
+        with self.assertRaises(ValueError):
+            document.edit(0, 100, 1, "x")
+        with self.assertRaises(ValueError):
+            document.edit(0, 1, 5, "x")
+        with self.assertRaises(ValueError):
+            document.edit(4, 4, 5, "x")
+
+
+if __name__ == "__main__":
+    unittest.main()
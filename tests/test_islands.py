@@ -0,0 +1,88 @@
+import json
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PAGE = """---
+count = wire(0)
+title = "Docs"
+
+def increment():
+    count.value += 1
+---
+<article>
+  <h1>{title}</h1>
+  <p>Plenty of static prose.</p>
+</article>
+<div class="counter">
+  <span>{count}</span>
+  <button @click={increment}>+</button>
+</div>
+<footer><a href="/">Home</a></footer>
+"""
+
+
+class TestAnalyzeIslands(unittest.TestCase):
+    def test_static_content_is_not_an_island(self) -> None:
+        manifest = pywire_parser.analyze_islands(pywire_parser.parse(PAGE))
+        self.assertEqual([i.tag for i in manifest.islands], ["div"])
+        self.assertFalse(manifest.is_static)
+
+    def test_island_handlers_and_wires(self) -> None:
+        manifest = pywire_parser.analyze_islands(pywire_parser.parse(PAGE))
+        (island,) = manifest.islands
+        self.assertEqual(island.id, "island-1")
+        self.assertEqual(island.handlers, [("click", "increment")])
+        self.assertEqual(island.wires, ["count"])
+
+    def test_fully_static_page(self) -> None:
+        doc = pywire_parser.parse("<main><h1>Hello</h1><p>{'static'}</p></main>\n")
+        self.assertTrue(pywire_parser.analyze_islands(doc).is_static)
+
+    def test_top_level_block_reading_a_wire(self) -> None:
+        source = "---\nitems = wire([])\n---\n{$for item in items}<li>{item}</li>{/for}\n"
+        (island,) = pywire_parser.analyze_islands(pywire_parser.parse(source)).islands
+        self.assertIsNone(island.tag)
+        self.assertEqual(island.wires, ["items"])
+        start = source.index("{$for")
+        self.assertEqual((island.start_byte, island.end_byte), (start, len(source) - 1))
+
+    def test_bindings_are_entry_points(self) -> None:
+        source = "---\nname = wire('')\n---\n<form><input bind:value={name} /></form>\n"
+        (island,) = pywire_parser.analyze_islands(pywire_parser.parse(source)).islands
+        self.assertEqual(island.tag, "input")
+        self.assertEqual(island.handlers, [("bind:value", "name")])
+
+    def test_to_dict_is_json_ready(self) -> None:
+        manifest = pywire_parser.analyze_islands(pywire_parser.parse(PAGE))
+        data = json.loads(json.dumps(manifest.to_dict()))
+        self.assertFalse(data["static"])
+        self.assertEqual(data["islands"][0]["handlers"], [{"event": "click", "handler": "increment"}])
+
+
+class TestProjectIslands(unittest.TestCase):
+    def setUp(self) -> None:
+        self.index = pywire_parser.ProjectIndex()
+        self.index.add("components/Badge.wire", "<span class='badge'><slot></slot></span>\n")
+        self.index.add(
+            "components/Toggle.wire",
+            "---\non = wire(False)\n---\n<button @click={on.set(not on.value)}>{on}</button>\n",
+        )
+        self.index.add("pages/index.wire", "<h1><Badge>new</Badge></h1>\n<Toggle />\n")
+
+    def test_components_are_islands_without_the_index(self) -> None:
+        doc = self.index.get("pages/index.wire")
+        islands = pywire_parser.analyze_islands(doc).islands
+        self.assertEqual([i.component for i in islands], ["Badge", "Toggle"])
+
+    def test_static_components_are_resolved(self) -> None:
+        islands = self.index.islands("pages/index.wire").islands
+        self.assertEqual([i.component for i in islands], ["Toggle"])
+
+    def test_unknown_path(self) -> None:
+        with self.assertRaises(KeyError):
+            self.index.islands("pages/missing.wire")
+
+
+if __name__ == "__main__":
+    unittest.main()
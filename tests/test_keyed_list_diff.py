@@ -0,0 +1,63 @@
+import random
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def apply_diff(old: list, new: list, diff) -> list:
+    """Replay the diff the way the client does."""
+    nodes = {i: key for i, key in enumerate(old)}
+    for i in diff.removed:
+        del nodes[i]
+    result: list = []
+    inserted = set(diff.inserted)
+    for i in range(len(new) - 1, -1, -1):
+        if i in inserted:
+            result.insert(0, new[i])
+        else:
+            result.insert(0, nodes[diff.sources[i]])
+    return result
+
+
+class TestKeyedListDiff(unittest.TestCase):
+    def test_noop(self) -> None:
+        diff = pywire_parser.keyed_list_diff([1, 2, 3], [1, 2, 3])
+        self.assertTrue(diff.is_noop)
+
+    def test_single_move(self) -> None:
+        diff = pywire_parser.keyed_list_diff(["a", "b", "c", "d"], ["d", "a", "b", "c"])
+        self.assertEqual(diff.moved, [0])
+        self.assertEqual(diff.removed, [])
+        self.assertEqual(diff.inserted, [])
+
+    def test_insert_and_remove(self) -> None:
+        diff = pywire_parser.keyed_list_diff(["a", "b", "c"], ["a", "x", "c"])
+        self.assertEqual(diff.removed, [1])
+        self.assertEqual(diff.inserted, [1])
+        self.assertEqual(diff.moved, [])
+        self.assertEqual(diff.sources, [0, -1, 2])
+
+    def test_duplicate_keys(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.keyed_list_diff([1, 1], [1])
+
+    def test_random_permutations(self) -> None:
+        rng = random.Random(7)
+        for _ in range(100):
+            old = rng.sample(range(30), rng.randint(0, 20))
+            new = rng.sample(range(30), rng.randint(0, 20))
+            diff = pywire_parser.keyed_list_diff(old, new)
+            self.assertEqual(apply_diff(old, new, diff), new)
+            kept = [s for s in diff.sources if s >= 0]
+            # Unmoved items must already be in increasing old order.
+            stable = [
+                diff.sources[i]
+                for i in range(len(new))
+                if diff.sources[i] >= 0 and i not in diff.moved
+            ]
+            self.assertEqual(stable, sorted(stable))
+            self.assertLessEqual(len(diff.moved), len(kept))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,71 @@
+import random
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+# Seed corpus; mutations of these approximate half-typed files in the editor.
+CORPUS = [
+    '!path "/users/:id"\n!cache max-age=60\n---\nuser = load(id)\n---\n<h1>{user.name}</h1>\n',
+    '<ul>\n  {$for item in items}\n    <li class="a {item.cls}">{item}</li>\n  {/for}\n</ul>\n',
+    '{$if ok}<p $if={shown} @click={go}>yes</p>{$elif maybe}<b>?</b>{$else}no{/if}',
+    "<input value='x' {name} {**rest} disabled />",
+    "<script>let a = '<div>';</script><style>p{color:red}</style>",
+    '{$try}<Card title="é{x}"/>{$except ValueError as e}{e}{/try}',
+    "{$await data}{$then rows}{rows}{$catch err}{err}{/await}",
+]
+
+NOISE = ["<", ">", "{", "}", "$", "/", '"', "'", "=", "!", "-", "\n", "é", "‍", "\x00"]
+
+
+def mutations(source: str, rng: random.Random):
+    # Every prefix: the file as it looks while being typed.
+    for end in range(len(source) + 1):
+        yield source[:end]
+    for _ in range(200):
+        chars = list(source)
+        for _ in range(rng.randint(1, 4)):
+            op = rng.randrange(3)
+            at = rng.randint(0, len(chars))
+            if op == 0:
+                chars.insert(at, rng.choice(NOISE))
+            elif op == 1 and chars:
+                del chars[min(at, len(chars) - 1)]
+            else:
+                chars[at:at] = list(rng.choice(CORPUS)[: rng.randint(0, 12)])
+        yield "".join(chars)
+
+
+class TestLenientParse(unittest.TestCase):
+    def test_never_raises(self) -> None:
+        rng = random.Random(229)
+        for seed in CORPUS:
+            for source in mutations(seed, rng):
+                try:
+                    doc = pywire_parser.parse(source, lenient=True)
+                except BaseException as e:  # noqa: BLE001 - the contract covers everything
+                    self.fail(f"lenient parse raised {e!r} for {source!r}")
+                self.assertIsInstance(doc, pywire_parser.ParsedDocument)
+                for d in doc.diagnostics:
+                    self.assertIn(d.severity, ("error", "warning"))
+                    self.assertGreaterEqual(d.line, 1)
+
+    def test_invalid_directive_becomes_diagnostic(self) -> None:
+        source = "!cache max-age=soon\n<p>hi</p>\n"
+        with self.assertRaises(ValueError):
+            pywire_parser.parse(source)
+        doc = pywire_parser.parse(source, lenient=True)
+        self.assertIsNone(doc.cache_policy)
+        codes = [d.code for d in doc.diagnostics]
+        self.assertIn("invalid-directive", codes)
+
+    def test_unterminated_attribute_value(self) -> None:
+        doc = pywire_parser.parse('<a href="', lenient=True)
+        self.assertIsInstance(doc.diagnostics, list)
+
+    def test_clean_source_has_no_diagnostics(self) -> None:
+        doc = pywire_parser.parse("<p>hello</p>\n", lenient=True)
+        self.assertEqual(doc.diagnostics, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,59 @@
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestProjectIndex(unittest.TestCase):
+    def test_add_remove_and_scan(self) -> None:
+        index = pywire_parser.ProjectIndex()
+        index.add("pages/index.wire", "<p>home</p>\n")
+        self.assertIn("pages/index.wire", index)
+        self.assertEqual(len(index), 1)
+        self.assertTrue(index.remove("pages/index.wire"))
+        self.assertFalse(index.remove("pages/index.wire"))
+        with self.assertRaises(KeyError):
+            index.get("pages/index.wire")
+
+        with tempfile.TemporaryDirectory() as root:
+            Path(root, "pages").mkdir()
+            Path(root, "pages", "about.wire").write_text("<p>about</p>\n")
+            Path(root, "pages", "notes.txt").write_text("skip me")
+            index = pywire_parser.ProjectIndex.from_directory(root)
+            self.assertEqual(index.paths(), ["pages/about.wire"])
+
+
+class TestLinkHints(unittest.TestCase):
+    def test_hints_follow_components(self) -> None:
+        index = pywire_parser.ProjectIndex()
+        index.add(
+            "pages/index.wire",
+            "!path '/'\n\n<Nav />\n<a href=\"/posts/42?ref=home\">Post</a>\n"
+            "<img src=\"https://cdn.example.com/hero.jpg\">\n",
+        )
+        index.add(
+            "pages/post.wire",
+            "!path '/posts/:id'\n\n{$await load_post()}<p>post</p>{/await}\n",
+        )
+        index.add(
+            "components/Nav.wire",
+            "<a href=\"/about\">About</a>\n<a href=\"/\">Home</a>\n"
+            "<script src=\"https://analytics.example.net/a.js\"></script>\n",
+        )
+        self.assertEqual(index.components(), {"Nav": "components/Nav.wire"})
+
+        hints = {h.route.path: h for h in pywire_parser.link_hints(index)}
+        home = hints["/"]
+        self.assertEqual(home.route.file, "pages/index.wire")
+        self.assertEqual(home.components, ["components/Nav.wire"])
+        self.assertEqual(home.prefetch, ["/posts/42?ref=home"])
+        self.assertEqual(
+            home.preconnect,
+            ["https://cdn.example.com", "https://analytics.example.net"],
+        )
+        self.assertEqual(hints["/posts/:id"].data, ["load_post()"])
+
+
+if __name__ == "__main__":
+    unittest.main()
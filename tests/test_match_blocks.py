@@ -0,0 +1,92 @@
+import ast
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.ast_nodes import CaseAttribute, DefaultAttribute, MatchAttribute
+from pywire.compiler.codegen.template import TemplateCodegen
+from pywire.compiler.exceptions import PyWireSyntaxError
+from pywire.compiler.parser import PyWireParser
+
+SOURCE = """{$match status}
+{$case "ok"}
+    <p>Done</p>
+{$case Point(x=0, y=y) if y > limit}
+    <p>On axis at {y}</p>
+{$default}
+    <p>Unknown</p>
+{/match}
+"""
+
+
+class TestMatchBlocks(unittest.TestCase):
+    def test_keywords(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        blocks = [n for n in doc.template if n.is_block]
+        self.assertEqual(
+            [n.block_keyword for n in blocks],
+            ["match", "case", "case", "default", "/match"],
+        )
+        self.assertEqual(blocks[0].keyword, pywire_parser.BlockKeyword.MATCH)
+        self.assertEqual(blocks[0].expression, "status")
+        self.assertEqual(blocks[1].keyword, pywire_parser.BlockKeyword.CASE)
+        self.assertEqual(blocks[3].keyword, pywire_parser.BlockKeyword.DEFAULT)
+        self.assertIsNotNone(blocks[0].close_index)
+        opener = doc.template.index(blocks[0])
+        self.assertEqual(blocks[1].open_index, opener)
+        self.assertEqual(blocks[3].open_index, opener)
+
+    def test_resolved_nests_arms(self) -> None:
+        doc = pywire_parser.parse(SOURCE, resolved=True)
+        (match,) = [n for n in doc.template if n.block_keyword == "match"]
+        arms = [c for c in match.children if c.is_block]
+        self.assertEqual(
+            [a.block_keyword for a in arms], ["case", "case", "default"]
+        )
+        self.assertIn("p", [c.tag for c in arms[2].children])
+
+    def test_case_captures_are_bound(self) -> None:
+        doc = pywire_parser.parse(
+            "---\nstatus = wire(None)\nlimit = 3\n---\n" + SOURCE
+        )
+        names = [d.message for d in pywire_parser.check_state(doc)]
+        self.assertFalse(any("'y'" in m for m in names), names)
+
+    def test_parser_attributes(self) -> None:
+        parsed = PyWireParser().parse(SOURCE)
+        match = next(
+            n
+            for n in parsed.template
+            if any(isinstance(a, MatchAttribute) for a in n.special_attributes)
+        )
+        markers = [a for c in match.children for a in c.special_attributes]
+        cases = [a for a in markers if isinstance(a, CaseAttribute)]
+        self.assertEqual(
+            [c.pattern for c in cases], ['"ok"', "Point(x=0, y=y) if y > limit"]
+        )
+        self.assertTrue(any(isinstance(a, DefaultAttribute) for a in markers))
+
+    def test_codegen(self) -> None:
+        parsed = PyWireParser().parse(SOURCE)
+        func_def, _ = TemplateCodegen().generate_render_method(parsed.template)
+        ast.fix_missing_locations(func_def)
+        code = ast.unparse(func_def)
+
+        self.assertIn("match self.status:", code)
+        self.assertIn("case 'ok':", code)
+        self.assertIn("case Point(x=0, y=y) if y > self.limit:", code)
+        self.assertIn("case _:", code)
+        self.assertNotIn("self.y", code)
+
+    def test_content_outside_arm(self) -> None:
+        with self.assertRaises(PyWireSyntaxError):
+            PyWireParser().parse("{$match x}\n<p>stray</p>\n{$case 1}\nOne\n{/match}\n")
+
+    def test_default_must_be_last(self) -> None:
+        with self.assertRaises(PyWireSyntaxError):
+            PyWireParser().parse(
+                "{$match x}\n{$default}\nAny\n{$case 1}\nOne\n{/match}\n"
+            )
+
+
+if __name__ == "__main__":
+    unittest.main()
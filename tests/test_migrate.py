@@ -0,0 +1,64 @@
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestMigrate(unittest.TestCase):
+    def setUp(self) -> None:
+        self._tmp = tempfile.TemporaryDirectory()
+        self.path = Path(self._tmp.name) / "page.wire"
+
+    def tearDown(self) -> None:
+        self._tmp.cleanup()
+
+    def migrate(self, source: str, **options: str) -> str:
+        self.path.write_text(source)
+        (result,) = pywire_parser.codemod([self.path], "migrate", options or None)
+        self.assertIsNone(result.error)
+        return self.path.read_text()
+
+    def test_deprecation_data(self) -> None:
+        codes = [d.code for d in pywire_parser.deprecations()]
+        self.assertEqual(codes, ["directive-attributes", "colon-binding", "tag-blocks"])
+        for d in pywire_parser.deprecations():
+            self.assertEqual(d.since, "0.2.0")
+            self.assertTrue(d.old and d.new and d.message)
+
+    def test_colon_bindings(self) -> None:
+        self.assertEqual(
+            self.migrate('<a :href="url" :title class="x">Go</a>\n'),
+            '<a href={url} {title} class="x">Go</a>\n',
+        )
+
+    def test_tag_blocks(self) -> None:
+        self.assertEqual(
+            self.migrate(
+                "<$for each={item in items} key={item.id}>\n"
+                "  <$if when={item.visible}><p>{item}</p></$if>\n"
+                "</$for>\n"
+            ),
+            "{$for item in items, key=item.id}\n"
+            "  {$if item.visible}<p>{item}</p>{/if}\n"
+            "{/for}\n",
+        )
+
+    def test_directive_attributes(self) -> None:
+        self.assertEqual(
+            self.migrate('<p $if={ok} class="a">Hi</p>\n'),
+            '{$if ok}<p class="a">Hi</p>{/if}\n',
+        )
+
+    def test_rule_subset(self) -> None:
+        source = '<p $if={ok} :title="t">Hi</p>\n'
+        self.assertEqual(
+            self.migrate(source, rules="colon-binding"),
+            "<p $if={ok} title={t}>Hi</p>\n",
+        )
+        with self.assertRaises(ValueError):
+            pywire_parser.codemod([self.path], "migrate", {"rules": "nope"})
+
+
+if __name__ == "__main__":
+    unittest.main()
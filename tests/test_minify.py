@@ -0,0 +1,45 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestMinify(unittest.TestCase):
+    def test_layout_whitespace_and_comments(self) -> None:
+        source = "<ul>\n  <!-- items -->\n  <li>One</li>\n  <li>Two</li>\n</ul>\n"
+        self.assertEqual(pywire_parser.minify(source), "<ul><li>One</li><li>Two</li></ul>")
+
+    def test_whitespace_between_inline_elements_is_kept(self) -> None:
+        source = "<p>\n  <b>bold</b>\n  <i>italic</i>\n</p>"
+        self.assertEqual(pywire_parser.minify(source), "<p> <b>bold</b> <i>italic</i> </p>")
+
+    def test_text_whitespace_collapses(self) -> None:
+        source = "<p>Hello,\n     {name}!   Welcome.</p>"
+        self.assertEqual(pywire_parser.minify(source), "<p>Hello, {name}! Welcome.</p>")
+
+    def test_boolean_attributes(self) -> None:
+        source = '<input disabled="disabled" required="" value="" checked={on}>'
+        self.assertEqual(
+            pywire_parser.minify(source), '<input disabled required value="" checked={on}>'
+        )
+
+    def test_verbatim_content_is_kept(self) -> None:
+        source = "<pre>\n  a\n    b\n</pre>\n<script>\n  let x  =  1;\n</script>"
+        self.assertEqual(pywire_parser.minify(source), source)
+
+    def test_frontmatter_and_template_comments_are_kept(self) -> None:
+        source = "---\nname = 'x'\n---\n{# pywire: ignore #}\n<div>\n  <p>{name}</p>\n</div>\n"
+        minified = pywire_parser.minify(source)
+        self.assertTrue(minified.startswith("---\nname = 'x'\n---\n{# pywire: ignore #}"))
+        self.assertTrue(minified.endswith("<div><p>{name}</p></div>"))
+
+    def test_accepts_a_document(self) -> None:
+        doc = pywire_parser.parse("<div>\n  <p>x</p>\n</div>\n")
+        self.assertEqual(pywire_parser.minify(doc), "<div><p>x</p></div>")
+
+    def test_rejects_other_types(self) -> None:
+        with self.assertRaises(TypeError):
+            pywire_parser.minify(42)
+
+
+if __name__ == "__main__":
+    unittest.main()
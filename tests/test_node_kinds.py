@@ -0,0 +1,42 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+NodeKind = pywire_parser.NodeKind
+BlockKeyword = pywire_parser.BlockKeyword
+AttrKind = pywire_parser.AttrKind
+
+
+class TestKindEnums(unittest.TestCase):
+    def test_block_keyword_string_compat(self) -> None:
+        self.assertEqual(BlockKeyword.ELIF.value, "elif")
+        self.assertEqual(str(BlockKeyword.FOR), "for")
+        self.assertNotEqual(BlockKeyword.IF, BlockKeyword.ELIF)
+        self.assertEqual(len({BlockKeyword.IF, BlockKeyword.IF}), 1)
+
+    def test_node_kinds(self) -> None:
+        doc = pywire_parser.parse(
+            '{$if ok}<p class="a" @click={go} $show hidden x={y} {name}>{v}</p>{/if}'
+        )
+        opening, p, closing = doc.template
+        self.assertEqual(opening.kind, NodeKind.BLOCK)
+        self.assertEqual(opening.keyword, BlockKeyword.IF)
+        self.assertEqual(closing.kind, NodeKind.END_BLOCK)
+        self.assertEqual(closing.keyword, BlockKeyword.IF)
+        self.assertEqual(closing.block_keyword, "/if")
+
+        self.assertEqual(p.kind, NodeKind.ELEMENT)
+        self.assertIsNone(p.keyword)
+        self.assertEqual(p.children[0].kind, NodeKind.INTERPOLATION)
+
+        self.assertEqual(p.attribute_kind("class"), AttrKind.STATIC)
+        self.assertEqual(p.attribute_kind("@click"), AttrKind.EVENT)
+        self.assertEqual(p.attribute_kind("$show"), AttrKind.DIRECTIVE)
+        self.assertEqual(p.attribute_kind("hidden"), AttrKind.BOOLEAN)
+        self.assertEqual(p.attribute_kind("x"), AttrKind.REACTIVE)
+        self.assertEqual(p.attribute_kind("__pw_sh_name"), AttrKind.SHORTHAND)
+        self.assertIsNone(p.attribute_kind("missing"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,43 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestNodeSpans(unittest.TestCase):
+    def test_element_span(self) -> None:
+        source = "<div>\n  <p>hi</p>\n</div>"
+        (div,) = [n for n in pywire_parser.parse(source).template if n.tag == "div"]
+        self.assertEqual((div.start_byte, div.end_byte), (0, len(source)))
+        self.assertEqual((div.end_line, div.end_column), (3, 6))
+        (p,) = [n for n in div.children if n.tag == "p"]
+        self.assertEqual(source[p.start_byte : p.end_byte], "<p>hi</p>")
+        self.assertEqual((p.line, p.column, p.end_line, p.end_column), (2, 2, 2, 11))
+
+    def test_interpolation_span(self) -> None:
+        source = "<p>{ count }</p>"
+        (p,) = pywire_parser.parse(source).template
+        (interp,) = [n for n in p.children if n.expression is not None]
+        self.assertEqual(source[interp.start_byte : interp.end_byte], "{ count }")
+
+    def test_multibyte_source_uses_byte_offsets(self) -> None:
+        source = "<p>é</p><b>x</b>"
+        b = pywire_parser.parse(source).template[-1]
+        self.assertEqual(source.encode()[b.start_byte : b.end_byte], b"<b>x</b>")
+
+    def test_directive_span(self) -> None:
+        source = "!path '/home'\n\n<p>x</p>\n"
+        (d,) = pywire_parser.parse(source).directives
+        self.assertEqual(d.start_byte, 0)
+        self.assertEqual(source[d.start_byte : d.end_byte].strip(), "!path '/home'")
+        self.assertEqual(d.end_line, 1)
+
+    def test_compiler_nodes_carry_spans(self) -> None:
+        from pywire.compiler.parser import PyWireParser
+
+        parsed = PyWireParser().parse("<h1>Title</h1>")
+        h1 = parsed.template[0]
+        self.assertEqual((h1.start_byte, h1.end_byte), (0, 14))
+
+
+if __name__ == "__main__":
+    unittest.main()
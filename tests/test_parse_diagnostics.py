@@ -0,0 +1,104 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.exceptions import PyWireSyntaxError
+from pywire.compiler.parser import PyWireParser
+
+
+def errors(source):
+    return [d for d in pywire_parser.parse(source).diagnostics if d.severity == "error"]
+
+
+class TestParseDiagnostics(unittest.TestCase):
+    def test_valid_template_has_no_errors(self) -> None:
+        self.assertEqual(errors("<div>\n  <p>{x}</p>\n</div>"), [])
+
+    def test_unclosed_tag(self) -> None:
+        (d,) = errors("<main>\n  <div>\n</main>")
+        self.assertEqual(d.code, "unclosed-tag")
+        self.assertIn("<div>", d.message)
+        self.assertEqual(d.line, 2)
+
+    def test_stray_block_close(self) -> None:
+        source = "<p>x</p>\n{/if}\n"
+        (d,) = errors(source)
+        self.assertEqual((d.code, d.line, d.column), ("unmatched-block-close", 2, 0))
+        self.assertEqual(source[d.start_byte : d.end_byte], "{/if}")
+
+    def test_unclosed_block(self) -> None:
+        (d,) = errors("{$if ready}\n<p>x</p>\n")
+        self.assertEqual(d.code, "unclosed-block")
+        self.assertEqual(d.line, 1)
+
+    def test_mismatched_block_close(self) -> None:
+        source = "{$if ready}\n<p>x</p>\n{/for}\n"
+        found = {d.code: d for d in errors(source)}
+        close, opener = found["unmatched-block-close"], found["unclosed-block"]
+        self.assertIn("'{$if}' at line 1", close.message)
+        self.assertEqual(close.related, [(1, 0, 1, 11)])
+        self.assertEqual(opener.related, [(3, 0, 3, 6)])
+
+    def test_close_tag_mismatch(self) -> None:
+        found = {d.code: d for d in errors("<section>\n  <div>x</span>\n</section>\n")}
+        close, opener = found["unexpected-close-tag"], found["unclosed-tag"]
+        self.assertEqual((close.line, close.column), (2, 8))
+        self.assertIn("open <div> at line 2", close.message)
+        self.assertEqual(close.related, [(2, 2, 2, 7)])
+        self.assertEqual(opener.related, [(2, 8, 2, 15)])
+
+    def test_unclosed_tag_relates_closer(self) -> None:
+        (d,) = errors("<main>\n  <div>\n</main>")
+        self.assertIn("before </main> at line 3", d.message)
+        self.assertEqual(d.related, [(3, 0, 3, 7)])
+
+    def test_unclosed_tag_at_end(self) -> None:
+        (d,) = errors("<p>x</p>\n<section>\n<h2>y</h2>\n")
+        self.assertEqual((d.code, d.line), ("unclosed-tag", 2))
+        self.assertEqual(d.related, [(4, 0, 4, 0)])
+
+    def test_unclosed_tag_inside_block(self) -> None:
+        (d,) = errors("{$if a}\n<div>\n{$else}\n<span></span>\n{/if}\n")
+        self.assertEqual((d.code, d.line), ("unclosed-tag", 2))
+        self.assertIn("'{$else}' at line 3", d.message)
+
+    def test_optional_end_tags(self) -> None:
+        self.assertEqual(errors("<ul><li>a<li>b</ul>\n<table><tr><td>1<td>2</table>\n"), [])
+
+    def test_stray_close_tag(self) -> None:
+        codes = [d.code for d in errors("<p>x</p></span>")]
+        self.assertIn("unexpected-close-tag", codes)
+
+    def test_bad_attribute_syntax(self) -> None:
+        codes = [d.code for d in errors('<div class="a" =oops>x</div>')]
+        self.assertIn("invalid-attribute", codes)
+
+    def test_span_covers_range(self) -> None:
+        (d,) = errors("{$for x in xs}\n")
+        self.assertGreaterEqual(d.end_byte, d.start_byte)
+        self.assertGreaterEqual(
+            (d.end_line, d.end_column), (d.line, d.column)
+        )
+
+    def test_raise_for_errors_uses_syntax_error(self) -> None:
+        doc = pywire_parser.parse("{/for}")
+        with self.assertRaises(pywire_parser.PyWireSyntaxError):
+            doc.raise_for_errors()
+
+    def test_compiler_surfaces_errors(self) -> None:
+        with self.assertRaises(PyWireSyntaxError) as ctx:
+            PyWireParser().parse("<p>x</p>\n{/if}\n", "page.wire")
+        self.assertEqual(ctx.exception.line, 2)
+
+    def test_compiler_accepts_mapped_syntax(self) -> None:
+        for source in (
+            "{$match status}\n{$case 1}<p>one</p>\n{$default}<p>other</p>\n{/match}\n",
+            "<pre>\n  {$if ok -}\n    x\n  {- /if}\n</pre>\n",
+            "<p>a{# note #}b</p>\n{# {$if x} #}\n",
+        ):
+            with self.subTest(source=source):
+                self.assertEqual(errors(source), [])
+                PyWireParser().parse(source, "page.wire")
+
+
+if __name__ == "__main__":
+    unittest.main()
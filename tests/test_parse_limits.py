@@ -0,0 +1,40 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestParseLimits(unittest.TestCase):
+    def test_defaults_are_unlimited(self) -> None:
+        limits = pywire_parser.ParseLimits()
+        self.assertIsNone(limits.max_source_bytes)
+        self.assertIsNone(limits.max_nodes)
+        self.assertIsNone(limits.max_attributes)
+        pywire_parser.parse("<p>hi</p>", limits=limits)
+
+    def test_source_size(self) -> None:
+        limits = pywire_parser.ParseLimits(max_source_bytes=8)
+        with self.assertRaises(ValueError):
+            pywire_parser.parse("<p>hello</p>", limits=limits)
+        doc = pywire_parser.parse("<p>hello</p>", lenient=True, limits=limits)
+        self.assertEqual(doc.template, [])
+        self.assertEqual([d.code for d in doc.diagnostics], ["limit-exceeded"])
+        pywire_parser.parse("<p>x</p>", limits=limits)
+
+    def test_node_count(self) -> None:
+        source = "<ul>" + "<li>x</li>" * 50 + "</ul>"
+        limits = pywire_parser.ParseLimits(max_nodes=20)
+        with self.assertRaises(ValueError):
+            pywire_parser.parse(source, limits=limits)
+        doc = pywire_parser.parse(source, lenient=True, limits=limits)
+        self.assertIn("limit-exceeded", [d.code for d in doc.diagnostics])
+
+    def test_attribute_count(self) -> None:
+        attrs = " ".join(f"a{i}='{i}'" for i in range(10))
+        limits = pywire_parser.ParseLimits(max_attributes=5)
+        with self.assertRaises(ValueError):
+            pywire_parser.parse(f"<div {attrs}></div>", limits=limits)
+        pywire_parser.parse("<div a='1' b='2'></div>", limits=limits)
+
+
+if __name__ == "__main__":
+    unittest.main()
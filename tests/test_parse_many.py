@@ -0,0 +1,47 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestParseMany(unittest.TestCase):
+    def test_matches_single_parse_in_order(self) -> None:
+        sources = {f"page{i}.wire": f"<h{i % 6 + 1}>{i}</h{i % 6 + 1}>" for i in range(50)}
+        docs = pywire_parser.parse_many(sources)
+        self.assertEqual(list(docs), list(sources))
+        for name, source in sources.items():
+            expected = pywire_parser.parse(source)
+            self.assertEqual(
+                [n.tag for n in docs[name].template],
+                [n.tag for n in expected.template],
+            )
+
+    def test_empty(self) -> None:
+        self.assertEqual(dict(pywire_parser.parse_many({})), {})
+
+    def test_annotate_uses_names(self) -> None:
+        docs = pywire_parser.parse_many({"a.wire": "<p>x</p>"}, annotate=True)
+        (p,) = docs["a.wire"].template
+        self.assertEqual(p.attributes["data-pw-src"], "a.wire:1")
+
+    def test_options_match_parse(self) -> None:
+        limits = pywire_parser.ParseLimits(max_source_bytes=20)
+        sources = {"a.wire": "<p>{'x' + 'y'}</p><!-- c -->", "b.wire": "<p>" + "x" * 40 + "</p>"}
+        docs = pywire_parser.parse_many(
+            sources, lenient=True, limits=limits, strip_comments=True, fold_constants=True
+        )
+        self.assertEqual([d.code for d in docs["b.wire"].diagnostics], ["limit-exceeded"])
+        expected = pywire_parser.parse(
+            sources["a.wire"], lenient=True, strip_comments=True, fold_constants=True
+        )
+        (p,) = docs["a.wire"].template
+        self.assertEqual(p.children[0].folded_from, expected.template[0].children[0].folded_from)
+        with self.assertRaises(ValueError):
+            pywire_parser.parse_many(sources, limits=limits)
+
+    def test_non_string_source_rejected(self) -> None:
+        with self.assertRaises(TypeError):
+            pywire_parser.parse_many({"a.wire": 1})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,74 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestParsedAttributes(unittest.TestCase):
+    def attrs(self, source: str) -> list:
+        (node,) = pywire_parser.parse(source).template
+        return node.parsed_attributes
+
+    def test_quoted_value(self) -> None:
+        source = "<a href='/x' title=\"t\">x</a>"
+        href, title = self.attrs(source)
+        self.assertEqual(href.name, "href")
+        self.assertEqual(href.value, "/x")
+        self.assertEqual(href.raw_value, "'/x'")
+        self.assertEqual(href.quote, "'")
+        self.assertEqual(title.quote, '"')
+        self.assertEqual(
+            source[href.value_start_byte : href.value_end_byte], "/x"
+        )
+        self.assertEqual(href.kind, pywire_parser.AttrKind.STATIC)
+        self.assertFalse(href.has_interpolation)
+
+    def test_unquoted_and_boolean(self) -> None:
+        width, hidden = self.attrs("<img width=10 hidden>")
+        self.assertEqual(width.value, "10")
+        self.assertIsNone(width.quote)
+        self.assertIsNone(hidden.value)
+        self.assertIsNone(hidden.raw_value)
+        self.assertEqual(hidden.kind, pywire_parser.AttrKind.BOOLEAN)
+
+    def test_interpolation(self) -> None:
+        cls, click = self.attrs('<p class="a {b}" @click={go}>x</p>')
+        self.assertTrue(cls.has_interpolation)
+        self.assertEqual(click.name, "@click")
+        self.assertEqual(click.kind, pywire_parser.AttrKind.EVENT)
+
+    def test_shorthand(self) -> None:
+        source = "<input { checked }>"
+        (attr,) = self.attrs(source)
+        self.assertTrue(attr.is_shorthand)
+        self.assertFalse(attr.is_spread)
+        self.assertEqual(attr.name, "checked")
+        self.assertEqual(attr.value, "checked")
+        self.assertEqual(attr.raw_value, "{ checked }")
+        self.assertEqual(
+            source[attr.value_start_byte : attr.value_end_byte], "checked"
+        )
+        self.assertEqual(attr.kind, pywire_parser.AttrKind.SHORTHAND)
+
+    def test_spreads_are_all_kept(self) -> None:
+        source = '<div {**a} id="x" {**b}></div>'
+        first, id_, second = self.attrs(source)
+        self.assertTrue(first.is_spread)
+        self.assertEqual(first.name, "**")
+        self.assertEqual(first.value, "a")
+        self.assertEqual(second.value, "b")
+        self.assertEqual(second.kind, pywire_parser.AttrKind.SPREAD)
+        (div,) = pywire_parser.parse(source).template
+        self.assertEqual(div.attributes["__pywire_spread__"], "{**b}")
+
+    def test_position(self) -> None:
+        _, attr = self.attrs('<p id="a"\n   class="b">x</p>')
+        self.assertEqual((attr.line, attr.column), (2, 3))
+
+    def test_dict_view_unchanged(self) -> None:
+        (node,) = pywire_parser.parse('<p {x} y="1">z</p>').template
+        self.assertEqual(node.attributes, {"__pw_sh_x": "{x}", "y": "1"})
+        self.assertEqual(node.attribute_kind("__pw_sh_x"), pywire_parser.AttrKind.SHORTHAND)
+
+
+if __name__ == "__main__":
+    unittest.main()
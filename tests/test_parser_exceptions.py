@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestParserExceptions(unittest.TestCase):
+    def test_hierarchy(self) -> None:
+        for name in ("PyWireSyntaxError", "PyWireLimitError", "PyWireDirectiveError"):
+            cls = getattr(pywire_parser, name)
+            self.assertTrue(issubclass(cls, pywire_parser.PyWireParseError))
+        self.assertTrue(issubclass(pywire_parser.PyWireParseError, ValueError))
+        self.assertTrue(issubclass(pywire_parser.PyWireGrammarError, RuntimeError))
+
+    def test_limit_error(self) -> None:
+        limits = pywire_parser.ParseLimits(max_source_bytes=4)
+        with self.assertRaises(pywire_parser.PyWireLimitError):
+            pywire_parser.parse("<div></div>", limits=limits)
+
+    def test_directive_error(self) -> None:
+        with self.assertRaises(pywire_parser.PyWireDirectiveError):
+            pywire_parser.parse("!cache max-age=soon\n\n<p>x</p>\n")
+
+    def test_raise_for_errors_syntax(self) -> None:
+        doc = pywire_parser.parse("<div>\n  <p>{x</p>\n</div>", lenient=True)
+        with self.assertRaises(pywire_parser.PyWireSyntaxError) as ctx:
+            doc.raise_for_errors()
+        self.assertGreaterEqual(ctx.exception.line, 1)
+
+    def test_raise_for_errors_limit(self) -> None:
+        limits = pywire_parser.ParseLimits(max_source_bytes=4)
+        doc = pywire_parser.parse("<div></div>", limits=limits, lenient=True)
+        with self.assertRaises(pywire_parser.PyWireLimitError):
+            doc.raise_for_errors()
+
+    def test_raise_for_errors_clean(self) -> None:
+        doc = pywire_parser.parse("<p data-pw-x='1'>x</p>")
+        self.assertIsNone(doc.raise_for_errors())
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,27 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestPanicGuard(unittest.TestCase):
+    def test_exception_type_is_exported(self) -> None:
+        self.assertTrue(
+            issubclass(pywire_parser.InternalParserError, RuntimeError)
+        )
+
+    def test_pathological_inputs_do_not_escape_as_panics(self) -> None:
+        # Inputs that used to hit unchecked slicing in the mapper.
+        sources = ['<a href="', "<a href='", "<a {", "<a {**", "<p {}>", '<p x="é']
+        for source in sources:
+            try:
+                pywire_parser.parse(source)
+            except pywire_parser.InternalParserError as e:
+                self.fail(f"parser panicked on {source!r}: {e}")
+            except Exception:
+                pass
+            except BaseException as e:  # pyo3 PanicException
+                self.fail(f"panic escaped for {source!r}: {e!r}")
+
+
+if __name__ == "__main__":
+    unittest.main()
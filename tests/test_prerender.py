@@ -0,0 +1,73 @@
+import tempfile
+import unittest
+from pathlib import Path
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestPrerender(unittest.TestCase):
+    def test_writes_static_routes_only(self) -> None:
+        docs = [
+            pywire_parser.parse(
+                "!path {'about': '/about', 'user': '/users/:id'}\n\n"
+                '<h1>{site_name}</h1>\n<img src="/logo.png">\n'
+                "{$if show_footer}<footer>bye</footer>{$else}<p>none</p>{/if}\n"
+            ),
+            pywire_parser.parse("!path '/'\n\n<p>{'home' }</p>\n"),
+        ]
+        calls = []
+
+        def resolver(expr, route):
+            calls.append((expr, route.path))
+            return {"site_name": "Acme & Co", "show_footer": False}[expr]
+
+        with tempfile.TemporaryDirectory() as out:
+            pages = pywire_parser.prerender(
+                docs, resolver, out, {"/logo.png": "/logo.3f2a.png"}
+            )
+            self.assertEqual([p.route.path for p in pages], ["/about", "/"])
+            about = Path(out, "about", "index.html").read_text()
+            self.assertIn("<h1>Acme &amp; Co</h1>", about)
+            self.assertIn('<img src="/logo.3f2a.png">', about)
+            self.assertIn("<p>none</p>", about)
+            self.assertNotIn("footer", about)
+            self.assertEqual(Path(out, "index.html").read_text().strip(), "<p>home</p>")
+            self.assertEqual(pages[0].size, len(about.encode()))
+            self.assertEqual(
+                pages[0].etag, pywire_parser.content_etag(about.encode())
+            )
+        # Literals are folded without calling back into Python.
+        self.assertNotIn("'home'", [c[0] for c in calls])
+
+    def test_attributes_keep_source_order(self) -> None:
+        doc = pywire_parser.parse(
+            "!path '/'\n\n<a title={label} href=\"/x\" class=\"btn\">x</a>\n"
+        )
+        with tempfile.TemporaryDirectory() as out:
+            pywire_parser.prerender([doc], lambda e, r: "Go", out)
+            html = Path(out, "index.html").read_text()
+        self.assertIn('<a title="Go" href="/x" class="btn">x</a>', html)
+
+    def test_unsupported_block_raises(self) -> None:
+        doc = pywire_parser.parse(
+            "!path '/list'\n\n{$for x in items}<p>{x}</p>{/for}\n"
+        )
+        with tempfile.TemporaryDirectory() as out:
+            with self.assertRaises(ValueError):
+                pywire_parser.prerender([doc], lambda e, r: [], out)
+
+    def test_static_attribute_character_references(self) -> None:
+        doc = pywire_parser.parse(
+            "!path '/'\n\n"
+            '<img alt="Don&#39;t &amp; won&#x27;t" title="a&nbsp;b&copy;">'
+            '<a href="?a=1&copy=2">x</a>\n'
+        )
+        with tempfile.TemporaryDirectory() as out:
+            pywire_parser.prerender([doc], lambda expr, route: None, out)
+            html = Path(out, "index.html").read_text()
+        self.assertIn('alt="Don\'t &amp; won\'t"', html)
+        self.assertIn('title="a\u00a0b\u00a9"', html)
+        self.assertIn('href="?a=1&amp;copy=2"', html)
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,40 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """<main>
+  <ul>
+    {$for item in items}
+      <li class={item.cls} @click={pick(item)}>{item.name}</li>
+    {/for}
+  </ul>
+  <footer><p>static</p></footer>
+</main>
+"""
+
+
+class TestProfile(unittest.TestCase):
+    def test_phase_timings(self) -> None:
+        report = pywire_parser.profile(SOURCE)
+        for phase in (report.parse_seconds, report.map_seconds, report.analysis_seconds):
+            self.assertGreaterEqual(phase, 0.0)
+        self.assertAlmostEqual(
+            report.total_seconds,
+            report.parse_seconds + report.map_seconds + report.analysis_seconds,
+        )
+
+    def test_subtrees_ranked_by_expressions(self) -> None:
+        report = pywire_parser.profile(SOURCE, top=3)
+        self.assertLessEqual(len(report.subtrees), 3)
+        self.assertGreater(report.nodes, 0)
+        # li: class, @click and {item.name}; the for block adds one more to ul.
+        self.assertEqual(report.expressions, 4)
+        tags = [s.tag for s in report.subtrees]
+        self.assertEqual(tags[:2], ["main", "ul"])
+        self.assertEqual(report.subtrees[0].expressions, 4)
+        costs = [(s.expressions, s.nodes) for s in report.subtrees]
+        self.assertEqual(costs, sorted(costs, reverse=True))
+
+
+if __name__ == "__main__":
+    unittest.main()
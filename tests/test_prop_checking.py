@@ -0,0 +1,45 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+CARD = """!component Card
+!props(title: str, count: int = 0, ratio: Optional[float] = None, **rest)
+
+<div>{title} {count}</div>
+"""
+
+
+def report(page: str):
+    index = pywire_parser.ProjectIndex()
+    index.add("components/Card.wire", CARD)
+    index.add("pages/index.wire", page)
+    return index.check_props()
+
+
+class TestPropChecking(unittest.TestCase):
+    def test_valid_usage(self) -> None:
+        page = '<Card title="Hi" count={3} ratio={None} />\n'
+        self.assertEqual(report(page), {})
+
+    def test_string_literal_for_int_prop(self) -> None:
+        (d,) = report('<Card title="Hi" count="3" />\n')["pages/index.wire"]
+        self.assertEqual((d.code, d.severity), ("prop-type-mismatch", "error"))
+        self.assertIn("'count' expects int but is given str", d.message)
+        self.assertIn("components/Card.wire", d.message)
+
+    def test_literal_expression_mismatch(self) -> None:
+        (d,) = report('<Card title={42} />\n')["pages/index.wire"]
+        self.assertIn("'title' expects str but is given int", d.message)
+
+    def test_missing_required_prop(self) -> None:
+        (d,) = report("<main>\n  <Card count={1} />\n</main>\n")["pages/index.wire"]
+        self.assertEqual((d.code, d.line), ("missing-prop", 2))
+        self.assertIn("'title'", d.message)
+
+    def test_dynamic_values_and_spreads_are_trusted(self) -> None:
+        page = "<Card title={name} count={n} />\n<Card {**props} />\n"
+        self.assertEqual(report(page), {})
+
+
+if __name__ == "__main__":
+    unittest.main()
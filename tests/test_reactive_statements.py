@@ -0,0 +1,50 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """---
+price = wire(10)
+$: total = price * qty
+$: count += step
+@reactive
+def doubled(k=2):
+    tmp = total * k
+    return tmp
+---
+<p>{total}</p>
+"""
+
+
+class TestReactiveStatements(unittest.TestCase):
+    def test_label_statements(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        total, count, _ = doc.reactive
+        self.assertEqual(total.kind, "label")
+        self.assertEqual(total.line, 3)
+        self.assertEqual(total.writes, ["total"])
+        self.assertEqual(total.reads, ["price", "qty"])
+        self.assertEqual(count.reads, ["count", "step"])
+
+    def test_decorated_function(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        fn = doc.reactive[-1]
+        self.assertEqual(fn.kind, "decorator")
+        self.assertEqual(fn.line, 5)
+        self.assertEqual(fn.writes, ["doubled"])
+        self.assertEqual(fn.reads, ["total"])
+
+    def test_no_frontmatter(self) -> None:
+        self.assertEqual(pywire_parser.parse("<p>x</p>").reactive, [])
+
+    def test_dependency_graph(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        graph = pywire_parser.DepGraph()
+        graph.add_reactive(doc.reactive)
+        self.assertEqual(
+            graph.dirty_order(["price"]), ["price", "total", "doubled"]
+        )
+        self.assertIn("count", graph.dirty_set(["step"]))
+
+
+if __name__ == "__main__":
+    unittest.main()
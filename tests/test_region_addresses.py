@@ -0,0 +1,66 @@
+import re
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PAGE = """<main>
+  <h1>Static</h1>
+  <p class={cls}>{count}</p>
+  {$for item in items, key=item.id}
+    <li>{item.name}</li>
+  {/for}
+</main>
+"""
+
+
+def regions(doc):
+    out = []
+
+    def walk(nodes):
+        for n in nodes:
+            if n.region:
+                out.append(n.region)
+            walk(n.children)
+
+    walk(doc.template)
+    return out
+
+
+class TestRegionAddresses(unittest.TestCase):
+    def test_regions_found(self) -> None:
+        found = regions(pywire_parser.parse(PAGE, resolved=True))
+        self.assertEqual(len(found), 3)
+        p, loop, li = found
+        self.assertRegex(p, r"^p\.[0-9a-f]{6}$")
+        self.assertRegex(loop, r"^for\.[0-9a-f]{6}\[item\.id\]$")
+        self.assertTrue(li.startswith(loop + "/li."))
+
+    def test_static_elements_are_not_regions(self) -> None:
+        doc = pywire_parser.parse("<div><h1>Hi</h1></div>")
+        self.assertEqual(regions(doc), [])
+
+    def test_stable_when_siblings_are_added(self) -> None:
+        before = regions(pywire_parser.parse(PAGE, resolved=True))
+        edited = PAGE.replace("<h1>Static</h1>", "<h1>Static</h1>\n  <b>{extra}</b>")
+        after = regions(pywire_parser.parse(edited, resolved=True))
+        self.assertEqual([r for r in after if not r.startswith("b.")], before)
+
+    def test_flat_and_resolved_agree(self) -> None:
+        self.assertEqual(
+            regions(pywire_parser.parse(PAGE)),
+            regions(pywire_parser.parse(PAGE, resolved=True)),
+        )
+
+    def test_identical_siblings_and_ids(self) -> None:
+        doc = pywire_parser.parse("<p>{a}</p><p>{a}</p><p id='x'>{a}</p>")
+        first, second, third = regions(doc)
+        self.assertEqual(second, first + "~2")
+        self.assertEqual(third, "p#x")
+
+    def test_loop_target_without_key(self) -> None:
+        doc = pywire_parser.parse("{$for row in rows}<tr>{row}</tr>{/for}")
+        self.assertTrue(re.match(r"for\.[0-9a-f]{6}\[row\]", regions(doc)[0]))
+
+
+if __name__ == "__main__":
+    unittest.main()
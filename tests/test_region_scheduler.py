@@ -0,0 +1,50 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestRegionScheduler(unittest.TestCase):
+    def setUp(self) -> None:
+        self.scheduler = pywire_parser.RegionScheduler()
+        self.scheduler.add_region("page")
+        self.scheduler.add_region("sidebar", "page")
+        self.scheduler.add_region("counter", "sidebar")
+        self.scheduler.add_region("table", "page")
+
+    def test_dedupes_diamond_dependencies(self) -> None:
+        self.scheduler.subscribe("a", "table")
+        self.scheduler.subscribe("b", "table")
+        self.assertEqual(self.scheduler.schedule(["a", "b"]), ["table"])
+
+    def test_ancestor_covers_descendants(self) -> None:
+        self.scheduler.subscribe("count", "counter")
+        self.scheduler.subscribe("user", "sidebar")
+        self.scheduler.subscribe("rows", "table")
+        self.assertEqual(self.scheduler.schedule(["count"]), ["counter"])
+        self.assertEqual(
+            self.scheduler.schedule(["rows", "count", "user"]), ["sidebar", "table"]
+        )
+
+    def test_reparent_under_descendant_is_rejected(self) -> None:
+        with self.assertRaises(ValueError):
+            self.scheduler.add_region("page", "counter")
+        with self.assertRaises(ValueError):
+            self.scheduler.add_region("sidebar", "sidebar")
+        self.scheduler.add_region("counter", "table")
+        self.scheduler.subscribe("count", "counter")
+        self.scheduler.subscribe("rows", "table")
+        self.assertEqual(self.scheduler.schedule(["count", "rows"]), ["table"])
+        self.scheduler.remove_region("page")
+        self.assertEqual(self.scheduler.schedule(["count"]), [])
+
+    def test_remove_region_drops_subtree(self) -> None:
+        self.scheduler.subscribe("count", "counter")
+        self.scheduler.remove_region("sidebar")
+        self.assertEqual(self.scheduler.schedule(["count"]), [])
+        self.assertEqual(len(self.scheduler), 2)
+        with self.assertRaises(KeyError):
+            self.scheduler.subscribe("count", "counter")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,65 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestRegionSequencing(unittest.TestCase):
+    def test_sequencer_counts_per_region(self) -> None:
+        seq = pywire_parser.RegionSequencer()
+        self.assertEqual([seq.next("a"), seq.next("a"), seq.next("b")], [1, 2, 1])
+        self.assertEqual(seq.current("a"), 2)
+        seq.reset("a")
+        self.assertEqual(seq.next("a"), 1)
+
+    def test_in_order_updates_pass_through(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        self.assertEqual(buf.push("a", 1, "one"), [("a", 1, "one")])
+        self.assertEqual(buf.push("a", 2, "two"), [("a", 2, "two")])
+
+    def test_late_frame_is_held_until_gap_fills(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        self.assertEqual(buf.push("a", 2, "two"), [])
+        self.assertEqual(buf.pending, 1)
+        self.assertEqual(
+            buf.push("a", 1, "one"), [("a", 1, "one"), ("a", 2, "two")]
+        )
+        self.assertEqual(buf.applied("a"), 2)
+
+    def test_stale_and_duplicate_frames_are_dropped(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        buf.push("a", 1, "one")
+        buf.push("a", 3, "three")
+        self.assertEqual(buf.push("a", 1, "again"), [])
+        self.assertEqual(buf.push("a", 3, "again"), [])
+        self.assertEqual(buf.dropped, 2)
+
+    def test_regions_are_independent(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        self.assertEqual(buf.push("a", 2, "x"), [])
+        self.assertEqual(buf.push("b", 1, "y"), [("b", 1, "y")])
+
+    def test_flush_gives_up_on_gaps(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        buf.push("a", 3, "three")
+        buf.push("a", 5, "five")
+        self.assertEqual(buf.flush(), [("a", 3, "three"), ("a", 5, "five")])
+        self.assertEqual(buf.push("a", 4, "four"), [])
+
+    def test_max_pending_releases_buffer(self) -> None:
+        buf = pywire_parser.ReorderBuffer(max_pending=2)
+        buf.push("a", 2, "b")
+        buf.push("a", 3, "c")
+        self.assertEqual(
+            buf.push("a", 4, "d"), [("a", 2, "b"), ("a", 3, "c"), ("a", 4, "d")]
+        )
+
+    def test_resync_skips_older_updates(self) -> None:
+        buf = pywire_parser.ReorderBuffer()
+        buf.push("a", 3, "three")
+        buf.push("a", 6, "six")
+        self.assertEqual(buf.resync("a", 5), [("a", 6, "six")])
+        self.assertEqual(buf.dropped, 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
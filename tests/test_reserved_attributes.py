@@ -0,0 +1,34 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def warnings(source):
+    doc = pywire_parser.parse(source)
+    return [d for d in doc.diagnostics if d.severity == "warning"]
+
+
+class TestReservedAttributes(unittest.TestCase):
+    def test_reserved_prefixes(self) -> None:
+        for name in ("data-pw-region", "data-pywire-reload", "__pywire_spread__", "__pw_on_click"):
+            with self.subTest(name=name):
+                (w,) = warnings(f'<div {name}="x"></div>')
+                self.assertEqual(w.code, "reserved-attribute")
+                self.assertIn(name, w.message)
+
+    def test_generated_event_attributes(self) -> None:
+        (w,) = warnings('<button data-on-click="save">Save</button>')
+        self.assertEqual(w.code, "shadowed-attribute")
+        self.assertIn("@click", w.message)
+
+    def test_span_points_at_attribute(self) -> None:
+        (w,) = warnings('<div>\n  <p class="a" data-pw-x="1"></p>\n</div>')
+        self.assertEqual((w.line, w.column), (2, 15))
+
+    def test_framework_syntax_is_not_flagged(self) -> None:
+        source = '<input {name} {**rest} @click={go} $permanent data-id="1">'
+        self.assertEqual(warnings(source), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,91 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def shape(nodes):
+    out = []
+    for n in nodes:
+        label = n.tag or n.block_keyword or (n.text_content or "").strip()
+        out.append((label, shape(n.children)) if n.children else label)
+    return out
+
+
+class TestResolvedParse(unittest.TestCase):
+    def test_flat_by_default(self) -> None:
+        doc = pywire_parser.parse("{$if a}<p>a</p>{/if}")
+        self.assertEqual([n.block_keyword for n in doc.template], ["if", None, "/if"])
+
+    def test_bodies_nest_under_markers(self) -> None:
+        doc = pywire_parser.parse(
+            "{$if a}<p>a</p>{$elif b}<b>b</b>{$else}<i>c</i>{/if}", resolved=True
+        )
+        self.assertEqual(
+            shape(doc.template),
+            [
+                (
+                    "if",
+                    [("p", ["a"]), ("elif", [("b", ["b"])]), ("else", [("i", ["c"])])],
+                )
+            ],
+        )
+        (opener,) = doc.template
+        self.assertIsNotNone(opener.close_line)
+        self.assertIsNone(opener.close_index)
+
+    def test_nested_blocks_inside_elements(self) -> None:
+        doc = pywire_parser.parse(
+            "<ul>{$for x in xs}{$try}<li>{x}</li>{$except E}<li>!</li>{/try}{/for}</ul>",
+            resolved=True,
+        )
+        self.assertEqual(
+            shape(doc.template),
+            [
+                (
+                    "ul",
+                    [
+                        (
+                            "for",
+                            [
+                                (
+                                    "try",
+                                    [("li", ["interpolation"]), ("except", [("li", ["!"])])],
+                                )
+                            ],
+                        )
+                    ],
+                )
+            ],
+        )
+
+    def test_unmatched_closer_is_kept(self) -> None:
+        doc = pywire_parser.parse("<p>x</p>{/if}", resolved=True)
+        self.assertEqual(shape(doc.template), [("p", ["x"]), "/if"])
+
+
+class TestConditionalBranches(unittest.TestCase):
+    def test_if_chain_branches(self) -> None:
+        doc = pywire_parser.parse(
+            "{$if a > 1}<p>a</p>{$elif b}<b>b</b>{$else}<i>c</i>{/if}", resolved=True
+        )
+        (cond,) = doc.template
+        branches = cond.branches
+        self.assertEqual([b.keyword for b in branches], ["if", "elif", "else"])
+        self.assertEqual([b.condition for b in branches], ["a > 1", "b", None])
+        self.assertEqual([[c.tag for c in b.children] for b in branches], [["p"], ["b"], ["i"]])
+
+    def test_nested_if_inside_branch(self) -> None:
+        doc = pywire_parser.parse(
+            "{$if a}x{$else}{$if b}<p>y</p>{/if}{/if}", resolved=True
+        )
+        outer = doc.template[0].branches
+        inner = outer[1].children[0]
+        self.assertEqual([b.keyword for b in inner.branches], ["if"])
+
+    def test_flat_mode_has_no_branches(self) -> None:
+        doc = pywire_parser.parse("{$if a}x{$else}y{/if}")
+        self.assertEqual(doc.template[0].branches, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,35 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestResponseEtag(unittest.TestCase):
+    def test_streamed_etag_matches_full_body(self) -> None:
+        out = pywire_parser.HtmlSerializer(etag=True)
+        out.start_tag("p")
+        out.text("hello")
+        first = out.take()
+        self.assertIsNone(out.etag)
+        out.end_tag("p")
+        body, etag = out.finish_with_etag()
+        self.assertEqual(first + body, b"<p>hello</p>")
+        self.assertEqual(etag, pywire_parser.content_etag(b"<p>hello</p>"))
+        self.assertEqual(out.etag, etag)
+        self.assertTrue(etag.startswith('"') and etag.endswith('"'))
+        self.assertEqual(len(etag), 34)
+
+    def test_etag_changes_with_content(self) -> None:
+        self.assertNotEqual(
+            pywire_parser.content_etag(b"<p>a</p>"),
+            pywire_parser.content_etag(b"<p>b</p>"),
+        )
+
+    def test_etag_disabled_by_default(self) -> None:
+        out = pywire_parser.HtmlSerializer()
+        out.raw("<br>")
+        self.assertEqual(out.finish_with_etag(), (b"<br>", None))
+        self.assertIsNone(out.etag)
+
+
+if __name__ == "__main__":
+    unittest.main()
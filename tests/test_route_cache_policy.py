@@ -0,0 +1,43 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestRouteCachePolicy(unittest.TestCase):
+    def test_cache_directive_is_structured(self) -> None:
+        doc = pywire_parser.parse(
+            "!path '/pricing'\n!cache max-age=60 vary=session,cookie:theme public\n\n<h1>Pricing</h1>\n"
+        )
+        policy = doc.cache_policy
+        self.assertIsNotNone(policy)
+        self.assertEqual(policy.max_age, 60)
+        self.assertEqual(policy.vary, ["session", "cookie:theme"])
+        self.assertTrue(policy.public)
+        self.assertEqual(policy.header(), "public, max-age=60")
+        self.assertEqual(policy.line, 2)
+
+    def test_route_table_carries_policy(self) -> None:
+        doc = pywire_parser.parse(
+            "!path {'list': '/posts', 'detail': '/posts/:id'}\n!cache no-store\n\n<p>x</p>\n"
+        )
+        routes = pywire_parser.extract_routes(doc)
+        self.assertEqual(
+            [(r.name, r.path) for r in routes],
+            [("list", "/posts"), ("detail", "/posts/:id")],
+        )
+        self.assertTrue(all(r.cache_policy.no_store for r in routes))
+
+    def test_no_cache_directive(self) -> None:
+        doc = pywire_parser.parse("!path '/'\n\n<p>x</p>\n")
+        self.assertIsNone(doc.cache_policy)
+        self.assertIsNone(pywire_parser.extract_routes(doc)[0].cache_policy)
+
+    def test_invalid_cache_directive(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.parse("!cache max-age=soon\n\n<p>x</p>\n")
+        with self.assertRaises(ValueError):
+            pywire_parser.parse("!cache max-age=1\n!cache max-age=2\n\n<p>x</p>\n")
+
+
+if __name__ == "__main__":
+    unittest.main()
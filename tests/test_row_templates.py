@@ -0,0 +1,82 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def loop(source):
+    doc = pywire_parser.parse(source)
+    return next(n for n in doc.template if n.block_keyword == "for")
+
+
+class TestRowTemplates(unittest.TestCase):
+    def test_parts_and_slots(self) -> None:
+        template = loop(
+            '{$for row in rows}<tr class="row-{row.kind}"><td>{row.name}</td></tr>{/for}'
+        ).row_template
+        self.assertEqual((template.target, template.iterable), ("row", "rows"))
+        self.assertEqual(template.parts, ['<tr class="row-', '"><td>', "</td></tr>"])
+        slots = [(s.kind, s.expression) for s in template.slots]
+        self.assertEqual(slots, [("value", "row.kind"), ("text", "row.name")])
+
+    def test_keyed_loop(self) -> None:
+        template = loop("{$for item in items, key=item.id}<li>{item.name}</li>{/for}").row_template
+        self.assertEqual((template.target, template.iterable), ("item", "items"))
+        self.assertEqual([s.expression for s in template.slots], ["item.name"])
+
+    def test_static_character_references(self) -> None:
+        template = loop(
+            '{$for p in people}<img alt="Don&#39;t {p}" title="a&nbsp;b&amp;c">{/for}'
+        ).row_template
+        self.assertEqual(template.parts, ['<img alt="Don\'t ', '" title="a\u00a0b&amp;c">'])
+
+    def test_render_substitutes_and_escapes(self) -> None:
+        template = loop("{$for item in items}<li>{item}</li>{/for}").row_template
+        html = template.render([("a",), ("<b>",), (None,)])
+        self.assertEqual(html, "<li>a</li><li>&lt;b&gt;</li><li></li>")
+
+    def test_whole_attribute_slots(self) -> None:
+        template = loop(
+            "{$for o in opts}<option value={o.id} selected={o.on}>x</option>{/for}"
+        ).row_template
+        self.assertEqual([s.attribute for s in template.slots], ["value", "selected"])
+        html = template.render([(1, True), (2, False), ('"q"', None)])
+        self.assertEqual(
+            html,
+            '<option value="1" selected>x</option>'
+            '<option value="2">x</option>'
+            '<option value="&quot;q&quot;">x</option>',
+        )
+
+    def test_per_item(self) -> None:
+        template = loop(
+            "{$for i, item in enumerate(items)}<p title={label}>{i}: {item}</p>{/for}"
+        ).row_template
+        self.assertEqual([s.per_item for s in template.slots], [False, True, True])
+
+    def test_row_length_is_checked(self) -> None:
+        template = loop("{$for item in items}<li>{item}</li>{/for}").row_template
+        with self.assertRaises(ValueError):
+            template.render([("a", "b")])
+
+    def test_bodies_that_cannot_be_specialized(self) -> None:
+        for source in (
+            "{$for x in xs}{$if x}<b>{x}</b>{/if}{/for}",
+            "{$for x in xs}<Card item={x} />{/for}",
+            "{$for x in xs}<button @click={pick(x)}>{x}</button>{/for}",
+        ):
+            with self.subTest(source=source):
+                self.assertIsNone(loop(source).row_template)
+
+    def test_inner_loop_is_specialized(self) -> None:
+        doc = pywire_parser.parse(
+            "{$for g in groups}<h2>{g.name}</h2>{$for x in g.items}<i>{x}</i>{/for}{/for}",
+            resolved=True,
+        )
+        (outer,) = doc.template
+        self.assertIsNone(outer.row_template)
+        inner = next(c for c in outer.children if c.block_keyword == "for")
+        self.assertEqual(inner.row_template.parts, ["<i>", "</i>"])
+
+
+if __name__ == "__main__":
+    unittest.main()
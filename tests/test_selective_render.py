@@ -0,0 +1,89 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+PAGE = """<h1>{title}</h1>
+<p class="count">{len(items)} items</p>
+<ul>
+{$for item in items}
+  <li>{item}</li>
+{/for}
+</ul>
+"""
+
+
+class TestRenderDirty(unittest.TestCase):
+    def setUp(self) -> None:
+        self.doc = pywire_parser.parse(PAGE)
+        self.before = {"title": "Inbox", "items": ["a", "b"]}
+
+    def test_only_changed_regions_are_sent(self) -> None:
+        update = pywire_parser.render_dirty(
+            self.doc, self.before, {**self.before, "title": "Archive"}
+        )
+        self.assertEqual(update.changed, ["title"])
+        (region,) = update.regions
+        self.assertTrue(region.startswith("h1."))
+        self.assertEqual(
+            update.payload,
+            f'<template data-pw-region="{region}"><h1>Archive</h1></template>'.encode(),
+        )
+
+    def test_loop_region(self) -> None:
+        update = pywire_parser.render_dirty(
+            self.doc, self.before, {**self.before, "items": ["a", "b", "c"]}
+        )
+        self.assertEqual(len(update.regions), 2)
+        self.assertIn(b"<p class=\"count\">3 items</p>", update.payload)
+        self.assertIn(b"<li>c</li>", update.payload)
+
+    def test_identical_output_is_skipped(self) -> None:
+        update = pywire_parser.render_dirty(
+            self.doc, self.before, {**self.before, "items": ("a", "b")}
+        )
+        self.assertEqual(update.changed, ["items"])
+        self.assertEqual(update.regions, [])
+        self.assertEqual(len(update.unchanged), 2)
+        self.assertEqual(update.payload, b"")
+
+    def test_custom_resolver(self) -> None:
+        calls = []
+
+        def resolver(expr, namespace):
+            calls.append(expr)
+            return eval(expr, dict(namespace))
+
+        pywire_parser.render_dirty(
+            self.doc, self.before, {**self.before, "title": "x"}, resolver
+        )
+        self.assertEqual(calls, ["title", "title"])
+
+    def test_replay(self) -> None:
+        current = {**self.before, "title": "Archive", "items": ["c"]}
+        update = pywire_parser.render_dirty(self.doc, self.before, current, record=True)
+        trace = update.trace
+        self.assertEqual(sorted(trace.regions), sorted(update.regions + update.unchanged))
+        replayed = pywire_parser.replay(trace)
+        self.assertEqual(replayed.payload, update.payload)
+        self.assertEqual(replayed.regions, update.regions)
+        self.assertIsNone(replayed.trace)
+
+    def test_replay_from_bytes(self) -> None:
+        current = {**self.before, "items": []}
+        update = pywire_parser.render_dirty(self.doc, self.before, current, record=True)
+        data = update.trace.to_bytes()
+        restored = pywire_parser.RenderTrace.from_bytes(data)
+        self.assertEqual(restored.changed, ["items"])
+        self.assertEqual(pywire_parser.replay(data).payload, update.payload)
+
+    def test_trace_is_only_recorded_on_request(self) -> None:
+        update = pywire_parser.render_dirty(self.doc, self.before, {**self.before, "title": "x"})
+        self.assertIsNone(update.trace)
+
+    def test_replay_rejects_other_data(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.replay(pywire_parser.dumps_state({"a": 1}))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,108 @@
+import ast
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+from pywire.compiler.ast_nodes import TemplateNode
+from pywire.compiler.codegen.template import TemplateCodegen
+
+CARD = """<my-card>
+  <template shadowrootmode="open">
+    <style>:host { display: block }</style>
+    <header><slot name="title"></slot></header>
+    <template><p>inert</p></template>
+    <slot></slot>
+  </template>
+  <h2 slot="title">Hello</h2>
+</my-card>
+"""
+
+
+def find_all(nodes, tag, out=None):
+    out = [] if out is None else out
+    for node in nodes:
+        if node.tag == tag:
+            out.append(node)
+        find_all(node.children, tag, out)
+    return out
+
+
+class TestShadowRootParsing(unittest.TestCase):
+    def test_mode_is_exposed(self) -> None:
+        doc = pywire_parser.parse(CARD)
+        outer, inner = find_all(doc.template, "template")
+        self.assertEqual(outer.shadow_root_mode, "open")
+        self.assertIsNone(inner.shadow_root_mode)
+
+    def test_nested_content_stays_inside_the_template(self) -> None:
+        doc = pywire_parser.parse(CARD)
+        host = find_all(doc.template, "my-card")[0]
+        shadow = find_all(host.children, "template")[0]
+        tags = [c.tag for c in shadow.children if c.tag]
+        self.assertEqual(tags, ["style", "header", "template", "slot"])
+        self.assertEqual([c.tag for c in host.children if c.tag], ["template", "h2"])
+
+    def test_slots_in_shadow_tree_are_native(self) -> None:
+        doc = pywire_parser.parse(CARD)
+        for slot in find_all(doc.template, "slot"):
+            self.assertFalse(slot.is_slot)
+            self.assertIsNone(slot.slot_name)
+        h2 = find_all(doc.template, "h2")[0]
+        self.assertEqual(h2.slot_name, "title")
+
+    def test_mode_is_case_insensitive_and_validated(self) -> None:
+        closed = pywire_parser.parse('<template shadowrootmode="Closed"></template>')
+        self.assertEqual(closed.template[0].shadow_root_mode, "closed")
+        other = pywire_parser.parse('<template shadowrootmode="bogus"><slot></slot></template>')
+        self.assertIsNone(other.template[0].shadow_root_mode)
+        self.assertTrue(other.template[0].children[0].is_slot)
+
+    def test_slot_outside_shadow_root_is_a_pywire_slot(self) -> None:
+        doc = pywire_parser.parse('<div><slot name="a"></slot></div>')
+        slot = find_all(doc.template, "slot")[0]
+        self.assertTrue(slot.is_slot)
+        self.assertEqual(slot.slot_name, "a")
+
+
+class TestShadowRootCodegen(unittest.TestCase):
+    def render_code(self, nodes) -> str:
+        func_def, _ = TemplateCodegen().generate_render_method(nodes)
+        return ast.unparse(ast.fix_missing_locations(func_def))
+
+    def test_native_slot_is_emitted_as_markup(self) -> None:
+        slot = TemplateNode(tag="slot", attributes={"name": "title"}, line=3, column=4)
+        template = TemplateNode(
+            tag="template",
+            attributes={"shadowrootmode": "open"},
+            children=[slot],
+            line=2,
+            column=2,
+            shadow_root_mode="open",
+        )
+        host = TemplateNode(tag="my-card", children=[template], line=1, column=0)
+        code = self.render_code([host])
+        self.assertIn("<template", code)
+        self.assertIn("<slot", code)
+        self.assertNotIn("render_slot", code)
+
+    def test_slot_outside_shadow_root_renders_fill(self) -> None:
+        slot = TemplateNode(tag="slot", attributes={"name": "title"}, line=1, column=0)
+        code = self.render_code([slot])
+        self.assertIn("render_slot", code)
+
+
+class TestShadowRootHtml(unittest.TestCase):
+    def test_end_tag_inside_template_does_not_close_outer_element(self) -> None:
+        html = (
+            '<div><template shadowrootmode="open"><p>x</div></p></template>'
+            "<span>light</span></div>"
+        )
+        out = pywire_parser.email_html(html).html
+        self.assertEqual(
+            out,
+            '<div><template shadowrootmode="open"><p>x</p></template>'
+            "<span>light</span></div>",
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,34 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestSlotNodes(unittest.TestCase):
+    def test_slot_definitions(self) -> None:
+        doc = pywire_parser.parse(
+            '<div><slot/><slot name="footer"><p>Fallback</p></slot></div>'
+        )
+        (div,) = doc.template
+        default, footer = [c for c in div.children if c.tag == "slot"]
+        self.assertTrue(default.is_slot)
+        self.assertEqual(default.slot_name, "default")
+        self.assertTrue(footer.is_slot)
+        self.assertEqual(footer.slot_name, "footer")
+        self.assertEqual([c.tag for c in footer.children], ["p"])
+        self.assertFalse(div.is_slot)
+        self.assertIsNone(div.slot_name)
+
+    def test_slot_fills(self) -> None:
+        doc = pywire_parser.parse(
+            '<Card><h2 slot="header">Title</h2><p>Body</p><b slot={which}>x</b></Card>'
+        )
+        (card,) = doc.template
+        header, body, dynamic = card.children
+        self.assertEqual(header.slot_name, "header")
+        self.assertFalse(header.is_slot)
+        self.assertIsNone(body.slot_name)
+        self.assertIsNone(dynamic.slot_name)
+
+
+if __name__ == "__main__":
+    unittest.main()
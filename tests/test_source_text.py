@@ -0,0 +1,33 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestSourceText(unittest.TestCase):
+    def test_elements_keep_their_markup(self) -> None:
+        source = "<div   class='a'  data-x=1>\n  <b>Hi</b>\n</div>\n"
+        (div,) = pywire_parser.parse(source).template
+        self.assertEqual(div.source_text, source.rstrip("\n"))
+        bold = next(c for c in div.children if c.tag == "b")
+        self.assertEqual(bold.source_text, "<b>Hi</b>")
+
+    def test_blocks_and_interpolations(self) -> None:
+        source = "{$if  ok }<p>{ name }</p>{/if}\n"
+        nodes = pywire_parser.parse(source).template
+        self.assertEqual(nodes[0].source_text, "{$if  ok }")
+        self.assertEqual(nodes[1].children[0].source_text, "{ name }")
+
+    def test_matches_byte_offsets(self) -> None:
+        source = "---\nx = 1\n---\n<p>café {x}</p>\n"
+        (p,) = pywire_parser.parse(source).template
+        raw = source.encode()[p.start_byte : p.end_byte].decode()
+        self.assertEqual(p.source_text, raw)
+
+    def test_folded_nodes_keep_the_expression(self) -> None:
+        doc = pywire_parser.parse("<p>{'a' + 'b'}</p>", fold_constants=True)
+        (text,) = doc.template[0].children
+        self.assertEqual((text.text_content, text.source_text), ("ab", "{'a' + 'b'}"))
+
+
+if __name__ == "__main__":
+    unittest.main()
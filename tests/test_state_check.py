@@ -0,0 +1,56 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def check(source):
+    doc = pywire_parser.parse(source)
+    return [(d.code, d.line) for d in pywire_parser.check_state(doc)]
+
+
+class TestStateCheck(unittest.TestCase):
+    def test_clean_page(self) -> None:
+        source = (
+            "---\nfrom app import fmt\ncount = wire(0)\n---\n"
+            "<p>{fmt(count)}</p>\n"
+            "<ul>{$for item in range(count)}<li>{item}</li>{/for}</ul>\n"
+        )
+        self.assertEqual(check(source), [])
+
+    def test_unused_wire(self) -> None:
+        source = "---\ncount = wire(0)\nlabel = wire('x')\n---\n<p>{label}</p>\n"
+        self.assertEqual(check(source), [("unused-wire", 2)])
+
+    def test_wire_read_by_other_wire(self) -> None:
+        source = (
+            "---\ncount = wire(0)\ndoubled = wire(lambda: count * 2)\n---\n"
+            "<p>{doubled}</p>\n"
+        )
+        self.assertEqual(check(source), [])
+
+    def test_write_only_handler_is_not_a_read(self) -> None:
+        source = "---\nclicks = wire(0)\n---\n<button @click={clicks += 1}>+</button>\n"
+        self.assertEqual(check(source), [("unused-wire", 2)])
+
+    def test_undefined_template_name(self) -> None:
+        source = "---\ncount = wire(0)\n---\n<p>{count}</p>\n<p>{totl}</p>\n"
+        diagnostics = pywire_parser.check_state(pywire_parser.parse(source))
+        (d,) = diagnostics
+        self.assertEqual((d.code, d.line, d.severity), ("undefined-name", 5, "warning"))
+        self.assertIn("totl", d.message)
+
+    def test_comprehension_and_kwargs_are_bound(self) -> None:
+        source = (
+            "---\nitems = wire([])\n---\n"
+            "<p>{', '.join(str(x) for x in items)}</p>\n"
+            "<p>{sorted(items, reverse=True)}</p>\n"
+        )
+        self.assertEqual(check(source), [])
+
+    def test_nolint(self) -> None:
+        source = "!nolint unused-wire\n---\ncount = wire(0)\n---\n<p>x</p>\n"
+        self.assertEqual(check(source), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
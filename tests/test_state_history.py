@@ -0,0 +1,76 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def summary(changes: list) -> list:
+    return [(c.kind, c.path, c.old, c.new) for c in changes]
+
+
+class TestStateHistory(unittest.TestCase):
+    def test_nested_changes(self) -> None:
+        history = pywire_parser.StateHistory()
+        first = history.record(
+            {"count": 1, "todos": [{"title": "a", "done": False}], "tags": {"x"}}
+        )
+        state = {"count": 2, "todos": [{"title": "a", "done": True}, {"title": "b"}]}
+        second = history.record({**state, "tags": {"x"}, "scores": {"a b": 1}})
+        self.assertEqual((first, second), (0, 1))
+        self.assertEqual(
+            summary(history.diff(0, 1)),
+            [
+                ("changed", "count", 1, 2),
+                ("changed", "todos[0].done", False, True),
+                ("added", "todos[1]", None, {"title": "b"}),
+                ("added", "scores", None, {"a b": 1}),
+            ],
+        )
+        self.assertEqual(summary(history.changes(1)), summary(history.diff(0, 1)))
+
+    def test_non_identifier_keys(self) -> None:
+        history = pywire_parser.StateHistory()
+        history.record({"scores": {"a b": 1, 3: "x"}})
+        history.record({"scores": {"a b": 2}})
+        self.assertEqual(
+            summary(history.changes(1)),
+            [("changed", "scores['a b']", 1, 2), ("removed", "scores[3]", "x", None)],
+        )
+
+    def test_sets_compare_unordered(self) -> None:
+        history = pywire_parser.StateHistory()
+        history.record({"tags": {"a", "b", "c"}})
+        history.record({"tags": {"c", "b", "a"}})
+        self.assertEqual(history.changes(1), [])
+
+    def test_recorded_state_is_a_copy(self) -> None:
+        history = pywire_parser.StateHistory()
+        state = {"items": [1]}
+        history.record(state)
+        state["items"].append(2)
+        history.record(state)
+        self.assertEqual(history.state(0), {"items": [1]})
+        self.assertEqual(summary(history.changes(1)), [("added", "items[1]", None, 2)])
+
+    def test_ring_is_bounded(self) -> None:
+        history = pywire_parser.StateHistory(capacity=2)
+        for n in range(4):
+            history.record({"n": n})
+        self.assertEqual(history.renders, [2, 3])
+        self.assertEqual(history.latest, 3)
+        self.assertEqual(len(history), 2)
+        with self.assertRaises(IndexError):
+            history.diff(1, 3)
+
+    def test_type_change(self) -> None:
+        history = pywire_parser.StateHistory()
+        history.record({"value": [1, 2]})
+        history.record({"value": (1, 2)})
+        self.assertEqual(summary(history.changes(1)), [("changed", "value", [1, 2], (1, 2))])
+
+    def test_rejects_non_builtin_values(self) -> None:
+        with self.assertRaises(TypeError):
+            pywire_parser.StateHistory().record({"x": object()})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,49 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestStateSnapshot(unittest.TestCase):
+    def test_round_trip_nested_state(self) -> None:
+        state = {
+            "count": 3,
+            "neg": -42,
+            "big": 2**80,
+            "ratio": 0.5,
+            "name": "héllo",
+            "raw": b"\x00\x01",
+            "flags": [True, False, None],
+            "pair": (1, "a"),
+            "tags": {"x", "y"},
+            "frozen": frozenset({1, 2}),
+            "nested": {"items": [{"id": 1}, {"id": 2}]},
+        }
+        data = pywire_parser.dumps_state(state)
+        self.assertEqual(pywire_parser.loads_state(data), state)
+
+    def test_schema_version(self) -> None:
+        data = pywire_parser.dumps_state({"a": 1}, schema_version=7)
+        self.assertEqual(pywire_parser.snapshot_schema_version(data), 7)
+        self.assertEqual(pywire_parser.loads_state(data, schema_version=7), {"a": 1})
+        with self.assertRaises(ValueError):
+            pywire_parser.loads_state(data, schema_version=8)
+
+    def test_rejects_unsupported_types(self) -> None:
+        with self.assertRaises(TypeError):
+            pywire_parser.dumps_state({"obj": object()})
+
+    def test_rejects_cycles(self) -> None:
+        cyclic: list = []
+        cyclic.append(cyclic)
+        with self.assertRaises(ValueError):
+            pywire_parser.dumps_state(cyclic)
+
+    def test_rejects_corrupt_input(self) -> None:
+        data = pywire_parser.dumps_state([1, 2, 3])
+        for bad in (b"", b"XYZ\x01\x00", data[:-1], data + b"\x00"):
+            with self.assertRaises(ValueError):
+                pywire_parser.loads_state(bad)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,51 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+CARD = '<div class="card"><h2>Title</h2><p>Body &amp; more</p></div>'
+
+
+class TestStaticChunks(unittest.TestCase):
+    def test_identical_subtrees_share_a_chunk(self) -> None:
+        source = (
+            "---\nitems = []\n---\n"
+            f"{{$for item in items}}{CARD}<span>{{item}}</span>{{/for}}\n"
+            f"{{$if not items}}{CARD}{{/if}}\n"
+        )
+        doc = pywire_parser.parse(source)
+        cards = [n for n in doc.template if n.tag == "div"]
+        self.assertEqual(len(cards), 2)
+        self.assertEqual({n.static_chunk for n in cards}, {0})
+        self.assertEqual(doc.static_chunks, [CARD])
+
+    def test_only_the_outermost_static_element_has_a_chunk(self) -> None:
+        doc = pywire_parser.parse(CARD)
+        (card,) = doc.template
+        self.assertEqual(card.static_chunk, 0)
+        self.assertTrue(all(c.static_chunk is None for c in card.children))
+
+    def test_dynamic_elements_have_no_chunk(self) -> None:
+        doc = pywire_parser.parse(
+            '<ul class={cls}><li>one</li><li title="a {b}">two</li><li>one</li></ul>'
+        )
+        (ul,) = doc.template
+        self.assertIsNone(ul.static_chunk)
+        self.assertEqual([li.static_chunk for li in ul.children], [0, None, 0])
+        self.assertEqual(doc.static_chunks, ["<li>one</li>"])
+
+    def test_distinct_markup_gets_distinct_chunks(self) -> None:
+        doc = pywire_parser.parse("<p>a</p>\n<p>b</p>\n<p>a</p>\n")
+        chunks = [n.static_chunk for n in doc.template if n.tag]
+        self.assertEqual(chunks, [0, 1, 0])
+        self.assertEqual(doc.static_chunks, ["<p>a</p>", "<p>b</p>"])
+
+    def test_excluded_elements(self) -> None:
+        doc = pywire_parser.parse(
+            "<style>p { color: red }</style><Card>x</Card><button @click={go}>Go</button>"
+        )
+        self.assertTrue(all(n.static_chunk is None for n in doc.template))
+        self.assertEqual(doc.static_chunks, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,46 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestStaticRoutes(unittest.TestCase):
+    def routes(self, source: str):
+        return pywire_parser.extract_routes(pywire_parser.parse(source))
+
+    def test_plain_page_is_static(self) -> None:
+        (route,) = self.routes("!path '/about'\n\n<h1>About us</h1>\n")
+        self.assertTrue(route.is_static)
+        self.assertEqual(route.dynamic_reasons, [])
+
+    def test_wires_and_request_data_are_dynamic(self) -> None:
+        (route,) = self.routes(
+            "!path '/counter'\n---\ncount = wire(0)\n---\n<p>{$count}</p>\n"
+        )
+        self.assertFalse(route.is_static)
+        self.assertIn("wire state", route.dynamic_reasons)
+
+        (route,) = self.routes("!path '/me'\n\n<p>{request.user}</p>\n")
+        self.assertEqual(route.dynamic_reasons, ["request data (request)"])
+
+        (route,) = self.routes("!path '/x'\n\n<button @click={go}>Go</button>\n")
+        self.assertEqual(route.dynamic_reasons, ["event handler"])
+
+    def test_route_parameters_only_affect_that_route(self) -> None:
+        routes = self.routes(
+            "!path {'list': '/posts', 'detail': '/posts/:id'}\n\n<p>Posts</p>\n"
+        )
+        self.assertEqual([r.is_static for r in routes], [True, False])
+        self.assertEqual(routes[1].dynamic_reasons, ["route parameter"])
+
+    def test_sitemap_lists_static_routes(self) -> None:
+        routes = self.routes(
+            "!path {'list': '/posts', 'detail': '/posts/:id'}\n\n<p>Posts</p>\n"
+        )
+        xml = pywire_parser.sitemap_xml(routes, "https://example.com/")
+        self.assertIn("<loc>https://example.com/posts</loc>", xml)
+        self.assertNotIn(":id", xml)
+        self.assertTrue(xml.startswith("<?xml"))
+
+
+if __name__ == "__main__":
+    unittest.main()
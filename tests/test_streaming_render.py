@@ -0,0 +1,51 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestStreamingRender(unittest.TestCase):
+    def test_serializer_escapes_and_streams(self) -> None:
+        out = pywire_parser.HtmlSerializer()
+        out.start_tag("a", [("href", '/x?a=1&b="2"'), ("hidden", None)])
+        out.text("<b> & co")
+        out.end_tag("a")
+        first = out.take()
+        self.assertEqual(
+            first, b'<a href="/x?a=1&amp;b=&quot;2&quot;" hidden>&lt;b&gt; &amp; co</a>'
+        )
+        out.raw("<br>")
+        self.assertEqual(out.finish(), b"<br>")
+        self.assertEqual(len(out), len(first) + 4)
+
+    def test_placeholders_and_fill_frames(self) -> None:
+        out = pywire_parser.HtmlSerializer()
+        out.open_placeholder("feed")
+        out.text("Loading...")
+        out.close_placeholder("feed")
+        self.assertEqual(
+            out.finish(), b"<!--pw-defer:feed-->Loading...<!--/pw-defer:feed-->"
+        )
+
+        encoder = pywire_parser.StreamEncoder(out.placeholders)
+        self.assertEqual(encoder.pending, ["feed"])
+        with self.assertRaises(ValueError):
+            encoder.finish()
+        frame = encoder.fill("feed", b"<ul></ul>")
+        self.assertEqual(frame, b'<template data-pw-fill="feed"><ul></ul></template>')
+        with self.assertRaises(ValueError):
+            encoder.fill("feed", b"again")
+        self.assertEqual(encoder.finish(), b"<!--pw-stream-end-->")
+
+    def test_invalid_placeholder_usage(self) -> None:
+        out = pywire_parser.HtmlSerializer()
+        with self.assertRaises(ValueError):
+            out.open_placeholder("bad id-->")
+        out.open_placeholder("a")
+        with self.assertRaises(ValueError):
+            out.close_placeholder("b")
+        with self.assertRaises(ValueError):
+            out.finish()
+
+
+if __name__ == "__main__":
+    unittest.main()
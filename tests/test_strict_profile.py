@@ -0,0 +1,60 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestStrictProfile(unittest.TestCase):
+    def check(self, source: str, profile=None):
+        doc = pywire_parser.parse(source)
+        return pywire_parser.check_profile(doc, profile or pywire_parser.StrictProfile.amp())
+
+    def test_amp_allows_plain_markup(self) -> None:
+        self.assertEqual(
+            self.check('<div class="a" data-x="1"><amp-img src="a.png" width="10"></amp-img></div>'),
+            [],
+        )
+
+    def test_violations(self) -> None:
+        diagnostics = self.check(
+            '<div onclick="go()"><iframe src="x"></iframe><p @click={save} foo="1">x</p>'
+            "<script>alert(1)</script></div>"
+        )
+        self.assertEqual(
+            [(d.code, d.severity) for d in diagnostics],
+            [
+                ("profile-inline-script", "error"),
+                ("profile-element", "error"),
+                ("profile-inline-script", "error"),
+                ("profile-attribute", "error"),
+                ("profile-inline-script", "error"),
+            ],
+        )
+
+    def test_rendered_attribute_names(self) -> None:
+        profile = pywire_parser.StrictProfile(["a", "input"], ["href", "value"])
+        source = "<a {href} {**extra}>x</a><input bind:value={name} bind:checked={on}>"
+        (d,) = self.check(source, profile)
+        self.assertEqual(d.code, "profile-attribute")
+        self.assertIn("'checked'", d.message)
+
+    def test_css_budget(self) -> None:
+        profile = pywire_parser.StrictProfile(
+            ["p", "style"], ["style"], max_css_bytes=20
+        )
+        diagnostics = self.check(
+            "<style>p { color: red }</style><p style=\"margin: 0; padding: 0\">x</p>",
+            profile,
+        )
+        (d,) = diagnostics
+        self.assertEqual(d.code, "profile-css-size")
+        self.assertEqual(d.line, 1)
+
+    def test_custom_allowlist_prefixes(self) -> None:
+        profile = pywire_parser.StrictProfile(["x-*"], ["data-*"], allow_inline_scripts=True)
+        self.assertEqual(self.check('<x-card data-id="1" @click={go}></x-card>', profile), [])
+        (d,) = self.check("<x-card title='t'></x-card>", profile)
+        self.assertEqual(d.code, "profile-attribute")
+
+
+if __name__ == "__main__":
+    unittest.main()
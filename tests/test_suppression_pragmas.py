@@ -0,0 +1,62 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def codes(doc):
+    return [d.code for d in doc.diagnostics]
+
+
+class TestSuppressionPragmas(unittest.TestCase):
+    def test_unsuppressed_warning(self) -> None:
+        doc = pywire_parser.parse("<p data-pw-x='1'>x</p>")
+        self.assertIn("reserved-attribute", codes(doc))
+        self.assertEqual(doc.suppressed, {})
+
+    def test_file_scope_nolint(self) -> None:
+        doc = pywire_parser.parse(
+            "!nolint reserved-attribute\n\n<p data-pw-x='1'>x</p>\n<b data-pw-y='2'>y</b>"
+        )
+        self.assertNotIn("reserved-attribute", codes(doc))
+        self.assertEqual(doc.suppressed, {"reserved-attribute": 2})
+
+    def test_file_scope_comment_pragma(self) -> None:
+        doc = pywire_parser.parse(
+            "{# pywire: disable=reserved-attribute #}\n<p data-pw-x='1'>x</p>"
+        )
+        self.assertEqual(doc.suppressed, {"reserved-attribute": 1})
+
+    def test_node_scope_covers_next_element_only(self) -> None:
+        doc = pywire_parser.parse(
+            "<main>\n"
+            "  {# pywire: disable=reserved-attribute #}\n"
+            "  <div>\n"
+            "    <p data-pw-x='1'>x</p>\n"
+            "  </div>\n"
+            "  <p data-pw-y='2'>y</p>\n"
+            "</main>"
+        )
+        self.assertEqual(doc.suppressed, {"reserved-attribute": 1})
+        (d,) = doc.diagnostics
+        self.assertEqual(d.line, 6)
+
+    def test_trailing_pragma_without_rules(self) -> None:
+        doc = pywire_parser.parse(
+            "<main>\n  <p data-pw-x='1'>x</p> {# pywire: disable #}\n</main>"
+        )
+        self.assertEqual(codes(doc), [])
+
+    def test_other_rules_not_suppressed(self) -> None:
+        doc = pywire_parser.parse(
+            "!nolint unused-wire\n\n<p data-pw-x='1'>x</p>"
+        )
+        self.assertIn("reserved-attribute", codes(doc))
+
+    def test_is_suppressed_for_external_lints(self) -> None:
+        doc = pywire_parser.parse("!nolint unused-wire\n\n<p>x</p>")
+        self.assertTrue(doc.is_suppressed("unused-wire", 3))
+        self.assertFalse(doc.is_suppressed("other", 3))
+
+
+if __name__ == "__main__":
+    unittest.main()
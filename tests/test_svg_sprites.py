@@ -0,0 +1,47 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+ICON = '<svg class="{cls}" viewBox="0 0 24 24"><path d="M4 12h16"></path></svg>'
+
+
+class TestSvgSprites(unittest.TestCase):
+    def setUp(self) -> None:
+        self.index = pywire_parser.ProjectIndex()
+        self.index.add(
+            "pages/a.wire",
+            "<nav>" + ICON.format(cls="sm") + ICON.format(cls="lg") + "</nav>\n",
+        )
+        self.index.add("pages/b.wire", "<footer>" + ICON.format(cls="sm") + "</footer>\n")
+        self.index.add(
+            "pages/c.wire", '<svg viewBox="0 0 8 8"><circle r="{size}"></circle></svg>\n'
+        )
+
+    def test_repeated_static_icons_become_symbols(self) -> None:
+        plan = pywire_parser.extract_sprites(self.index)
+        self.assertEqual(len(plan), 1)
+        (symbol,) = plan.symbols
+        self.assertTrue(symbol.id.startswith("pw-icon-"))
+        self.assertEqual(
+            [o[0] for o in symbol.occurrences],
+            ["pages/a.wire", "pages/a.wire", "pages/b.wire"],
+        )
+        self.assertIn(f'<symbol id="{symbol.id}" viewBox="0 0 24 24">', plan.sprite())
+        self.assertIn('<path d="M4 12h16"></path>', symbol.markup)
+
+    def test_rewrite_usages(self) -> None:
+        plan = pywire_parser.extract_sprites(self.index)
+        symbol_id = plan.symbols[0].id
+        out = pywire_parser.rewrite_sprites(
+            "<nav>" + ICON.format(cls="sm") + "</nav>\n", plan
+        )
+        self.assertEqual(
+            out, f'<nav><svg class="sm"><use href="#{symbol_id}"></use></svg></nav>\n'
+        )
+
+    def test_min_occurrences(self) -> None:
+        self.assertEqual(len(pywire_parser.extract_sprites(self.index, 4)), 0)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,47 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def first_child(doc):
+    (root,) = [n for n in doc.template if n.tag == "div"]
+    return [n for n in root.children if n.tag][0]
+
+
+class TestTabWidth(unittest.TestCase):
+    def test_tab_expands_to_default_width(self) -> None:
+        doc = pywire_parser.parse("<div>\n\t<p>x</p>\n</div>")
+        p = first_child(doc)
+        self.assertEqual((p.line, p.column), (2, 1))
+        self.assertEqual(p.display_column, 4)
+
+    def test_custom_tab_width(self) -> None:
+        doc = pywire_parser.parse("<div>\n\t\t<p>x</p>\n</div>", tab_width=8)
+        p = first_child(doc)
+        self.assertEqual(p.column, 2)
+        self.assertEqual(p.display_column, 16)
+
+    def test_tab_after_text_snaps_to_next_stop(self) -> None:
+        doc = pywire_parser.parse("<div>\n  \t<p>x</p>\n</div>", tab_width=4)
+        self.assertEqual(first_child(doc).display_column, 4)
+
+    def test_spaces_match_raw_column(self) -> None:
+        doc = pywire_parser.parse("<div>\n    <p>x</p>\n</div>")
+        p = first_child(doc)
+        self.assertEqual(p.display_column, p.column)
+
+    def test_diagnostics_have_display_columns(self) -> None:
+        doc = pywire_parser.parse(
+            "<div>\n\t<p data-pw-x='1'>x</p>\n</div>", tab_width=2
+        )
+        (d,) = [d for d in doc.diagnostics if d.code == "reserved-attribute"]
+        self.assertGreater(d.display_column, d.column)
+
+    def test_multibyte_prefix_counts_characters(self) -> None:
+        doc = pywire_parser.parse("<div>é<p>x</p></div>")
+        p = first_child(doc)
+        self.assertEqual(p.column - p.display_column, 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
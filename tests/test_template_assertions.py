@@ -0,0 +1,81 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+HTML = """
+<nav class="main nav">
+  <ul>
+    <li><a href="/" class="active">Home</a></li>
+    <li><a href="/about">About
+        us</a></li>
+  </ul>
+</nav>
+<p id="intro">Hello &amp; welcome</p>
+"""
+
+
+class TestSelect(unittest.TestCase):
+    def test_selectors(self) -> None:
+        self.assertEqual(len(pywire_parser.select(HTML, "a")), 2)
+        self.assertEqual(len(pywire_parser.select(HTML, "nav > ul > li > a")), 2)
+        self.assertEqual(len(pywire_parser.select(HTML, "nav > a")), 0)
+        self.assertEqual(len(pywire_parser.select(HTML, "nav.main a.active")), 1)
+        self.assertEqual(len(pywire_parser.select(HTML, "a[href^=/a], #intro")), 2)
+        self.assertEqual(len(pywire_parser.select(HTML, "[class~='nav']")), 1)
+
+    def test_match_text_is_normalized(self) -> None:
+        (about,) = pywire_parser.select(HTML, "a[href='/about']")
+        self.assertEqual(about.text, "About us")
+        self.assertEqual(about.attributes["href"], "/about")
+        self.assertIsNone(about.line)
+
+    def test_multibyte_whitespace_is_a_descendant_combinator(self) -> None:
+        self.assertEqual(len(pywire_parser.select(HTML, "nav\u00a0a")), 2)
+        self.assertEqual(len(pywire_parser.select(HTML, "nav >\u3000a")), 0)
+
+    def test_invalid_selector(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.select(HTML, "a:hover")
+        with self.assertRaises(ValueError):
+            pywire_parser.select(HTML, "a[href")
+
+    def test_rejects_other_types(self) -> None:
+        with self.assertRaises(TypeError):
+            pywire_parser.select(42, "a")
+
+
+class TestAssertContains(unittest.TestCase):
+    def test_passes_and_returns_match(self) -> None:
+        match = pywire_parser.assert_contains(
+            HTML, "li a", text="About   us", attrs={"href": "/about"}
+        )
+        self.assertEqual(match.tag, "a")
+        pywire_parser.assert_contains(HTML, "p#intro", text="Hello & welcome")
+        pywire_parser.assert_contains(HTML, "a", attrs={"class": None})
+
+    def test_failure_lists_nearest_matches(self) -> None:
+        with self.assertRaises(AssertionError) as ctx:
+            pywire_parser.assert_contains(HTML, "a", text="Contact")
+        message = str(ctx.exception)
+        self.assertIn("Nearest matches", message)
+        self.assertIn('text "Home" does not contain "Contact"', message)
+
+        with self.assertRaises(AssertionError) as ctx:
+            pywire_parser.assert_contains(HTML, "a", attrs={"href": "/aboot"})
+        self.assertIn('href="/about", expected "/aboot"', str(ctx.exception))
+
+    def test_failure_with_partial_selector(self) -> None:
+        with self.assertRaises(AssertionError) as ctx:
+            pywire_parser.assert_contains(HTML, "a.missing")
+        message = str(ctx.exception)
+        self.assertIn("does not match selector", message)
+        self.assertIn('<a href="/" class="active">Home</a>', message)
+
+    def test_template_document(self) -> None:
+        doc = pywire_parser.parse('<ul>\n  <li class="item">{name}</li>\n</ul>\n')
+        match = pywire_parser.assert_contains(doc, "ul > li.item", text="{name}")
+        self.assertEqual(match.line, 2)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,26 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestTemplateComments(unittest.TestCase):
+    def test_comments_are_dropped(self) -> None:
+        doc = pywire_parser.parse(
+            "{# page note #}\n<div>{# inner #}<p>Hi {# aside #}there</p></div>"
+        )
+        div = [n for n in doc.template if n.tag == "div"][0]
+        (p,) = div.children
+        text = "".join(c.text_content or "" for c in p.children)
+        self.assertEqual(text, "Hi there")
+        for node in doc.template:
+            self.assertNotIn("note", node.text_content or "")
+            self.assertNotEqual(node.expression, "# page note #")
+
+    def test_html_comments_are_kept(self) -> None:
+        doc = pywire_parser.parse("<div><!-- sent --><p>x</p></div>")
+        (div,) = doc.template
+        self.assertTrue(div.children[0].is_comment)
+
+
+if __name__ == "__main__":
+    unittest.main()
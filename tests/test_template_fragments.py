@@ -0,0 +1,47 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = '''---
+badge = html("""<span class="badge">{count}</span>""")
+# html("<b>not a call</b>")
+label = "html('<i>plain string</i>')"
+icon = ui.html(
+    '<i class="icon"></i>'
+)
+---
+<div>{badge}</div>
+'''
+
+
+class TestTemplateFragments(unittest.TestCase):
+    def test_off_by_default(self) -> None:
+        self.assertEqual(pywire_parser.parse(SOURCE).fragments, [])
+
+    def test_fragments_found(self) -> None:
+        doc = pywire_parser.parse(SOURCE, fragments=True)
+        self.assertEqual([f.function for f in doc.fragments], ["html", "ui.html"])
+
+    def test_positions_are_in_outer_file(self) -> None:
+        doc = pywire_parser.parse(SOURCE, fragments=True)
+        badge, icon = doc.fragments
+        self.assertEqual((badge.line, badge.column), (2, 17))
+        (span,) = [n for n in badge.document.template if n.tag == "span"]
+        self.assertEqual((span.line, span.column), (2, 17))
+        (interp,) = [n for n in span.children if n.is_block]
+        self.assertEqual(interp.expression, "count")
+        (i,) = [n for n in icon.document.template if n.tag == "i"]
+        self.assertEqual((i.line, i.column), (6, 5))
+
+    def test_fragment_diagnostics(self) -> None:
+        doc = pywire_parser.parse(
+            "---\nx = html('<p data-pw-x=\"1\">x</p>')\n---\n<p></p>\n",
+            fragments=True,
+        )
+        (fragment,) = doc.fragments
+        (d,) = fragment.document.diagnostics
+        self.assertEqual((d.code, d.line), ("reserved-attribute", 2))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,47 @@
+import random
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def apply_edits(text: str, edits: list) -> str:
+    # Back-to-front keeps earlier offsets valid, mirroring the client.
+    for edit in reversed(edits):
+        text = text[: edit.offset] + edit.insert + text[edit.offset + edit.delete :]
+    return text
+
+
+class TestTextDiff(unittest.TestCase):
+    def test_identical(self) -> None:
+        self.assertEqual(pywire_parser.text_diff("same", "same"), [])
+
+    def test_append_is_single_insert(self) -> None:
+        edits = pywire_parser.text_diff("line 1\n", "line 1\nline 2\n")
+        self.assertEqual(len(edits), 1)
+        self.assertEqual((edits[0].offset, edits[0].delete), (7, 0))
+        self.assertEqual(edits[0].insert, "line 2\n")
+
+    def test_minimal_middle_edit(self) -> None:
+        edits = pywire_parser.text_diff("the quick fox", "the slow fox")
+        self.assertEqual(apply_edits("the quick fox", edits), "the slow fox")
+        self.assertTrue(all(e.offset >= 4 for e in edits))
+
+    def test_utf16_offsets(self) -> None:
+        old, new = "😀 a", "😀 b"
+        (edit,) = pywire_parser.text_diff(old, new)
+        self.assertEqual(edit.offset, 3)
+        (edit,) = pywire_parser.text_diff(old, new, unit="char")
+        self.assertEqual(edit.offset, 2)
+
+    def test_random_round_trip(self) -> None:
+        rng = random.Random(1234)
+        for _ in range(200):
+            old = "".join(rng.choice("abc\n") for _ in range(rng.randint(0, 40)))
+            new = "".join(rng.choice("abcd\n") for _ in range(rng.randint(0, 40)))
+            for max_cost in (2, 2000):
+                edits = pywire_parser.text_diff(old, new, max_cost=max_cost, unit="char")
+                self.assertEqual(apply_edits(old, edits), new)
+
+
+if __name__ == "__main__":
+    unittest.main()
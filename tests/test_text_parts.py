@@ -0,0 +1,34 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestTextParts(unittest.TestCase):
+    def parts(self, source: str) -> list:
+        (node,) = pywire_parser.parse(source).template
+        return node.text_parts
+
+    def test_split_around_interpolation(self) -> None:
+        parts = self.parts("<p>Hello, {name}!</p>")
+        self.assertEqual([p.kind for p in parts], ["static", "dynamic", "static"])
+        self.assertEqual(
+            [parts[0].text, parts[1].expression, parts[2].text], ["Hello, ", "name", "!"]
+        )
+
+    def test_dynamic_parts_are_addressed_below_the_region(self) -> None:
+        (node,) = pywire_parser.parse("<p>{a} and {a}</p>").template
+        first, _, second = node.text_parts
+        self.assertRegex(first.address, "^" + node.region + r"/text\.[0-9a-f]{6}$")
+        self.assertEqual(second.address, first.address + "~2")
+
+    def test_static_text_has_no_parts(self) -> None:
+        self.assertEqual(self.parts("<p>Hello</p>"), [])
+
+    def test_address_is_stable_when_text_changes(self) -> None:
+        before = self.parts("<p>Hello, {name}!</p>")[1].address
+        after = self.parts("<p>Hi there, {name}.</p>")[1].address
+        self.assertEqual(before, after)
+
+
+if __name__ == "__main__":
+    unittest.main()
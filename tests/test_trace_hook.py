@@ -0,0 +1,40 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestTraceHook(unittest.TestCase):
+    def tearDown(self) -> None:
+        pywire_parser.set_trace_hook(None)
+
+    def test_spans_are_forwarded(self) -> None:
+        events = []
+        pywire_parser.set_trace_hook(lambda name, secs, fields: events.append((name, secs, fields)))
+        pywire_parser.parse("<p>hello</p>\n")
+
+        names = [name for name, _, _ in events]
+        self.assertIn("parse", names)
+        parse = next(e for e in events if e[0] == "parse")
+        self.assertGreaterEqual(parse[1], 0.0)
+        self.assertEqual(parse[2]["bytes"], len("<p>hello</p>\n"))
+        self.assertIn("nodes", parse[2])
+
+    def test_serialize_span(self) -> None:
+        events = []
+        pywire_parser.set_trace_hook(lambda name, secs, fields: events.append((name, fields)))
+        s = pywire_parser.HtmlSerializer()
+        s.text("hi")
+        s.finish()
+        serialize = [f for name, f in events if name == "serialize"]
+        self.assertEqual(serialize, [{"bytes": 2}])
+
+    def test_can_be_switched_off(self) -> None:
+        events = []
+        pywire_parser.set_trace_hook(lambda *args: events.append(args))
+        pywire_parser.set_trace_hook(None)
+        pywire_parser.parse("<p>hello</p>\n")
+        self.assertEqual(events, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,45 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestTrimMarkers(unittest.TestCase):
+    def parse(self, source: str):
+        return pywire_parser.parse(source).template
+
+    def test_block_markers(self) -> None:
+        nodes = self.parse("<pre>\n  {$if ok -}\n    x\n  {- /if}\n</pre>")
+        pre = nodes[0]
+        opener, *_, closer = [n for n in pre.children if n.is_block]
+        self.assertEqual(opener.block_keyword, "if")
+        self.assertEqual(opener.expression, "ok")
+        self.assertFalse(opener.trim_before)
+        self.assertTrue(opener.trim_after)
+        self.assertEqual(closer.block_keyword, "/if")
+        self.assertTrue(closer.trim_before)
+        self.assertFalse(closer.trim_after)
+        texts = [n.text_content for n in pre.children if n.tag is None and not n.is_block]
+        self.assertEqual(texts, ["\n  ", "x", "\n"])
+
+    def test_interpolation_markers(self) -> None:
+        (p,) = self.parse("<p> a  {-name-}  b </p>")
+        text_before, interp, text_after = p.children
+        self.assertEqual(interp.expression, "name")
+        self.assertTrue(interp.trim_before and interp.trim_after)
+        self.assertEqual(text_before.text_content, " a")
+        self.assertEqual(text_after.text_content, "b ")
+
+    def test_negation_is_not_a_marker(self) -> None:
+        (p,) = self.parse("<p> {-count} </p>")
+        interp = p.children[1]
+        self.assertEqual(interp.expression, "-count")
+        self.assertFalse(interp.trim_before or interp.trim_after)
+        self.assertEqual(p.children[0].text_content, " ")
+
+    def test_empty_text_is_dropped(self) -> None:
+        (p,) = self.parse("<p>  {x -}  </p>")
+        self.assertEqual(len(p.children), 2)
+
+
+if __name__ == "__main__":
+    unittest.main()
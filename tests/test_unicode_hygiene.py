@@ -0,0 +1,40 @@
+import unicodedata
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def codes(doc):
+    return [d.code for d in doc.diagnostics]
+
+
+class TestUnicodeHygiene(unittest.TestCase):
+    def test_off_by_default(self) -> None:
+        doc = pywire_parser.parse("<p>{tot‍al}</p>")
+        self.assertNotIn("invisible-character", codes(doc))
+
+    def test_zero_width_joiner_in_interpolation(self) -> None:
+        doc = pywire_parser.parse("<p>\n  {tot‍al}\n</p>", normalize_unicode=True)
+        (d,) = [d for d in doc.diagnostics if d.code == "invisible-character"]
+        self.assertEqual(d.severity, "warning")
+        self.assertIn("U+200D", d.message)
+        self.assertEqual((d.line, d.column), (2, 6))
+
+    def test_confusable_in_attribute_expression(self) -> None:
+        doc = pywire_parser.parse('<a href={сount}>x</a>', normalize_unicode=True)
+        (d,) = [d for d in doc.diagnostics if d.code == "confusable-character"]
+        self.assertIn("looks like 'c'", d.message)
+
+    def test_string_literals_are_not_confusables(self) -> None:
+        doc = pywire_parser.parse("<p>{'привет'}</p>", normalize_unicode=True)
+        self.assertEqual(codes(doc), [])
+
+    def test_expressions_are_nfc_normalized(self) -> None:
+        decomposed = unicodedata.normalize("NFD", "café")
+        doc = pywire_parser.parse(f"<p>{{{decomposed}}}</p>", normalize_unicode=True)
+        interpolation = doc.template[0].children[0]
+        self.assertEqual(interpolation.expression, "café")
+
+
+if __name__ == "__main__":
+    unittest.main()
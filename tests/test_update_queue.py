@@ -0,0 +1,54 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestUpdateQueue(unittest.TestCase):
+    def test_fifo_order(self) -> None:
+        queue = pywire_parser.UpdateQueue()
+        queue.push("a", b"1")
+        queue.push("b", b"2")
+        self.assertEqual(queue.drain(), [("a", b"1"), ("b", b"2")])
+        self.assertIsNone(queue.pop())
+
+    def test_coalesces_same_region_in_place(self) -> None:
+        queue = pywire_parser.UpdateQueue()
+        queue.push("a", b"old")
+        queue.push("b", b"x")
+        queue.push("a", b"new")
+        self.assertEqual(queue.regions, ["a", "b"])
+        self.assertEqual(queue.pop(), ("a", b"new"))
+        self.assertEqual((queue.queued, queue.coalesced), (3, 1))
+
+    def test_drops_oldest_region_when_full(self) -> None:
+        queue = pywire_parser.UpdateQueue(capacity=2)
+        queue.push("a", 1)
+        queue.push("b", 2)
+        self.assertTrue(queue.is_full())
+        self.assertEqual(queue.push("c", 3), "a")
+        self.assertEqual(queue.regions, ["b", "c"])
+        self.assertEqual(queue.dropped, 1)
+        self.assertEqual(queue.take_dropped(), ["a"])
+        self.assertEqual(queue.take_dropped(), [])
+
+    def test_requeued_region_is_no_longer_reported_dropped(self) -> None:
+        queue = pywire_parser.UpdateQueue(capacity=1)
+        queue.push("a", 1)
+        queue.push("b", 2)
+        queue.push("a", 3)
+        self.assertEqual(queue.take_dropped(), ["b"])
+
+    def test_drain_limit(self) -> None:
+        queue = pywire_parser.UpdateQueue()
+        for region in "abc":
+            queue.push(region, region)
+        self.assertEqual(len(queue.drain(2)), 2)
+        self.assertEqual(len(queue), 1)
+
+    def test_zero_capacity_rejected(self) -> None:
+        with self.assertRaises(ValueError):
+            pywire_parser.UpdateQueue(capacity=0)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,52 @@
+import builtins
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def undefined(source: str) -> list:
+    doc = pywire_parser.parse(source, validate_names=True)
+    return [
+        (d.line, d.message) for d in doc.diagnostics if d.code == "undefined-name"
+    ]
+
+
+class TestValidateNames(unittest.TestCase):
+    def test_typo_is_reported(self) -> None:
+        source = "---\ncount = wire(0)\n---\n<p>{countt}</p>\n"
+        self.assertEqual(undefined(source), [(4, "'countt' is not defined in the frontmatter")])
+
+    def test_off_by_default(self) -> None:
+        doc = pywire_parser.parse("<p>{countt}</p>\n")
+        self.assertEqual([d for d in doc.diagnostics if d.code == "undefined-name"], [])
+
+    def test_known_names(self) -> None:
+        source = (
+            "---\n"
+            "from app.models import (\n"
+            "    User,\n"
+            "    Team as Group,\n"
+            ")\n"
+            "import os.path\n"
+            "def fmt(value):\n"
+            "    return str(value)\n"
+            "---\n"
+            "!const LIMIT = 3\n"
+            "<p>{fmt(User)} {Group} {os.path.sep} {len(request.path)} {LIMIT}</p>\n"
+            "{$for item in range(LIMIT)}<i>{item}</i>{/for}\n"
+        )
+        self.assertEqual(undefined(source), [])
+
+    def test_every_builtin_is_known(self) -> None:
+        names = [n for n in dir(builtins) if not n.startswith("_")]
+        source = "".join(f"<p>{{{name}}}</p>\n" for name in names)
+        self.assertEqual(undefined(source), [])
+
+    def test_pragma_silences(self) -> None:
+        source = "{# pywire: disable=undefined-name #}\n<p>{missing}</p>\n"
+        doc = pywire_parser.parse(source, validate_names=True)
+        self.assertEqual(doc.suppressed.get("undefined-name"), 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+fingerprint = pywire_parser.value_fingerprint
+
+
+class TestValueFingerprint(unittest.TestCase):
+    def test_equal_values_match(self) -> None:
+        a = {"user": {"id": 1, "tags": ["x", "y"]}, "page": 2, "ok": None}
+        b = {"page": 2, "ok": None, "user": {"tags": ["x", "y"], "id": 1}}
+        self.assertEqual(fingerprint(a), fingerprint(b))
+        self.assertEqual(fingerprint({1, 2, 3}), fingerprint({3, 2, 1}))
+        self.assertEqual(fingerprint(0.0), fingerprint(-0.0))
+
+    def test_different_values_differ(self) -> None:
+        self.assertNotEqual(fingerprint([1, 2]), fingerprint([2, 1]))
+        self.assertNotEqual(fingerprint([1]), fingerprint((1,)))
+        self.assertNotEqual(fingerprint("1"), fingerprint(1))
+        self.assertNotEqual(fingerprint({"a": 1}), fingerprint({"a": 2}))
+        self.assertNotEqual(fingerprint(2**70), fingerprint(2**71))
+
+    def test_cycles_and_depth(self) -> None:
+        cyclic: list = [1]
+        cyclic.append(cyclic)
+        self.assertEqual(fingerprint(cyclic), fingerprint(cyclic))
+
+        deep_a = [[[1]]]
+        deep_b = [[[2]]]
+        self.assertNotEqual(fingerprint(deep_a), fingerprint(deep_b))
+        self.assertEqual(
+            fingerprint(deep_a, max_depth=2), fingerprint(deep_b, max_depth=2)
+        )
+
+    def test_unsupported_type(self) -> None:
+        with self.assertRaises(TypeError):
+            fingerprint([object()])
+
+
+if __name__ == "__main__":
+    unittest.main()
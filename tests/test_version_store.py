@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestVersionStore(unittest.TestCase):
+    def test_bumps(self) -> None:
+        store = pywire_parser.VersionStore()
+        self.assertEqual(store.version("count"), 0)
+        self.assertEqual(store.bump("count"), 1)
+        self.assertEqual(store.bump("count"), 2)
+        store.bump_many(["name", "count"])
+        self.assertEqual((store.version("count"), store.version("name")), (3, 1))
+        self.assertEqual(store.clock, 4)
+        self.assertEqual(len(store), 2)
+
+    def test_snapshots(self) -> None:
+        store = pywire_parser.VersionStore()
+        store.bump("a")
+        snapshot = store.snapshot(["a", "b"])
+        self.assertEqual((snapshot.clock, len(snapshot)), (1, 2))
+        self.assertFalse(store.changed_since(snapshot))
+        store.bump("c")
+        self.assertFalse(store.changed_since(snapshot))
+        store.bump("b")
+        self.assertTrue(store.changed_since(snapshot))
+        self.assertEqual(store.changed_wires(snapshot), ["b"])
+        self.assertTrue(store.any_changed_after(["b"], snapshot.clock))
+        self.assertFalse(store.any_changed_after(["a", "unknown"], snapshot.clock))
+
+    def test_foreign_snapshot(self) -> None:
+        store, other = pywire_parser.VersionStore(), pywire_parser.VersionStore()
+        snapshot = other.snapshot(["x", "y", "z"])
+        with self.assertRaises(ValueError):
+            store.changed_since(snapshot)
+        with self.assertRaises(ValueError):
+            store.changed_wires(snapshot)
+
+
+if __name__ == "__main__":
+    unittest.main()
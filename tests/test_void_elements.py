@@ -0,0 +1,41 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+
+def warnings(source: str) -> list:
+    doc = pywire_parser.parse(source)
+    return [(d.code, d.line) for d in doc.diagnostics if d.severity == "warning"]
+
+
+class TestVoidElements(unittest.TestCase):
+    def test_flags(self) -> None:
+        doc = pywire_parser.parse("<p>a</p><br><img src='x.png' /><Card />")
+        p, br, img, card = [n for n in doc.template if n.tag]
+        self.assertEqual((p.is_void, p.is_self_closing), (False, False))
+        self.assertEqual((br.is_void, br.is_self_closing), (True, False))
+        self.assertEqual((img.is_void, img.is_self_closing), (True, True))
+        self.assertEqual((card.is_void, card.is_self_closing), (False, True))
+
+    def test_self_closed_non_void(self) -> None:
+        self.assertEqual(warnings("<main>\n  <div/>\n</main>"), [("self-closing-non-void", 2)])
+
+    def test_omitted_end_tag_with_self_closed_child(self) -> None:
+        doc = pywire_parser.parse("<ul><li><img src=x /></ul>")
+        (ul,) = doc.template
+        (li,) = [n for n in ul.children if n.tag]
+        self.assertFalse(li.is_self_closing)
+        self.assertTrue(li.children[0].is_self_closing)
+        self.assertEqual(warnings("<ul><li><img src=x /></ul>"), [])
+
+    def test_void_with_content(self) -> None:
+        codes = [code for code, _ in warnings("<input>text</input>")]
+        self.assertIn("void-element-content", codes)
+
+    def test_allowed_forms(self) -> None:
+        source = '<br/><hr><svg><circle r="1"/><path d="M0 0"/></svg><Icon /><slot />'
+        self.assertEqual(warnings(source), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,57 @@
+import unittest
+
+from pywire import _pywire_parser as pywire_parser
+
+SOURCE = """---
+count = 0
+items = []
+title = "Hello"
+total = wire(0)
+
+def increment():
+    global count
+    count += 1
+
+def add(item):
+    items.append(item)
+    total.value += 1
+---
+<h1>{title}</h1>
+<p>{count} / {total}</p>
+<ul>{$for item in items}<li>{item}</li>{/for}</ul>
+"""
+
+
+class TestWireSuggestions(unittest.TestCase):
+    def test_mutated_and_rendered(self) -> None:
+        doc = pywire_parser.parse(SOURCE)
+        suggestions = pywire_parser.suggest_wires(doc)
+        self.assertEqual([s.name for s in suggestions], ["count", "items"])
+        count = suggestions[0]
+        self.assertEqual((count.line, count.column), (2, 0))
+        self.assertEqual(count.fix, "count = wire(0)")
+        self.assertEqual(count.mutations, [(9, 4)])
+        self.assertEqual([line for line, _ in count.references], [16])
+
+    def test_event_handler_mutation(self) -> None:
+        doc = pywire_parser.parse(
+            "---\nclicks = 0\n---\n<button @click={clicks += 1}>{clicks}</button>\n"
+        )
+        (s,) = pywire_parser.suggest_wires(doc)
+        self.assertEqual(s.name, "clicks")
+        self.assertEqual(s.mutations[0][0], 4)
+
+    def test_unrendered_variables_ignored(self) -> None:
+        doc = pywire_parser.parse("---\nn = 0\ndef f():\n    n = 1\n---\n<p>x</p>\n")
+        self.assertEqual(pywire_parser.suggest_wires(doc), [])
+
+    def test_suppressed(self) -> None:
+        doc = pywire_parser.parse(
+            "!nolint wire-candidate\n---\nclicks = 0\n---\n"
+            "<button @click={clicks += 1}>{clicks}</button>\n"
+        )
+        self.assertEqual(pywire_parser.suggest_wires(doc), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,75 @@
+import unittest
+import xml.etree.ElementTree as ET
+
+from pywire import _pywire_parser as pywire_parser
+
+
+class TestXhtmlOutput(unittest.TestCase):
+    def test_html_mode_unchanged(self) -> None:
+        out = pywire_parser.HtmlSerializer()
+        self.assertFalse(out.xhtml)
+        out.start_tag("input", [("disabled", None)])
+        out.end_tag("input")
+        self.assertEqual(out.finish(), b"<input disabled></input>")
+
+    def test_void_and_boolean_attributes(self) -> None:
+        out = pywire_parser.HtmlSerializer(xhtml=True)
+        out.start_tag("p")
+        out.start_tag("input", [("disabled", None), ("value", 'a "b" & <c>')])
+        out.end_tag("input")
+        out.start_tag("br")
+        out.end_tag("p")
+        self.assertEqual(
+            out.finish(),
+            b'<p xmlns="http://www.w3.org/1999/xhtml">'
+            b'<input disabled="disabled" value="a &quot;b&quot; &amp; &lt;c>" />'
+            b"<br /></p>",
+        )
+
+    def test_namespaces(self) -> None:
+        out = pywire_parser.HtmlSerializer(xhtml=True)
+        out.start_tag("html", [("lang", "en")])
+        out.start_tag("svg")
+        out.start_tag("use", [("xlink:href", "#icon")], self_closing=True)
+        out.end_tag("svg")
+        out.start_tag("math")
+        out.end_tag("math")
+        out.end_tag("html")
+        body = out.finish()
+        root = ET.fromstring(body)
+        self.assertEqual(root.tag, "{http://www.w3.org/1999/xhtml}html")
+        svg, math = list(root)
+        self.assertEqual(svg.tag, "{http://www.w3.org/2000/svg}svg")
+        self.assertEqual(math.tag, "{http://www.w3.org/1998/Math/MathML}math")
+        (use,) = list(svg)
+        self.assertEqual(use.get("{http://www.w3.org/1999/xlink}href"), "#icon")
+
+    def test_explicit_namespace_kept(self) -> None:
+        out = pywire_parser.HtmlSerializer(xhtml=True)
+        out.start_tag("feed", [("xmlns", "http://www.w3.org/2005/Atom")])
+        out.end_tag("feed")
+        self.assertEqual(out.finish(), b'<feed xmlns="http://www.w3.org/2005/Atom"></feed>')
+
+    def test_comment_is_well_formed(self) -> None:
+        out = pywire_parser.HtmlSerializer(xhtml=True)
+        out.start_tag("div")
+        out.comment("a -- b -")
+        out.end_tag("div")
+        ET.fromstring(out.finish())
+
+    def test_comment_dash_runs(self) -> None:
+        for text in ("---", "a ----- b", "x--", "-"):
+            with self.subTest(text=text):
+                out = pywire_parser.HtmlSerializer(xhtml=True)
+                out.start_tag("div")
+                out.comment(text)
+                out.end_tag("div")
+                html = out.finish()
+                body = html.split(b"<!--", 1)[1].rsplit(b"-->", 1)[0]
+                self.assertNotIn(b"--", body)
+                self.assertFalse(body.endswith(b"-"))
+                ET.fromstring(html)
+
+
+if __name__ == "__main__":
+    unittest.main()